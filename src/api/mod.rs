@@ -0,0 +1,234 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result, Section,
+};
+use fs_err as fs;
+use tracing::{info, instrument};
+
+use crate::build::{
+    copy_dir, generate_api_descriptions, get_caller_utils_crate, read_kit_toml, read_metadata,
+    run_command, zip_directory,
+};
+
+/// Dir, relative to the package dir, that `kit api publish-client` vendors the caller-utils
+///  crate into, unless a registry is given
+pub const DEFAULT_CLIENTS_DIR: &str = "clients";
+
+/// Dir, within the package's `target/`, that the caller-utils crate is staged in to be published
+const PUBLISH_DIR: &str = "publish-client";
+
+/// Dir, within the package's `target/`, that `kit api export` stages the bundle in
+const EXPORT_DIR: &str = "api-export";
+
+/// README of the bundle of `kit api export`
+const EXPORT_README: &str = r#"# {package_name} API {version}
+
+The API of the {package_name} package, version {version}:
+
+- `wit/`: the WIT interfaces of its processes
+- `openapi.json`: OpenAPI spec of its HTTP handlers, if any
+- `schema.json`: JSON Schema of the types of the API, as they are sent over HTTP & WebSocket,
+  if any
+- `ts/`: TypeScript SDK (types & a client) for its HTTP & WebSocket handlers, if any
+
+Generated by `kit api export`.
+"#;
+
+/// Make the Cargo.toml of a standalone copy of the caller-utils crate: at `version`, publishable
+///  (to `registry` only, if given), and a workspace of its own, so that it builds as a
+///  dependency from wherever it is copied to
+fn format_client_cargo_toml(
+    cargo_toml: &str,
+    version: &str,
+    registry: Option<&str>,
+) -> Result<String> {
+    let mut doc = cargo_toml.parse::<toml_edit::DocumentMut>()?;
+    let package = doc
+        .get_mut("package")
+        .and_then(|p| p.as_table_mut())
+        .ok_or_else(|| eyre!("caller-utils Cargo.toml has no [package]"))?;
+    package["version"] = toml_edit::value(version);
+    match registry {
+        Some(registry) => {
+            let mut registries = toml_edit::Array::new();
+            registries.push(registry);
+            package["publish"] = toml_edit::value(registries);
+        }
+        None => {
+            package.remove("publish");
+        }
+    }
+    if !doc.contains_key("workspace") {
+        doc["workspace"] = toml_edit::table();
+    }
+    Ok(doc.to_string())
+}
+
+/// Copy the caller-utils crate at `crate_dir` to `out_dir`, with its WIT moved out of its
+///  `target/` (which is neither packaged nor, usually, committed) into `wit/`
+fn copy_client_crate(
+    crate_dir: &Path,
+    out_dir: &Path,
+    version: &str,
+    registry: Option<&str>,
+) -> Result<()> {
+    if out_dir.exists() {
+        fs::remove_dir_all(out_dir)?;
+    }
+    copy_dir(crate_dir.join("src"), out_dir.join("src"))?;
+    copy_dir(crate_dir.join("target").join("wit"), out_dir.join("wit"))?;
+
+    let lib_rs_path = out_dir.join("src").join("lib.rs");
+    let lib_rs = fs::read_to_string(&lib_rs_path)?;
+    fs::write(
+        &lib_rs_path,
+        lib_rs.replace("path: \"target/wit\"", "path: \"wit\""),
+    )?;
+
+    let cargo_toml = fs::read_to_string(crate_dir.join("Cargo.toml"))?;
+    fs::write(
+        out_dir.join("Cargo.toml"),
+        format_client_cargo_toml(&cargo_toml, version, registry)?,
+    )?;
+    Ok(())
+}
+
+/// Version the caller-utils crate generated by `kit build --hyperapp` and publish it to
+///  `registry`, or else vendor it into `out_dir` (relative to the package dir), so that other
+///  packages can depend on it like any other crate
+#[instrument(level = "trace", skip_all)]
+pub fn execute_publish_client(
+    package_dir: &Path,
+    version: Option<&str>,
+    registry: Option<&str>,
+    out_dir: Option<&Path>,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    let package_dir = fs::canonicalize(package_dir)?;
+    let config = read_kit_toml(&package_dir)?.caller_utils;
+    let (crate_name, crate_path) = get_caller_utils_crate(&package_dir, &config)?;
+    let crate_dir = package_dir.join(&crate_path);
+    if !crate_dir.join("src").join("lib.rs").exists() {
+        return Err(eyre!("caller-utils crate not found at {crate_dir:?}")
+            .with_suggestion(|| "Generate it with `kit build --hyperapp` first."));
+    }
+    let version = match version {
+        Some(version) => version.to_string(),
+        None => read_metadata(&package_dir)?.properties.current_version,
+    };
+    let registry = registry.or(config.registry.as_deref());
+
+    let Some(registry) = registry else {
+        let out_dir = package_dir
+            .join(out_dir.unwrap_or(Path::new(DEFAULT_CLIENTS_DIR)))
+            .join(&crate_name);
+        copy_client_crate(&crate_dir, &out_dir, &version, None)?;
+        info!(
+            "Vendored {crate_name} {version} into {out_dir:?}; depend on it with\n{} = {{ path = {:?} }}",
+            crate_name.replace("-", "_"),
+            out_dir.strip_prefix(&package_dir).unwrap_or(&out_dir),
+        );
+        return Ok(());
+    };
+
+    let out_dir: PathBuf = package_dir
+        .join("target")
+        .join(PUBLISH_DIR)
+        .join(&crate_name);
+    copy_client_crate(&crate_dir, &out_dir, &version, Some(registry))?;
+    let mut command = Command::new("cargo");
+    command
+        .args(["publish", "--registry", registry, "--allow-dirty"])
+        .current_dir(&out_dir);
+    if dry_run {
+        command.arg("--dry-run");
+    }
+    run_command(&mut command, verbose)
+        .wrap_err_with(|| format!("Failed to publish {crate_name} {version} to {registry}"))
+        .with_suggestion(|| {
+            "Check the registry is configured in .cargo/config.toml, and that all of the crate's \
+             dependencies (including `hyperware_process_lib`) are from a registry."
+        })?;
+    if dry_run {
+        info!("Checked {crate_name} {version} can be published to {registry}");
+    } else {
+        info!(
+            "Published {crate_name} {version} to {registry}; depend on it with\n{} = {{ version = \"{version}\", registry = \"{registry}\" }}",
+            crate_name.replace("-", "_"),
+        );
+    }
+    Ok(())
+}
+
+/// Bundle the API of the package at its current version into a zip at `output`
+///  [default: `target/<package>-api-<version>.zip`]: its WIT, the OpenAPI spec of its HTTP
+///  handlers, the JSON Schema of its types & its TypeScript SDK, all generated from `api/`
+#[instrument(level = "trace", skip_all)]
+pub fn execute_export(package_dir: &Path, output: Option<&Path>) -> Result<()> {
+    let package_dir = fs::canonicalize(package_dir)?;
+    let metadata = read_metadata(&package_dir)?;
+    let package_name = &metadata.properties.package_name;
+    let version = &metadata.properties.current_version;
+
+    let api_dir = package_dir.join("api");
+    let mut wit_files: Vec<PathBuf> = match fs::read_dir(&api_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "wit"))
+            .collect(),
+        Err(_) => vec![],
+    };
+    if wit_files.is_empty() {
+        return Err(
+            eyre!("No WIT files found in {api_dir:?}: there is no API to export").with_suggestion(
+                || "Generate them with `kit wit generate` or `kit build --hyperapp`.",
+            ),
+        );
+    }
+    wit_files.sort();
+
+    let bundle_name = format!("{package_name}-api-{version}");
+    let bundle_dir = package_dir
+        .join("target")
+        .join(EXPORT_DIR)
+        .join(&bundle_name);
+    if bundle_dir.exists() {
+        fs::remove_dir_all(&bundle_dir)?;
+    }
+    fs::create_dir_all(bundle_dir.join("wit"))?;
+    for wit_file in &wit_files {
+        fs::copy(
+            wit_file,
+            bundle_dir.join("wit").join(wit_file.file_name().unwrap()),
+        )?;
+    }
+    generate_api_descriptions(&package_dir, &bundle_dir)?;
+    fs::write(
+        bundle_dir.join("README.md"),
+        EXPORT_README
+            .replace("{package_name}", package_name)
+            .replace("{version}", version),
+    )?;
+
+    let output = match output {
+        Some(output) => output.to_path_buf(),
+        None => package_dir
+            .join("target")
+            .join(format!("{bundle_name}.zip")),
+    };
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let output_str = output
+        .to_str()
+        .ok_or_else(|| eyre!("Output path {output:?} is not valid UTF-8"))?;
+    zip_directory(&bundle_dir, output_str)
+        .wrap_err_with(|| format!("Failed to write API bundle {output:?}"))?;
+
+    info!("Exported {package_name} API {version} to {output:?}");
+    Ok(())
+}
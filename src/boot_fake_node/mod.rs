@@ -444,6 +444,7 @@ pub async fn execute(
     node_home: PathBuf,
     node_port: u16,
     fakechain_port: u16,
+    fakechain_name: Option<&str>,
     rpc: Option<&str>,
     mut fake_node_name: String,
     password: &str,
@@ -488,8 +489,18 @@ pub async fn execute(
     }
 
     // boot fakechain
-    let anvil_process =
-        chain::start_chain(fakechain_port, recv_kill_in_start_chain, false, false).await?;
+    let fakechain_port = chain::get_chain_port(fakechain_port, fakechain_name)?;
+    let anvil_process = chain::start_chain(
+        fakechain_port,
+        fakechain_name,
+        None,
+        None,
+        None,
+        recv_kill_in_start_chain,
+        false,
+        false,
+    )
+    .await?;
 
     if let Some(rpc) = rpc {
         args.extend_from_slice(&["--rpc".into(), rpc.into()]);
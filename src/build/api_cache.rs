@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::Result;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, instrument};
+
+use crate::KIT_CACHE;
+
+use super::DepSource;
+
+// Default number of seconds a fetched dependency API is used before refetching
+pub const DEFAULT_API_CACHE_TTL: u64 = 60 * 60;
+
+const API_CACHE_DIR_NAME: &str = "apis";
+const API_CACHE_ENTRY_NAME: &str = "latest.json";
+
+/// Most recently fetched version of a dependency API, stored at
+///  `KIT_CACHE/apis/<source>/<package_id>/latest.json`; the API itself is at
+///  `KIT_CACHE/apis/<source>/<package_id>/<version>/`
+#[derive(Debug, Serialize, Deserialize)]
+struct ApiCacheEntry {
+    version: String,
+    fetched_at: u64,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// APIs fetched from different `--dep-source`s are cached apart: a dir or registry may serve
+//  a different API than the node does under the same package ID & version
+fn get_api_cache_dir(package_id: &str, dep_source: &DepSource) -> PathBuf {
+    let source = match dep_source {
+        DepSource::Node => "node".to_string(),
+        DepSource::Dir(ref dir) => {
+            format!("dir-{:x}", Sha256::digest(dir.to_string_lossy().as_bytes()))
+        }
+        DepSource::Git(ref source) => format!("git-{:x}", Sha256::digest(source.as_bytes())),
+        DepSource::Http(ref source) => format!("http-{:x}", Sha256::digest(source.as_bytes())),
+    };
+    PathBuf::from(KIT_CACHE)
+        .join(API_CACHE_DIR_NAME)
+        .join(source)
+        .join(package_id)
+}
+
+/// Get the cached API dir for `package_id` from `dep_source`, if it was fetched less than
+///  `ttl` seconds ago; if `ttl` is `None`, any cached API is returned regardless of age
+#[instrument(level = "trace", skip_all)]
+pub fn get_cached_api(
+    package_id: &str,
+    dep_source: &DepSource,
+    ttl: Option<u64>,
+) -> Result<Option<PathBuf>> {
+    let cache_dir = get_api_cache_dir(package_id, dep_source);
+    let entry_path = cache_dir.join(API_CACHE_ENTRY_NAME);
+    if !entry_path.exists() {
+        return Ok(None);
+    }
+    let entry: ApiCacheEntry = serde_json::from_slice(&fs::read(&entry_path)?)?;
+    let api_dir = cache_dir.join(&entry.version);
+    if !api_dir.is_dir() {
+        return Ok(None);
+    }
+    if let Some(ttl) = ttl {
+        let age = now().saturating_sub(entry.fetched_at);
+        if age >= ttl {
+            debug!("cached API for {package_id} is stale ({age}s >= {ttl}s)");
+            return Ok(None);
+        }
+    }
+    Ok(Some(api_dir))
+}
+
+/// Copy the `.wit` and `.wasm` files of an API freshly fetched from `dep_source` into the cache
+#[instrument(level = "trace", skip_all)]
+pub fn cache_api(
+    package_id: &str,
+    dep_source: &DepSource,
+    version: &str,
+    zip_dir: &Path,
+) -> Result<PathBuf> {
+    let cache_dir = get_api_cache_dir(package_id, dep_source);
+    let version = if version.is_empty() {
+        "unknown"
+    } else {
+        version
+    };
+    let api_dir = cache_dir.join(version);
+    if api_dir.exists() {
+        fs::remove_dir_all(&api_dir)?;
+    }
+    fs::create_dir_all(&api_dir)?;
+    for entry in fs::read_dir(zip_dir)? {
+        let path = entry?.path();
+        let maybe_ext = path.extension().and_then(|s| s.to_str());
+        if Some("wit") == maybe_ext || Some("wasm") == maybe_ext {
+            fs::copy(&path, api_dir.join(path.file_name().unwrap()))?;
+        }
+    }
+    let entry = ApiCacheEntry {
+        version: version.to_string(),
+        fetched_at: now(),
+    };
+    fs::write(
+        cache_dir.join(API_CACHE_ENTRY_NAME),
+        serde_json::to_string_pretty(&entry)?,
+    )?;
+    Ok(api_dir)
+}
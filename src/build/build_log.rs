@@ -0,0 +1,156 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+
+use color_eyre::{eyre::eyre, Result};
+use serde::Deserialize;
+use tracing::{error, info, instrument, warn};
+
+/// Target of the structured build events emitted for `--log-format json`;
+///  these are filtered out of the human-readable text output
+pub const BUILD_LOG_TARGET: &str = "kit::build_log";
+pub const DEFAULT_LOG_FORMAT: &str = "text";
+
+/// A line of `cargo build --message-format=json` output; only compiler messages are used
+#[derive(Debug, Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<Diagnostic>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Diagnostic {
+    message: String,
+    level: String,
+    code: Option<DiagnosticCode>,
+    spans: Vec<DiagnosticSpan>,
+    rendered: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiagnosticCode {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiagnosticSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+}
+
+/// Timer for a build step (the package, its UI, or one of its processes);
+///  emits `started` on creation and `finished` or `failed`, with the elapsed time, on `end()`
+pub struct BuildStep {
+    step: &'static str,
+    name: String,
+    start: Instant,
+}
+
+impl BuildStep {
+    pub fn start(step: &'static str, path: &Path) -> Self {
+        let name = path.display().to_string();
+        info!(target: BUILD_LOG_TARGET, step, name = %name, status = "started");
+        BuildStep {
+            step,
+            name,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn end<T>(self, result: Result<T>) -> Result<T> {
+        let elapsed_ms = self.start.elapsed().as_millis() as u64;
+        match result {
+            Ok(_) => info!(
+                target: BUILD_LOG_TARGET,
+                step = self.step,
+                name = %self.name,
+                status = "finished",
+                elapsed_ms,
+            ),
+            Err(ref e) => error!(
+                target: BUILD_LOG_TARGET,
+                step = self.step,
+                name = %self.name,
+                status = "failed",
+                elapsed_ms,
+                error = %e,
+            ),
+        }
+        result
+    }
+}
+
+fn emit_diagnostic(process: &str, diagnostic: Diagnostic) {
+    let span = diagnostic.spans.iter().find(|s| s.is_primary);
+    let file = span.map(|s| s.file_name.as_str()).unwrap_or_default();
+    let line = span.map(|s| s.line_start).unwrap_or_default();
+    let column = span.map(|s| s.column_start).unwrap_or_default();
+    let code = diagnostic.code.map(|c| c.code).unwrap_or_default();
+    let rendered = diagnostic.rendered.unwrap_or_default();
+    let message = diagnostic.message;
+    match diagnostic.level.as_str() {
+        "error" | "error: internal compiler error" => error!(
+            target: BUILD_LOG_TARGET,
+            step = "diagnostic",
+            process,
+            level = %diagnostic.level,
+            code = %code,
+            file,
+            line,
+            column,
+            rendered = %rendered,
+            "{message}"
+        ),
+        "warning" => warn!(
+            target: BUILD_LOG_TARGET,
+            step = "diagnostic",
+            process,
+            level = %diagnostic.level,
+            code = %code,
+            file,
+            line,
+            column,
+            rendered = %rendered,
+            "{message}"
+        ),
+        _ => info!(
+            target: BUILD_LOG_TARGET,
+            step = "diagnostic",
+            process,
+            level = %diagnostic.level,
+            code = %code,
+            file,
+            line,
+            column,
+            rendered = %rendered,
+            "{message}"
+        ),
+    }
+}
+
+/// Run a `cargo build --message-format=json` and emit its compiler diagnostics as build events
+#[instrument(level = "trace", skip_all)]
+pub fn run_cargo_with_json_diagnostics(cmd: &mut Command, process: &str) -> Result<()> {
+    let output = cmd.output()?;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Ok(message) = serde_json::from_str::<CargoMessage>(line) else {
+            continue;
+        };
+        if message.reason != "compiler-message" {
+            continue;
+        }
+        if let Some(diagnostic) = message.message {
+            emit_diagnostic(process, diagnostic);
+        }
+    }
+    if !output.status.success() {
+        return Err(eyre!(
+            "Failed to compile {process}: cargo exited with code {:?}\nstderr: {}",
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr),
+        ));
+    }
+    Ok(())
+}
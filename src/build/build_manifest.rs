@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use color_eyre::Result;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use walkdir::WalkDir;
+
+use hyperware_process_lib::kernel_types::Erc721Metadata;
+
+use super::{hash_zip_pkg, make_pkg_publisher};
+
+pub const BUILD_MANIFEST_NAME: &str = "build-manifest.json";
+
+/// Machine-readable description of a build, written to `target/build-manifest.json`
+///
+/// Written next to the package zip rather than into `pkg/`: the source commit & toolchain
+/// versions it records differ between machines, so would break reproducible builds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildManifest {
+    pub package: String,
+    pub version: String,
+    pub kit_version: String,
+    /// `git rev-parse HEAD` of the package dir, if it is in a git repo
+    pub source_commit: Option<String>,
+    /// tool -> `<tool> --version` output
+    pub toolchains: BTreeMap<String, String>,
+    pub features: Vec<String>,
+    pub artifacts: Vec<BuildArtifact>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildArtifact {
+    /// Path relative to `pkg/`
+    pub path: String,
+    pub sha256: String,
+}
+
+fn get_command_output(package_dir: &Path, program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(package_dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        debug!("`{program} {args:?}` failed; omitting from build manifest");
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn write_build_manifest(
+    package_dir: &Path,
+    metadata: &Erc721Metadata,
+    features: &str,
+    toolchain: &str,
+) -> Result<()> {
+    let pkg_dir = package_dir.join("pkg");
+    let manifest_path = package_dir.join("target").join(BUILD_MANIFEST_NAME);
+
+    let mut artifacts = vec![];
+    let mut entries: Vec<_> = WalkDir::new(&pkg_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .collect();
+    entries.sort_by_key(|entry| entry.path().to_owned());
+    for entry in entries {
+        let path = entry.path();
+        artifacts.push(BuildArtifact {
+            path: path.strip_prefix(&pkg_dir)?.to_string_lossy().to_string(),
+            sha256: hash_zip_pkg(path)?,
+        });
+    }
+
+    let mut toolchains = BTreeMap::new();
+    for (tool, program, args) in [
+        ("rustc", "rustc", vec![toolchain, "--version"]),
+        ("cargo", "cargo", vec![toolchain, "--version"]),
+        ("wasm-tools", "wasm-tools", vec!["--version"]),
+    ] {
+        if let Some(version) = get_command_output(package_dir, program, &args) {
+            toolchains.insert(tool.to_string(), version);
+        }
+    }
+
+    let manifest = BuildManifest {
+        package: make_pkg_publisher(metadata),
+        version: metadata.properties.current_version.clone(),
+        kit_version: env!("CARGO_PKG_VERSION").to_string(),
+        source_commit: get_command_output(package_dir, "git", &["rev-parse", "HEAD"]),
+        toolchains,
+        features: features
+            .split(',')
+            .filter(|f| !f.is_empty())
+            .map(|f| f.to_string())
+            .collect(),
+        artifacts,
+    };
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
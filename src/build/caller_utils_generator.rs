@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -6,11 +6,34 @@ use color_eyre::{
     eyre::{bail, eyre, WrapErr},
     Result,
 };
+use sha2::{Digest, Sha256};
 use tracing::{debug, info, instrument, warn};
 
 use toml::Value;
 use walkdir::WalkDir;
 
+use super::caller_utils_ts_generator::{
+    parse_args_comment, parse_returns_comment, parse_tuple_types, split_top_level,
+};
+use super::kit_toml::{CallerUtilsConfig, WitBindgenConfig};
+
+const DEFAULT_CALLER_UTILS_VERSION: &str = "0.1.0";
+// Hash of the inputs the caller-utils crate was last generated from, in its `target/`
+const API_HASH_FILE_NAME: &str = "api-hash.txt";
+// Generated in the api dir by `create_caller_utils_crate()` itself, so not an input
+const TYPES_WIT_FILE_NAME: &str = "types.wit";
+// With `[caller-utils] commit-generated`, the dir of the crate's `src/` that the expanded
+//  bindings & the stubs are written to, each file starting with `GENERATED_HEADER`
+const GENERATED_DIR_NAME: &str = "generated";
+const GENERATED_HEADER: &str =
+    "// @generated by `kit build` from the package's WIT API (api/): do not edit by hand\n\n";
+// Derives the generated types always have: the stubs (de)serialize them
+const DEFAULT_DERIVES: [&str; 3] = [
+    "serde::Deserialize",
+    "serde::Serialize",
+    "process_macros::SerdeJsonInto",
+];
+
 // Convert kebab-case to snake_case
 pub fn to_snake_case(s: &str) -> String {
     s.replace('-', "_")
@@ -43,6 +66,7 @@ fn find_world_names(api_dir: &Path) -> Result<Vec<String>> {
     // Look for world definition files
     for entry in WalkDir::new(api_dir)
         .max_depth(1)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(Result::ok)
     {
@@ -95,6 +119,9 @@ fn wit_type_to_rust(wit_type: &str) -> String {
         "u32" => "u32".to_string(),
         "s64" => "i64".to_string(),
         "u64" => "u64".to_string(),
+        // 128-bit integers, from signature comments: strings in WIT but JSON numbers on the wire
+        "s128" => "i128".to_string(),
+        "u128" => "u128".to_string(),
         // Floating point types
         "f32" => "f32".to_string(),
         "f64" => "f64".to_string(),
@@ -111,28 +138,37 @@ fn wit_type_to_rust(wit_type: &str) -> String {
             let inner_type = &t[5..t.len() - 1];
             format!("Vec<{}>", wit_type_to_rust(inner_type))
         }
+        // Rust maps, from signature comments: lowered to `list<tuple<k, v>>` in WIT
+        //  but (de)serialized as JSON objects by the process
+        t if t.starts_with("map<") => {
+            let types = split_top_level(&t[4..t.len() - 1]);
+            let rust_types: Vec<String> = types.iter().map(|t| wit_type_to_rust(t)).collect();
+            format!("{}<{}>", MAP_ALIAS, rust_types.join(", "))
+        }
+        // Rust arrays, from signature comments: lowered to `list<t>` in WIT
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).first() {
+            Some(inner_type) => format!("Vec<{}>", wit_type_to_rust(inner_type)),
+            None => "Vec<()>".to_string(),
+        },
         t if t.starts_with("option<") => {
             let inner_type = &t[7..t.len() - 1];
             format!("Option<{}>", wit_type_to_rust(inner_type))
         }
         t if t.starts_with("result<") => {
             let inner_part = &t[7..t.len() - 1];
-            if let Some(comma_pos) = inner_part.find(',') {
-                let ok_type = &inner_part[..comma_pos].trim();
-                let err_type = &inner_part[comma_pos + 1..].trim();
-                format!(
+            match split_top_level(inner_part).as_slice() {
+                [ok_type, err_type] => format!(
                     "Result<{}, {}>",
                     wit_type_to_rust(ok_type),
                     wit_type_to_rust(err_type)
-                )
-            } else {
-                format!("Result<{}, ()>", wit_type_to_rust(inner_part))
+                ),
+                _ => format!("Result<{}, ()>", wit_type_to_rust(inner_part)),
             }
         }
         t if t.starts_with("tuple<") => {
             let inner_types = &t[6..t.len() - 1];
-            let rust_types: Vec<String> = inner_types
-                .split(", ")
+            let rust_types: Vec<String> = split_top_level(inner_types)
+                .iter()
                 .map(|t| wit_type_to_rust(t))
                 .collect();
             format!("({})", rust_types.join(", "))
@@ -142,6 +178,14 @@ fn wit_type_to_rust(wit_type: &str) -> String {
     }
 }
 
+// Whether a WIT type from a signature comment contains a 128-bit integer (`u128` or `s128`),
+//  which is a string in the WIT itself
+fn has_128_bit_int(wit_type: &str) -> bool {
+    wit_type
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .any(|t| t == "u128" || t == "s128")
+}
+
 // Structure to represent a field in a WIT signature struct
 #[derive(Debug)]
 struct SignatureField {
@@ -149,100 +193,14 @@ struct SignatureField {
     wit_type: String,
 }
 
-/// Parse a tuple type string like "tuple<u64, bool>" into its element types
-fn parse_tuple_types(tuple_type: &str) -> Vec<String> {
-    if !tuple_type.starts_with("tuple<") || !tuple_type.ends_with(">") {
-        return vec![];
-    }
-    let inner = &tuple_type[6..tuple_type.len() - 1];
-    if inner.is_empty() {
-        return vec![];
-    }
-    // Handle nested generics by tracking angle bracket depth
-    let mut types = Vec::new();
-    let mut current = String::new();
-    let mut depth = 0;
-
-    for c in inner.chars() {
-        match c {
-            '<' => {
-                depth += 1;
-                current.push(c);
-            }
-            '>' => {
-                depth -= 1;
-                current.push(c);
-            }
-            ',' if depth == 0 => {
-                types.push(current.trim().to_string());
-                current = String::new();
-            }
-            _ => {
-                current.push(c);
-            }
-        }
-    }
-    if !current.trim().is_empty() {
-        types.push(current.trim().to_string());
-    }
-    types
-}
-
-/// Parse args comment like `// args: (foo: u64, bar: bool)` into parameter names
-fn parse_args_comment(comment: &str) -> Vec<String> {
-    let comment = comment.trim().trim_start_matches("//").trim();
-    if !comment.starts_with("args:") {
-        return vec![];
-    }
-    let args_part = comment.trim_start_matches("args:").trim();
-    if !args_part.starts_with("(") || !args_part.ends_with(")") {
-        return vec![];
-    }
-    let inner = &args_part[1..args_part.len() - 1];
-    if inner.is_empty() {
-        return vec![];
-    }
-
-    let mut names = Vec::new();
-    // Handle nested generics by tracking angle bracket depth
-    let mut current = String::new();
-    let mut depth = 0;
-
-    for c in inner.chars() {
-        match c {
-            '<' => {
-                depth += 1;
-                current.push(c);
-            }
-            '>' => {
-                depth -= 1;
-                current.push(c);
-            }
-            ',' if depth == 0 => {
-                // Extract name from "name: type"
-                if let Some(name) = current.split(':').next() {
-                    let name = name.trim();
-                    if !name.is_empty() {
-                        names.push(name.to_string());
-                    }
-                }
-                current = String::new();
-            }
-            _ => {
-                current.push(c);
-            }
-        }
-    }
-    // Handle last parameter
-    if !current.trim().is_empty() {
-        if let Some(name) = current.split(':').next() {
-            let name = name.trim();
-            if !name.is_empty() {
-                names.push(name.to_string());
-            }
-        }
-    }
-    names
+/// Parse a `// wit-rename: <renamed> = <original>` comment, recorded by `kit build --wit-rename`
+///  for each identifier whose digits it rewrote as words
+fn parse_wit_rename_comment(comment: &str) -> Option<(String, String)> {
+    let (renamed, original) = comment
+        .trim()
+        .strip_prefix("// wit-rename:")?
+        .split_once('=')?;
+    Some((renamed.trim().to_string(), original.trim().to_string()))
 }
 
 // Structure to represent a WIT signature struct
@@ -252,6 +210,8 @@ struct SignatureStruct {
     attr_type: String,
     fields: Vec<SignatureField>,
     args_comment: Option<String>, // Parsed from // args: (name: type, ...) comment
+    returns_comment: Option<String>, // Parsed from // returns: type comment, if present
+    docs: Vec<String>,            // Parsed from /// doc comments above the signature
 }
 
 // Find all interface imports in the selected world WIT file(s)
@@ -263,6 +223,7 @@ fn find_interfaces_in_world(api_dir: &Path, world_name: &str) -> Result<Vec<Stri
     // Index world definition files by world name
     for entry in WalkDir::new(api_dir)
         .max_depth(1)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(Result::ok)
     {
@@ -347,10 +308,26 @@ fn parse_wit_file(file_path: &Path) -> Result<(Vec<SignatureStruct>, Vec<String>
     let lines: Vec<_> = content.lines().collect();
     let mut i = 0;
     let mut pending_args_comment: Option<String> = None;
+    let mut pending_returns_comment: Option<String> = None;
+    // Identifiers renamed by --wit-rename: function & argument names are restored to the
+    //  originals (which the process expects); types keep the names wit-bindgen gives them
+    let mut renames: HashMap<String, String> = HashMap::new();
+    let mut pending_docs: Vec<String> = Vec::new();
 
     while i < lines.len() {
         let line = lines[i].trim();
 
+        // Collect doc comments: they document the handler of the signature record they precede
+        if let Some(doc) = line.strip_prefix("///") {
+            pending_docs.push(doc.strip_prefix(' ').unwrap_or(doc).to_string());
+        } else if !line.starts_with("//") && !line.contains("-signature-") {
+            pending_docs.clear();
+        }
+
+        if let Some((renamed, original)) = parse_wit_rename_comment(line) {
+            debug!(renamed = %renamed, original = %original, "Found wit-rename comment");
+            renames.insert(renamed, original);
+        }
         // Look for record definitions that aren't signature structs
         if line.starts_with("record ") && !line.contains("-signature-") {
             let record_name = line
@@ -369,18 +346,37 @@ fn parse_wit_file(file_path: &Path) -> Result<(Vec<SignatureStruct>, Vec<String>
             debug!(name = %variant_name, "Found type definition (variant)");
             type_names.push(variant_name.to_string());
         }
+        // Look for enum & type alias definitions
+        else if line.starts_with("enum ") || line.starts_with("type ") {
+            let type_name = line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .trim_end_matches(['{', ';']);
+            debug!(name = %type_name, "Found type definition (enum or alias)");
+            type_names.push(type_name.to_string());
+        }
         // Look for args comment above record: // args: (name: type, ...)
         else if line.starts_with("// args:") {
             // Store this comment - it will be used by the next signature record
             pending_args_comment = Some(line.to_string());
             debug!(args_comment = %line, "Found args comment");
         }
+        // Look for returns comment above record: // returns: type
+        else if line.starts_with("// returns:") {
+            pending_returns_comment = Some(line.to_string());
+            debug!(returns_comment = %line, "Found returns comment");
+        }
         // Look for signature record definitions
         else if line.starts_with("record ") && line.contains("-signature-") {
             let record_name = line
                 .trim_start_matches("record ")
                 .trim_end_matches(" {")
                 .trim();
+            let record_name = renames
+                .get(record_name)
+                .map(String::as_str)
+                .unwrap_or(record_name);
             debug!(name = %record_name, "Found signature record");
 
             // Extract function name and attribute type
@@ -395,8 +391,22 @@ fn parse_wit_file(file_path: &Path) -> Result<(Vec<SignatureStruct>, Vec<String>
             let attr_type = parts[1].to_string();
             debug!(function = %function_name, attr_type = %attr_type, "Extracted function name and type");
 
-            // Use the pending args comment if present
-            let args_comment = pending_args_comment.take();
+            // Use the pending args & returns comments if present
+            let args_comment = pending_args_comment.take().map(|comment| {
+                if renames.is_empty() {
+                    return comment;
+                }
+                let args: Vec<String> = parse_args_comment(&comment)
+                    .into_iter()
+                    .map(|(name, wit_type)| {
+                        let name = renames.get(&name).unwrap_or(&name);
+                        format!("{}: {}", name, wit_type)
+                    })
+                    .collect();
+                format!("// args: ({})", args.join(", "))
+            });
+            let returns_comment = pending_returns_comment.take();
+            let docs = std::mem::take(&mut pending_docs);
 
             // Parse fields
             let mut fields = Vec::new();
@@ -432,6 +442,8 @@ fn parse_wit_file(file_path: &Path) -> Result<(Vec<SignatureStruct>, Vec<String>
                 attr_type,
                 fields,
                 args_comment,
+                returns_comment,
+                docs,
             });
         }
 
@@ -447,8 +459,23 @@ fn parse_wit_file(file_path: &Path) -> Result<(Vec<SignatureStruct>, Vec<String>
     Ok((signatures, type_names))
 }
 
-// Generate a Rust async function from a signature struct
-fn generate_async_function(signature: &SignatureStruct) -> Option<String> {
+// Private aliases used by the generated stubs: these never shadow the names
+// publicly re-exported from each interface
+const ADDRESS_ALIAS: &str = "CallerUtilsAddress";
+const APP_SEND_ERROR_ALIAS: &str = "CallerUtilsAppSendError";
+const REQUEST_ALIAS: &str = "CallerUtilsRequest";
+const SEND_ALIAS: &str = "caller_utils_send";
+const JSON_ALIAS: &str = "caller_utils_json";
+const MAP_ALIAS: &str = "CallerUtilsMap";
+// Constant of the generated crate: the seconds the stubs without a `timeout_secs` param wait
+//  for a response, from `[caller-utils] timeout` in kit.toml
+const TIMEOUT_CONST: &str = "DEFAULT_TIMEOUT_SECS";
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+// Generate the stubs of a signature struct of the `interface_name` interface: a Rust async
+//  function, for processes running the hyperapp async executor, and a blocking one, for those
+//  that do not, each with a `_with_timeout` variant
+fn generate_stubs(signature: &SignatureStruct, interface_name: &str) -> Option<String> {
     // Convert function name from kebab-case to snake_case
     let snake_function_name = to_snake_case(&signature.function_name);
 
@@ -459,17 +486,13 @@ fn generate_async_function(signature: &SignatureStruct) -> Option<String> {
     let full_function_name = format!("{}_{}_rpc", snake_function_name, signature.attr_type);
     debug!(name = %full_function_name, "Generating function stub");
 
-    // Extract arg names from the args comment if present
-    let arg_names: Vec<String> = signature
+    // Extract arg names & types from the args comment if present
+    let args: Vec<(String, String)> = signature
         .args_comment
         .as_ref()
-        .map(|c| {
-            parse_args_comment(c)
-                .into_iter()
-                .map(|n| to_snake_case(&n))
-                .collect()
-        })
+        .map(|c| parse_args_comment(c))
         .unwrap_or_default();
+    let arg_names: Vec<String> = args.iter().map(|(n, _)| to_snake_case(n)).collect();
     if !arg_names.is_empty() {
         debug!(arg_names = ?arg_names, "Parsed arg names from comment");
     }
@@ -477,6 +500,7 @@ fn generate_async_function(signature: &SignatureStruct) -> Option<String> {
     // Extract parameters and return type
     let mut params = Vec::new();
     let mut param_names = Vec::new();
+    let mut param_values = Vec::new(); // As serialized into the request body
     let mut return_type = "()".to_string();
     let mut target_param = "";
 
@@ -489,10 +513,18 @@ fn generate_async_function(signature: &SignatureStruct) -> Option<String> {
                 target_param = "&str";
             } else {
                 // Use a distinct alias for hyperware_process_lib::Address to avoid WIT name clashes
-                target_param = "&ProcessAddress";
+                target_param = "&CallerUtilsAddress";
             }
         } else if field.name == "returning" {
-            return_type = rust_type;
+            // Maps are only distinguishable from lists of tuples by the returns comment
+            return_type = match signature
+                .returns_comment
+                .as_deref()
+                .and_then(parse_returns_comment)
+            {
+                Some(wit_type) => wit_type_to_rust(&wit_type),
+                None => rust_type,
+            };
             debug!(return_type = %return_type, "Identified return type");
         } else if field.name == "arg-types" {
             // Parse the arg-types tuple to extract individual parameter types
@@ -503,8 +535,32 @@ fn generate_async_function(signature: &SignatureStruct) -> Option<String> {
                     .get(i)
                     .cloned()
                     .unwrap_or_else(|| format!("arg{}", i));
-                let param_rust_type = wit_type_to_rust(wit_type);
+                // Maps & 128-bit integers are only distinguishable from lists of tuples &
+                //  strings by the args comment
+                let wit_type = match args.get(i) {
+                    Some((_, arg_type))
+                        if arg_type.contains("map<") || has_128_bit_int(arg_type) =>
+                    {
+                        arg_type
+                    }
+                    _ => wit_type,
+                };
+                // Arrays are taken as such, so that the compiler checks their length,
+                //  and sent as lists
+                let (param_rust_type, param_value) = match args.get(i) {
+                    Some((_, arg_type)) if arg_type.starts_with("array<") => {
+                        match split_top_level(&arg_type[6..arg_type.len() - 1]).as_slice() {
+                            [inner_type, len] => (
+                                format!("[{}; {}]", wit_type_to_rust(inner_type), len),
+                                format!("{}.to_vec()", param_name),
+                            ),
+                            _ => (wit_type_to_rust(wit_type), param_name.clone()),
+                        }
+                    }
+                    _ => (wit_type_to_rust(wit_type), param_name.clone()),
+                };
                 params.push(format!("{}: {}", param_name, param_rust_type));
+                param_values.push(param_value);
                 param_names.push(param_name);
                 debug!(param_name = param_names.last().unwrap(), wit_type = %wit_type, "Added tuple parameter");
             }
@@ -512,6 +568,7 @@ fn generate_async_function(signature: &SignatureStruct) -> Option<String> {
             // Legacy support: handle individual parameter fields (for backwards compatibility)
             let field_name_snake = to_snake_case(&field.name);
             params.push(format!("{}: {}", field_name_snake, rust_type));
+            param_values.push(field_name_snake.clone());
             param_names.push(field_name_snake);
             debug!(
                 param_name = param_names.last().unwrap(),
@@ -536,52 +593,424 @@ fn generate_async_function(signature: &SignatureStruct) -> Option<String> {
     };
 
     // Wrap the return type in a Result<_, AppSendError>
-    let wrapped_return_type = format!("Result<{}, AppSendError>", return_type);
+    let wrapped_return_type = format!("Result<{}, {}>", return_type, APP_SEND_ERROR_ALIAS);
 
-    // For HTTP endpoints, generate commented-out implementation
-    if signature.attr_type == "http" {
+    // HTTP endpoints, WebSocket handlers & pushed events are not called by Request, so get no stub
+    if matches!(signature.attr_type.as_str(), "http" | "ws" | "ws-push") {
         return None;
     }
 
-    // Format JSON parameters correctly
-    let json_params = if param_names.is_empty() {
+    // Format JSON parameters correctly: 128-bit integers do not fit in a `serde_json::Value`, so
+    //  bodies with them are serialized directly
+    let has_128_bit_args = args.iter().any(|(_, arg_type)| has_128_bit_int(arg_type));
+    let json_params = if has_128_bit_args {
+        debug!(params = ?param_values, "Generating JSON with 128-bit integer parameters");
+        let value = if param_values.len() == 1 {
+            param_values[0].clone()
+        } else {
+            format!("({})", param_values.join(", "))
+        };
+        format!(
+            "{}::from([(\"{}\", {})])",
+            MAP_ALIAS, pascal_function_name, value
+        )
+    } else if param_values.is_empty() {
         // No parameters case
         debug!("Generating JSON with no parameters");
-        format!("json!({{\"{}\" : null}})", pascal_function_name)
-    } else if param_names.len() == 1 {
+        format!("{}!({{\"{}\" : null}})", JSON_ALIAS, pascal_function_name)
+    } else if param_values.len() == 1 {
         // Single parameter case
-        debug!(param = %param_names[0], "Generating JSON with single parameter");
+        debug!(param = %param_values[0], "Generating JSON with single parameter");
         format!(
-            "json!({{\"{}\": {}}})",
-            pascal_function_name, param_names[0]
+            "{}!({{\"{}\": {}}})",
+            JSON_ALIAS, pascal_function_name, param_values[0]
         )
     } else {
         // Multiple parameters case - use tuple format
-        debug!(params = ?param_names, "Generating JSON with multiple parameters (tuple)");
+        debug!(params = ?param_values, "Generating JSON with multiple parameters (tuple)");
         format!(
-            "json!({{\"{}\": ({})}})",
+            "{}!({{\"{}\": ({})}})",
+            JSON_ALIAS,
             pascal_function_name,
-            param_names.join(", ")
+            param_values.join(", ")
+        )
+    };
+
+    let default_timeout = format!("crate::{}", TIMEOUT_CONST);
+
+    // Carry the handler's doc comments over to its stub
+    let docs: String = signature
+        .docs
+        .iter()
+        .map(|doc| {
+            if doc.is_empty() {
+                "///\n".to_string()
+            } else {
+                format!("/// {}\n", doc)
+            }
+        })
+        .collect();
+    let docs = if docs.is_empty() {
+        docs
+    } else {
+        format!("{}///\n", docs)
+    };
+
+    // The `_with_timeout` variants take the timeout after the handler's params, and the others
+    //  call them with the crate's default
+    let timeout_params = if all_params.is_empty() {
+        "timeout_secs: u64".to_string()
+    } else {
+        format!("{}, timeout_secs: u64", all_params)
+    };
+    let mut call_args: Vec<&str> = Vec::new();
+    if !target_param.is_empty() {
+        call_args.push("target");
+    }
+    call_args.extend(param_names.iter().map(String::as_str));
+    call_args.push(&default_timeout);
+
+    // Document what each stub calls, so that `cargo doc` on the crate describes the API: the
+    //  target process & attribute of the handler, its Rust signature, and the stub's arguments
+    let mut arg_docs = String::new();
+    if !target_param.is_empty() {
+        arg_docs.push_str(&format!(
+            "/// * `target` - Address of the `{}` process to call\n",
+            interface_name
+        ));
+    }
+    for param in &params {
+        if let Some((name, rust_type)) = param.split_once(": ") {
+            arg_docs.push_str(&format!("/// * `{}` - `{}`\n", name, rust_type));
+        }
+    }
+    let details_with = |arg_docs: &str| {
+        format!(
+            "///\n/// Calls the `#[{}]` handler `{}` of the `{}` process:\n/// `fn {}({}) -> {}`\n{}",
+            signature.attr_type,
+            snake_function_name,
+            interface_name,
+            snake_function_name,
+            params.join(", "),
+            return_type,
+            if arg_docs.is_empty() {
+                String::new()
+            } else {
+                format!("///\n/// # Arguments\n///\n{}", arg_docs)
+            }
         )
     };
+    let details = details_with(&arg_docs);
+    let timeout_details = details_with(&format!(
+        "{}/// * `timeout_secs` - Seconds to wait for the response\n",
+        arg_docs
+    ));
+    let stub_docs = |summary: &str, details: &str| format!("{}/// {}\n{}", docs, summary, details);
 
     // Generate function with implementation using send
     debug!("Generating standard RPC stub implementation");
-    Some(format!(
-        "/// Generated stub for `{}` {} RPC call\npub async fn {}({}) -> {} {{\n    let body = {};\n    let body = serde_json::to_vec(&body).unwrap();\n    let request = Request::to(target)\n        .body(body);\n    send::<{}>(request).await\n}}",
-        signature.function_name,
-        signature.attr_type,
+    let async_stub = format!(
+        "{}pub async fn {}({}) -> {} {{\n    {}_with_timeout({}).await\n}}\n\n{}pub async fn {}_with_timeout({}) -> {} {{\n    let body = {};\n    let body = serde_json::to_vec(&body).unwrap();\n    let request = {}::to(target)\n        .body(body)\n        .expects_response(timeout_secs);\n    {}::<{}>(request).await\n}}",
+        stub_docs(
+            &format!(
+                "Generated stub for `{}` {} RPC call, waiting up to [`crate::{}`] seconds for the response",
+                signature.function_name, signature.attr_type, TIMEOUT_CONST
+            ),
+            &details
+        ),
         full_function_name,
         all_params,
         wrapped_return_type,
+        full_function_name,
+        call_args.join(", "),
+        stub_docs(
+            &format!(
+                "Generated stub for `{}` {} RPC call, waiting up to `timeout_secs` seconds for the response",
+                signature.function_name, signature.attr_type
+            ),
+            &timeout_details
+        ),
+        full_function_name,
+        timeout_params,
+        wrapped_return_type,
         json_params,
+        REQUEST_ALIAS,
+        SEND_ALIAS,
         return_type
-    ))
+    );
+
+    // Generate the blocking variants using send_and_await_response
+    debug!("Generating blocking RPC stub implementation");
+    let blocking_stub = format!(
+        "{}pub fn {}_blocking({}) -> ::anyhow::Result<{}> {{\n    {}_blocking_with_timeout({})\n}}\n\n{}pub fn {}_blocking_with_timeout({}) -> ::anyhow::Result<{}> {{\n    let body = {};\n    let body = serde_json::to_vec(&body).unwrap();\n    let response = {}::to(target)\n        .body(body)\n        .send_and_await_response(timeout_secs)??;\n    Ok(serde_json::from_slice::<{}>(response.body())?)\n}}",
+        stub_docs(
+            &format!(
+                "Generated blocking stub for `{}` {} RPC call, waiting up to [`crate::{}`] seconds for\n///  the response: for processes without the hyperapp async executor",
+                signature.function_name, signature.attr_type, TIMEOUT_CONST
+            ),
+            &details
+        ),
+        full_function_name,
+        all_params,
+        return_type,
+        full_function_name,
+        call_args.join(", "),
+        stub_docs(
+            &format!(
+                "Generated blocking stub for `{}` {} RPC call, waiting up to `timeout_secs` seconds for\n///  the response: for processes without the hyperapp async executor",
+                signature.function_name, signature.attr_type
+            ),
+            &timeout_details
+        ),
+        full_function_name,
+        timeout_params,
+        return_type,
+        json_params,
+        REQUEST_ALIAS,
+        return_type
+    );
+    Some(format!("{}\n\n{}", async_stub, blocking_stub))
 }
 
-// Create the caller-utils crate with a single lib.rs file
-#[instrument(level = "trace", skip_all)]
-fn create_caller_utils_crate(api_dir: &Path, base_dir: &Path) -> Result<()> {
+// The derives of the generated types, and their ownership model (`None` being the default,
+//  `owning`), from the kit.toml configuration of wit-bindgen
+fn resolve_wit_bindgen_config(config: &WitBindgenConfig) -> Result<(Vec<&str>, Option<&str>)> {
+    let mut derives: Vec<&str> = DEFAULT_DERIVES.to_vec();
+    for derive in &config.additional_derives {
+        let derive = derive.trim();
+        if derive.is_empty() || syn::parse_str::<syn::Path>(derive).is_err() {
+            bail!(
+                "Invalid derive '{}' in `[caller-utils.wit-bindgen] additional-derives` of kit.toml: \
+                 expected a path like `PartialEq` or `serde::Serialize`",
+                derive
+            );
+        }
+        if !derives.contains(&derive) {
+            derives.push(derive);
+        }
+    }
+    let ownership = match config.ownership.as_deref() {
+        None | Some("owning") => None,
+        Some(ownership @ ("borrowing" | "borrowing-duplicate-if-necessary")) => Some(ownership),
+        Some(other) => bail!(
+            "Unknown `[caller-utils.wit-bindgen] ownership` '{}' in kit.toml: \
+             expected `owning`, `borrowing` or `borrowing-duplicate-if-necessary`",
+            other
+        ),
+    };
+    Ok((derives, ownership))
+}
+
+// Format the options of the crate's `wit_bindgen::generate!` after `path` & `world`, one per
+//  line, from its kit.toml configuration
+fn format_wit_bindgen_options(config: &WitBindgenConfig) -> Result<String> {
+    let (derives, ownership) = resolve_wit_bindgen_config(config)?;
+    let mut options = format!(
+        "    generate_unused_types: true,\n    additional_derives: [{}],\n",
+        derives.join(", ")
+    );
+    if let Some(ownership) = ownership {
+        options.push_str(&format!(
+            "    ownership: Borrowing {{ duplicate_if_necessary: {} }},\n",
+            ownership == "borrowing-duplicate-if-necessary"
+        ));
+    }
+    if config.async_support {
+        options.push_str("    async: true,\n");
+    }
+    Ok(options)
+}
+
+// Expand the bindings of `world` in `wit_dir` with the `wit-bindgen` CLI, as the crate's
+//  `wit_bindgen::generate!` would, into `src/generated/bindings.rs`
+fn write_expanded_bindings(
+    caller_utils_dir: &Path,
+    wit_dir: &Path,
+    world_name: &str,
+    config: &WitBindgenConfig,
+) -> Result<()> {
+    let (derives, ownership) = resolve_wit_bindgen_config(config)?;
+    let out_dir = caller_utils_dir.join("target").join("bindings");
+    if out_dir.exists() {
+        fs::remove_dir_all(&out_dir)?;
+    }
+    fs::create_dir_all(&out_dir)?;
+
+    let mut args = vec![
+        "rust".to_string(),
+        wit_dir.to_string_lossy().to_string(),
+        "--world".to_string(),
+        world_name.to_string(),
+        "--out-dir".to_string(),
+        out_dir.to_string_lossy().to_string(),
+        "--format".to_string(),
+        "--generate-unused-types".to_string(),
+    ];
+    for derive in derives {
+        args.push("--additional-derive-attribute".to_string());
+        args.push(derive.to_string());
+    }
+    if let Some(ownership) = ownership {
+        args.push("--ownership".to_string());
+        args.push(ownership.to_string());
+    }
+    if config.async_support {
+        args.push("--async".to_string());
+        args.push("all".to_string());
+    }
+    let output = std::process::Command::new("wit-bindgen")
+        .args(&args)
+        .output()
+        .map_err(|e| {
+            eyre!(
+                "Failed to run `wit-bindgen` to expand the caller-utils bindings: {}\n\
+                 Suggestion: Install the wit-bindgen CLI (`cargo install wit-bindgen-cli`), \
+                 or unset `[caller-utils] commit-generated` in kit.toml.",
+                e
+            )
+        })?;
+    if !output.status.success() {
+        bail!(
+            "`wit-bindgen rust` failed to expand the caller-utils bindings:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    // wit-bindgen writes a single `<world>.rs`
+    let bindings_file = fs::read_dir(&out_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| path.extension().and_then(|e| e.to_str()) == Some("rs"))
+        .ok_or_else(|| {
+            eyre!(
+                "`wit-bindgen rust` wrote no bindings to {}",
+                out_dir.display()
+            )
+        })?;
+    let bindings = fs::read_to_string(&bindings_file)?;
+
+    let generated_dir = caller_utils_dir.join("src").join(GENERATED_DIR_NAME);
+    fs::create_dir_all(&generated_dir)?;
+    write_if_changed(
+        &generated_dir.join("bindings.rs"),
+        &format!("{}{}", GENERATED_HEADER, bindings),
+    )?;
+    Ok(())
+}
+
+// Hash the inputs of the caller-utils crate: the WIT files of the API (regenerated from the
+//  hyperprocess impls each hyperapp build, so this changes whenever their API does), its
+//  Cargo.toml (metadata & dependencies), whether its generated code is committed, the
+//  wit-bindgen options, the stubs' default timeout, and the kit version (i.e., the generator
+//  itself)
+fn hash_caller_utils_inputs(
+    api_dir: &Path,
+    cargo_toml: &str,
+    commit_generated: bool,
+    wit_bindgen_options: &str,
+    timeout_secs: u64,
+) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(cargo_toml.as_bytes());
+    hasher.update([commit_generated as u8]);
+    hasher.update(timeout_secs.to_le_bytes());
+    hasher.update(wit_bindgen_options.as_bytes());
+    let mut wit_files: Vec<PathBuf> = WalkDir::new(api_dir)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.is_file()
+                && path.extension().map_or(false, |ext| ext == "wit")
+                && path
+                    .file_name()
+                    .map_or(false, |name| name != TYPES_WIT_FILE_NAME)
+        })
+        .collect();
+    wit_files.sort();
+    for path in wit_files {
+        hasher.update(path.file_name().unwrap().to_string_lossy().as_bytes());
+        hasher.update(fs::read(&path)?);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// The Cargo.toml of the caller-utils crate: its `[caller-utils]` metadata, and its
+//  dependencies, to which those of `[caller-utils.dependencies]` are added (or override)
+fn format_caller_utils_cargo_toml(
+    crate_name: &str,
+    version: &str,
+    hyperware_dep: &str,
+    config: &CallerUtilsConfig,
+) -> String {
+    let mut dependencies: Vec<(String, String)> = [
+        ("anyhow", r#""1.0""#),
+        ("process_macros", r#""0.1.0""#),
+        ("futures-util", r#""0.3""#),
+        ("serde", r#"{ version = "1.0", features = ["derive"] }"#),
+        ("serde_json", r#""1.0""#),
+        ("hyperware_process_lib", hyperware_dep),
+        ("once_cell", r#""1.20.2""#),
+        ("futures", r#""0.3""#),
+        ("uuid", r#"{ version = "1.0" }"#),
+        ("wit-bindgen", r#""0.41.0""#),
+    ]
+    .into_iter()
+    .map(|(name, dependency)| (name.to_string(), dependency.to_string()))
+    .collect();
+    for (name, dependency) in &config.dependencies {
+        let dependency = dependency.to_string();
+        match dependencies.iter_mut().find(|(n, _)| n == name) {
+            Some(existing) => existing.1 = dependency,
+            None => dependencies.push((name.clone(), dependency)),
+        }
+    }
+    let dependencies: String = dependencies
+        .iter()
+        .map(|(name, dependency)| format!("{} = {}\n", name, dependency))
+        .collect();
+
+    let mut package = format!(
+        "name = \"{}\"\nversion = \"{}\"\nedition = \"2021\"\n",
+        crate_name.replace("-", "_"),
+        version
+    );
+    if let Some(ref license) = config.license {
+        package.push_str(&format!("license = {}\n", Value::String(license.clone())));
+    }
+    if !config.publish {
+        package.push_str("publish = false\n");
+    }
+
+    format!(
+        r#"[package]
+{}
+[dependencies]
+{}
+[lib]
+crate-type = ["cdylib", "lib"]
+"#,
+        package, dependencies
+    )
+}
+
+// Write `contents` to `path` only if they differ from what is there, so that unchanged
+//  files keep their mtime and do not trigger recompilation of dependent crates
+fn write_if_changed(path: &Path, contents: &str) -> Result<bool> {
+    if fs::read_to_string(path).ok().as_deref() == Some(contents) {
+        return Ok(false);
+    }
+    fs::write(path, contents)?;
+    Ok(true)
+}
+
+/// Get the name of the caller-utils crate and its dir relative to the base directory:
+///  from `kit.toml` if set, else `<package>-caller-utils` in `target/`
+pub fn get_caller_utils_crate(
+    base_dir: &Path,
+    config: &CallerUtilsConfig,
+) -> Result<(String, String)> {
     // Extract package name from base directory
     let package_name = base_dir
         .file_name()
@@ -589,10 +1018,50 @@ fn create_caller_utils_crate(api_dir: &Path, base_dir: &Path) -> Result<()> {
         .ok_or_else(|| eyre!("Could not extract package name from base directory"))?;
 
     // Create crate name by prepending package name
-    let crate_name = format!("{}-caller-utils", package_name);
+    let crate_name = config
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("{}-caller-utils", package_name));
+
+    let crate_path = match config.path {
+        Some(ref path) => {
+            if path.is_absolute() {
+                bail!(
+                    "caller-utils path {} must be relative to the package dir",
+                    path.display()
+                );
+            }
+            path.to_str()
+                .ok_or_else(|| eyre!("caller-utils path {} is not valid UTF-8", path.display()))?
+                .trim_end_matches('/')
+                .to_string()
+        }
+        None => format!("target/{}", crate_name),
+    };
+    Ok((crate_name, crate_path))
+}
+
+// Get the workspace member for the caller-utils crate.
+// Replacing the last character with `?` forces cargo to interpret it as a glob, and so as
+//  optional, which allows building from scratch (i.e. before caller-utils has been generated)
+fn get_caller_utils_member(crate_path: &str) -> String {
+    let mut member = crate_path.to_string();
+    member.pop();
+    member.push('?');
+    member
+}
+
+// Create the caller-utils crate with a single lib.rs file
+#[instrument(level = "trace", skip_all)]
+fn create_caller_utils_crate(
+    api_dir: &Path,
+    base_dir: &Path,
+    config: &CallerUtilsConfig,
+) -> Result<()> {
+    let (crate_name, crate_path) = get_caller_utils_crate(base_dir, config)?;
 
     // Path to the new crate
-    let caller_utils_dir = base_dir.join("target").join(&crate_name);
+    let caller_utils_dir = base_dir.join(&crate_path);
     debug!(
         path = %caller_utils_dir.display(),
         crate_name = %crate_name,
@@ -605,37 +1074,35 @@ fn create_caller_utils_crate(api_dir: &Path, base_dir: &Path) -> Result<()> {
     debug!("Created project directory structure");
 
     // Get hyperware_process_lib dependency from the process's Cargo.toml
-    let hyperware_dep = get_hyperware_process_lib_dependency(base_dir)?;
+    let hyperware_dep =
+        get_hyperware_process_lib_dependency(base_dir, &get_caller_utils_member(&crate_path))?;
     debug!("Got hyperware_process_lib dependency: {}", hyperware_dep);
 
-    // Create Cargo.toml with updated dependencies
-    let cargo_toml = format!(
-        r#"[package]
-name = "{}"
-version = "0.1.0"
-edition = "2021"
-publish = false
-
-[dependencies]
-anyhow = "1.0"
-process_macros = "0.1.0"
-futures-util = "0.3"
-serde = {{ version = "1.0", features = ["derive"] }}
-serde_json = "1.0"
-hyperware_process_lib = {}
-once_cell = "1.20.2"
-futures = "0.3"
-uuid = {{ version = "1.0" }}
-wit-bindgen = "0.41.0"
-
-[lib]
-crate-type = ["cdylib", "lib"]
-"#,
-        crate_name.replace("-", "_"),
-        hyperware_dep
-    );
+    let version = config
+        .version
+        .as_deref()
+        .unwrap_or(DEFAULT_CALLER_UTILS_VERSION);
+    let cargo_toml = format_caller_utils_cargo_toml(&crate_name, version, &hyperware_dep, config);
+
+    // Skip regeneration if the API is unchanged, so dependent crates do not recompile
+    let wit_bindgen_options = format_wit_bindgen_options(&config.wit_bindgen)?;
+    let timeout_secs = config.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS);
+    let api_hash = hash_caller_utils_inputs(
+        api_dir,
+        &cargo_toml,
+        config.commit_generated,
+        &wit_bindgen_options,
+        timeout_secs,
+    )?;
+    let api_hash_path = caller_utils_dir.join("target").join(API_HASH_FILE_NAME);
+    if caller_utils_dir.join("src").join("lib.rs").exists()
+        && fs::read_to_string(&api_hash_path).ok().as_deref() == Some(api_hash.as_str())
+    {
+        debug!("API unchanged since {} was generated; skipping", crate_name);
+        return Ok(());
+    }
 
-    fs::write(caller_utils_dir.join("Cargo.toml"), cargo_toml)
+    write_if_changed(&caller_utils_dir.join("Cargo.toml"), &cargo_toml)
         .with_context(|| format!("Failed to write {} Cargo.toml", crate_name))?;
 
     debug!("Created Cargo.toml for {}", crate_name);
@@ -648,13 +1115,13 @@ crate-type = ["cdylib", "lib"]
     } else if world_names.len() == 1 {
         &world_names[0]
     } else {
-        let path = api_dir.join("types.wit");
+        let path = api_dir.join(TYPES_WIT_FILE_NAME);
         let mut content = "world types {\n".to_string();
         for world_name in world_names {
             content.push_str(&format!("    include {world_name};\n"));
         }
         content.push_str("}\n");
-        fs::write(&path, &content)?;
+        write_if_changed(&path, &content)?;
         "types"
     };
 
@@ -668,6 +1135,7 @@ crate-type = ["cdylib", "lib"]
     let mut wit_files = Vec::new();
     for entry in WalkDir::new(api_dir)
         .max_depth(1)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(Result::ok)
     {
@@ -699,8 +1167,8 @@ crate-type = ["cdylib", "lib"]
         "Found WIT interface files for stub generation"
     );
 
-    // Generate content for each module and collect types
-    let mut module_contents = HashMap::<String, String>::new();
+    // Generate content for each module and collect types; modules are written in name order
+    let mut module_contents = BTreeMap::<String, String>::new();
 
     for wit_file in &wit_files {
         // Extract the interface name from the file name
@@ -728,7 +1196,7 @@ crate-type = ["cdylib", "lib"]
 
                 // Add function implementations
                 for signature in &signatures {
-                    if let Some(function_impl) = generate_async_function(signature) {
+                    if let Some(function_impl) = generate_stubs(signature, &interface_name) {
                         mod_content.push_str(&function_impl);
                         mod_content.push_str("\n\n");
                     }
@@ -750,7 +1218,7 @@ crate-type = ["cdylib", "lib"]
 
     // Create import statements for each interface using "hyperware::process::{interface_name}::*"
     // Use a HashSet to track which interfaces we've already processed to avoid duplicates
-    let mut processed_interfaces = std::collections::HashSet::new();
+    let mut processed_interfaces = HashSet::new();
     let mut interface_use_statements = Vec::new();
 
     for interface_name in &interface_imports {
@@ -758,7 +1226,26 @@ crate-type = ["cdylib", "lib"]
         let snake_interface_name = to_snake_case(interface_name);
 
         // Only add the import if we haven't processed this interface yet
-        if processed_interfaces.insert(snake_interface_name.clone()) {
+        if !processed_interfaces.insert(snake_interface_name.clone()) {
+            continue;
+        }
+        // The types of a shared types interface (`types-<package>`) are re-exported by name:
+        //  the interfaces that `use` them re-export aliases of them too, which these shadow
+        let shared_types = interface_types
+            .get(interface_name.trim_start_matches('%'))
+            .filter(|types| interface_name.starts_with("types-") && !types.is_empty());
+        if let Some(types) = shared_types {
+            let mut type_names: Vec<String> = types
+                .iter()
+                .map(|t| to_pascal_case(t.trim_start_matches('%')))
+                .collect();
+            type_names.sort();
+            interface_use_statements.push(format!(
+                "pub use crate::hyperware::process::{}::{{{}}};",
+                snake_interface_name,
+                type_names.join(", ")
+            ));
+        } else {
             // Create wildcard import for this interface
             interface_use_statements.push(format!(
                 "pub use crate::hyperware::process::{}::*;",
@@ -767,23 +1254,115 @@ crate-type = ["cdylib", "lib"]
         }
     }
 
-    // Create single lib.rs with all modules inline
+    // Sync target/wit with the WIT files, rewriting only those that changed (and removing those
+    //  no longer in the API), so that unchanged files keep their mtime
+    let target_wit_dir = caller_utils_dir.join("target").join("wit");
+    debug!("Syncing directory: {}", target_wit_dir.display());
+    fs::create_dir_all(&target_wit_dir)?;
+
+    let mut wit_file_names = HashSet::new();
+    for entry in WalkDir::new(api_dir)
+        .max_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if path.is_file() && path.extension().map_or(false, |ext| ext == "wit") {
+            let file_name = path.file_name().unwrap();
+            let target_path = target_wit_dir.join(file_name);
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            if write_if_changed(&target_path, &content).with_context(|| {
+                format!(
+                    "Failed to copy {} to {}",
+                    path.display(),
+                    target_path.display()
+                )
+            })? {
+                debug!(
+                    "Copied {} to target/wit directory",
+                    file_name.to_string_lossy()
+                );
+            }
+            wit_file_names.insert(file_name.to_os_string());
+        }
+    }
+    for entry in fs::read_dir(&target_wit_dir)? {
+        let path = entry?.path();
+        if path.is_file() && !wit_file_names.contains(path.file_name().unwrap_or_default()) {
+            debug!("Removing stale {}", path.display());
+            fs::remove_file(&path)?;
+        }
+    }
+
+    // Create single lib.rs with all modules inline: with `commit-generated`, the stubs go in
+    //  src/generated/ next to the expanded bindings instead, and lib.rs includes both
     let mut lib_rs = String::new();
 
-    lib_rs.push_str("wit_bindgen::generate!({\n");
-    lib_rs.push_str("    path: \"target/wit\",\n");
-    lib_rs.push_str(&format!("    world: \"{}\",\n", world_name));
-    lib_rs.push_str("    generate_unused_types: true,\n");
-    lib_rs.push_str("    additional_derives: [serde::Deserialize, serde::Serialize, process_macros::SerdeJsonInto],\n");
-    lib_rs.push_str("});\n\n");
+    if config.commit_generated {
+        write_expanded_bindings(
+            &caller_utils_dir,
+            &target_wit_dir,
+            world_name,
+            &config.wit_bindgen,
+        )?;
+        lib_rs.push_str(GENERATED_HEADER);
+    } else {
+        lib_rs.push_str("wit_bindgen::generate!({\n");
+        lib_rs.push_str("    path: \"target/wit\",\n");
+        lib_rs.push_str(&format!("    world: \"{}\",\n", world_name));
+        lib_rs.push_str(&wit_bindgen_options);
+        lib_rs.push_str("});\n\n");
+    }
 
     lib_rs.push_str("/// Generated caller utilities for RPC function stubs\n\n");
 
-    // Add global imports
-    lib_rs.push_str("pub use hyperware_process_lib::hyperapp::AppSendError;\n");
-    lib_rs.push_str("pub use hyperware_process_lib::hyperapp::send;\n");
-    lib_rs.push_str("pub use hyperware_process_lib::{Address as ProcessAddress, Request};\n");
-    lib_rs.push_str("use serde_json::json;\n\n");
+    // Add global imports: only those the stubs need, aliased so they never
+    //  shadow the interface types re-exported below
+    let interface_type_names: HashSet<String> = interface_types
+        .values()
+        .flatten()
+        .map(|t| to_pascal_case(t))
+        .collect();
+    let has_stubs = module_contents.values().any(|c| !c.is_empty());
+    let needs_address = module_contents.values().any(|c| c.contains(ADDRESS_ALIAS));
+    let needs_map = module_contents.values().any(|c| c.contains(MAP_ALIAS));
+    if has_stubs && !interface_type_names.contains("AppSendError") {
+        // callers match on the stubs' error type, so re-export it unless it clashes
+        lib_rs.push_str("pub use hyperware_process_lib::hyperapp::AppSendError;\n");
+    }
+    if has_stubs {
+        lib_rs.push_str(&format!(
+            "use hyperware_process_lib::hyperapp::{{send as {}, AppSendError as {}}};\n",
+            SEND_ALIAS, APP_SEND_ERROR_ALIAS,
+        ));
+        lib_rs.push_str(&format!(
+            "use hyperware_process_lib::Request as {};\n",
+            REQUEST_ALIAS,
+        ));
+        lib_rs.push_str(&format!("use serde_json::json as {};\n", JSON_ALIAS));
+    }
+    if needs_address {
+        lib_rs.push_str(&format!(
+            "use hyperware_process_lib::Address as {};\n",
+            ADDRESS_ALIAS,
+        ));
+    }
+    if needs_map {
+        lib_rs.push_str(&format!(
+            "use std::collections::HashMap as {};\n",
+            MAP_ALIAS,
+        ));
+    }
+    lib_rs.push_str("\n");
+
+    if has_stubs {
+        lib_rs.push_str(&format!(
+            "/// Seconds the stubs wait for a response, unless given a `timeout_secs`\npub const {}: u64 = {};\n\n",
+            TIMEOUT_CONST, timeout_secs,
+        ));
+    }
 
     // Add interface use statements
     if !interface_use_statements.is_empty() {
@@ -806,48 +1385,34 @@ crate-type = ["cdylib", "lib"]
         lib_rs.push_str("}\n\n");
     }
 
+    if config.commit_generated {
+        let stubs_path = caller_utils_dir
+            .join("src")
+            .join(GENERATED_DIR_NAME)
+            .join("caller_utils.rs");
+        debug!("Writing generated code to {}", stubs_path.display());
+        write_if_changed(&stubs_path, &lib_rs)
+            .with_context(|| format!("Failed to write {}", stubs_path.display()))?;
+        lib_rs = format!(
+            "{GENERATED_HEADER}include!(\"{GENERATED_DIR_NAME}/bindings.rs\");\n\
+             include!(\"{GENERATED_DIR_NAME}/caller_utils.rs\");\n"
+        );
+    } else {
+        let generated_dir = caller_utils_dir.join("src").join(GENERATED_DIR_NAME);
+        if generated_dir.exists() {
+            debug!("Removing stale {}", generated_dir.display());
+            fs::remove_dir_all(&generated_dir)?;
+        }
+    }
+
     // Write lib.rs
     let lib_rs_path = caller_utils_dir.join("src").join("lib.rs");
     debug!("Writing generated code to {}", lib_rs_path.display());
 
-    fs::write(&lib_rs_path, lib_rs)
+    write_if_changed(&lib_rs_path, &lib_rs)
         .with_context(|| format!("Failed to write lib.rs: {}", lib_rs_path.display()))?;
 
-    // Create target/wit directory and copy all WIT files
-    let target_wit_dir = caller_utils_dir.join("target").join("wit");
-    debug!("Creating directory: {}", target_wit_dir.display());
-
-    // Remove the directory if it exists to ensure clean state
-    if target_wit_dir.exists() {
-        debug!("Removing existing target/wit directory");
-        fs::remove_dir_all(&target_wit_dir)?;
-    }
-
-    fs::create_dir_all(&target_wit_dir)?;
-
-    // Copy all WIT files to target/wit
-    for entry in WalkDir::new(api_dir)
-        .max_depth(1)
-        .into_iter()
-        .filter_map(Result::ok)
-    {
-        let path = entry.path();
-        if path.is_file() && path.extension().map_or(false, |ext| ext == "wit") {
-            let file_name = path.file_name().unwrap();
-            let target_path = target_wit_dir.join(file_name);
-            fs::copy(path, &target_path).with_context(|| {
-                format!(
-                    "Failed to copy {} to {}",
-                    path.display(),
-                    target_path.display()
-                )
-            })?;
-            debug!(
-                "Copied {} to target/wit directory",
-                file_name.to_string_lossy()
-            );
-        }
-    }
+    fs::write(&api_hash_path, api_hash)?;
 
     Ok(())
 }
@@ -908,7 +1473,10 @@ fn read_cargo_toml(path: &Path) -> Result<Value> {
 
 // Get hyperware_process_lib dependency from the process Cargo.toml files
 #[instrument(level = "trace", skip_all)]
-fn get_hyperware_process_lib_dependency(base_dir: &Path) -> Result<String> {
+fn get_hyperware_process_lib_dependency(
+    base_dir: &Path,
+    caller_utils_member: &str,
+) -> Result<String> {
     const DEFAULT_DEP: &str =
         r#"{ git = "https://github.com/hyperware-ai/hyperapp-macro", rev = "4c944b2" }"#;
 
@@ -925,7 +1493,7 @@ fn get_hyperware_process_lib_dependency(base_dir: &Path) -> Result<String> {
 
     for member in members.iter().filter_map(|m| m.as_str()) {
         // Skip generated directories
-        if member.starts_with("target/") {
+        if member.starts_with("target/") || member == caller_utils_member {
             continue;
         }
 
@@ -986,7 +1554,7 @@ fn get_hyperware_process_lib_dependency(base_dir: &Path) -> Result<String> {
 
 // Update workspace Cargo.toml to include the caller-utils crate
 #[instrument(level = "trace", skip_all)]
-fn update_workspace_cargo_toml(base_dir: &Path, crate_name: &str) -> Result<()> {
+fn update_workspace_cargo_toml(base_dir: &Path, crate_path: &str) -> Result<()> {
     let workspace_cargo_toml = base_dir.join("Cargo.toml");
     debug!(
         path = %workspace_cargo_toml.display(),
@@ -1018,9 +1586,7 @@ fn update_workspace_cargo_toml(base_dir: &Path, crate_name: &str) -> Result<()>
         if let Some(members) = workspace.get_mut("members") {
             if let Some(members_array) = members.as_array_mut() {
                 // Check if caller-utils is already in the members list
-                // Using a `?` forces cargo to interpret it as optional, which allows building from scratch (i.e. before caller-utils has been generated)
-                let crate_name_without_s = crate_name.trim_end_matches('s');
-                let target_path = format!("target/{}?", crate_name_without_s);
+                let target_path = get_caller_utils_member(crate_path);
                 let caller_utils_exists = members_array
                     .iter()
                     .any(|m| m.as_str().map_or(false, |s| s == target_path));
@@ -1055,16 +1621,22 @@ fn update_workspace_cargo_toml(base_dir: &Path, crate_name: &str) -> Result<()>
 
 // Add caller-utils as a dependency to hyperware:process crates
 #[instrument(level = "trace", skip_all)]
-pub fn add_caller_utils_to_projects(projects: &[PathBuf], base_dir: &Path) -> Result<()> {
-    // Extract package name from base directory
-    let package_name = base_dir
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| eyre!("Could not extract package name from base directory"))?;
-
-    // Create crate name by prepending package name
-    let crate_name = format!("{}-caller-utils", package_name);
+pub fn add_caller_utils_to_projects(
+    projects: &[PathBuf],
+    base_dir: &Path,
+    config: &CallerUtilsConfig,
+) -> Result<()> {
+    let (crate_name, crate_path) = get_caller_utils_crate(base_dir, config)?;
+    let crate_dependency_path = format!("../{}", crate_path);
     let crate_name_underscore = crate_name.replace("-", "_");
+    // A publishable crate is depended on by version too: cargo requires it of the dependencies
+    //  of published crates, and `[patch]`es only apply to dependencies on a registry version
+    let crate_dependency_version = config.publish.then(|| {
+        config
+            .version
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CALLER_UTILS_VERSION.to_string())
+    });
     for project_path in projects {
         let cargo_toml_path = project_path.join("Cargo.toml");
         debug!(
@@ -1087,26 +1659,39 @@ pub fn add_caller_utils_to_projects(projects: &[PathBuf], base_dir: &Path) -> Re
             )
         })?;
 
-        // Add caller-utils to dependencies if not already present
+        // Add caller-utils to dependencies if not already present, else point it at the crate
         if let Some(dependencies) = parsed_toml.get_mut("dependencies") {
             if let Some(deps_table) = dependencies.as_table_mut() {
-                if !deps_table.contains_key(&crate_name_underscore) {
+                if let Some(dep_table) = deps_table
+                    .get_mut(&crate_name_underscore)
+                    .and_then(|d| d.as_table_mut())
+                {
+                    dep_table.insert(
+                        "path".to_string(),
+                        Value::String(crate_dependency_path.clone()),
+                    );
+                    if let Some(ref version) = crate_dependency_version {
+                        dep_table.insert("version".to_string(), Value::String(version.clone()));
+                    }
+                    debug!(project = ?project_path.file_name().unwrap_or_default(), "{} dependency already exists", crate_name_underscore);
+                } else if !deps_table.contains_key(&crate_name_underscore) {
                     deps_table.insert(
                         crate_name_underscore.clone(),
                         Value::Table({
                             let mut t = toml::map::Map::new();
                             t.insert(
                                 "path".to_string(),
-                                Value::String(format!("../target/{}", crate_name)),
+                                Value::String(crate_dependency_path.clone()),
                             );
+                            if let Some(ref version) = crate_dependency_version {
+                                t.insert("version".to_string(), Value::String(version.clone()));
+                            }
                             t.insert("optional".to_string(), Value::Boolean(true));
                             t
                         }),
                     );
 
                     debug!(project = ?project_path.file_name().unwrap_or_default(), "Successfully added {} dependency", crate_name_underscore);
-                } else {
-                    debug!(project = ?project_path.file_name().unwrap_or_default(), "{} dependency already exists", crate_name_underscore);
                 }
             }
         }
@@ -1153,7 +1738,8 @@ pub fn add_caller_utils_to_projects(projects: &[PathBuf], base_dir: &Path) -> Re
             )
         })?;
 
-        fs::write(&cargo_toml_path, updated_content).with_context(|| {
+        // only touch the Cargo.toml if it changed, else the project always recompiles
+        write_if_changed(&cargo_toml_path, &updated_content).with_context(|| {
             format!(
                 "Failed to write updated project Cargo.toml: {}",
                 cargo_toml_path.display()
@@ -1166,21 +1752,18 @@ pub fn add_caller_utils_to_projects(projects: &[PathBuf], base_dir: &Path) -> Re
 
 // Create caller-utils crate and integrate with the workspace
 #[instrument(level = "trace", skip_all)]
-pub fn create_caller_utils(base_dir: &Path, api_dir: &Path) -> Result<()> {
-    // Extract package name from base directory
-    let package_name = base_dir
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| eyre!("Could not extract package name from base directory"))?;
-
-    // Create crate name by prepending package name
-    let crate_name = format!("{}-caller-utils", package_name);
+pub fn create_caller_utils(
+    base_dir: &Path,
+    api_dir: &Path,
+    config: &CallerUtilsConfig,
+) -> Result<()> {
+    let (crate_name, crate_path) = get_caller_utils_crate(base_dir, config)?;
 
     // Step 1: Create the caller-utils crate
-    create_caller_utils_crate(api_dir, base_dir)?;
+    create_caller_utils_crate(api_dir, base_dir, config)?;
 
     // Step 2: Update workspace Cargo.toml
-    update_workspace_cargo_toml(base_dir, &crate_name)?;
+    update_workspace_cargo_toml(base_dir, &crate_path)?;
 
     info!("Successfully created {} and copied the imports", crate_name);
     Ok(())
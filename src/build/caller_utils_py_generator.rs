@@ -0,0 +1,623 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use tracing::{debug, info, instrument};
+
+use super::caller_utils_ts_generator::{
+    case_wire_name, find_hyperapp_wit_files, parse_args_comment, parse_returns_comment,
+    parse_tuple_types, parse_wit_file, split_top_level, to_pascal_case, to_snake_case,
+    SignatureStruct, WitEnum, WitRecord, WitVariant,
+};
+
+const PYTHON_KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// The runtime of the generated client: its error type & the (de)serialization of values
+const PY_HEADER: &str = r#"from __future__ import annotations
+
+import enum
+from dataclasses import dataclass
+from typing import Any, Dict, List, Optional, Tuple, Union
+
+import requests
+
+
+class ApiError(Exception):
+    """An HTTP request failed, or the API returned an error (in `details`)"""
+
+    def __init__(self, message: str, details: Any = None):
+        super().__init__(message)
+        self.details = details
+
+
+def _to_json(value: Any) -> Any:
+    if hasattr(value, "to_json"):
+        return value.to_json()
+    if isinstance(value, enum.Enum):
+        return value.value
+    if isinstance(value, (list, tuple)):
+        return [_to_json(v) for v in value]
+    if isinstance(value, dict):
+        return {_to_json(k): _to_json(v) for k, v in value.items()}
+    return value
+
+"#;
+
+/// The client, sending requests to the node & unwrapping the Result-style responses
+const PY_CLIENT: &str = r#"class Client:
+    """Client for the HTTP API at `base_url`, the URL of a process on a node,
+    e.g. `https://my-node.example.com/my-process:my-package:publisher.os`
+    """
+
+    def __init__(self, base_url: str, session: Optional[requests.Session] = None):
+        self.base_url = base_url.rstrip("/")
+        self.session = session or requests.Session()
+{namespaces}
+    def request(self, path: str, method: str, data: Any) -> Any:
+        url = f"{self.base_url}{path}" if path.startswith("/") else f"{self.base_url}/{path}"
+        if method in ("GET", "HEAD"):
+            response = self.session.request(method, url)
+        else:
+            response = self.session.request(method, url, json=_to_json(data))
+        if not response.ok:
+            raise ApiError(f"HTTP request failed with status: {response.status_code}", response.text)
+        body = response.json()
+        if isinstance(body, dict) and "Err" in body:
+            raise ApiError("API returned an error", body["Err"])
+        if isinstance(body, dict) and body.get("Ok") is not None:
+            return body["Ok"]
+        return body
+"#;
+
+// A Python identifier for a WIT identifier: snake_case, suffixed by `_` if a keyword
+fn to_python_name(name: &str) -> String {
+    let name = to_snake_case(name);
+    if PYTHON_KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+// Convert a PascalCase hyperapp name to snake_case, for the attribute of its API on `Client`
+pub(super) fn pascal_to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+// A Python docstring (ending in a newline) from doc comments, indented by `indent`
+fn generate_docstring(docs: &[String], indent: &str) -> String {
+    match docs {
+        [] => String::new(),
+        [doc] => format!("{}\"\"\"{}\"\"\"\n", indent, doc),
+        docs => {
+            let mut docstring = format!("{}\"\"\"{}\n", indent, docs[0]);
+            for doc in &docs[1..] {
+                if doc.is_empty() {
+                    docstring.push('\n');
+                } else {
+                    docstring.push_str(&format!("{}{}\n", indent, doc));
+                }
+            }
+            docstring.push_str(&format!("{}\"\"\"\n", indent));
+            docstring
+        }
+    }
+}
+
+// Convert a WIT type (including the richer types of signature comments) to a Python type hint
+fn wit_type_to_python(wit_type: &str) -> String {
+    match wit_type {
+        "s8" | "u8" | "s16" | "u16" | "s32" | "u32" | "s64" | "u64" | "s128" | "u128" => {
+            "int".to_string()
+        }
+        "f32" | "f64" => "float".to_string(),
+        "string" | "address" => "str".to_string(),
+        "bool" => "bool".to_string(),
+        "_" => "None".to_string(),
+        t if t.starts_with("list<") => format!("List[{}]", wit_type_to_python(&t[5..t.len() - 1])),
+        t if t.starts_with("map<") => {
+            let types: Vec<String> = split_top_level(&t[4..t.len() - 1])
+                .iter()
+                .map(|t| wit_type_to_python(t))
+                .collect();
+            format!("Dict[{}]", types.join(", "))
+        }
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).first() {
+            Some(inner_type) => format!("List[{}]", wit_type_to_python(inner_type)),
+            None => "List[Any]".to_string(),
+        },
+        t if t.starts_with("option<") => {
+            format!("Optional[{}]", wit_type_to_python(&t[7..t.len() - 1]))
+        }
+        // `{"Ok": ...}` or `{"Err": ...}`, where not unwrapped by the client
+        t if t.starts_with("result<") => "Dict[str, Any]".to_string(),
+        t if t.starts_with("tuple<") => {
+            let types: Vec<String> = parse_tuple_types(t)
+                .iter()
+                .map(|t| wit_type_to_python(t))
+                .collect();
+            if types.is_empty() {
+                "Tuple[()]".to_string()
+            } else {
+                format!("Tuple[{}]", types.join(", "))
+            }
+        }
+        "value" => "Any".to_string(),
+        _ => to_pascal_case(wit_type),
+    }
+}
+
+// A Python expression converting the JSON value `expr` to the Python type of a WIT type;
+//  `expr` itself if no conversion is needed. `depth` names the variables of comprehensions
+fn wit_type_from_json(
+    wit_type: &str,
+    expr: &str,
+    aliases: &BTreeMap<String, String>,
+    depth: usize,
+) -> String {
+    let var = format!("v{}", depth);
+    match wit_type {
+        "s8" | "u8" | "s16" | "u16" | "s32" | "u32" | "s64" | "u64" | "s128" | "u128" | "f32"
+        | "f64" | "string" | "address" | "bool" | "_" | "value" => expr.to_string(),
+        t if t.starts_with("list<") || t.starts_with("array<") => {
+            let inner_type = split_top_level(&t[t.find('<').unwrap() + 1..t.len() - 1])
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            let converted = wit_type_from_json(&inner_type, &var, aliases, depth + 1);
+            if converted == var {
+                expr.to_string()
+            } else {
+                format!("[{} for {} in {}]", converted, var, expr)
+            }
+        }
+        t if t.starts_with("map<") => match split_top_level(&t[4..t.len() - 1]).as_slice() {
+            [key_type, value_type] => {
+                let key = format!("k{}", depth);
+                // JSON object keys are strings, even those of numbers
+                let converted_key = if wit_type_to_python(key_type) == "int" {
+                    format!("int({})", key)
+                } else {
+                    wit_type_from_json(key_type, &key, aliases, depth + 1)
+                };
+                let converted_value = wit_type_from_json(value_type, &var, aliases, depth + 1);
+                if converted_key == key && converted_value == var {
+                    expr.to_string()
+                } else {
+                    format!(
+                        "{{{}: {} for {}, {} in {}.items()}}",
+                        converted_key, converted_value, key, var, expr
+                    )
+                }
+            }
+            _ => expr.to_string(),
+        },
+        t if t.starts_with("option<") => {
+            let converted = wit_type_from_json(&t[7..t.len() - 1], expr, aliases, depth);
+            if converted == expr {
+                expr.to_string()
+            } else {
+                format!("None if {} is None else {}", expr, converted)
+            }
+        }
+        t if t.starts_with("result<") => expr.to_string(),
+        t if t.starts_with("tuple<") => {
+            let converted: Vec<String> = parse_tuple_types(t)
+                .iter()
+                .enumerate()
+                .map(|(i, t)| wit_type_from_json(t, &format!("{}[{}]", expr, i), aliases, depth))
+                .collect();
+            format!("({},)", converted.join(", "))
+        }
+        t => match aliases.get(t) {
+            Some(rhs) => wit_type_from_json(rhs, expr, aliases, depth),
+            None => format!("{}.from_json({})", to_pascal_case(t), expr),
+        },
+    }
+}
+
+// Generate a dataclass for a WIT record, (de)serialized under the JSON names of its fields
+fn generate_python_record(record: &WitRecord, aliases: &BTreeMap<String, String>) -> String {
+    let class_name = to_pascal_case(&record.name);
+    let mut fields = String::new();
+    let mut from_json = String::new();
+    let mut to_json = String::new();
+    for field in &record.fields {
+        let field_name = to_python_name(&field.name);
+        let json_name = field
+            .wire_name
+            .clone()
+            .unwrap_or_else(|| to_snake_case(&field.name));
+        fields.push_str(&format!(
+            "    {}: {}\n",
+            field_name,
+            wit_type_to_python(&field.wit_type)
+        ));
+        // Optional fields may be left out of the JSON
+        let value = if field.wit_type.starts_with("option<") {
+            format!("value.get({:?})", json_name)
+        } else {
+            format!("value[{:?}]", json_name)
+        };
+        from_json.push_str(&format!(
+            "            {}={},\n",
+            field_name,
+            wit_type_from_json(&field.wit_type, &value, aliases, 0)
+        ));
+        to_json.push_str(&format!(
+            "            {:?}: _to_json(self.{}),\n",
+            json_name, field_name
+        ));
+    }
+
+    format!(
+        "@dataclass\nclass {0}:\n{1}{2}\n    @classmethod\n    def from_json(cls, value: Dict[str, Any]) -> {0}:\n        return cls(\n{3}        )\n\n    def to_json(self) -> Dict[str, Any]:\n        return {{\n{4}        }}\n",
+        class_name,
+        generate_docstring(&record.docs, "    "),
+        fields,
+        from_json,
+        to_json
+    )
+}
+
+// Generate an `enum.Enum` of the JSON names of the cases of a WIT enum (or variant without data)
+fn generate_python_enum(name: &str, cases: &[String], docs: &[String]) -> String {
+    let class_name = to_pascal_case(name);
+    let members: String = cases
+        .iter()
+        .map(|case| format!("    {} = {:?}\n", to_python_name(case).to_uppercase(), case))
+        .collect();
+    format!(
+        "class {0}(str, enum.Enum):\n{1}{2}\n    @classmethod\n    def from_json(cls, value: str) -> {0}:\n        return cls(value)\n",
+        class_name,
+        generate_docstring(docs, "    "),
+        members
+    )
+}
+
+// Generate a dataclass for a WIT variant with data: its case's JSON name & that case's data
+fn generate_python_variant(variant: &WitVariant, aliases: &BTreeMap<String, String>) -> String {
+    let class_name = to_pascal_case(&variant.name);
+    let mut docs = variant.docs.clone();
+    let cases: Vec<String> = variant
+        .cases
+        .iter()
+        .map(|case| match case.data_type {
+            Some(ref data_type) if data_type.trim().starts_with("record") => {
+                format!("`{}` (Dict[str, Any])", case_wire_name(case))
+            }
+            Some(ref data_type) => format!(
+                "`{}` ({})",
+                case_wire_name(case),
+                wit_type_to_python(data_type.trim())
+            ),
+            None => format!("`{}`", case_wire_name(case)),
+        })
+        .collect();
+    docs.push(format!("One of: {}", cases.join(", ")));
+
+    let mut from_json = String::new();
+    for case in &variant.cases {
+        let Some(ref data_type) = case.data_type else {
+            continue;
+        };
+        // Inline records are left as dicts
+        if data_type.trim().starts_with("record") {
+            continue;
+        }
+        let converted = wit_type_from_json(data_type.trim(), "data", aliases, 0);
+        if converted != "data" {
+            from_json.push_str(&format!(
+                "        if tag == {:?}:\n            return cls(tag, {})\n",
+                case_wire_name(case),
+                converted
+            ));
+        }
+    }
+
+    format!(
+        "@dataclass\nclass {0}:\n{1}    tag: str\n    value: Any = None\n\n    @classmethod\n    def from_json(cls, value: Any) -> {0}:\n        if isinstance(value, str):\n            return cls(value)\n        tag, data = next(iter(value.items()))\n{2}        return cls(tag, data)\n\n    def to_json(self) -> Any:\n        return {{self.tag: _to_json(self.value)}}\n",
+        class_name,
+        generate_docstring(&docs, "    "),
+        from_json
+    )
+}
+
+// Generate a method of a hyperapp's API class calling an HTTP handler
+fn generate_python_method(
+    signature: &SignatureStruct,
+    aliases: &BTreeMap<String, String>,
+) -> String {
+    let function_name = to_python_name(&signature.function_name);
+    let pascal_function_name = to_pascal_case(&signature.function_name);
+    let args = signature
+        .args_comment
+        .as_deref()
+        .map(parse_args_comment)
+        .unwrap_or_default();
+
+    let mut params = Vec::new();
+    let mut param_names = Vec::new();
+    let mut return_type = "None".to_string();
+    let mut converted_return = "response".to_string();
+    for field in &signature.fields {
+        if field.name == "arg-types" {
+            for (i, wit_type) in parse_tuple_types(&field.wit_type).iter().enumerate() {
+                // The args comment has the richer types (maps, arrays, 128-bit integers)
+                let (param_name, wit_type) = match args.get(i) {
+                    Some((name, arg_type)) => (to_python_name(name), arg_type.as_str()),
+                    None => (format!("arg{}", i), wit_type.as_str()),
+                };
+                params.push(format!("{}: {}", param_name, wit_type_to_python(wit_type)));
+                param_names.push(param_name);
+            }
+        } else if field.name == "returning" {
+            let wit_type = signature
+                .returns_comment
+                .as_deref()
+                .and_then(parse_returns_comment)
+                .unwrap_or_else(|| field.wit_type.clone());
+            // The client unwraps the `Ok` of Result-style responses
+            let ok_wit_type = wit_type
+                .strip_prefix("result<")
+                .and_then(|t| t.strip_suffix('>'))
+                .and_then(|t| split_top_level(t).into_iter().next())
+                .unwrap_or(wit_type);
+            return_type = wit_type_to_python(&ok_wit_type);
+            converted_return = wit_type_from_json(&ok_wit_type, "response", aliases, 0);
+        }
+    }
+
+    let data = match param_names.as_slice() {
+        [] => "None".to_string(),
+        [param_name] => param_name.clone(),
+        param_names => format!("[{}]", param_names.join(", ")),
+    };
+    let mut method_params = vec!["self".to_string()];
+    method_params.extend(params);
+
+    format!(
+        "    def {}({}) -> {}:\n{}        response = self._client.request({:?}, {:?}, {{{:?}: {}}})\n        return {}\n",
+        function_name,
+        method_params.join(", "),
+        return_type,
+        generate_docstring(&signature.docs, "        "),
+        signature.http_path.as_deref().unwrap_or("/api"),
+        signature.http_method.as_deref().unwrap_or("POST"),
+        pascal_function_name,
+        data,
+        converted_return
+    )
+}
+
+/// Create a typed Python module at `py_client_path` for calling the HTTP handlers of the
+///  package's hyperapp processes, as described by the WIT files in `api_dir`: a dataclass
+///  (or `enum.Enum`) per WIT type, and a `Client` with an attribute per process whose methods
+///  call its handlers with `requests`
+#[instrument(level = "trace", skip_all)]
+pub fn create_python_client(
+    api_dir: &Path,
+    py_client_path: &Path,
+    package_name: &str,
+) -> Result<()> {
+    let mut aliases = BTreeMap::new();
+    let mut types = Vec::new();
+    let mut type_names = BTreeSet::new();
+    let mut namespaces: Vec<(String, Vec<SignatureStruct>)> = Vec::new();
+    let mut enums: Vec<WitEnum> = Vec::new();
+    let mut records: Vec<WitRecord> = Vec::new();
+    let mut variants: Vec<WitVariant> = Vec::new();
+
+    for (hyperapp_name, wit_files) in find_hyperapp_wit_files(api_dir) {
+        let mut signatures = Vec::new();
+        for wit_file in wit_files {
+            let wit_types = parse_wit_file(&wit_file)?;
+            signatures.extend(
+                wit_types
+                    .signatures
+                    .into_iter()
+                    .filter(|signature| signature.attr_type == "http"),
+            );
+            // Types `use`d by several interfaces are defined once
+            aliases.extend(
+                wit_types
+                    .aliases
+                    .into_iter()
+                    .filter(|(name, _)| name != "value"),
+            );
+            enums.extend(
+                wit_types
+                    .enums
+                    .into_iter()
+                    .filter(|e| type_names.insert(e.name.clone())),
+            );
+            records.extend(
+                wit_types
+                    .records
+                    .into_iter()
+                    .filter(|r| type_names.insert(r.name.clone())),
+            );
+            variants.extend(
+                wit_types
+                    .variants
+                    .into_iter()
+                    .filter(|v| type_names.insert(v.name.clone())),
+            );
+        }
+        if !signatures.is_empty() {
+            namespaces.push((hyperapp_name, signatures));
+        }
+    }
+    if namespaces.is_empty() {
+        bail!(
+            "No HTTP handlers found in the WIT files of {}: nothing to generate a Python client for.\n\
+             \n\
+             Suggestion: Add #[http] handlers to the hyperapp processes and build with --hyperapp.",
+            api_dir.display()
+        );
+    }
+    debug!(namespaces = namespaces.len(), "Generating Python client");
+
+    for (name, rhs) in &aliases {
+        types.push(format!(
+            "{} = {}\n",
+            to_pascal_case(name),
+            wit_type_to_python(rhs)
+        ));
+    }
+    for enum_def in &enums {
+        let cases: Vec<String> = enum_def.cases.iter().map(case_wire_name).collect();
+        types.push(generate_python_enum(&enum_def.name, &cases, &enum_def.docs));
+    }
+    for record in &records {
+        types.push(generate_python_record(record, &aliases));
+    }
+    for variant in &variants {
+        if variant.cases.iter().any(|case| case.data_type.is_some()) {
+            types.push(generate_python_variant(variant, &aliases));
+        } else {
+            let cases: Vec<String> = variant.cases.iter().map(case_wire_name).collect();
+            types.push(generate_python_enum(&variant.name, &cases, &variant.docs));
+        }
+    }
+
+    let mut py_content = format!(
+        "\"\"\"Typed client for the HTTP API of {}, generated from its WIT by `kit build --py-client`: do not edit\"\"\"\n\n{}",
+        package_name, PY_HEADER
+    );
+    for type_def in types {
+        py_content.push_str(&format!("\n{}\n", type_def));
+    }
+
+    let mut client_namespaces = String::new();
+    for (hyperapp_name, signatures) in &namespaces {
+        py_content.push_str(&format!(
+            "\nclass {0}Api:\n    \"\"\"HTTP handlers of {0}\"\"\"\n\n    def __init__(self, client: Client):\n        self._client = client\n",
+            hyperapp_name
+        ));
+        for signature in signatures {
+            py_content.push_str(&format!(
+                "\n{}",
+                generate_python_method(signature, &aliases)
+            ));
+        }
+        py_content.push('\n');
+        client_namespaces.push_str(&format!(
+            "        self.{} = {}Api(self)\n",
+            to_python_name(&pascal_to_snake_case(hyperapp_name)),
+            hyperapp_name
+        ));
+    }
+    py_content.push_str(&format!(
+        "\n{}",
+        PY_CLIENT.replace("{namespaces}", &client_namespaces)
+    ));
+
+    if let Some(parent) = py_client_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(py_client_path, py_content).with_context(|| {
+        format!(
+            "Failed to write Python client: {}",
+            py_client_path.display()
+        )
+    })?;
+
+    info!(
+        "Successfully created Python client at {}",
+        py_client_path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_python_client_mirrors_http_api() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface my-app {
+    /// An item
+    record item {
+        id: u64,
+        // serde: itemKind
+        kind: kind,
+        parent: option<item>
+    }
+
+    enum kind {
+        small,
+        large
+    }
+
+    /// Get an item
+    // Function signature for: get-item (http)
+    // HTTP: POST /api/item
+    // args: (id: u64, from: string)
+    record get-item-signature-http {
+        target: string,
+        arg-types: tuple<u64, string>,
+        returning: result<item, string>
+    }
+
+    // Function signature for: get-counts (http)
+    // HTTP: GET /api/counts
+    // returns: map<u32, list<item>>
+    record get-counts-signature-http {
+        target: string,
+        returning: list<tuple<u32, list<item>>>
+    }
+}
+"#;
+        fs::write(api_dir.join("my-app.wit"), wit_content).unwrap();
+
+        let py_client_path = temp_dir.path().join("client.py");
+        create_python_client(&api_dir, &py_client_path, "my-package").unwrap();
+        let py_content = fs::read_to_string(&py_client_path).unwrap();
+
+        assert!(
+            py_content.contains(
+                "@dataclass\nclass Item:\n    \"\"\"An item\"\"\"\n    id: int\n    kind: Kind\n    parent: Optional[Item]\n"
+            ),
+            "Records should become dataclasses, got:\n{}",
+            py_content
+        );
+        assert!(py_content.contains("            kind=Kind.from_json(value[\"itemKind\"]),\n"));
+        assert!(py_content.contains("            \"itemKind\": _to_json(self.kind),\n"));
+        assert!(py_content.contains("class Kind(str, enum.Enum):\n    SMALL = \"Small\"\n"));
+        assert!(
+            py_content.contains(
+                "    def get_item(self, id: int, from_: str) -> Item:\n        \"\"\"Get an item\"\"\"\n        response = self._client.request(\"/api/item\", \"POST\", {\"GetItem\": [id, from_]})\n        return Item.from_json(response)\n"
+            ),
+            "Handlers should become typed methods, got:\n{}",
+            py_content
+        );
+        assert!(py_content.contains(
+            "    def get_counts(self) -> Dict[int, List[Item]]:\n        response = self._client.request(\"/api/counts\", \"GET\", {\"GetCounts\": None})\n        return {int(k0): [Item.from_json(v1) for v1 in v0] for k0, v0 in response.items()}\n"
+        ));
+        assert!(py_content.contains("        self.my_app = MyAppApi(self)\n"));
+    }
+}
@@ -1,12 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use color_eyre::{eyre::WrapErr, Result};
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
 use tracing::{debug, info, instrument, warn};
 
 use walkdir::WalkDir;
 
+use super::kit_toml::TypeScriptConfig;
+
 // Strip % prefix from WIT identifiers (used to escape keywords)
 fn strip_wit_escape(s: &str) -> &str {
     s.strip_prefix('%').unwrap_or(s)
@@ -101,6 +106,9 @@ fn wit_type_to_typescript(wit_type: &str) -> String {
     match wit_type {
         // Integer types - all become number in TypeScript
         "s8" | "u8" | "s16" | "u16" | "s32" | "u32" | "s64" | "u64" => "number".to_string(),
+        // 128-bit integers, from signature comments: strings in WIT but JSON numbers on the
+        //  wire, revived as bigints only when too large for a number (see `JSON_HELPERS`)
+        "s128" | "u128" => "(bigint | number)".to_string(),
         // Floating point types
         "f32" | "f64" => "number".to_string(),
         // Other primitive types
@@ -119,6 +127,20 @@ fn wit_type_to_typescript(wit_type: &str) -> String {
                 format!("{}[]", wit_type_to_typescript(inner_type))
             }
         }
+        // Rust maps, from signature comments: lowered to `list<tuple<k, v>>` in WIT
+        //  but (de)serialized as JSON objects by the process
+        t if t.starts_with("map<") => {
+            let ts_types: Vec<String> = split_top_level(&t[4..t.len() - 1])
+                .iter()
+                .map(|t| wit_type_to_typescript(t))
+                .collect();
+            format!("Record<{}>", ts_types.join(", "))
+        }
+        // Rust arrays, from signature comments: lowered to `list<t>` in WIT
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).first() {
+            Some(inner_type) => format!("{}[]", wit_type_to_typescript(inner_type)),
+            None => "unknown[]".to_string(),
+        },
         t if t.starts_with("option<") => {
             let inner_type = &t[7..t.len() - 1];
             format!("{} | null", wit_type_to_typescript(inner_type))
@@ -231,32 +253,27 @@ fn extract_result_ok_type(wit_type: &str) -> Option<String> {
 
 // Structure to represent a field in a WIT signature struct
 #[derive(Debug)]
-struct SignatureField {
-    name: String,
-    wit_type: String,
+pub(super) struct SignatureField {
+    pub(super) name: String,
+    pub(super) wit_type: String,
+    pub(super) docs: Vec<String>,
+    pub(super) wire_name: Option<String>, // Parsed from a // serde: <wire name> comment above the field
 }
 
-/// Parse a tuple type string like "tuple<u64, bool>" into its element types
-fn parse_tuple_types(tuple_type: &str) -> Vec<String> {
-    if !tuple_type.starts_with("tuple<") || !tuple_type.ends_with(">") {
-        return vec![];
-    }
-    let inner = &tuple_type[6..tuple_type.len() - 1];
-    if inner.is_empty() {
-        return vec![];
-    }
-    // Handle nested generics by tracking angle bracket depth
+/// Split a comma-separated list of types, like the inside of a `tuple<...>`,
+///  at its top level (i.e. not within nested generics)
+pub(super) fn split_top_level(inner: &str) -> Vec<String> {
     let mut types = Vec::new();
     let mut current = String::new();
     let mut depth = 0;
 
     for c in inner.chars() {
         match c {
-            '<' => {
+            '<' | '(' => {
                 depth += 1;
                 current.push(c);
             }
-            '>' => {
+            '>' | ')' => {
                 depth -= 1;
                 current.push(c);
             }
@@ -275,8 +292,20 @@ fn parse_tuple_types(tuple_type: &str) -> Vec<String> {
     types
 }
 
-/// Parse args comment like `// args: (foo: u64, bar: bool)` into parameter names
-fn parse_args_comment(comment: &str) -> Vec<String> {
+/// Parse a tuple type string like "tuple<u64, bool>" into its element types
+pub(super) fn parse_tuple_types(tuple_type: &str) -> Vec<String> {
+    if !tuple_type.starts_with("tuple<") || !tuple_type.ends_with(">") {
+        return vec![];
+    }
+    let inner = &tuple_type[6..tuple_type.len() - 1];
+    if inner.is_empty() {
+        return vec![];
+    }
+    split_top_level(inner)
+}
+
+/// Parse args comment like `// args: (foo: u64, bar: bool)` into parameter names and types
+pub(super) fn parse_args_comment(comment: &str) -> Vec<(String, String)> {
     let comment = comment.trim().trim_start_matches("//").trim();
     if !comment.starts_with("args:") {
         return vec![];
@@ -290,128 +319,211 @@ fn parse_args_comment(comment: &str) -> Vec<String> {
         return vec![];
     }
 
-    let mut names = Vec::new();
-    // Handle nested generics by tracking angle bracket depth
-    let mut current = String::new();
-    let mut depth = 0;
-
-    for c in inner.chars() {
-        match c {
-            '<' => {
-                depth += 1;
-                current.push(c);
-            }
-            '>' => {
-                depth -= 1;
-                current.push(c);
-            }
-            ',' if depth == 0 => {
-                // Extract name from "name: type"
-                if let Some(name) = current.split(':').next() {
-                    let name = name.trim();
-                    if !name.is_empty() {
-                        names.push(name.to_string());
-                    }
-                }
-                current = String::new();
-            }
-            _ => {
-                current.push(c);
+    split_top_level(inner)
+        .into_iter()
+        .filter_map(|arg| {
+            // Extract name & type from "name: type"
+            let (name, wit_type) = arg.split_once(':')?;
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
             }
+            Some((name.to_string(), wit_type.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Parse returns comment like `// returns: map<string, u64>` into the return type
+pub(super) fn parse_returns_comment(comment: &str) -> Option<String> {
+    let comment = comment.trim().trim_start_matches("//").trim();
+    let wit_type = comment.strip_prefix("returns:")?.trim();
+    if wit_type.is_empty() {
+        return None;
+    }
+    Some(wit_type.to_string())
+}
+
+/// Restore the identifiers renamed by `kit build --wit-rename`, recorded in
+///  `// wit-rename: <renamed> = <original>` comments, to their originals: TypeScript
+///  types are generated from the WIT, so must use the names the process (de)serializes
+fn restore_wit_renames(content: &str) -> String {
+    let renames: HashMap<&str, &str> = content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("// wit-rename:")?.split_once('='))
+        .map(|(renamed, original)| (renamed.trim(), original.trim()))
+        .collect();
+    if renames.is_empty() {
+        return content.to_string();
+    }
+
+    let mut restored = String::with_capacity(content.len());
+    let mut ident = String::new();
+    for c in content.chars().chain(std::iter::once('\0')) {
+        if c.is_alphanumeric() || c == '-' {
+            ident.push(c);
+            continue;
+        }
+        restored.push_str(renames.get(ident.as_str()).copied().unwrap_or(&ident));
+        ident.clear();
+        if c != '\0' {
+            restored.push(c);
         }
     }
-    // Handle last parameter
-    if !current.trim().is_empty() {
-        if let Some(name) = current.split(':').next() {
-            let name = name.trim();
-            if !name.is_empty() {
-                names.push(name.to_string());
-            }
+    restored
+}
+
+/// Parse a `/// doc` comment line into its text
+fn parse_doc_comment(line: &str) -> Option<String> {
+    let doc = line.trim().strip_prefix("///")?;
+    Some(doc.strip_prefix(' ').unwrap_or(doc).to_string())
+}
+
+/// Parse a `// serde: <wire name>` comment, recorded by the WIT generator for fields & cases
+///  whose JSON name is not the default derived from their WIT identifier, into the JSON name
+fn parse_serde_comment(line: &str) -> Option<String> {
+    let wire_name = line.trim().strip_prefix("// serde:")?.trim();
+    (!wire_name.is_empty()).then(|| wire_name.to_string())
+}
+
+/// A TypeScript property name for a JSON key: quoted, unless it is a valid identifier
+fn to_property_name(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        key.to_string()
+    } else {
+        format!("{:?}", key)
+    }
+}
+
+/// Generate a JSDoc comment (ending in a newline) from doc comments, indented by `indent`
+fn generate_jsdoc(docs: &[String], indent: &str) -> String {
+    if docs.is_empty() {
+        return String::new();
+    }
+    let mut jsdoc = format!("{}/**\n", indent);
+    for doc in docs {
+        if doc.is_empty() {
+            jsdoc.push_str(&format!("{} *\n", indent));
+        } else {
+            jsdoc.push_str(&format!("{} * {}\n", indent, doc));
         }
     }
-    names
+    jsdoc.push_str(&format!("{} */\n", indent));
+    jsdoc
 }
 
 // Structure to represent a WIT signature struct
 #[derive(Debug)]
-struct SignatureStruct {
-    function_name: String,
-    attr_type: String,
-    fields: Vec<SignatureField>,
-    http_method: Option<String>,
-    http_path: Option<String>,
-    args_comment: Option<String>, // Parsed from // args: (name: type, ...) comment
+pub(super) struct SignatureStruct {
+    pub(super) function_name: String,
+    pub(super) attr_type: String,
+    pub(super) fields: Vec<SignatureField>,
+    pub(super) http_method: Option<String>,
+    pub(super) http_path: Option<String>,
+    pub(super) args_comment: Option<String>, // Parsed from // args: (name: type, ...) comment
+    pub(super) returns_comment: Option<String>, // Parsed from // returns: type comment, if present
+    pub(super) docs: Vec<String>,            // Parsed from /// doc comments above the signature
 }
 
 // Structure to represent a WIT record
 #[derive(Debug)]
-struct WitRecord {
-    name: String,
-    fields: Vec<SignatureField>,
+pub(super) struct WitRecord {
+    pub(super) name: String,
+    pub(super) fields: Vec<SignatureField>,
+    pub(super) docs: Vec<String>,
 }
 
 // Structure to represent a WIT variant case with optional data
 #[derive(Debug)]
-struct WitVariantCase {
-    name: String,
-    data_type: Option<String>,
+pub(super) struct WitVariantCase {
+    pub(super) name: String,
+    pub(super) data_type: Option<String>,
+    pub(super) wire_name: Option<String>, // Parsed from a // serde: <wire name> comment above the case
 }
 
 // Structure to represent a WIT variant
 #[derive(Debug)]
-struct WitVariant {
-    name: String,
-    cases: Vec<WitVariantCase>,
+pub(super) struct WitVariant {
+    pub(super) name: String,
+    pub(super) cases: Vec<WitVariantCase>,
+    pub(super) docs: Vec<String>,
 }
 
 // Structure to represent a WIT enum (variant without data)
 #[derive(Debug)]
-struct WitEnum {
-    name: String,
-    cases: Vec<String>,
+pub(super) struct WitEnum {
+    pub(super) name: String,
+    pub(super) cases: Vec<WitVariantCase>,
+    pub(super) docs: Vec<String>,
 }
 
 // Structure to hold all parsed WIT types
-struct WitTypes {
-    signatures: Vec<SignatureStruct>,
-    records: Vec<WitRecord>,
-    variants: Vec<WitVariant>,
-    enums: Vec<WitEnum>,
-    aliases: Vec<(String, String)>,
+pub(super) struct WitTypes {
+    pub(super) signatures: Vec<SignatureStruct>,
+    pub(super) records: Vec<WitRecord>,
+    pub(super) variants: Vec<WitVariant>,
+    pub(super) enums: Vec<WitEnum>,
+    pub(super) aliases: Vec<(String, String)>,
+    pub(super) used_types: Vec<(String, String)>, // (namespace, type name) of types `use`d from interfaces
 }
 
 // Structure to hold types grouped by hyperapp
 struct HyperappTypes {
     _name: String,
     signatures: Vec<SignatureStruct>,
+    ws_signatures: Vec<SignatureStruct>,
+    push_signatures: Vec<SignatureStruct>,
     records: Vec<WitRecord>,
     variants: Vec<WitVariant>,
     enums: Vec<WitEnum>,
     aliases: Vec<(String, String)>,
+    used_types: Vec<(String, String)>,
 }
 
 // Parse WIT file to extract function signatures, records, and variants
 #[instrument(level = "trace", skip_all)]
-fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
+pub(super) fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
     debug!(file = %file_path.display(), "Parsing WIT file");
 
     let content = fs::read_to_string(file_path)
         .with_context(|| format!("Failed to read WIT file: {}", file_path.display()))?;
+    let content = restore_wit_renames(&content);
 
     let mut signatures = Vec::new();
     let mut records = Vec::new();
     let mut variants = Vec::new();
     let mut enums = Vec::new();
     let mut aliases = Vec::new();
+    let mut used_types = Vec::new();
 
     // Simple parser for WIT files to extract record definitions
     let lines: Vec<_> = content.lines().collect();
     let mut i = 0;
     let mut pending_args_comment: Option<String> = None;
+    let mut pending_returns_comment: Option<String> = None;
+    let mut pending_docs: Vec<String> = Vec::new();
 
     while i < lines.len() {
         let line = lines[i].trim();
 
+        // Collect doc comments: they document the next item (other comments may intervene)
+        if let Some(doc) = parse_doc_comment(line) {
+            pending_docs.push(doc);
+            i += 1;
+            continue;
+        }
+        let docs = if line.starts_with("//") {
+            Vec::new()
+        } else {
+            std::mem::take(&mut pending_docs)
+        };
+
         // Look for args comment above record: // args: (name: type, ...)
         if line.starts_with("// args:") {
             pending_args_comment = Some(line.to_string());
@@ -420,8 +532,34 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
             continue;
         }
 
+        // Look for returns comment above record: // returns: type
+        if line.starts_with("// returns:") {
+            pending_returns_comment = Some(line.to_string());
+            debug!(returns_comment = %line, "Found returns comment");
+            i += 1;
+            continue;
+        }
+
+        // Look for types used from other interfaces (like a shared types interface):
+        //  use <interface>.{name, ...};
+        if let Some(rest) = line.strip_prefix("use ") {
+            if let Some((interface, names)) = rest.trim_end_matches(';').split_once('.') {
+                let interface = strip_wit_escape(interface.trim());
+                // Types are generated in the namespace of the hyperapp of their interface
+                let namespace = extract_hyperapp_name(Path::new(&format!("{}.wit", interface)));
+                if let Some(namespace) = namespace.filter(|_| interface != "standard") {
+                    for name in names.trim_matches(|c| c == '{' || c == '}').split(',') {
+                        let name = strip_wit_escape(name.trim());
+                        if !name.is_empty() {
+                            debug!(interface = %interface, name = %name, "Found used type");
+                            used_types.push((namespace.clone(), name.to_string()));
+                        }
+                    }
+                }
+            }
+        }
         // Look for type aliases
-        if line.starts_with("type ") {
+        else if line.starts_with("type ") {
             // Expect: type name = rhs
             let rest = line
                 .trim_start_matches("type ")
@@ -491,16 +629,23 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
                     }
                 }
 
-                // Use the pending args comment if present
+                // Use the pending args & returns comments if present
                 let args_comment = pending_args_comment.take();
+                let returns_comment = pending_returns_comment.take();
 
                 // Parse fields
                 let mut fields = Vec::new();
                 i += 1;
 
+                let mut field_docs = Vec::new();
                 while i < lines.len() && !lines[i].trim().starts_with("}") {
                     let field_line = lines[i].trim();
 
+                    if let Some(doc) = parse_doc_comment(field_line) {
+                        field_docs.push(doc);
+                        i += 1;
+                        continue;
+                    }
                     // Skip comments and empty lines
                     if field_line.starts_with("//") || field_line.is_empty() {
                         i += 1;
@@ -517,6 +662,8 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
                         fields.push(SignatureField {
                             name: field_name,
                             wit_type: field_type,
+                            docs: std::mem::take(&mut field_docs),
+                            wire_name: None,
                         });
                     }
 
@@ -530,6 +677,8 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
                     http_method,
                     http_path,
                     args_comment,
+                    returns_comment,
+                    docs,
                 });
             } else {
                 // This is a regular record
@@ -539,9 +688,21 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
                 let mut fields = Vec::new();
                 i += 1;
 
+                let mut field_docs = Vec::new();
+                let mut field_wire_name = None;
                 while i < lines.len() && !lines[i].trim().starts_with("}") {
                     let field_line = lines[i].trim();
 
+                    if let Some(doc) = parse_doc_comment(field_line) {
+                        field_docs.push(doc);
+                        i += 1;
+                        continue;
+                    }
+                    if let Some(wire_name) = parse_serde_comment(field_line) {
+                        field_wire_name = Some(wire_name);
+                        i += 1;
+                        continue;
+                    }
                     // Skip comments and empty lines
                     if field_line.starts_with("//") || field_line.is_empty() {
                         i += 1;
@@ -558,6 +719,8 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
                         fields.push(SignatureField {
                             name: field_name,
                             wit_type: field_type,
+                            docs: std::mem::take(&mut field_docs),
+                            wire_name: field_wire_name.take(),
                         });
                     }
 
@@ -567,6 +730,7 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
                 records.push(WitRecord {
                     name: record_name.to_string(),
                     fields,
+                    docs,
                 });
             }
         }
@@ -583,11 +747,17 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
 
             // Parse cases
             let mut cases = Vec::new();
+            let mut case_wire_name = None;
             i += 1;
 
             while i < lines.len() && !lines[i].trim().starts_with("}") {
                 let case_line = lines[i].trim();
 
+                if let Some(wire_name) = parse_serde_comment(case_line) {
+                    case_wire_name = Some(wire_name);
+                    i += 1;
+                    continue;
+                }
                 // Skip comments and empty lines
                 if case_line.starts_with("//") || case_line.is_empty() {
                     i += 1;
@@ -611,6 +781,7 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
                 cases.push(WitVariantCase {
                     name: case_name,
                     data_type,
+                    wire_name: case_wire_name.take(),
                 });
 
                 i += 1;
@@ -619,6 +790,7 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
             variants.push(WitVariant {
                 name: variant_name.to_string(),
                 cases,
+                docs,
             });
         }
         // Look for enum definitions
@@ -634,11 +806,17 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
 
             // Parse enum cases
             let mut cases = Vec::new();
+            let mut case_wire_name = None;
             i += 1;
 
             while i < lines.len() && !lines[i].trim().starts_with("}") {
                 let case_line = lines[i].trim();
 
+                if let Some(wire_name) = parse_serde_comment(case_line) {
+                    case_wire_name = Some(wire_name);
+                    i += 1;
+                    continue;
+                }
                 // Skip comments and empty lines
                 if case_line.starts_with("//") || case_line.is_empty() {
                     i += 1;
@@ -648,7 +826,11 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
                 // Parse enum case (simple name without data)
                 let case_name = strip_wit_escape(case_line.trim_end_matches(',')).to_string();
                 debug!(case = %case_name, "Found enum case");
-                cases.push(case_name);
+                cases.push(WitVariantCase {
+                    name: case_name,
+                    data_type: None,
+                    wire_name: case_wire_name.take(),
+                });
 
                 i += 1;
             }
@@ -656,6 +838,7 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
             enums.push(WitEnum {
                 name: enum_name.to_string(),
                 cases,
+                docs,
             });
         }
 
@@ -676,6 +859,7 @@ fn parse_wit_file(file_path: &Path) -> Result<WitTypes> {
         variants,
         enums,
         aliases,
+        used_types,
     })
 }
 
@@ -685,13 +869,22 @@ fn generate_typescript_interface(record: &WitRecord) -> String {
     let mut fields = Vec::new();
 
     for field in &record.fields {
-        let field_name = to_snake_case(&field.name);
+        let field_name = match &field.wire_name {
+            Some(wire_name) => to_property_name(wire_name),
+            None => to_snake_case(&field.name),
+        };
         let ts_type = wit_type_to_typescript(&field.wit_type);
-        fields.push(format!("  {}: {};", field_name, ts_type));
+        fields.push(format!(
+            "{}  {}: {};",
+            generate_jsdoc(&field.docs, "  "),
+            field_name,
+            ts_type
+        ));
     }
 
     format!(
-        "export interface {} {{\n{}\n}}",
+        "{}export interface {} {{\n{}\n}}",
+        generate_jsdoc(&record.docs, ""),
         interface_name,
         fields.join("\n")
     )
@@ -702,18 +895,35 @@ fn generate_typescript_enum(enum_def: &WitEnum) -> String {
     let type_name = to_pascal_case(&enum_def.name);
 
     // Generate as TypeScript enum with string values
-    let mut enum_str = format!("export enum {} {{\n", type_name);
+    let mut enum_str = format!(
+        "{}export enum {} {{\n",
+        generate_jsdoc(&enum_def.docs, ""),
+        type_name
+    );
 
     for case in &enum_def.cases {
-        let case_pascal = to_pascal_case(case);
-        // Use the PascalCase value as the string value to match the original Rust enum
-        enum_str.push_str(&format!("  {} = \"{}\",\n", case_pascal, case_pascal));
+        let case_pascal = to_pascal_case(&case.name);
+        // Use the PascalCase value as the string value to match the original Rust enum,
+        //  unless it is (de)serialized under another name
+        let value = case
+            .wire_name
+            .clone()
+            .unwrap_or_else(|| case_pascal.clone());
+        enum_str.push_str(&format!("  {} = {:?},\n", case_pascal, value));
     }
 
     enum_str.push_str("}");
     enum_str
 }
 
+/// The JSON name of a variant case: PascalCase, as the original Rust variant, unless it is
+///  (de)serialized under another name
+pub(super) fn case_wire_name(case: &WitVariantCase) -> String {
+    case.wire_name
+        .clone()
+        .unwrap_or_else(|| to_pascal_case(&case.name))
+}
+
 // Generate TypeScript type from a WIT variant
 fn generate_typescript_variant(variant: &WitVariant) -> String {
     let type_name = to_pascal_case(&variant.name);
@@ -726,36 +936,57 @@ fn generate_typescript_variant(variant: &WitVariant) -> String {
         let cases: Vec<String> = variant
             .cases
             .iter()
-            .map(|case| format!("\"{}\"", to_pascal_case(&case.name)))
+            .map(|case| format!("{:?}", case_wire_name(case)))
             .collect();
-        format!("export type {} = {};", type_name, cases.join(" | "))
+        format!(
+            "{}export type {} = {};",
+            generate_jsdoc(&variant.docs, ""),
+            type_name,
+            cases.join(" | ")
+        )
     } else {
-        // Tagged union - generate as discriminated union
-        let cases: Vec<String> = variant
-            .cases
-            .iter()
-            .map(|case| {
-                let case_name = to_pascal_case(&case.name);
-                if let Some(ref data_type) = case.data_type {
-                    // Handle record types specially
-                    if data_type.trim().starts_with("record {") {
-                        // Parse record fields from the data type
-                        let record_content = data_type.trim_start_matches("record").trim();
-                        let fields = parse_inline_record_fields(record_content);
-                        format!("{{ {}: {} }}", case_name, fields)
+        // Externally tagged, as serde (de)serializes it: `{ "<Case>": data }`, or `"<Case>"` for
+        //  cases without data; a namespace of type guards narrows it to a case
+        let mut cases = Vec::new();
+        let mut guards = Vec::new();
+        for case in &variant.cases {
+            let wire_name = case_wire_name(case);
+            let guard_name = format!("is{}", to_pascal_case(&case.name));
+            let (case_type, check) = match case.data_type {
+                Some(ref data_type) => {
+                    let data_ts_type = if data_type.trim().starts_with("record {") {
+                        parse_inline_record_fields(data_type.trim_start_matches("record").trim())
                     } else {
-                        // Simple type
-                        let ts_type = wit_type_to_typescript(data_type);
-                        format!("{{ {}: {} }}", case_name, ts_type)
-                    }
-                } else {
-                    // Case without data - still use object format for consistency
-                    format!("{{ {}: null }}", case_name)
+                        wit_type_to_typescript(data_type)
+                    };
+                    (
+                        format!("{{ {}: {} }}", to_property_name(&wire_name), data_ts_type),
+                        format!(
+                            "typeof value === 'object' && value !== null && {:?} in value",
+                            wire_name
+                        ),
+                    )
                 }
-            })
-            .collect();
+                None => (
+                    format!("{:?}", wire_name),
+                    format!("value === {:?}", wire_name),
+                ),
+            };
+            guards.push(format!(
+                "  /** Whether `value` is the `{}` case */\n  export function {}(value: {}): value is {} {{\n    return {};\n  }}",
+                wire_name, guard_name, type_name, case_type, check
+            ));
+            cases.push(case_type);
+        }
 
-        format!("export type {} = {};", type_name, cases.join(" | "))
+        format!(
+            "{}export type {} = {};\n\nexport namespace {} {{\n{}\n}}",
+            generate_jsdoc(&variant.docs, ""),
+            type_name,
+            cases.join(" | "),
+            type_name,
+            guards.join("\n\n")
+        )
     }
 }
 
@@ -793,10 +1024,168 @@ fn parse_inline_record_fields(record_str: &str) -> String {
     format!("{{ {} }}", fields.join(", "))
 }
 
+/// Convert a WIT type to a zod schema, mirroring `wit_type_to_typescript()`
+fn wit_type_to_zod(wit_type: &str) -> String {
+    match wit_type {
+        "s8" | "u8" | "s16" | "u16" | "s32" | "u32" | "s64" | "u64" | "f32" | "f64" => {
+            "z.number()".to_string()
+        }
+        "s128" | "u128" => "z.union([z.bigint(), z.number()])".to_string(),
+        "string" | "address" => "z.string()".to_string(),
+        "bool" => "z.boolean()".to_string(),
+        "_" => "z.null()".to_string(),
+        t if t.starts_with("list<") => format!("z.array({})", wit_type_to_zod(&t[5..t.len() - 1])),
+        t if t.starts_with("map<") => match split_top_level(&t[4..t.len() - 1]).as_slice() {
+            [key_type, value_type] => {
+                // JSON object keys are strings, even those of numbers
+                let key_schema = if wit_type_to_typescript(key_type) == "number" {
+                    "z.coerce.number()".to_string()
+                } else {
+                    wit_type_to_zod(key_type)
+                };
+                format!("z.record({}, {})", key_schema, wit_type_to_zod(value_type))
+            }
+            _ => "z.record(z.string(), z.unknown())".to_string(),
+        },
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).as_slice() {
+            [inner_type, len] => {
+                format!("z.array({}).length({})", wit_type_to_zod(inner_type), len)
+            }
+            [inner_type] => format!("z.array({})", wit_type_to_zod(inner_type)),
+            _ => "z.array(z.unknown())".to_string(),
+        },
+        t if t.starts_with("option<") => {
+            format!("{}.nullable()", wit_type_to_zod(&t[7..t.len() - 1]))
+        }
+        t if t.starts_with("result<") => {
+            let types = split_top_level(&t[7..t.len() - 1]);
+            let ok_schema = types
+                .first()
+                .map(|t| wit_type_to_zod(t))
+                .unwrap_or_else(|| "z.null()".to_string());
+            let err_schema = types
+                .get(1)
+                .map(|t| wit_type_to_zod(t))
+                .unwrap_or_else(|| "z.null()".to_string());
+            format!(
+                "z.union([z.object({{ Ok: {} }}), z.object({{ Err: {} }})])",
+                ok_schema, err_schema
+            )
+        }
+        t if t.starts_with("tuple<") => {
+            let schemas: Vec<String> = split_top_level(&t[6..t.len() - 1])
+                .iter()
+                .map(|t| wit_type_to_zod(t))
+                .collect();
+            format!("z.tuple([{}])", schemas.join(", "))
+        }
+        // Custom types are referenced lazily, since they may be defined later, or recursively
+        _ => format!("z.lazy(() => {}Schema)", to_pascal_case(wit_type)),
+    }
+}
+
+/// Indent each non-empty line by two spaces, for a definition within a namespace
+fn indent_lines(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("  {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Declare the zod schema of a TypeScript type, typed so that recursive schemas type-check
+fn generate_zod_schema(type_name: &str, schema: &str) -> String {
+    format!(
+        "export const {0}Schema: z.ZodType<{0}> = {1};",
+        type_name, schema
+    )
+}
+
+// Generate the zod schema of a WIT record's interface
+fn generate_zod_record_schema(record: &WitRecord) -> String {
+    let fields: Vec<String> = record
+        .fields
+        .iter()
+        .map(|field| {
+            let field_name = match &field.wire_name {
+                Some(wire_name) => to_property_name(wire_name),
+                None => to_snake_case(&field.name),
+            };
+            format!("  {}: {},", field_name, wit_type_to_zod(&field.wit_type))
+        })
+        .collect();
+    generate_zod_schema(
+        &to_pascal_case(&record.name),
+        &format!("z.object({{\n{}\n}})", fields.join("\n")),
+    )
+}
+
+// Generate the zod schema of a WIT variant's type
+fn generate_zod_variant_schema(variant: &WitVariant) -> String {
+    let has_data = variant.cases.iter().any(|case| case.data_type.is_some());
+    let schema = if !has_data {
+        let cases: Vec<String> = variant
+            .cases
+            .iter()
+            .map(|case| format!("{:?}", case_wire_name(case)))
+            .collect();
+        format!("z.enum([{}])", cases.join(", "))
+    } else {
+        let cases: Vec<String> = variant
+            .cases
+            .iter()
+            .map(|case| {
+                let case_name = to_property_name(&case_wire_name(case));
+                let data_schema = match case.data_type {
+                    Some(ref data_type) if data_type.trim().starts_with("record {") => {
+                        zod_inline_record_schema(data_type.trim().trim_start_matches("record"))
+                    }
+                    Some(ref data_type) => wit_type_to_zod(data_type),
+                    None => return format!("z.literal({:?})", case_wire_name(case)),
+                };
+                format!("z.object({{ {}: {} }})", case_name, data_schema)
+            })
+            .collect();
+        if cases.len() == 1 {
+            cases[0].clone()
+        } else {
+            format!("z.union([\n  {},\n])", cases.join(",\n  "))
+        }
+    };
+    generate_zod_schema(&to_pascal_case(&variant.name), &schema)
+}
+
+// Helper to generate the zod schema of inline record fields, as `parse_inline_record_fields()`
+fn zod_inline_record_schema(record_str: &str) -> String {
+    let fields: Vec<String> = record_str
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+        .filter_map(|field| {
+            let (field_name, field_type) = field.split_once(':')?;
+            let field_name = to_snake_case(strip_wit_escape(field_name.trim()));
+            Some(format!(
+                "{}: {}",
+                field_name,
+                wit_type_to_zod(field_type.trim())
+            ))
+        })
+        .collect();
+    format!("z.object({{ {} }})", fields.join(", "))
+}
+
 // Generate TypeScript interface and function from a signature struct
 fn generate_typescript_function(
     signature: &SignatureStruct,
     _use_namespace: bool,
+    zod: bool,
 ) -> (String, String, String) {
     // Convert function name from kebab-case to camelCase
     let camel_function_name = to_snake_case(&signature.function_name);
@@ -804,17 +1193,13 @@ fn generate_typescript_function(
 
     debug!(name = %camel_function_name, "Generating TypeScript function");
 
-    // Extract arg names from the args comment if present
-    let arg_names: Vec<String> = signature
+    // Extract arg names & types from the args comment if present
+    let args: Vec<(String, String)> = signature
         .args_comment
         .as_ref()
-        .map(|c| {
-            parse_args_comment(c)
-                .into_iter()
-                .map(|n| to_camel_case(&n))
-                .collect()
-        })
+        .map(|c| parse_args_comment(c))
         .unwrap_or_default();
+    let arg_names: Vec<String> = args.iter().map(|(n, _)| to_camel_case(n)).collect();
     if !arg_names.is_empty() {
         debug!(arg_names = ?arg_names, "Parsed arg names from comment");
     }
@@ -823,8 +1208,10 @@ fn generate_typescript_function(
     let mut params = Vec::new();
     let mut param_names = Vec::new();
     let mut param_types = Vec::new();
+    let mut length_checks = Vec::new(); // Of array parameters, which are lists in WIT
     let mut full_return_type = "void".to_string();
     let mut unwrapped_return_type = "void".to_string();
+    let mut unwrapped_return_schema = "z.unknown()".to_string();
 
     let http_method = signature
         .http_method
@@ -845,13 +1232,25 @@ fn generate_typescript_function(
             // Skip target field as it's handled internally
             continue;
         } else if field.name == "returning" {
+            // Maps are only distinguishable from lists of tuples by the returns comment
+            let wit_type = signature
+                .returns_comment
+                .as_deref()
+                .and_then(parse_returns_comment)
+                .unwrap_or_else(|| field.wit_type.clone());
+            let ts_type = wit_type_to_typescript(&wit_type);
             full_return_type = ts_type.clone();
             // Check if it's a Result type and extract the Ok type
-            if let Some(ok_type) = extract_result_ok_type(&field.wit_type) {
+            if let Some(ok_type) = extract_result_ok_type(&wit_type) {
                 unwrapped_return_type = ok_type;
             } else {
                 unwrapped_return_type = ts_type;
             }
+            let ok_wit_type = wit_type
+                .strip_prefix("result<")
+                .and_then(|t| t.strip_suffix('>'))
+                .and_then(|t| split_top_level(t).into_iter().next());
+            unwrapped_return_schema = wit_type_to_zod(ok_wit_type.as_deref().unwrap_or(&wit_type));
             debug!(return_type = %unwrapped_return_type, "Identified return type");
         } else if field.name == "arg-types" {
             // Parse the arg-types tuple to extract individual parameter types
@@ -862,6 +1261,25 @@ fn generate_typescript_function(
                     .get(i)
                     .cloned()
                     .unwrap_or_else(|| format!("arg{}", i));
+                // Maps & 128-bit integers are only distinguishable from lists of tuples &
+                //  strings by the args comment
+                let wit_type = match args.get(i) {
+                    Some((_, arg_type))
+                        if arg_type.contains("map<") || has_128_bit_int(arg_type) =>
+                    {
+                        arg_type
+                    }
+                    _ => wit_type,
+                };
+                // Arrays are only distinguishable from lists by the args comment
+                if let Some((_, arg_type)) = args.get(i).filter(|(_, t)| t.starts_with("array<")) {
+                    if let [_, len] = split_top_level(&arg_type[6..arg_type.len() - 1]).as_slice() {
+                        length_checks.push(format!(
+                            "  if ({0}.length !== {1}) {{\n    throw new ApiError(`{0} must have {1} elements, got ${{{0}.length}}`);\n  }}\n",
+                            param_name, len
+                        ));
+                    }
+                }
                 let param_ts_type = wit_type_to_typescript(wit_type);
                 params.push(format!("{}: {}", param_name, param_ts_type));
                 param_names.push(param_name);
@@ -921,11 +1339,41 @@ fn generate_typescript_function(
             param_names.join(", ")
         )
     };
+    // Check the lengths of arrays before sending them
+    let data_construction = format!("{}{}", length_checks.concat(), data_construction);
+
+    // Describe the function by the handler's doc comments, if any, or else by its name
+    let description = if signature.docs.is_empty() {
+        format!(" * {}", camel_function_name)
+    } else {
+        signature
+            .docs
+            .iter()
+            .map(|doc| {
+                if doc.is_empty() {
+                    " *".to_string()
+                } else {
+                    format!(" * {}", doc)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    // Validate the response against its schema, if generated
+    let parse = if zod {
+        format!(
+            ", (response) => validate({}, response, 'Response of {}')",
+            unwrapped_return_schema, camel_function_name
+        )
+    } else {
+        String::new()
+    };
 
     // Function returns the unwrapped type since parseResponse extracts it
     let function_impl = format!(
-        "/**\n * {}\n{} * @returns Promise with result\n * @throws ApiError if the request fails\n */\nexport async function {}({}): Promise<{}> {{\n{}\n\n  return await apiRequest<{}, {}>('{}', '{}', data);\n}}",
-        camel_function_name,
+        "/**\n{}\n{} * @returns Promise with result\n * @throws ApiError if the request fails\n */\nexport async function {}({}): Promise<{}> {{\n{}\n\n  return await apiRequest<{}, {}>('{}', '{}', data{});\n}}",
+        description,
         params.iter().map(|p| format!(" * @param {}", p)).collect::<Vec<_>>().join("\n"),
         camel_function_name,
         function_params,
@@ -934,7 +1382,8 @@ fn generate_typescript_function(
         request_interface_name,
         unwrapped_return_type,  // Pass unwrapped type to apiRequest, not Response type
         http_path,
-        http_method
+        http_method,
+        parse
     );
 
     // Only return implementations for HTTP endpoints
@@ -946,68 +1395,657 @@ fn generate_typescript_function(
     }
 }
 
-// Public entry point for creating TypeScript caller-utils
-#[instrument(level = "trace", skip_all)]
-pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result<()> {
-    // Path to the new TypeScript file
-    let ui_target_dir = base_dir.join("target").join("ui");
-    let caller_utils_path = ui_target_dir.join("caller-utils.ts");
-
-    debug!(
-        api_dir = %api_dir.display(),
-        call_utils_path = %caller_utils_path.display(),
-        "Creating TypeScript caller-utils"
-    );
+/// Whether a WIT type from a signature comment contains a 128-bit integer (`u128` or `s128`),
+///  which is a string in the WIT itself
+fn has_128_bit_int(wit_type: &str) -> bool {
+    wit_type
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .any(|t| t == "u128" || t == "s128")
+}
 
-    // Find all WIT files in the api directory and group by hyperapp
-    let mut hyperapp_files: HashMap<String, Vec<PathBuf>> = HashMap::new();
+/// JSON (de)serialization of requests & responses: the process (de)serializes 128-bit integers
+///  as JSON numbers, which may be too large for a `number`
+const JSON_HELPERS: &str = r#"const BIGINT_MARKER = '__caller_utils_bigint__';
+
+/**
+ * Serialize to JSON, writing bigints (128-bit integers) as JSON numbers
+ */
+function stringifyJson(data: unknown): string {
+  return JSON.stringify(data, (_key, value) =>
+    typeof value === 'bigint' ? `${BIGINT_MARKER}${value}` : value,
+  ).replace(new RegExp(`"${BIGINT_MARKER}(-?\\d+)"`, 'g'), '$1');
+}
 
-    for entry in WalkDir::new(api_dir)
-        .max_depth(1)
-        .into_iter()
-        .filter_map(Result::ok)
-    {
-        let path = entry.path();
-        if path.is_file() && path.extension().map_or(false, |ext| ext == "wit") {
-            // Exclude world definition files
-            if let Ok(content) = fs::read_to_string(path) {
-                if !content.contains("world ") {
-                    // Extract hyperapp name from filename
-                    if let Some(hyperapp_name) = extract_hyperapp_name(path) {
-                        debug!(file = %path.display(), hyperapp = %hyperapp_name, "Adding WIT file for parsing");
-                        hyperapp_files
-                            .entry(hyperapp_name)
-                            .or_insert_with(Vec::new)
-                            .push(path.to_path_buf());
-                    }
-                } else {
-                    debug!(file = %path.display(), "Skipping world definition WIT file");
-                }
-            }
-        }
+/**
+ * Parse JSON, reviving integers too large for a number (128-bit integers) as bigints,
+ *  where the runtime provides their source text
+ */
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+function parseJson(text: string): any {
+  return JSON.parse(text, (_key, value, context?: { source?: string }) => {
+    const source = context?.source;
+    if (typeof value === 'number' && !Number.isSafeInteger(value) && source && /^-?\d+$/.test(source)) {
+      return BigInt(source);
     }
+    return value;
+  });
+}
 
-    debug!(
-        hyperapps = hyperapp_files.len(),
-        "Found hyperapps for TypeScript generation"
-    );
+"#;
 
-    // Generate TypeScript content
-    let mut ts_content = String::new();
+/// Base URL of the caller-utils for the package's UI: that the UI is served at
+const UI_BASE_URL: &str = r#"function getBaseUrl(): string {
+  return import.meta.env.BASE_URL || window.location.origin;
+}
 
-    // Add the header with common utilities (always present)
-    ts_content.push_str("// Define a custom error type for API errors\n");
-    ts_content.push_str("export class ApiError extends Error {\n");
-    ts_content.push_str("  constructor(message: string, public readonly details?: unknown) {\n");
-    ts_content.push_str("    super(message);\n");
-    ts_content.push_str("    this.name = 'ApiError';\n");
-    ts_content.push_str("  }\n");
-    ts_content.push_str("}\n\n");
+"#;
 
-    ts_content.push_str("// Parser for the Result-style responses\n");
-    ts_content.push_str("// eslint-disable-next-line @typescript-eslint/no-explicit-any\n");
-    ts_content.push_str("export function parseResponse<T>(response: any): T {\n");
-    ts_content.push_str("  try {\n");
+/// Base URL of the TypeScript SDK: set by its user, since it is not served by the node
+const SDK_BASE_URL: &str = r#"let baseUrl: string | undefined;
+
+/**
+ * Set the URL of the process that requests are sent to,
+ *  e.g. `https://my-node.example.com/my-process:my-package:publisher.os`
+ */
+export function setBaseUrl(url: string): void {
+  baseUrl = url.replace(/\/+$/, '');
+}
+
+function getBaseUrl(): string {
+  if (baseUrl === undefined) {
+    throw new ApiError('No base URL set: call setBaseUrl() first');
+  }
+  return baseUrl;
+}
+
+"#;
+
+/// Version range of zod that the generated schemas are written against
+const ZOD_VERSION: &str = "^3.23.0";
+
+const SDK_TSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2020",
+    "module": "ES2020",
+    "moduleResolution": "node",
+    "lib": ["ES2020", "DOM"],
+    "declaration": true,
+    "outDir": "dist",
+    "rootDir": "src",
+    "strict": true,
+    "skipLibCheck": true
+  },
+  "include": ["src"]
+}
+"#;
+
+const SDK_README: &str = r#"# {sdk_name}
+
+TypeScript client for the HTTP & WebSocket API of {package_name}, generated by `kit build --ts-sdk`.
+
+```ts
+import { setBaseUrl } from '{sdk_name}';
+
+setBaseUrl('https://my-node.example.com/my-process:{package_name}:publisher.os');
+```
+
+Functions & types are exported in a namespace per process interface.
+Run `npm publish` to build & publish the package.
+"#;
+
+/// Validation of responses & messages against the zod schemas of their types
+const ZOD_VALIDATE: &str = r#"/**
+ * Validate a response or message against the schema of its type, throwing an ApiError
+ *  describing any mismatch between the two
+ */
+function validate<T>(schema: z.ZodType<T>, value: unknown, source: string): T {
+  const result = schema.safeParse(value);
+  if (!result.success) {
+    throw new ApiError(`${source} does not match its type: ${result.error.message}`, result.error.issues);
+  }
+  return result.data;
+}
+
+"#;
+
+/// WebSocket wrapper exchanging JSON messages of type `T`, reconnecting when the connection
+///  drops, which the typed WebSocket clients extend
+const TYPED_WEB_SOCKET: &str = r#"/**
+ * Options of a TypedWebSocket
+ */
+export interface TypedWebSocketOptions {
+  /** Whether to reconnect when the connection drops [default: true] */
+  reconnect?: boolean;
+  /** Milliseconds before reconnecting, doubled on each failed attempt, up to 30s [default: 1000] */
+  reconnectDelay?: number;
+}
+
+/**
+ * WebSocket connection exchanging JSON messages of type T, which reconnects when dropped and
+ * queues the messages sent while disconnected
+ */
+export class TypedWebSocket<T> {
+  private ws!: WebSocket;
+  private readonly url: URL;
+  private readonly parse?: (message: unknown) => T;
+  private readonly options: Required<TypedWebSocketOptions>;
+  private readonly handlers = new Set<(message: T) => void>();
+  private readonly queue: string[] = [];
+  private attempts = 0;
+  private closed = false;
+
+  constructor(path: string, parse?: (message: unknown) => T, options: TypedWebSocketOptions = {}) {
+    const BASE_URL = getBaseUrl();
+    this.url = new URL(
+      path.startsWith('/') ? `${BASE_URL}${path}` : `${BASE_URL}/${path}`,
+      typeof window === 'undefined' ? undefined : window.location.href,
+    );
+    this.url.protocol = this.url.protocol === 'https:' ? 'wss:' : 'ws:';
+    this.parse = parse;
+    this.options = { reconnect: true, reconnectDelay: 1000, ...options };
+    this.connect();
+  }
+
+  /** The current connection, replaced on reconnection */
+  get socket(): WebSocket {
+    return this.ws;
+  }
+
+  private connect(): void {
+    const socket = new WebSocket(this.url);
+    this.ws = socket;
+    socket.addEventListener('open', () => {
+      this.attempts = 0;
+      for (const data of this.queue.splice(0)) {
+        socket.send(data);
+      }
+    });
+    socket.addEventListener('message', (event) => {
+      const message = parseJson(event.data);
+      const typed = this.parse ? this.parse(message) : (message as T);
+      this.handlers.forEach((handler) => handler(typed));
+    });
+    socket.addEventListener('close', () => {
+      // Connections replaced by `reconnect()` or closed by `close()` are not reopened
+      if (socket !== this.ws || this.closed || !this.options.reconnect) {
+        return;
+      }
+      const delay = Math.min(this.options.reconnectDelay * 2 ** this.attempts++, 30000);
+      setTimeout(() => {
+        if (socket === this.ws && !this.closed) {
+          this.connect();
+        }
+      }, delay);
+    });
+  }
+
+  /** Reconnect now, e.g. after the node has restarted */
+  reconnect(): void {
+    const socket = this.ws;
+    this.closed = false;
+    this.attempts = 0;
+    this.connect();
+    socket.close();
+  }
+
+  /** Send a message, once connected */
+  send(message: T): void {
+    const data = stringifyJson(message);
+    if (this.ws.readyState === WebSocket.OPEN) {
+      this.ws.send(data);
+    } else {
+      this.queue.push(data);
+    }
+  }
+
+  /** Call `handler` with each message received; returns a function unsubscribing it */
+  subscribe(handler: (message: T) => void): () => void {
+    this.handlers.add(handler);
+    return () => {
+      this.handlers.delete(handler);
+    };
+  }
+
+  onMessage(handler: (message: T) => void): void {
+    this.subscribe(handler);
+  }
+
+  close(): void {
+    this.closed = true;
+    this.ws.close();
+  }
+}
+
+"#;
+
+// Generate a typed WebSocket client class for a [ws] handler's signature, with an
+//  `on<Case>()` subscription per case of its message type if that is one of `variants`, and
+//  a function connecting it
+fn generate_typescript_ws_client(
+    signature: &SignatureStruct,
+    variants: &[WitVariant],
+    zod: bool,
+) -> String {
+    let message_field = signature
+        .fields
+        .iter()
+        .find(|field| field.name == "message");
+    let message_type = message_field
+        .map(|field| wit_type_to_typescript(&field.wit_type))
+        .unwrap_or_else(|| "unknown".to_string());
+    let pascal_name = to_pascal_case(&signature.function_name);
+    let class_name = format!("{}Client", pascal_name);
+    let function_name = format!("connect{}", pascal_name);
+    debug!(name = %class_name, message_type = %message_type, "Generating WebSocket client");
+
+    let docs = if signature.docs.is_empty() {
+        vec![format!(
+            "Typed client of the {} WebSocket handler",
+            to_camel_case(&signature.function_name)
+        )]
+    } else {
+        signature.docs.clone()
+    };
+
+    // Validate messages against their schema, if generated
+    let parse = if zod {
+        let message_schema = message_field
+            .map(|field| wit_type_to_zod(&field.wit_type))
+            .unwrap_or_else(|| "z.unknown()".to_string());
+        format!(
+            "(message) => validate({}, message, 'Message of {}')",
+            message_schema, class_name
+        )
+    } else {
+        "undefined".to_string()
+    };
+
+    let mut class = format!(
+        "{}export class {} extends TypedWebSocket<{}> {{\n  constructor(path: string, options?: TypedWebSocketOptions) {{\n    super(path, {}, options);\n  }}\n",
+        generate_jsdoc(&docs, ""),
+        class_name,
+        message_type,
+        parse
+    );
+    // Messages are (de)serialized by serde: cases without data as their name, the others as
+    //  `{"<Case>": <data>}`
+    let variant = message_field.and_then(|field| {
+        variants
+            .iter()
+            .find(|variant| strip_wit_escape(&variant.name) == strip_wit_escape(&field.wit_type))
+    });
+    for case in variant.map(|v| v.cases.as_slice()).unwrap_or_default() {
+        let wire_name = case_wire_name(case);
+        let (handler_type, subscription) = match case.data_type {
+            None => (
+                "() => void".to_string(),
+                format!(
+                    "if ((message as unknown) === {:?}) {{\n        handler();\n      }}",
+                    wire_name
+                ),
+            ),
+            Some(ref data_type) => {
+                let data_type = data_type.trim();
+                let ts_type = if data_type.starts_with("record") {
+                    parse_inline_record_fields(data_type.trim_start_matches("record").trim())
+                } else {
+                    wit_type_to_typescript(data_type)
+                };
+                (
+                    format!("(data: {}) => void", ts_type),
+                    format!(
+                        "if (typeof message === 'object' && message !== null && {:?} in message) {{\n        handler(message[{:?}]);\n      }}",
+                        wire_name, wire_name
+                    ),
+                )
+            }
+        };
+        class.push_str(&format!(
+            "\n  /** Call `handler` with each `{}` message received; returns a function unsubscribing it */\n  on{}(handler: {}): () => void {{\n    return this.subscribe((message) => {{\n      {}\n    }});\n  }}\n",
+            wire_name,
+            to_pascal_case(&case.name),
+            handler_type,
+            subscription
+        ));
+    }
+    class.push('}');
+
+    let mut jsdoc = generate_jsdoc(
+        &[format!("Connect a {} to its WebSocket handler", class_name)],
+        "",
+    );
+    jsdoc.insert_str(
+        jsdoc.len() - " */\n".len(),
+        " * @param path - Path the WebSocket handler is bound to\n",
+    );
+    format!(
+        "{}\n\n{}export function {}(path: string, options?: TypedWebSocketOptions): {} {{\n  return new {}(path, options);\n}}",
+        class, jsdoc, function_name, class_name, class_name
+    )
+}
+
+// Generate a typed event emitter of the events a process pushes to its WebSocket clients,
+//  from its `push` signature: `on()` & `once()` listen for a case of the event type, if that is
+//  one of `variants` or `enums`, with the data of each case in `PushEventMap`
+fn generate_typescript_push_events(
+    signature: &SignatureStruct,
+    variants: &[WitVariant],
+    enums: &[WitEnum],
+    zod: bool,
+) -> String {
+    let event_field = signature.fields.iter().find(|field| field.name == "event");
+    let event_type = event_field
+        .map(|field| wit_type_to_typescript(&field.wit_type))
+        .unwrap_or_else(|| "unknown".to_string());
+    debug!(event_type = %event_type, "Generating push event emitter");
+
+    // Validate events against their schema, if generated
+    let parse = if zod {
+        let event_schema = event_field
+            .map(|field| wit_type_to_zod(&field.wit_type))
+            .unwrap_or_else(|| "z.unknown()".to_string());
+        format!(
+            "(message) => validate({}, message, 'Event of PushEvents')",
+            event_schema
+        )
+    } else {
+        "undefined".to_string()
+    };
+
+    // Events are (de)serialized by serde: cases without data as their name, the others as
+    //  `{"<Case>": <data>}`
+    let cases = event_field.and_then(|field| {
+        let name = strip_wit_escape(&field.wit_type);
+        variants
+            .iter()
+            .find(|variant| strip_wit_escape(&variant.name) == name)
+            .map(|variant| variant.cases.as_slice())
+            .or_else(|| {
+                enums
+                    .iter()
+                    .find(|enum_def| strip_wit_escape(&enum_def.name) == name)
+                    .map(|enum_def| enum_def.cases.as_slice())
+            })
+    });
+
+    let mut content = String::new();
+    if let Some(cases) = cases {
+        let event_data: String = cases
+            .iter()
+            .map(|case| {
+                let data_type = match case.data_type {
+                    None => "undefined".to_string(),
+                    Some(ref data_type) => {
+                        let data_type = data_type.trim();
+                        if data_type.starts_with("record") {
+                            parse_inline_record_fields(
+                                data_type.trim_start_matches("record").trim(),
+                            )
+                        } else {
+                            wit_type_to_typescript(data_type)
+                        }
+                    }
+                };
+                format!("  {}: {};\n", case_wire_name(case), data_type)
+            })
+            .collect();
+        content.push_str(&format!(
+            "{}export interface PushEventMap {{\n{}}}\n\n",
+            generate_jsdoc(
+                &[format!(
+                    "Data of each case of the {} events pushed",
+                    event_type
+                )],
+                ""
+            ),
+            event_data
+        ));
+    }
+
+    let docs = if signature.docs.is_empty() {
+        vec!["Events pushed to WebSocket clients".to_string()]
+    } else {
+        signature.docs.clone()
+    };
+    content.push_str(&format!(
+        "{}export class PushEvents extends TypedWebSocket<{}> {{\n  constructor(path: string, options?: TypedWebSocketOptions) {{\n    super(path, {}, options);\n  }}\n",
+        generate_jsdoc(&docs, ""),
+        event_type,
+        parse
+    ));
+    if cases.is_some() {
+        content.push_str(
+            r#"
+  /** Call `listener` with the data of each `event` pushed; returns a function unsubscribing it */
+  on<K extends keyof PushEventMap>(event: K, listener: (data: PushEventMap[K]) => void): () => void {
+    return this.subscribe((message) => {
+      const pushed: unknown = message;
+      const data: unknown =
+        typeof pushed === 'object' && pushed !== null
+          ? (pushed as Record<string, unknown>)[event]
+          : undefined;
+      if (pushed === event || data !== undefined) {
+        listener(data as PushEventMap[K]);
+      }
+    });
+  }
+
+  /** Call `listener` with the data of the next `event` pushed only; returns a function unsubscribing it */
+  once<K extends keyof PushEventMap>(event: K, listener: (data: PushEventMap[K]) => void): () => void {
+    const off = this.on(event, (data) => {
+      off();
+      listener(data);
+    });
+    return off;
+  }
+"#,
+        );
+    }
+    content.push('}');
+
+    let mut jsdoc = generate_jsdoc(
+        &["Connect a PushEvents to the WebSocket handler the events are pushed on".to_string()],
+        "",
+    );
+    jsdoc.insert_str(
+        jsdoc.len() - " */\n".len(),
+        " * @param path - Path the WebSocket handler is bound to\n",
+    );
+    format!(
+        "{}\n\n{}export function connectPushEvents(path: string, options?: TypedWebSocketOptions): PushEvents {{\n  return new PushEvents(path, options);\n}}",
+        content, jsdoc
+    )
+}
+
+/// The React hooks generated for the HTTP handlers, per the `react-hooks` of `[typescript]`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReactHooks {
+    /// Hooks tracking the data, error & loading state of calls with `useState`
+    Plain,
+    /// `useQuery` hooks for `GET` handlers & `useMutation` hooks for others, from react-query
+    ReactQuery,
+}
+
+/// The `useApiCall()` that the plain React hooks are built on
+const USE_API_CALL: &str = r#"/**
+ * State of the calls to an HTTP handler: the result or error of the latest, & whether it is in flight
+ */
+export interface ApiCall<A extends unknown[], R> {
+  data: R | undefined;
+  error: api.ApiError | undefined;
+  loading: boolean;
+  call: (...args: A) => Promise<R | undefined>;
+}
+
+function useApiCall<A extends unknown[], R>(request: (...args: A) => Promise<R>): ApiCall<A, R> {
+  const [data, setData] = useState<R>();
+  const [error, setError] = useState<api.ApiError>();
+  const [loading, setLoading] = useState(false);
+
+  const call = useCallback(
+    async (...args: A) => {
+      setLoading(true);
+      setError(undefined);
+      try {
+        const result = await request(...args);
+        setData(result);
+        return result;
+      } catch (e) {
+        setError(e instanceof api.ApiError ? e : new api.ApiError(String(e), e));
+        return undefined;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [request],
+  );
+
+  return { data, error, loading, call };
+}
+
+"#;
+
+/// Generate a `use<Handler>()` React hook for each HTTP handler, in the namespaces of the
+///  caller-utils, calling the caller-utils function of the handler
+fn generate_react_hooks(
+    hyperapp_types_map: &BTreeMap<String, HyperappTypes>,
+    react_hooks: ReactHooks,
+) -> String {
+    let mut hooks_content = match react_hooks {
+        ReactHooks::Plain => format!(
+            "import {{ useCallback, useState }} from 'react';\nimport * as api from './caller-utils';\n\n{}",
+            USE_API_CALL
+        ),
+        ReactHooks::ReactQuery => "import { useMutation, useQuery } from '@tanstack/react-query';\nimport * as api from './caller-utils';\n\n".to_string(),
+    };
+
+    for (hyperapp_name, hyperapp_data) in hyperapp_types_map {
+        let http_signatures: Vec<&SignatureStruct> = hyperapp_data
+            .signatures
+            .iter()
+            .filter(|signature| signature.attr_type == "http")
+            .collect();
+        if http_signatures.is_empty() {
+            continue;
+        }
+
+        hooks_content.push_str(&format!("export namespace {} {{\n", hyperapp_name));
+        for signature in http_signatures {
+            let function = format!(
+                "api.{}.{}",
+                hyperapp_name,
+                to_snake_case(&signature.function_name)
+            );
+            let docs = if signature.docs.is_empty() {
+                vec![format!("Call {}", to_snake_case(&signature.function_name))]
+            } else {
+                signature.docs.clone()
+            };
+            let hook_name = format!("use{}", to_pascal_case(&signature.function_name));
+            let is_query = signature.http_method.as_deref() == Some("GET");
+            let hook = match react_hooks {
+                ReactHooks::Plain => format!(
+                    "export function {}() {{\n  return useApiCall({});\n}}",
+                    hook_name, function
+                ),
+                ReactHooks::ReactQuery if is_query => format!(
+                    "export function {0}(...args: Parameters<typeof {1}>) {{\n  return useQuery({{\n    queryKey: ['{2}', '{3}', ...args],\n    queryFn: () => {1}(...args),\n  }});\n}}",
+                    hook_name,
+                    function,
+                    hyperapp_name,
+                    to_snake_case(&signature.function_name)
+                ),
+                ReactHooks::ReactQuery => format!(
+                    "export function {0}() {{\n  return useMutation({{\n    mutationFn: (args: Parameters<typeof {1}>) => {1}(...args),\n  }});\n}}",
+                    hook_name, function
+                ),
+            };
+            hooks_content.push_str(&generate_jsdoc(&docs, "  "));
+            hooks_content.push_str(&indent_lines(&hook));
+            hooks_content.push_str("\n\n");
+        }
+        hooks_content.push_str("}\n\n");
+    }
+
+    hooks_content
+}
+
+/// Find the WIT files in `api_dir`, other than world definitions, grouped by hyperapp name
+pub(super) fn find_hyperapp_wit_files(api_dir: &Path) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut hyperapp_files: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+
+    for entry in WalkDir::new(api_dir)
+        .max_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if path.is_file() && path.extension().map_or(false, |ext| ext == "wit") {
+            // Exclude world definition files
+            if let Ok(content) = fs::read_to_string(path) {
+                if !content.contains("world ") {
+                    // Extract hyperapp name from filename
+                    if let Some(hyperapp_name) = extract_hyperapp_name(path) {
+                        debug!(file = %path.display(), hyperapp = %hyperapp_name, "Adding WIT file for parsing");
+                        hyperapp_files
+                            .entry(hyperapp_name)
+                            .or_insert_with(Vec::new)
+                            .push(path.to_path_buf());
+                    }
+                } else {
+                    debug!(file = %path.display(), "Skipping world definition WIT file");
+                }
+            }
+        }
+    }
+    hyperapp_files
+}
+
+// Generate the TypeScript caller-utils for the WIT files in `api_dir`, with `base_url` defining
+//  `getBaseUrl()` & zod schemas validating responses if `zod`, and the React hooks wrapping them
+//  if `react_hooks`; `None` if there are no HTTP or WebSocket functions
+fn generate_typescript_caller_utils(
+    api_dir: &Path,
+    base_url: &str,
+    zod: bool,
+    react_hooks: Option<ReactHooks>,
+) -> Result<Option<(String, Option<String>)>> {
+    debug!(api_dir = %api_dir.display(), "Generating TypeScript caller-utils");
+
+    let hyperapp_files = find_hyperapp_wit_files(api_dir);
+
+    debug!(
+        hyperapps = hyperapp_files.len(),
+        "Found hyperapps for TypeScript generation"
+    );
+
+    // Generate TypeScript content
+    let mut ts_content = String::new();
+
+    if zod {
+        ts_content.push_str("import { z } from 'zod';\n\n");
+    }
+
+    // Add the header with common utilities (always present)
+    ts_content.push_str("// Define a custom error type for API errors\n");
+    ts_content.push_str("export class ApiError extends Error {\n");
+    ts_content.push_str("  constructor(message: string, public readonly details?: unknown) {\n");
+    ts_content.push_str("    super(message);\n");
+    ts_content.push_str("    this.name = 'ApiError';\n");
+    ts_content.push_str("  }\n");
+    ts_content.push_str("}\n\n");
+
+    ts_content.push_str(JSON_HELPERS);
+    ts_content.push_str(base_url);
+    if zod {
+        ts_content.push_str(ZOD_VALIDATE);
+    }
+
+    ts_content.push_str("// Parser for the Result-style responses\n");
+    ts_content.push_str("// eslint-disable-next-line @typescript-eslint/no-explicit-any\n");
+    ts_content.push_str("export function parseResponse<T>(response: any): T {\n");
+    ts_content.push_str("  try {\n");
     ts_content.push_str(
         "    if ('Ok' in response && response.Ok !== undefined && response.Ok !== null) {\n",
     );
@@ -1027,14 +2065,14 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
     ts_content.push_str(" * @param path - API endpoint path\n");
     ts_content.push_str(" * @param method - HTTP method (GET, POST, PUT, DELETE, etc.)\n");
     ts_content.push_str(" * @param data - Request data\n");
+    ts_content.push_str(" * @param parse - Validation of the response, if any\n");
     ts_content.push_str(" * @returns Promise with parsed response data\n");
     ts_content.push_str(" * @throws ApiError if the request fails or response contains an error\n");
     ts_content.push_str(" */\n");
     ts_content.push_str(
-        "async function apiRequest<T, R>(path: string, method: string, data: T): Promise<R> {\n",
+        "async function apiRequest<T, R>(path: string, method: string, data: T, parse?: (response: unknown) => R): Promise<R> {\n",
     );
-    ts_content
-        .push_str("  const BASE_URL = import.meta.env.BASE_URL || window.location.origin;\n\n");
+    ts_content.push_str("  const BASE_URL = getBaseUrl();\n\n");
     ts_content.push_str("  const requestOptions: RequestInit = {\n");
     ts_content.push_str("    method: method,\n");
     ts_content.push_str("    headers: {\n");
@@ -1043,7 +2081,7 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
     ts_content.push_str("  };\n\n");
     ts_content.push_str("  // Only add body for methods that support it\n");
     ts_content.push_str("  if (method !== 'GET' && method !== 'HEAD') {\n");
-    ts_content.push_str("    requestOptions.body = JSON.stringify(data);\n");
+    ts_content.push_str("    requestOptions.body = stringifyJson(data);\n");
     ts_content.push_str("  }\n\n");
     ts_content.push_str(
         "  const url = path.startsWith('/') ? `${BASE_URL}${path}` : `${BASE_URL}/${path}`;\n",
@@ -1053,12 +2091,14 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
     ts_content
         .push_str("    throw new ApiError(`HTTP request failed with status: ${result.status}`);\n");
     ts_content.push_str("  }\n\n");
-    ts_content.push_str("  const jsonResponse = await result.json();\n");
-    ts_content.push_str("  return parseResponse<R>(jsonResponse);\n");
+    ts_content.push_str("  const jsonResponse = parseJson(await result.text());\n");
+    ts_content.push_str("  const response = parseResponse<R>(jsonResponse);\n");
+    ts_content.push_str("  return parse ? parse(response) : response;\n");
     ts_content.push_str("}\n\n");
 
     // Collect types grouped by hyperapp
-    let mut hyperapp_types_map: HashMap<String, HyperappTypes> = HashMap::new();
+    // By hyperapp name, so that their namespaces are written in a deterministic order
+    let mut hyperapp_types_map: BTreeMap<String, HyperappTypes> = BTreeMap::new();
     let mut has_any_functions = false;
 
     // Process WIT files grouped by hyperapp
@@ -1066,10 +2106,13 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
         let mut hyperapp_data = HyperappTypes {
             _name: hyperapp_name.clone(),
             signatures: Vec::new(),
+            ws_signatures: Vec::new(),
+            push_signatures: Vec::new(),
             records: Vec::new(),
             variants: Vec::new(),
             enums: Vec::new(),
             aliases: Vec::new(),
+            used_types: Vec::new(),
         };
 
         // Parse each WIT file for this hyperapp
@@ -1149,14 +2192,27 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
                     // Collect all types for this hyperapp
                     hyperapp_data.records.extend(wit_types.records);
                     hyperapp_data.aliases.extend(wit_types.aliases);
+                    // Types used from another hyperapp's namespace are aliased into this one
+                    hyperapp_data.used_types.extend(
+                        wit_types
+                            .used_types
+                            .into_iter()
+                            .filter(|(namespace, _)| namespace != hyperapp_name),
+                    );
                     hyperapp_data.variants.extend(wit_types.variants);
                     hyperapp_data.enums.extend(wit_types.enums);
 
-                    // Only collect HTTP signatures
+                    // Only collect HTTP, WebSocket & pushed event signatures
                     for sig in wit_types.signatures {
                         if sig.attr_type == "http" {
                             hyperapp_data.signatures.push(sig);
                             has_any_functions = true;
+                        } else if sig.attr_type == "ws" {
+                            hyperapp_data.ws_signatures.push(sig);
+                            has_any_functions = true;
+                        } else if sig.attr_type == "ws-push" {
+                            hyperapp_data.push_signatures.push(sig);
+                            has_any_functions = true;
                         }
                     }
                 }
@@ -1167,6 +2223,8 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
         }
 
         if !hyperapp_data.signatures.is_empty()
+            || !hyperapp_data.ws_signatures.is_empty()
+            || !hyperapp_data.push_signatures.is_empty()
             || !hyperapp_data.records.is_empty()
             || !hyperapp_data.variants.is_empty()
             || !hyperapp_data.enums.is_empty()
@@ -1176,15 +2234,18 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
         }
     }
 
-    // If no HTTP functions were found, don't generate the file
+    // If no HTTP or WebSocket functions were found, don't generate the file
     if !has_any_functions {
-        debug!("No HTTP functions found in WIT files, skipping TypeScript generation");
-        return Ok(());
+        debug!("No HTTP or WebSocket functions found in WIT files, skipping TypeScript generation");
+        return Ok(None);
     }
 
-    // Create directories only after we know we have HTTP functions
-    fs::create_dir_all(&ui_target_dir)?;
-    debug!("Created UI target directory structure");
+    // Typed WebSocket clients & push event emitters share a wrapper around WebSocket
+    if hyperapp_types_map.values().any(|hyperapp_data| {
+        !hyperapp_data.ws_signatures.is_empty() || !hyperapp_data.push_signatures.is_empty()
+    }) {
+        ts_content.push_str(TYPED_WEB_SOCKET);
+    }
 
     // Generate TypeScript namespaces for each hyperapp
     for (hyperapp_name, hyperapp_data) in &hyperapp_types_map {
@@ -1195,6 +2256,28 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
         ts_content.push_str(&format!("export namespace {} {{\n", hyperapp_name));
 
         // Add custom types (aliases, records, variants, and enums) for this hyperapp
+        if !hyperapp_data.used_types.is_empty() {
+            ts_content.push_str("\n  // Types from other namespaces\n");
+            let mut used_types: Vec<&(String, String)> = hyperapp_data.used_types.iter().collect();
+            used_types.sort();
+            used_types.dedup();
+            for (namespace, type_name) in used_types {
+                let ts_type = to_pascal_case(type_name);
+                ts_content.push_str(&format!(
+                    "  export type {} = {}.{};\n",
+                    ts_type, namespace, ts_type
+                ));
+                if zod {
+                    ts_content.push_str(&format!(
+                        "  {}\n",
+                        generate_zod_schema(
+                            &ts_type,
+                            &format!("z.lazy(() => {}.{}Schema)", namespace, ts_type)
+                        )
+                    ));
+                }
+            }
+        }
         if !hyperapp_data.aliases.is_empty()
             || !hyperapp_data.records.is_empty()
             || !hyperapp_data.variants.is_empty()
@@ -1212,6 +2295,15 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
                     wit_type_to_typescript(rhs)
                 };
                 ts_content.push_str(&format!("  export type {} = {}\n", ts_alias, rhs_ts));
+                if zod {
+                    let rhs_zod = if alias_name == "value" {
+                        "z.unknown()".to_string()
+                    } else {
+                        wit_type_to_zod(rhs)
+                    };
+                    ts_content
+                        .push_str(&format!("  {}\n", generate_zod_schema(&ts_alias, &rhs_zod)));
+                }
             }
             if !hyperapp_data.aliases.is_empty() {
                 ts_content.push_str("\n");
@@ -1234,6 +2326,13 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
                     .join("\n");
                 ts_content.push_str(&indented);
                 ts_content.push_str("\n\n");
+                if zod {
+                    ts_content.push_str(&indent_lines(&generate_zod_schema(
+                        &to_pascal_case(&enum_def.name),
+                        &format!("z.nativeEnum({})", to_pascal_case(&enum_def.name)),
+                    )));
+                    ts_content.push_str("\n\n");
+                }
             }
 
             for record in &hyperapp_data.records {
@@ -1252,6 +2351,10 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
                     .join("\n");
                 ts_content.push_str(&indented);
                 ts_content.push_str("\n\n");
+                if zod {
+                    ts_content.push_str(&indent_lines(&generate_zod_record_schema(record)));
+                    ts_content.push_str("\n\n");
+                }
             }
 
             for variant in &hyperapp_data.variants {
@@ -1270,6 +2373,10 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
                     .join("\n");
                 ts_content.push_str(&indented);
                 ts_content.push_str("\n\n");
+                if zod {
+                    ts_content.push_str(&indent_lines(&generate_zod_variant_schema(variant)));
+                    ts_content.push_str("\n\n");
+                }
             }
         }
 
@@ -1279,7 +2386,7 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
 
             for signature in &hyperapp_data.signatures {
                 let (interface_def, type_def, _function_def) =
-                    generate_typescript_function(signature, true);
+                    generate_typescript_function(signature, true, zod);
 
                 if !interface_def.is_empty() {
                     // Indent interface definition
@@ -1317,7 +2424,7 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
             ts_content.push_str("\n  // API Functions\n");
 
             for signature in &hyperapp_data.signatures {
-                let (_, _, function_def) = generate_typescript_function(signature, true);
+                let (_, _, function_def) = generate_typescript_function(signature, true, zod);
 
                 if !function_def.is_empty() {
                     // Indent function definition
@@ -1338,12 +2445,95 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
             }
         }
 
+        // Add typed WebSocket clients for this hyperapp
+        if !hyperapp_data.ws_signatures.is_empty() {
+            ts_content.push_str("\n  // WebSocket Clients\n");
+
+            for signature in &hyperapp_data.ws_signatures {
+                let indented =
+                    generate_typescript_ws_client(signature, &hyperapp_data.variants, zod)
+                        .lines()
+                        .map(|line| {
+                            if line.is_empty() {
+                                String::new()
+                            } else {
+                                format!("  {}", line)
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                ts_content.push_str(&indented);
+                ts_content.push_str("\n\n");
+            }
+        }
+
+        // Add the typed emitter of the events this hyperapp pushes
+        for signature in &hyperapp_data.push_signatures {
+            ts_content.push_str("\n  // Pushed Events\n");
+            let indented = generate_typescript_push_events(
+                signature,
+                &hyperapp_data.variants,
+                &hyperapp_data.enums,
+                zod,
+            )
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("  {}", line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+            ts_content.push_str(&indented);
+            ts_content.push_str("\n\n");
+        }
+
         // Close namespace
         ts_content.push_str("}\n");
     }
 
     ts_content.push_str("\n");
 
+    let hooks_content =
+        react_hooks.map(|react_hooks| generate_react_hooks(&hyperapp_types_map, react_hooks));
+
+    Ok(Some((ts_content, hooks_content)))
+}
+
+// Public entry point for creating TypeScript caller-utils
+#[instrument(level = "trace", skip_all)]
+pub fn create_typescript_caller_utils(
+    base_dir: &Path,
+    api_dir: &Path,
+    config: &TypeScriptConfig,
+) -> Result<()> {
+    // Path to the new TypeScript file
+    let ui_target_dir = base_dir.join("target").join("ui");
+    let caller_utils_path = ui_target_dir.join("caller-utils.ts");
+
+    let react_hooks = match config.react_hooks.as_deref() {
+        None => None,
+        Some("react") => Some(ReactHooks::Plain),
+        Some("react-query") => Some(ReactHooks::ReactQuery),
+        Some(other) => bail!(
+            "Unknown `react-hooks` '{}' in the [typescript] table of kit.toml.\n\
+             \n\
+             Suggestion: Set `react-hooks` to \"react\" or \"react-query\".",
+            other
+        ),
+    };
+    let Some((ts_content, hooks_content)) =
+        generate_typescript_caller_utils(api_dir, UI_BASE_URL, config.zod, react_hooks)?
+    else {
+        return Ok(());
+    };
+
+    // Create directories only after we know we have HTTP or WebSocket functions
+    fs::create_dir_all(&ui_target_dir)?;
+    debug!("Created UI target directory structure");
+
     // Write the TypeScript file
     debug!(
         "Writing generated TypeScript code to {}",
@@ -1356,6 +2546,16 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
         )
     })?;
 
+    if let Some(hooks_content) = hooks_content {
+        let hooks_path = ui_target_dir.join("caller-utils-hooks.ts");
+        fs::write(&hooks_path, hooks_content).with_context(|| {
+            format!(
+                "Failed to write caller-utils-hooks.ts: {}",
+                hooks_path.display()
+            )
+        })?;
+    }
+
     info!(
         "Successfully created TypeScript caller-utils at {}",
         caller_utils_path.display()
@@ -1363,6 +2563,87 @@ pub fn create_typescript_caller_utils(base_dir: &Path, api_dir: &Path) -> Result
     Ok(())
 }
 
+/// Create an npm package at `sdk_dir` with a typed client for the package's HTTP & WebSocket
+///  handlers, for use from outside the package's UI (e.g. Node scripts, other frontends);
+///  `npm publish` in `sdk_dir` builds the `.js` and `.d.ts` files into `dist/` & publishes them
+#[instrument(level = "trace", skip_all)]
+pub fn create_typescript_sdk(
+    api_dir: &Path,
+    sdk_dir: &Path,
+    package_name: &str,
+    version: &str,
+    config: &TypeScriptConfig,
+) -> Result<()> {
+    if !write_typescript_sdk(api_dir, sdk_dir, package_name, version, config)? {
+        bail!(
+            "No HTTP or WebSocket handlers found in the WIT files of {}: nothing to generate a TypeScript SDK for.\n\
+             \n\
+             Suggestion: Add #[http] or #[ws] handlers to the hyperapp processes and build with --hyperapp.",
+            api_dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Create the npm package of `create_typescript_sdk()`, if the package has HTTP or WebSocket
+///  handlers; returns whether it does
+#[instrument(level = "trace", skip_all)]
+pub fn write_typescript_sdk(
+    api_dir: &Path,
+    sdk_dir: &Path,
+    package_name: &str,
+    version: &str,
+    config: &TypeScriptConfig,
+) -> Result<bool> {
+    let Some((ts_content, _)) =
+        generate_typescript_caller_utils(api_dir, SDK_BASE_URL, config.zod, None)?
+    else {
+        return Ok(false);
+    };
+
+    let src_dir = sdk_dir.join("src");
+    fs::create_dir_all(&src_dir)?;
+    fs::write(src_dir.join("index.ts"), ts_content)?;
+
+    let sdk_name = format!("{package_name}-sdk");
+    let mut package_json = serde_json::json!({
+        "name": sdk_name,
+        "version": version,
+        "description": format!("TypeScript client for the {package_name} HTTP & WebSocket API"),
+        "type": "module",
+        "main": "dist/index.js",
+        "types": "dist/index.d.ts",
+        "files": ["dist"],
+        "scripts": {
+            "build": "tsc",
+            "prepublishOnly": "npm run build",
+        },
+        "devDependencies": {
+            "typescript": "^5.0.0",
+        },
+    });
+    if config.zod {
+        package_json["dependencies"] = serde_json::json!({ "zod": ZOD_VERSION });
+    }
+    fs::write(
+        sdk_dir.join("package.json"),
+        format!("{}\n", serde_json::to_string_pretty(&package_json)?),
+    )?;
+    fs::write(sdk_dir.join("tsconfig.json"), SDK_TSCONFIG)?;
+    fs::write(
+        sdk_dir.join("README.md"),
+        SDK_README
+            .replace("{sdk_name}", &sdk_name)
+            .replace("{package_name}", package_name),
+    )?;
+
+    info!(
+        "Successfully created TypeScript SDK {sdk_name}@{version} at {}",
+        sdk_dir.display()
+    );
+    Ok(true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1403,7 +2684,8 @@ interface test {
         fs::write(&wit_file, wit_content).unwrap();
 
         // Generate TypeScript
-        let result = create_typescript_caller_utils(temp_dir.path(), &api_dir);
+        let result =
+            create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default());
         assert!(
             result.is_ok(),
             "Failed to generate TypeScript: {:?}",
@@ -1446,4 +2728,607 @@ interface test {
             "Enum reference in interface not found"
         );
     }
+
+    #[test]
+    fn test_wit_renames_are_restored() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        // As generated by `kit build --hyperapp --wit-rename`
+        let wit_content = r#"
+interface test {
+    // wit-rename: field-one = field1
+    // wit-rename: get-secret-v-two-signature-http = get-secret-v2-signature-http
+    // wit-rename: secret-v-two = secret-v2
+
+    record secret-v-two {
+        field-one: string
+    }
+
+    // Function signature for: get-secret-v-two (http)
+    // HTTP: POST /api/get-secret-v2
+    // args: (field-one: string)
+    record get-secret-v-two-signature-http {
+        target: string,
+        arg-types: tuple<string>,
+        returning: secret-v-two
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default())
+            .unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        assert!(
+            ts_content.contains("export interface SecretV2"),
+            "Renamed type should have its original name"
+        );
+        assert!(
+            ts_content.contains("field1: string"),
+            "Renamed field should have its original name"
+        );
+        assert!(
+            !ts_content.contains("VTwo") && !ts_content.contains("field_one"),
+            "No renamed identifiers should remain"
+        );
+    }
+
+    #[test]
+    fn test_doc_comments_become_jsdoc() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    /// A thing to do
+    record todo-item {
+        /// Unique id
+        id: u64,
+        done: bool
+    }
+
+    /// Get an item by its id
+    // Function signature for: get-item (http)
+    // HTTP: POST /api/get-item
+    // args: (id: u64)
+    record get-item-signature-http {
+        target: string,
+        arg-types: tuple<u64>,
+        returning: todo-item
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default())
+            .unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        // Types & functions are indented within their namespace
+        let ts_content: Vec<&str> = ts_content.lines().map(str::trim).collect();
+        let ts_content = ts_content.join("\n");
+        assert!(
+            ts_content.contains("/**\n* A thing to do\n*/\nexport interface TodoItem {"),
+            "Record docs should precede the interface, got:\n{}",
+            ts_content
+        );
+        assert!(
+            ts_content.contains("/**\n* Unique id\n*/\nid: number;\ndone: boolean;"),
+            "Field docs should precede the field, got:\n{}",
+            ts_content
+        );
+        assert!(
+            ts_content.contains("/**\n* Get an item by its id\n"),
+            "Handler docs should describe the function, got:\n{}",
+            ts_content
+        );
+    }
+
+    #[test]
+    fn test_ws_signatures_become_typed_clients() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    variant chat-message {
+        join(string),
+        leave
+    }
+
+    // Function signature for: chat (ws)
+    record chat-signature-ws {
+        message: chat-message
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default())
+            .unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        assert!(ts_content.contains("export class TypedWebSocket<T> {"));
+        assert!(
+            ts_content.contains(
+                "export function connectChat(path: string, options?: TypedWebSocketOptions): ChatClient {"
+            ),
+            "[ws] signature should become a typed client, got:\n{}",
+            ts_content
+        );
+        assert!(
+            ts_content.contains("export class ChatClient extends TypedWebSocket<ChatMessage> {")
+        );
+        assert!(ts_content.contains("onJoin(handler: (data: string) => void): () => void {"));
+        assert!(ts_content.contains("onLeave(handler: () => void): () => void {"));
+    }
+
+    #[test]
+    fn test_ws_push_signature_becomes_event_emitter() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    variant chat-event {
+        joined(string),
+        cleared
+    }
+
+    /// Events pushed to WebSocket clients
+    // Function signature for: push (ws-push)
+    record push-signature-ws-push {
+        event: chat-event
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default())
+            .unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        assert!(ts_content.contains("export class TypedWebSocket<T> {"));
+        assert!(
+            ts_content.contains(
+                "  export interface PushEventMap {\n    Joined: string;\n    Cleared: undefined;\n  }"
+            ),
+            "pushed events should be mapped to their data, got:\n{}",
+            ts_content
+        );
+        assert!(ts_content.contains("export class PushEvents extends TypedWebSocket<ChatEvent> {"));
+        assert!(ts_content.contains(
+            "on<K extends keyof PushEventMap>(event: K, listener: (data: PushEventMap[K]) => void): () => void {"
+        ));
+        assert!(ts_content.contains(
+            "export function connectPushEvents(path: string, options?: TypedWebSocketOptions): PushEvents {"
+        ));
+    }
+
+    #[test]
+    fn test_array_args_are_length_checked() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    // Function signature for: verify (http)
+    // HTTP: POST /api/verify
+    // args: (signature: array<u8, 64>)
+    record verify-signature-http {
+        target: string,
+        arg-types: tuple<list<u8>>,
+        returning: bool
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default())
+            .unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        assert!(ts_content.contains("export async function verify(signature: number[])"));
+        assert!(
+            ts_content.contains("if (signature.length !== 64) {"),
+            "Array args should have their length checked, got:\n{}",
+            ts_content
+        );
+    }
+
+    #[test]
+    fn test_128_bit_integers_are_bigints() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    // Function signature for: transfer (http)
+    // HTTP: POST /api/transfer
+    // args: (amount: u128)
+    // returns: option<s128>
+    record transfer-signature-http {
+        target: string,
+        arg-types: tuple<string>,
+        returning: option<string>
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default())
+            .unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        assert!(
+            ts_content.contains("export async function transfer(amount: (bigint | number))"),
+            "128-bit args should be bigints, got:\n{}",
+            ts_content
+        );
+        assert!(ts_content.contains("export type TransferResponse = (bigint | number) | null;"));
+        assert!(ts_content.contains("requestOptions.body = stringifyJson(data);"));
+    }
+
+    #[test]
+    fn test_serde_wire_names_are_used() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    record profile {
+        // serde: displayName
+        display-name: string,
+        // serde: @type
+        %type: string,
+        user-handle: string
+    }
+
+    enum status {
+        // serde: in_progress
+        in-progress,
+        finished
+    }
+
+    variant event {
+        // serde: renamed
+        updated(profile),
+        removed
+    }
+
+    // Function signature for: get-event (http)
+    // HTTP: POST /api/event
+    // args: (status: status)
+    record get-event-signature-http {
+        target: string,
+        arg-types: tuple<status>,
+        returning: event
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default())
+            .unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        for expected in [
+            "  displayName: string;",
+            "  \"@type\": string;",
+            "  user_handle: string;",
+            "  InProgress = \"in_progress\",",
+            "  Finished = \"Finished\",",
+            "{ renamed: Profile } | \"Removed\"",
+            "    export function isUpdated(value: Event): value is { renamed: Profile } {\n      return typeof value === 'object' && value !== null && \"renamed\" in value;\n    }",
+            "    export function isRemoved(value: Event): value is \"Removed\" {\n      return value === \"Removed\";\n    }",
+        ] {
+            assert!(
+                ts_content.contains(expected),
+                "Expected `{}`, got:\n{}",
+                expected,
+                ts_content
+            );
+        }
+    }
+
+    #[test]
+    fn test_shared_types_are_aliased_into_namespaces() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let types_content = r#"
+interface types-my-app {
+    use standard.{address};
+
+    record profile {
+        name: string
+    }
+}
+"#;
+        fs::write(api_dir.join("types-my-app.wit"), types_content).unwrap();
+        let chat_content = r#"
+interface chat {
+    use standard.{address};
+    use types-my-app.{profile};
+
+    // Function signature for: get-profile (http)
+    // HTTP: POST /api/profile
+    record get-profile-signature-http {
+        target: string,
+        returning: profile
+    }
+}
+"#;
+        fs::write(api_dir.join("chat.wit"), chat_content).unwrap();
+
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &TypeScriptConfig::default())
+            .unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        assert!(
+            ts_content.contains("export interface Profile {"),
+            "Shared types should be generated in their own namespace, got:\n{}",
+            ts_content
+        );
+        assert!(
+            ts_content.contains("  export type Profile = MyApp.Profile;"),
+            "Shared types should be aliased where they are used, got:\n{}",
+            ts_content
+        );
+    }
+
+    #[test]
+    fn test_zod_schemas_validate_responses() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    record item {
+        id: u64,
+        tags: list<string>,
+        parent: option<item>
+    }
+
+    variant event {
+        created(item),
+        deleted
+    }
+
+    // Function signature for: get-item (http)
+    // HTTP: POST /api/item
+    // args: (id: u64)
+    record get-item-signature-http {
+        target: string,
+        arg-types: tuple<u64>,
+        returning: result<item, string>
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        let config = TypeScriptConfig {
+            zod: true,
+            ..Default::default()
+        };
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &config).unwrap();
+
+        let ts_content = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("target")
+                .join("ui")
+                .join("caller-utils.ts"),
+        )
+        .unwrap();
+        assert!(ts_content.starts_with("import { z } from 'zod';"));
+        assert!(
+            ts_content.contains(
+                "export const ItemSchema: z.ZodType<Item> = z.object({\n    id: z.number(),\n    tags: z.array(z.string()),\n    parent: z.lazy(() => ItemSchema).nullable(),\n  });"
+            ),
+            "Records should get zod schemas, got:\n{}",
+            ts_content
+        );
+        assert!(ts_content.contains(
+            "z.object({ Created: z.lazy(() => ItemSchema) }),\n    z.literal(\"Deleted\"),"
+        ));
+        assert!(
+            ts_content.contains(
+                "data, (response) => validate(z.lazy(() => ItemSchema), response, 'Response of get_item'));"
+            ),
+            "Responses should be validated, got:\n{}",
+            ts_content
+        );
+    }
+
+    #[test]
+    fn test_react_hooks_wrap_http_handlers() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    // Function signature for: get-status (http)
+    // HTTP: GET /api/status
+    record get-status-signature-http {
+        target: string,
+        returning: string
+    }
+
+    /// Set the status
+    // Function signature for: set-status (http)
+    // HTTP: POST /api/status
+    // args: (status: string)
+    record set-status-signature-http {
+        target: string,
+        arg-types: tuple<string>,
+        returning: bool
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+        let hooks_path = temp_dir
+            .path()
+            .join("target")
+            .join("ui")
+            .join("caller-utils-hooks.ts");
+
+        let config = TypeScriptConfig {
+            react_hooks: Some("react".to_string()),
+            ..Default::default()
+        };
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &config).unwrap();
+        let hooks_content = fs::read_to_string(&hooks_path).unwrap();
+        assert!(
+            hooks_content.contains("  /**\n   * Set the status\n   */\n  export function useSetStatus() {\n    return useApiCall(api.Test.set_status);\n  }"),
+            "Each HTTP handler should get a hook, got:\n{}",
+            hooks_content
+        );
+
+        let config = TypeScriptConfig {
+            react_hooks: Some("react-query".to_string()),
+            ..Default::default()
+        };
+        create_typescript_caller_utils(temp_dir.path(), &api_dir, &config).unwrap();
+        let hooks_content = fs::read_to_string(&hooks_path).unwrap();
+        assert!(hooks_content.contains("queryKey: ['Test', 'get_status', ...args],"));
+        assert!(hooks_content.contains(
+            "mutationFn: (args: Parameters<typeof api.Test.set_status>) => api.Test.set_status(...args),"
+        ));
+
+        let config = TypeScriptConfig {
+            react_hooks: Some("vue".to_string()),
+            ..Default::default()
+        };
+        assert!(create_typescript_caller_utils(temp_dir.path(), &api_dir, &config).is_err());
+    }
+
+    #[test]
+    fn test_sdk_is_an_npm_package() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface test {
+    // Function signature for: get-status (http)
+    // HTTP: GET /api/status
+    record get-status-signature-http {
+        target: string,
+        returning: string
+    }
+}
+"#;
+        fs::write(api_dir.join("test.wit"), wit_content).unwrap();
+
+        let sdk_dir = temp_dir.path().join("sdk");
+        create_typescript_sdk(
+            &api_dir,
+            &sdk_dir,
+            "my-package",
+            "1.2.3",
+            &TypeScriptConfig::default(),
+        )
+        .unwrap();
+
+        let package_json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(sdk_dir.join("package.json")).unwrap())
+                .unwrap();
+        assert_eq!(package_json["name"], "my-package-sdk");
+        assert_eq!(package_json["version"], "1.2.3");
+        assert_eq!(package_json["types"], "dist/index.d.ts");
+        assert!(sdk_dir.join("tsconfig.json").exists());
+
+        let ts_content = fs::read_to_string(sdk_dir.join("src").join("index.ts")).unwrap();
+        assert!(
+            ts_content.contains("export function setBaseUrl(url: string): void {"),
+            "SDK should let its user set the base URL, got:\n{}",
+            ts_content
+        );
+        assert!(
+            !ts_content.contains("import.meta.env"),
+            "SDK should not depend on the UI's bundler, got:\n{}",
+            ts_content
+        );
+        assert!(ts_content.contains("export async function get_status("));
+
+        // no HTTP or WebSocket handlers: nothing to generate
+        fs::remove_file(api_dir.join("test.wit")).unwrap();
+        assert!(create_typescript_sdk(
+            &api_dir,
+            &sdk_dir,
+            "my-package",
+            "1.2.3",
+            &TypeScriptConfig::default(),
+        )
+        .is_err());
+    }
 }
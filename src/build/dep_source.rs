@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result, Section,
+};
+use fs_err as fs;
+use sha2::{Digest, Sha256};
+use tracing::{debug, instrument, warn};
+
+use crate::boot_fake_node::extract_zip;
+use crate::KIT_CACHE;
+
+use super::{hash_zip_pkg, run_command};
+
+const DEP_SOURCES_DIR_NAME: &str = "dep-sources";
+
+/// Where to get the APIs of `metadata.json` dependencies from
+#[derive(Debug, Clone, PartialEq)]
+pub enum DepSource {
+    /// The node at `--port`, which downloads the dependency if it is not installed
+    Node,
+    /// A local dir containing `<package_id>.zip`, `<package_id>-api.zip`
+    ///  or `<package_id>/api.zip` for each dependency
+    Dir(PathBuf),
+    /// A git repo laid out like [`DepSource::Dir`]
+    Git(String),
+    /// An HTTP registry serving the same paths as [`DepSource::Dir`]
+    Http(String),
+}
+
+impl FromStr for DepSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("dependency source cannot be empty".to_string());
+        }
+        if s == "node" {
+            Ok(DepSource::Node)
+        } else if let Some(git_url) = s.strip_prefix("git+") {
+            Ok(DepSource::Git(git_url.to_string()))
+        } else if s.starts_with("git@") || s.ends_with(".git") {
+            Ok(DepSource::Git(s.to_string()))
+        } else if s.starts_with("http://") || s.starts_with("https://") {
+            Ok(DepSource::Http(s.trim_end_matches('/').to_string()))
+        } else {
+            Ok(DepSource::Dir(PathBuf::from(s)))
+        }
+    }
+}
+
+fn get_api_zip_names(package_id: &str) -> [String; 3] {
+    [
+        format!("{package_id}.zip"),
+        format!("{package_id}-api.zip"),
+        format!("{package_id}/api.zip"),
+    ]
+}
+
+/// Clone the git dep source into the cache, or update it if already cloned
+#[instrument(level = "trace", skip_all)]
+fn sync_git_dep_source(git_url: &str) -> Result<PathBuf> {
+    let mut hasher = Sha256::new();
+    hasher.update(git_url.as_bytes());
+    let repo_dir = PathBuf::from(KIT_CACHE)
+        .join(DEP_SOURCES_DIR_NAME)
+        .join(format!("{:x}", hasher.finalize()));
+    if repo_dir.join(".git").exists() {
+        let result = run_command(
+            Command::new("git")
+                .args(["pull", "--ff-only"])
+                .current_dir(&repo_dir),
+            false,
+        );
+        if let Err(e) = result {
+            warn!("Failed to update dependency source {git_url}; using existing clone: {e}");
+        }
+    } else {
+        fs::create_dir_all(repo_dir.parent().unwrap())?;
+        run_command(
+            Command::new("git").args([
+                "clone",
+                "--depth",
+                "1",
+                git_url,
+                repo_dir.to_str().unwrap(),
+            ]),
+            false,
+        )
+        .wrap_err_with(|| format!("Failed to clone dependency source {git_url}"))?;
+    }
+    Ok(repo_dir)
+}
+
+fn find_api_zip_in_dir(dir: &Path, package_id: &str) -> Result<PathBuf> {
+    if !dir.is_dir() {
+        return Err(eyre!("Dependency source {dir:?} is not a directory"));
+    }
+    get_api_zip_names(package_id)
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+        .ok_or_else(|| {
+            eyre!("API for dependency {package_id} not found in {dir:?}")
+                .with_suggestion(|| format!("Expected one of {:?}.", get_api_zip_names(package_id)))
+        })
+}
+
+#[instrument(level = "trace", skip_all)]
+async fn download_api_zip(registry_url: &str, package_id: &str) -> Result<Vec<u8>> {
+    for name in get_api_zip_names(package_id) {
+        let url = format!("{registry_url}/{name}");
+        let response = reqwest::get(&url).await?;
+        if response.status() == reqwest::StatusCode::OK {
+            return Ok(response.bytes().await?.to_vec());
+        }
+        debug!("{url}: HTTP Status {}", response.status());
+    }
+    Err(eyre!(
+        "API for dependency {package_id} not found at {registry_url}"
+    ))
+}
+
+/// Get the API of `package_id` from a non-node dependency source;
+///  returns the dir of the extracted API and a hash of the API zip to use as its version
+#[instrument(level = "trace", skip_all)]
+pub async fn get_api_from_dep_source(
+    dep_source: &DepSource,
+    package_id: &str,
+) -> Result<(PathBuf, String)> {
+    let zip_dir = PathBuf::from(KIT_CACHE).join(format!("{package_id}-api"));
+    let zip_path = zip_dir.join(format!("{package_id}-api.zip"));
+    if zip_dir.exists() {
+        fs::remove_dir_all(&zip_dir)?;
+    }
+    fs::create_dir_all(&zip_dir)?;
+
+    match dep_source {
+        DepSource::Node => {
+            return Err(eyre!(
+                "get_api_from_dep_source() does not support DepSource::Node"
+            ))
+        }
+        DepSource::Dir(ref dir) => {
+            fs::copy(find_api_zip_in_dir(dir, package_id)?, &zip_path)?;
+        }
+        DepSource::Git(ref git_url) => {
+            let repo_dir = sync_git_dep_source(git_url)?;
+            fs::copy(find_api_zip_in_dir(&repo_dir, package_id)?, &zip_path)?;
+        }
+        DepSource::Http(ref registry_url) => {
+            fs::write(&zip_path, download_api_zip(registry_url, package_id).await?)?;
+        }
+    }
+
+    let version = hash_zip_pkg(&zip_path)?;
+    extract_zip(&zip_path)?;
+    Ok((zip_dir, version))
+}
@@ -0,0 +1,434 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use tracing::{debug, info, instrument};
+
+use super::caller_utils_ts_generator::{
+    case_wire_name, find_hyperapp_wit_files, parse_args_comment, parse_returns_comment,
+    parse_tuple_types, parse_wit_file, split_top_level, to_camel_case, to_pascal_case,
+    to_snake_case, SignatureStruct,
+};
+
+pub const GRAPHQL_SCHEMA_NAME: &str = "schema.graphql";
+pub const GRAPHQL_RESOLVERS_NAME: &str = "graphql-resolvers.ts";
+
+/// Scalars for the JSON values that have no GraphQL type of their own
+const GRAPHQL_SCALARS: &str = r#""""
+An integer that may not fit in 32 bits: a JSON number, or a string for 128-bit integers
+"""
+scalar BigInt
+
+"""
+Any JSON value: a tuple, map, result, or variant with data, as serialized by serde
+"""
+scalar JSON
+"#;
+
+const RESOLVERS_HEADER: &str = r#"// GraphQL resolvers of the package's HTTP API, generated by kit: each calls its handler through
+//  the caller-utils, so errors returned by a handler become GraphQL errors. Serve them with
+//  the schema in schema.graphql, e.g. with graphql-yoga:
+//  `createSchema({ typeDefs: readFileSync('schema.graphql', 'utf8'), resolvers })`.
+// Do NOT edit this file: it is machine generated.
+import * as api from './caller-utils';
+
+"#;
+
+// How a named WIT type is represented in GraphQL
+enum GraphqlKind {
+    // An object type, with an input type for arguments
+    Record,
+    // An enum, of variants without data too
+    Enum,
+    // A variant with data, which GraphQL cannot tag as serde does: taken as JSON
+    Json,
+    Alias(String),
+}
+
+// The GraphQL type of a WIT type (including the richer types of signature comments): non-null
+//  unless an option. Records are taken as their input types in arguments (`input`)
+fn wit_type_to_graphql(
+    wit_type: &str,
+    kinds: &BTreeMap<String, GraphqlKind>,
+    input: bool,
+) -> String {
+    match wit_type {
+        "s8" | "s16" | "s32" | "u8" | "u16" => "Int!".to_string(),
+        "u32" | "s64" | "u64" | "s128" | "u128" => "BigInt!".to_string(),
+        "f32" | "f64" => "Float!".to_string(),
+        "bool" => "Boolean!".to_string(),
+        "string" | "char" | "address" => "String!".to_string(),
+        // Handlers returning nothing resolve to null
+        "_" => "Boolean".to_string(),
+        t if t.starts_with("list<") => {
+            format!(
+                "[{}]!",
+                wit_type_to_graphql(&t[5..t.len() - 1], kinds, input)
+            )
+        }
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).as_slice() {
+            [inner_type, _] => format!("[{}]!", wit_type_to_graphql(inner_type, kinds, input)),
+            _ => "JSON!".to_string(),
+        },
+        t if t.starts_with("option<") => wit_type_to_graphql(&t[7..t.len() - 1], kinds, input)
+            .trim_end_matches('!')
+            .to_string(),
+        t if t.starts_with("result<") || t.starts_with("tuple<") || t.starts_with("map<") => {
+            "JSON!".to_string()
+        }
+        t => {
+            let type_name = to_pascal_case(t.trim_start_matches('%'));
+            match kinds.get(&type_name) {
+                Some(GraphqlKind::Record) if input => format!("{}Input!", type_name),
+                Some(GraphqlKind::Record) | Some(GraphqlKind::Enum) => format!("{}!", type_name),
+                Some(GraphqlKind::Alias(rhs)) => wit_type_to_graphql(rhs, kinds, input),
+                Some(GraphqlKind::Json) | None => "JSON!".to_string(),
+            }
+        }
+    }
+}
+
+// A GraphQL description (ending in a newline) from doc comments, indented by `indent`
+fn generate_description(docs: &[String], indent: &str) -> String {
+    if docs.is_empty() {
+        return String::new();
+    }
+    let mut description = format!("{}\"\"\"\n", indent);
+    for doc in docs {
+        description.push_str(&format!(
+            "{}{}\n",
+            indent,
+            doc.replace("\"\"\"", "\\\"\"\"")
+        ));
+    }
+    description.push_str(&format!("{}\"\"\"\n", indent));
+    description
+}
+
+// The field of `Query` (`GET` handlers) or `Mutation` (others) calling an HTTP handler, and
+//  its resolver. Handlers returning a result resolve to its ok value
+fn generate_operation(
+    hyperapp_name: &str,
+    signature: &SignatureStruct,
+    kinds: &BTreeMap<String, GraphqlKind>,
+) -> (String, String) {
+    let field_name = to_camel_case(&signature.function_name);
+    let args = signature
+        .args_comment
+        .as_deref()
+        .map(parse_args_comment)
+        .unwrap_or_default();
+
+    let mut graphql_args = Vec::new();
+    let mut call_args = Vec::new();
+    let mut return_type = "Boolean".to_string();
+    for field in &signature.fields {
+        if field.name == "arg-types" {
+            for (i, wit_type) in parse_tuple_types(&field.wit_type).iter().enumerate() {
+                // The args comment has the richer types (maps, arrays, 128-bit integers)
+                let (arg_name, wit_type) = match args.get(i) {
+                    Some((name, arg_type)) => (to_camel_case(name), arg_type.as_str()),
+                    None => (format!("arg{}", i), wit_type.as_str()),
+                };
+                graphql_args.push(format!(
+                    "{}: {}",
+                    arg_name,
+                    wit_type_to_graphql(wit_type, kinds, true)
+                ));
+                // The caller-utils take 128-bit integers as bigints
+                if wit_type == "u128" || wit_type == "s128" {
+                    call_args.push(format!("BigInt(args.{})", arg_name));
+                } else {
+                    call_args.push(format!("args.{}", arg_name));
+                }
+            }
+        } else if field.name == "returning" {
+            let wit_type = signature
+                .returns_comment
+                .as_deref()
+                .and_then(parse_returns_comment)
+                .unwrap_or_else(|| field.wit_type.clone());
+            let ok_type = wit_type
+                .strip_prefix("result<")
+                .and_then(|t| t.strip_suffix('>'))
+                .and_then(|t| split_top_level(t).into_iter().next())
+                .unwrap_or(wit_type);
+            return_type = wit_type_to_graphql(&ok_type, kinds, false);
+        }
+    }
+
+    let field = format!(
+        "{}  {}{}: {}\n",
+        generate_description(&signature.docs, "  "),
+        field_name,
+        if graphql_args.is_empty() {
+            String::new()
+        } else {
+            format!("({})", graphql_args.join(", "))
+        },
+        return_type
+    );
+    let function = format!(
+        "api.{}.{}",
+        hyperapp_name,
+        to_snake_case(&signature.function_name)
+    );
+    let resolver = if call_args.is_empty() {
+        format!("    {}: () => {}(),\n", field_name, function)
+    } else {
+        format!(
+            "    {}: (_parent: unknown, args: Record<string, any>) => {}({}),\n",
+            field_name,
+            function,
+            call_args.join(", ")
+        )
+    };
+    (field, resolver)
+}
+
+/// Create a GraphQL schema (SDL), at `ui_dir/schema.graphql`, of the HTTP handlers of the
+///  package's hyperapp processes, as described by the WIT files in `api_dir`: `GET` handlers
+///  are queries, others mutations. Their resolvers, calling the handlers through the
+///  TypeScript caller-utils (which must be in `ui_dir`), go in `ui_dir/graphql-resolvers.ts`.
+///  Returns the schema, or `None` (writing nothing) if there are no HTTP handlers
+#[instrument(level = "trace", skip_all)]
+pub fn create_graphql_schema(api_dir: &Path, ui_dir: &Path) -> Result<Option<String>> {
+    let mut all_wit_types = Vec::new();
+    for (hyperapp_name, wit_files) in find_hyperapp_wit_files(api_dir) {
+        for wit_file in wit_files {
+            all_wit_types.push((hyperapp_name.clone(), parse_wit_file(&wit_file)?));
+        }
+    }
+
+    let mut kinds = BTreeMap::new();
+    for (_, wit_types) in &all_wit_types {
+        for (name, rhs) in &wit_types.aliases {
+            kinds.insert(to_pascal_case(name), GraphqlKind::Alias(rhs.clone()));
+        }
+        for record in &wit_types.records {
+            kinds.insert(to_pascal_case(&record.name), GraphqlKind::Record);
+        }
+        for enum_def in &wit_types.enums {
+            kinds.insert(to_pascal_case(&enum_def.name), GraphqlKind::Enum);
+        }
+        for variant in &wit_types.variants {
+            let kind = if variant.cases.iter().all(|case| case.data_type.is_none()) {
+                GraphqlKind::Enum
+            } else {
+                GraphqlKind::Json
+            };
+            kinds.insert(to_pascal_case(&variant.name), kind);
+        }
+    }
+
+    let mut queries = (String::new(), String::new());
+    let mut mutations = (String::new(), String::new());
+    let mut field_names: BTreeMap<String, String> = BTreeMap::new();
+    for (hyperapp_name, wit_types) in &all_wit_types {
+        for signature in &wit_types.signatures {
+            if signature.attr_type != "http" {
+                continue;
+            }
+            let field_name = to_camel_case(&signature.function_name);
+            if let Some(other) = field_names.insert(field_name.clone(), hyperapp_name.clone()) {
+                if other != *hyperapp_name {
+                    bail!(
+                        "HTTP handler {} of both {} and {} would be the same GraphQL field\n\
+                         \n\
+                         Suggestion: Rename one of the handlers, or unset `[api] graphql` in kit.toml.",
+                        field_name,
+                        other,
+                        hyperapp_name
+                    );
+                }
+            }
+            let (field, resolver) = generate_operation(hyperapp_name, signature, &kinds);
+            let operations = if signature.http_method.as_deref() == Some("GET") {
+                &mut queries
+            } else {
+                &mut mutations
+            };
+            operations.0.push_str(&field);
+            operations.1.push_str(&resolver);
+        }
+    }
+    if field_names.is_empty() {
+        debug!("No HTTP handlers found in WIT files, skipping GraphQL schema generation");
+        return Ok(None);
+    }
+
+    let mut schema = GRAPHQL_SCALARS.to_string();
+    let mut type_names = BTreeMap::new();
+    for (_, wit_types) in &all_wit_types {
+        for enum_def in &wit_types.enums {
+            let cases: Vec<String> = enum_def.cases.iter().map(case_wire_name).collect();
+            type_names.insert(
+                to_pascal_case(&enum_def.name),
+                format!(
+                    "{}enum {} {{\n  {}\n}}\n",
+                    generate_description(&enum_def.docs, ""),
+                    to_pascal_case(&enum_def.name),
+                    cases.join("\n  ")
+                ),
+            );
+        }
+        for variant in &wit_types.variants {
+            if let Some(GraphqlKind::Enum) = kinds.get(&to_pascal_case(&variant.name)) {
+                let cases: Vec<String> = variant.cases.iter().map(case_wire_name).collect();
+                type_names.insert(
+                    to_pascal_case(&variant.name),
+                    format!(
+                        "{}enum {} {{\n  {}\n}}\n",
+                        generate_description(&variant.docs, ""),
+                        to_pascal_case(&variant.name),
+                        cases.join("\n  ")
+                    ),
+                );
+            }
+        }
+        for record in &wit_types.records {
+            let type_name = to_pascal_case(&record.name);
+            let mut output_fields = String::new();
+            let mut input_fields = String::new();
+            for field in &record.fields {
+                let json_name = field
+                    .wire_name
+                    .clone()
+                    .unwrap_or_else(|| to_snake_case(&field.name));
+                let description = generate_description(&field.docs, "  ");
+                output_fields.push_str(&format!(
+                    "{}  {}: {}\n",
+                    description,
+                    json_name,
+                    wit_type_to_graphql(&field.wit_type, &kinds, false)
+                ));
+                input_fields.push_str(&format!(
+                    "{}  {}: {}\n",
+                    description,
+                    json_name,
+                    wit_type_to_graphql(&field.wit_type, &kinds, true)
+                ));
+            }
+            let description = generate_description(&record.docs, "");
+            type_names.insert(
+                type_name.clone(),
+                format!(
+                    "{0}type {1} {{\n{2}}}\n\n{0}input {1}Input {{\n{3}}}\n",
+                    description, type_name, output_fields, input_fields
+                ),
+            );
+        }
+    }
+    for type_def in type_names.values() {
+        schema.push_str(&format!("\n{}", type_def));
+    }
+
+    // A schema must have a query type, even if all handlers are mutations
+    if queries.0.is_empty() {
+        queries.0 = "  \"\"\"\n  Always null: all HTTP handlers are mutations\n  \"\"\"\n  _empty: Boolean\n".to_string();
+    }
+    schema.push_str(&format!("\ntype Query {{\n{}}}\n", queries.0));
+    let mut resolvers = format!(
+        "{}export const resolvers = {{\n  Query: {{\n{}  }},\n",
+        RESOLVERS_HEADER, queries.1
+    );
+    if !mutations.0.is_empty() {
+        schema.push_str(&format!("\ntype Mutation {{\n{}}}\n", mutations.0));
+        resolvers.push_str(&format!("  Mutation: {{\n{}  }},\n", mutations.1));
+    }
+    resolvers.push_str("};\n");
+
+    fs::create_dir_all(ui_dir)?;
+    let schema_path = ui_dir.join(GRAPHQL_SCHEMA_NAME);
+    fs::write(&schema_path, &schema)
+        .with_context(|| format!("Failed to write GraphQL schema: {}", schema_path.display()))?;
+    let resolvers_path = ui_dir.join(GRAPHQL_RESOLVERS_NAME);
+    fs::write(&resolvers_path, &resolvers).with_context(|| {
+        format!(
+            "Failed to write GraphQL resolvers: {}",
+            resolvers_path.display()
+        )
+    })?;
+
+    info!(
+        "Successfully created GraphQL schema at {}",
+        schema_path.display()
+    );
+    Ok(Some(schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_graphql_schema_maps_handlers_to_operations() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface my-app {
+    /// An item for sale
+    record item {
+        id: u64,
+        // serde: displayName
+        name: option<string>,
+        color: color
+    }
+
+    enum color {
+        red,
+        dark-blue
+    }
+
+    // Function signature for: get-item (http)
+    // HTTP: GET /api/item
+    // args: (item-id: u64)
+    record get-item-signature-http {
+        target: string,
+        arg-types: tuple<u64>,
+        returning: result<item, string>
+    }
+
+    /// Add an item
+    // Function signature for: add-item (http)
+    // args: (item: item, count: u128)
+    record add-item-signature-http {
+        target: string,
+        arg-types: tuple<item, string>,
+        returning: list<item>
+    }
+}
+"#;
+        fs::write(api_dir.join("my-app.wit"), wit_content).unwrap();
+
+        let ui_dir = temp_dir.path().join("target/ui");
+        let schema = create_graphql_schema(&api_dir, &ui_dir).unwrap().unwrap();
+        assert_eq!(
+            fs::read_to_string(ui_dir.join(GRAPHQL_SCHEMA_NAME)).unwrap(),
+            schema
+        );
+
+        assert!(schema.contains("enum Color {\n  Red\n  DarkBlue\n}"));
+        assert!(schema
+            .contains("type Item {\n  id: BigInt!\n  displayName: String\n  color: Color!\n}"));
+        assert!(schema.contains("input ItemInput {\n"));
+        assert!(schema.contains("type Query {\n  getItem(itemId: BigInt!): Item!\n}"));
+        assert!(schema.contains(
+            "type Mutation {\n  \"\"\"\n  Add an item\n  \"\"\"\n  addItem(item: ItemInput!, count: BigInt!): [Item!]!\n}"
+        ));
+
+        let resolvers = fs::read_to_string(ui_dir.join(GRAPHQL_RESOLVERS_NAME)).unwrap();
+        assert!(resolvers.contains(
+            "    getItem: (_parent: unknown, args: Record<string, any>) => api.MyApp.get_item(args.itemId),\n"
+        ));
+        assert!(resolvers.contains("api.MyApp.add_item(args.item, BigInt(args.count))"));
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use super::run_command;
+
+pub const KIT_TOML_NAME: &str = "kit.toml";
+
+/// Project-level build configuration, read from `kit.toml` in the package dir
+///
+/// ```toml
+/// [hooks]
+/// pre-build = "protoc --rust_out=my-process/src/ proto/*.proto"
+/// post-build = "./scripts/check-sizes.sh"
+/// post-package = "cp target/*.zip dist/"
+///
+/// [wit]
+/// rev = "v1.0.0"
+///
+/// [cargo]
+/// args = ["--config", "profile.release.opt-level='s'"]
+/// rustflags = "-C target-feature=+simd128"
+///
+/// [processes.my-process]
+/// features = ["simulation-mode"]
+/// default-features = true
+///
+/// [processes.my-js-process]
+/// worlds = ["process-v1", "process-v2"]
+///
+/// [processes.my-hyperapp-process]
+/// wit-interface = "my-api"
+///
+/// [processes.my-hyperapp-process.wit-interfaces]
+/// my-api-admin = ["set_config", "ban_user"]
+///
+/// [processes.my-chat-process]
+/// ws-message = "ChatMessage"
+/// ws-push = "ChatEvent"
+/// world-includes = ["process-v1", "my-runtime-v0"]
+///
+/// [processes.my-chat-process.wit-type-mappings]
+/// ObjectId = "string"
+///
+/// [env]
+/// API_BASE_URL = "https://api.example.com"
+///
+/// [api]
+/// versioned = true
+/// shared-types = true
+/// serve-openapi = true
+/// graphql = true
+///
+/// [caller-utils]
+/// path = "crates/my-package-client"
+/// name = "my-package-client"
+/// version = "0.2.0"
+/// license = "MIT"
+/// registry = "my-registry"
+/// timeout = 60
+/// commit-generated = true
+///
+/// [caller-utils.dependencies]
+/// strum = { version = "0.26", features = ["derive"] }
+///
+/// [caller-utils.wit-bindgen]
+/// additional-derives = ["PartialEq", "Eq", "Hash", "strum::Display"]
+/// ownership = "borrowing-duplicate-if-necessary"
+///
+/// [typescript]
+/// zod = true
+/// react-hooks = "react-query"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct KitToml {
+    pub hooks: Hooks,
+    pub wit: WitConfig,
+    pub cargo: CargoConfig,
+    /// Process dir name -> per-process build configuration
+    pub processes: HashMap<String, ProcessConfig>,
+    /// Build-time constants set in the environment of Rust, JS and UI builds
+    pub env: BTreeMap<String, String>,
+    pub caller_utils: CallerUtilsConfig,
+    pub api: ApiConfig,
+    pub typescript: TypeScriptConfig,
+}
+
+/// Shell commands run from the package dir at stages of `kit build`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Hooks {
+    /// Before anything is built
+    pub pre_build: Option<String>,
+    /// After UI and processes are built, before `pkg/` is zipped
+    pub post_build: Option<String>,
+    /// After `pkg/` is zipped
+    pub post_package: Option<String>,
+}
+
+/// Where to get `hyperware.wit` from; at most one may be set [default: based on `wit_version`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct WitConfig {
+    /// URL of the WIT file
+    pub url: Option<String>,
+    /// Revision (tag, branch or commit) of the hyperware-wit repo
+    pub rev: Option<String>,
+    /// Local path to the WIT file, relative to the package dir
+    pub path: Option<PathBuf>,
+}
+
+/// Extra configuration for `cargo build` of Rust processes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CargoConfig {
+    /// Extra arguments appended to `cargo build`
+    pub args: Vec<String>,
+    /// Extra flags for rustc, added to those of `.cargo/config.toml` or `RUSTFLAGS`
+    pub rustflags: Option<String>,
+}
+
+/// Per-process configuration for `cargo build` of a Rust process
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ProcessConfig {
+    /// Features always enabled for this process, in addition to those from `--features`.
+    ///  Unlike `--features`, these are not dropped if missing from the process
+    pub features: Vec<String>,
+    /// Whether to build with the process's default features [default: false]
+    pub default_features: bool,
+    /// WIT worlds to build the process for, instead of the single world in `target/wit/`
+    ///  (or `--world`). The first is built into `pkg/<process>.wasm`, the rest into
+    ///  `pkg/<process>-<world>.wasm`. Rust processes pick their world in
+    ///  `wit_bindgen::generate!`, so are built with a feature of the same name as each world
+    pub worlds: Vec<String>,
+    /// Name of the WIT interface generated for a hyperapp process (which must be kebab-case)
+    ///  [default: its state type name, kebab-cased, without any `State` suffix]
+    pub wit_interface: Option<String>,
+    /// Further WIT interfaces to split a hyperapp process's API into: interface name (kebab-case)
+    ///  -> the handlers (Rust method names) it holds. Other handlers stay in the default interface
+    pub wit_interfaces: BTreeMap<String, Vec<String>>,
+    /// Rust type of the messages of a hyperapp process's `#[ws]` handlers. If set, their WIT
+    ///  signatures are generated, & the TypeScript caller-utils get typed WebSocket clients
+    ///  [default: `#[ws]` handlers are left out of the WIT]
+    pub ws_message: Option<String>,
+    /// Rust type of the events a hyperapp process pushes to its WebSocket clients (e.g. an enum
+    ///  broadcast with `ws_push_all_channels`). If set, a `push` WIT signature carrying them is generated,
+    ///  & the TypeScript caller-utils get a typed event emitter of them [default: none]
+    pub ws_push: Option<String>,
+    /// WIT types to alias Rust types (by name) that the process's hyperapp API uses but does not
+    ///  define to, e.g. `ObjectId = "string"`. `uuid::Uuid`, `chrono::DateTime`, `url::Url` and
+    ///  `rust_decimal::Decimal` are aliased to `string` unless mapped here
+    pub wit_type_mappings: BTreeMap<String, String>,
+    /// Worlds the generated WIT world of a hyperapp process includes, replacing any includes
+    ///  it has (its `types-` world still includes `lib`)
+    ///  [default: those it has, or `process-v1` for a new world]
+    pub world_includes: Option<Vec<String>>,
+}
+
+/// The WIT API generated by hyperapp builds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ApiConfig {
+    /// Whether to also write the generated WIT interfaces, under a `package <package>:api@<version>;`
+    ///  header, to `api/versions/<version>/` (`<version>` being the `current_version` of
+    ///  `metadata.json`), keeping those of previous versions so consumers can pin one [default: false]
+    pub versioned: bool,
+    /// Whether to define the types used by all hyperapp processes once, in a `types-<package>`
+    ///  interface that their interfaces (and other packages) `use`, rather than in each
+    ///  interface [default: false]
+    pub shared_types: bool,
+    /// Whether to also write the OpenAPI spec of the HTTP handlers, generated into
+    ///  `target/openapi.json`, to the `public/` dir of the package's UIs, so that they serve it
+    ///  at `<base URL>/openapi.json` [default: false]
+    pub serve_openapi: bool,
+    /// Whether to also generate a GraphQL schema of the HTTP handlers into
+    ///  `target/ui/schema.graphql` (`GET` handlers as queries, others as mutations), with
+    ///  resolvers calling them through the caller-utils in `target/ui/graphql-resolvers.ts`,
+    ///  for a GraphQL facade over the API [default: false]
+    pub graphql: bool,
+}
+
+/// The caller-utils crate generated by hyperapp builds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CallerUtilsConfig {
+    /// Dir to generate the crate in, relative to the package dir
+    ///  [default: `target/<package>-caller-utils`]
+    pub path: Option<PathBuf>,
+    /// [default: `<package>-caller-utils`]
+    pub name: Option<String>,
+    /// [default: `0.1.0`]
+    pub version: Option<String>,
+    /// SPDX license expression of the crate [default: none]
+    pub license: Option<String>,
+    /// Whether the crate may be published to a registry; if so, processes depend on it by
+    ///  `version` as well as `path`, so that it can be published, or `[patch]`ed in other
+    ///  workspaces [default: false]
+    pub publish: bool,
+    /// Registry (as named in `.cargo/config.toml`) that `kit api publish-client` publishes the
+    ///  crate to [default: none: it is vendored into `clients/`]
+    pub registry: Option<String>,
+    /// Seconds the crate's RPC stubs wait for a response, unless called through their
+    ///  `_with_timeout` variants: cross-node calls may need more than local ones [default: 30]
+    pub timeout: Option<u64>,
+    /// Dependencies added to the crate's, e.g. for the `additional-derives` of
+    ///  `[caller-utils.wit-bindgen]`, as in a Cargo.toml `[dependencies]` table
+    pub dependencies: BTreeMap<String, toml::Value>,
+    /// Whether to expand the crate's bindings (with the `wit-bindgen` CLI) & stubs into its
+    ///  `src/generated/`, rather than generating them at compile time, so that a crate at a
+    ///  checked-in `path` can be committed & read by rust-analyzer and reviewers [default: false]
+    pub commit_generated: bool,
+    /// Options of the crate's `wit_bindgen::generate!`
+    pub wit_bindgen: WitBindgenConfig,
+}
+
+/// The TypeScript caller-utils (and SDK) generated by hyperapp builds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TypeScriptConfig {
+    /// Whether to generate a zod schema for each type, & validate HTTP responses & WebSocket
+    ///  messages against them, throwing an `ApiError` on a mismatch. The UI must depend on
+    ///  `zod` [default: false]
+    pub zod: bool,
+    /// Whether to generate a `use<Handler>()` React hook for each HTTP handler, into
+    ///  `target/ui/caller-utils-hooks.ts`: `react`, for hooks tracking the data, error & loading
+    ///  state of calls, or `react-query`, for `useQuery` (`GET` handlers) & `useMutation` (other
+    ///  handlers) hooks, for which the UI must depend on `@tanstack/react-query`
+    ///  [default: no hooks]
+    pub react_hooks: Option<String>,
+}
+
+/// Options of a `wit_bindgen::generate!` that kit generates
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct WitBindgenConfig {
+    /// Derives added to the generated types, after the `serde::Deserialize`, `serde::Serialize`
+    ///  & `process_macros::SerdeJsonInto` they always have
+    pub additional_derives: Vec<String>,
+    /// How generated types own their data: `owning`, `borrowing` or
+    ///  `borrowing-duplicate-if-necessary` [default: `owning`]
+    pub ownership: Option<String>,
+    /// Whether to generate async bindings [default: false]
+    #[serde(rename = "async")]
+    pub async_support: bool,
+}
+
+/// Read `kit.toml` from the package dir, defaulting if it does not exist
+#[instrument(level = "trace", skip_all)]
+pub fn read_kit_toml(package_dir: &Path) -> Result<KitToml> {
+    let kit_toml_path = package_dir.join(KIT_TOML_NAME);
+    if !kit_toml_path.exists() {
+        return Ok(KitToml::default());
+    }
+    let kit_toml = fs::read_to_string(&kit_toml_path)?;
+    toml::from_str(&kit_toml).wrap_err_with(|| format!("Failed to parse {kit_toml_path:?}"))
+}
+
+fn get_git_sha(package_dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(package_dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Seconds since epoch: `$SOURCE_DATE_EPOCH` if set, else the time of the `HEAD` commit
+///  (so that reproducible builds stay reproducible), else now
+fn get_build_timestamp(package_dir: &Path) -> String {
+    if let Ok(timestamp) = std::env::var("SOURCE_DATE_EPOCH") {
+        return timestamp;
+    }
+    Command::new("git")
+        .args(["log", "-1", "--format=%ct"])
+        .current_dir(package_dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|timestamp| !timestamp.is_empty())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default()
+                .to_string()
+        })
+}
+
+/// Get the build-time constants to set in the environment of Rust, JS and UI builds:
+///  `KIT_GIT_SHA` and `KIT_BUILD_TIMESTAMP`, plus the `[env]` table of `kit.toml`,
+///  which takes precedence. In Rust, read these with `env!()`; UI builds additionally
+///  get each with a `VITE_` prefix so Vite exposes it as `import.meta.env.VITE_*`
+#[instrument(level = "trace", skip_all)]
+pub fn get_build_env(
+    package_dir: &Path,
+    env: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>> {
+    let mut build_env = BTreeMap::new();
+    build_env.insert(
+        "KIT_GIT_SHA".to_string(),
+        get_git_sha(package_dir).unwrap_or_else(|| "unknown".to_string()),
+    );
+    build_env.insert(
+        "KIT_BUILD_TIMESTAMP".to_string(),
+        get_build_timestamp(package_dir),
+    );
+    for (key, value) in env {
+        if key.is_empty() || key.contains('=') {
+            return Err(eyre!("Invalid {KIT_TOML_NAME} `[env]` key {key:?}"));
+        }
+        build_env.insert(key.clone(), value.clone());
+    }
+    Ok(build_env)
+}
+
+/// `build_env` plus a `VITE_`-prefixed copy of each variable, for UI builds
+pub fn get_ui_build_env(build_env: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    let mut ui_build_env = build_env.clone();
+    for (key, value) in build_env {
+        if !key.starts_with("VITE_") {
+            ui_build_env.insert(format!("VITE_{key}"), value.clone());
+        }
+    }
+    ui_build_env
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn run_hook(
+    package_dir: &Path,
+    hook_name: &str,
+    hook: &Option<String>,
+    verbose: bool,
+) -> Result<()> {
+    let Some(hook) = hook else {
+        return Ok(());
+    };
+    info!("Running {hook_name} hook `{hook}`...");
+    let output = run_command(
+        Command::new("bash")
+            .args(&["-c", hook])
+            .current_dir(package_dir),
+        verbose,
+    )
+    .wrap_err_with(|| format!("{KIT_TOML_NAME} {hook_name} hook failed"))?;
+    if let Some((stdout, stderr)) = output {
+        if !stdout.is_empty() {
+            info!("{}", stdout);
+        }
+        if !stderr.is_empty() {
+            info!("{}", stderr);
+        }
+    }
+    info!("Done running {hook_name} hook.");
+    Ok(())
+}
@@ -0,0 +1,689 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use tracing::{debug, info, instrument};
+
+use super::caller_utils_ts_generator::{
+    case_wire_name, find_hyperapp_wit_files, parse_args_comment, parse_returns_comment,
+    parse_tuple_types, parse_wit_file, split_top_level, to_camel_case, to_pascal_case,
+    to_snake_case, SignatureStruct, WitEnum, WitRecord, WitVariant,
+};
+
+const KOTLIN_KEYWORDS: [&str; 28] = [
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+const KOTLIN_HEADER: &str = r#"// Typed client for the HTTP API of {package_name}, generated from its WIT by
+//  `kit build --kotlin-client`. Do NOT edit this file: it is machine generated.
+//
+// Types are `@Serializable` as serde (de)serializes them: variants are externally tagged.
+// Requires kotlinx-serialization-json & okhttp.
+package {kotlin_package}
+
+import kotlinx.serialization.KSerializer
+import kotlinx.serialization.SerialName
+import kotlinx.serialization.Serializable
+import kotlinx.serialization.descriptors.SerialDescriptor
+import kotlinx.serialization.descriptors.buildClassSerialDescriptor
+import kotlinx.serialization.encoding.Decoder
+import kotlinx.serialization.encoding.Encoder
+import kotlinx.serialization.json.Json
+import kotlinx.serialization.json.JsonArray
+import kotlinx.serialization.json.JsonDecoder
+import kotlinx.serialization.json.JsonElement
+import kotlinx.serialization.json.JsonEncoder
+import kotlinx.serialization.json.JsonNull
+import kotlinx.serialization.json.JsonObject
+import kotlinx.serialization.json.JsonPrimitive
+import kotlinx.serialization.json.buildJsonArray
+import kotlinx.serialization.json.buildJsonObject
+import kotlinx.serialization.json.jsonObject
+import kotlinx.serialization.serializer
+import okhttp3.MediaType.Companion.toMediaType
+import okhttp3.OkHttpClient
+import okhttp3.Request
+import okhttp3.RequestBody.Companion.toRequestBody
+
+/** (De)serializes a variant as the JSON element its cases are externally tagged in */
+abstract class TaggedSerializer<T>(name: String) : KSerializer<T> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor(name)
+
+    abstract fun toJson(value: T): JsonElement
+
+    abstract fun fromJson(element: JsonElement): T
+
+    override fun serialize(encoder: Encoder, value: T) =
+        (encoder as JsonEncoder).encodeJsonElement(toJson(value))
+
+    override fun deserialize(decoder: Decoder): T =
+        fromJson((decoder as JsonDecoder).decodeJsonElement())
+}
+
+/** A WIT `result`: `{ "Ok": value }` or `{ "Err": error }` */
+@Serializable(with = ApiResultSerializer::class)
+sealed interface ApiResult<out T, out E> {
+    data class Ok<T>(val value: T) : ApiResult<T, Nothing>
+
+    data class Err<E>(val error: E) : ApiResult<Nothing, E>
+}
+
+class ApiResultSerializer<T, E>(
+    private val okSerializer: KSerializer<T>,
+    private val errSerializer: KSerializer<E>,
+) : TaggedSerializer<ApiResult<T, E>>("ApiResult") {
+    override fun toJson(value: ApiResult<T, E>): JsonElement = when (value) {
+        is ApiResult.Ok -> buildJsonObject { put("Ok", json.encodeToJsonElement(okSerializer, value.value)) }
+        is ApiResult.Err -> buildJsonObject { put("Err", json.encodeToJsonElement(errSerializer, value.error)) }
+    }
+
+    override fun fromJson(element: JsonElement): ApiResult<T, E> {
+        val tagged = element.jsonObject
+        return tagged["Ok"]?.let { ApiResult.Ok(json.decodeFromJsonElement(okSerializer, it)) }
+            ?: ApiResult.Err(json.decodeFromJsonElement(errSerializer, tagged.getValue("Err")))
+    }
+}
+"#;
+
+/// The client, sending requests to the node & deserializing the responses, with an accessor per
+///  process substituted for `{namespaces}`
+const KOTLIN_CLIENT: &str = r#"/** The node responded with an error status */
+class ApiException(val status: Int, val body: String) :
+    Exception("HTTP request failed with status $status: $body")
+
+internal val json = Json { ignoreUnknownKeys = true }
+
+/**
+ * Client for the HTTP API at [baseUrl], the URL of a process on a node,
+ * e.g. `http://localhost:8080/my-process:my-package:publisher.os`.
+ * Requests block: call them off the main thread.
+ */
+class Client(
+    baseUrl: String,
+    /** Sends the requests, e.g. with the node's auth cookie */
+    val http: OkHttpClient = OkHttpClient(),
+) {
+    val baseUrl: String = baseUrl.trimEnd('/')
+{namespaces}
+    /** Send `{ "<name>": args }` to the handler bound to [method] & [path], deserializing its response */
+    fun <R> request(
+        method: String,
+        path: String,
+        name: String,
+        args: JsonElement,
+        serializer: KSerializer<R>,
+    ): R {
+        val builder = Request.Builder().url("$baseUrl/${path.trimStart('/')}")
+        // Bodies of GET requests are not read by the node
+        if (method == "GET" || method == "HEAD") {
+            builder.method(method, null)
+        } else {
+            val body = JsonObject(mapOf(name to args)).toString()
+            builder.method(method, body.toRequestBody("application/json".toMediaType()))
+        }
+        http.newCall(builder.build()).execute().use { response ->
+            val body = response.body?.string().orEmpty()
+            if (!response.isSuccessful) {
+                throw ApiException(response.code, body)
+            }
+            return json.decodeFromString(serializer, body)
+        }
+    }
+}
+"#;
+
+// A Kotlin identifier for a WIT identifier: lowerCamelCase, in backticks if a keyword
+fn to_kotlin_name(name: &str) -> String {
+    let name = to_camel_case(name);
+    if KOTLIN_KEYWORDS.contains(&name.as_str()) {
+        format!("`{}`", name)
+    } else {
+        name
+    }
+}
+
+// KDoc (ending in a newline) from WIT doc comments
+fn generate_kdoc(docs: &[String], indent: &str) -> String {
+    match docs {
+        [] => String::new(),
+        [doc] => format!("{}/** {} */\n", indent, doc),
+        docs => {
+            let mut kdoc = format!("{}/**\n", indent);
+            for doc in docs {
+                if doc.is_empty() {
+                    kdoc.push_str(&format!("{} *\n", indent));
+                } else {
+                    kdoc.push_str(&format!("{} * {}\n", indent, doc));
+                }
+            }
+            kdoc.push_str(&format!("{} */\n", indent));
+            kdoc
+        }
+    }
+}
+
+// Convert a WIT type (including the richer types of signature comments) to a Kotlin type
+fn wit_type_to_kotlin(wit_type: &str) -> String {
+    match wit_type {
+        "s8" => "Byte".to_string(),
+        "s16" => "Short".to_string(),
+        "s32" => "Int".to_string(),
+        "s64" => "Long".to_string(),
+        "u8" => "UByte".to_string(),
+        "u16" => "UShort".to_string(),
+        "u32" => "UInt".to_string(),
+        "u64" => "ULong".to_string(),
+        "f32" => "Float".to_string(),
+        "f64" => "Double".to_string(),
+        "bool" => "Boolean".to_string(),
+        "string" | "char" | "address" => "String".to_string(),
+        // JSON numbers past 64 bits, & values without a Kotlin type of their own
+        "s128" | "u128" | "_" | "value" => "JsonElement".to_string(),
+        t if t.starts_with("list<") => {
+            format!("List<{}>", wit_type_to_kotlin(&t[5..t.len() - 1]))
+        }
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).as_slice() {
+            [inner_type, _] => format!("List<{}>", wit_type_to_kotlin(inner_type)),
+            _ => "JsonArray".to_string(),
+        },
+        t if t.starts_with("option<") => {
+            format!("{}?", wit_type_to_kotlin(&t[7..t.len() - 1]))
+        }
+        t if t.starts_with("result<") => match split_top_level(&t[7..t.len() - 1]).as_slice() {
+            [ok_type, err_type] => format!(
+                "ApiResult<{}, {}>",
+                wit_type_to_kotlin(ok_type),
+                wit_type_to_kotlin(err_type)
+            ),
+            [ok_type] => format!("ApiResult<{}, JsonElement>", wit_type_to_kotlin(ok_type)),
+            _ => "ApiResult<JsonElement, JsonElement>".to_string(),
+        },
+        // Maps are JSON objects, whatever their keys
+        t if t.starts_with("map<") => match split_top_level(&t[4..t.len() - 1]).as_slice() {
+            [_, value_type] => format!("Map<String, {}>", wit_type_to_kotlin(value_type)),
+            _ => "JsonObject".to_string(),
+        },
+        t if t.starts_with("tuple<") => "JsonArray".to_string(),
+        t => to_pascal_case(t.trim_start_matches('%')),
+    }
+}
+
+// A data class of `(name, WIT type, wire name, docs)` fields
+fn generate_kotlin_data_class(
+    type_name: &str,
+    fields: &[(String, String, String, Vec<String>)],
+    docs: &[String],
+    supertype: Option<&str>,
+    indent: &str,
+) -> String {
+    let mut properties = String::new();
+    for (name, wit_type, wire_name, field_docs) in fields {
+        properties.push_str(&generate_kdoc(field_docs, &format!("{}    ", indent)));
+        properties.push_str(&format!(
+            "{}    @SerialName({:?}) val {}: {},\n",
+            indent,
+            wire_name,
+            to_kotlin_name(name),
+            wit_type_to_kotlin(wit_type)
+        ));
+    }
+    let supertype = supertype
+        .map(|supertype| format!(" : {}", supertype))
+        .unwrap_or_default();
+    if fields.is_empty() {
+        return format!(
+            "{}{}@Serializable\n{}object {}{}\n",
+            generate_kdoc(docs, indent),
+            indent,
+            indent,
+            type_name,
+            supertype
+        );
+    }
+    format!(
+        "{0}{1}@Serializable\n{1}data class {2}(\n{3}{1}){4}\n",
+        generate_kdoc(docs, indent),
+        indent,
+        type_name,
+        properties,
+        supertype
+    )
+}
+
+fn generate_kotlin_record(record: &WitRecord) -> String {
+    let fields: Vec<(String, String, String, Vec<String>)> = record
+        .fields
+        .iter()
+        .map(|field| {
+            (
+                field.name.clone(),
+                field.wit_type.clone(),
+                field
+                    .wire_name
+                    .clone()
+                    .unwrap_or_else(|| to_snake_case(&field.name)),
+                field.docs.clone(),
+            )
+        })
+        .collect();
+    generate_kotlin_data_class(
+        &to_pascal_case(&record.name),
+        &fields,
+        &record.docs,
+        None,
+        "",
+    )
+}
+
+// Enums, and variants without data, are strings
+fn generate_kotlin_enum_class(name: &str, cases: &[(String, String)], docs: &[String]) -> String {
+    let cases: Vec<String> = cases
+        .iter()
+        .map(|(case_name, wire_name)| {
+            format!(
+                "    @SerialName({:?}) {}",
+                wire_name,
+                to_pascal_case(case_name)
+            )
+        })
+        .collect();
+    format!(
+        "{}@Serializable\nenum class {} {{\n{},\n}}\n",
+        generate_kdoc(docs, ""),
+        to_pascal_case(name),
+        cases.join(",\n")
+    )
+}
+
+fn generate_kotlin_enum(enum_def: &WitEnum) -> String {
+    let cases: Vec<(String, String)> = enum_def
+        .cases
+        .iter()
+        .map(|case| (case.name.clone(), case_wire_name(case)))
+        .collect();
+    generate_kotlin_enum_class(&enum_def.name, &cases, &enum_def.docs)
+}
+
+// Variants with data are sealed interfaces, externally tagged by their serializer:
+//  `{ "<Case>": data }`, or `"<Case>"` for cases without data
+fn generate_kotlin_variant(variant: &WitVariant) -> String {
+    if variant.cases.iter().all(|case| case.data_type.is_none()) {
+        let cases: Vec<(String, String)> = variant
+            .cases
+            .iter()
+            .map(|case| (case.name.clone(), case_wire_name(case)))
+            .collect();
+        return generate_kotlin_enum_class(&variant.name, &cases, &variant.docs);
+    }
+
+    let type_name = to_pascal_case(&variant.name);
+    let mut cases = String::new();
+    let mut unit_decodes = String::new();
+    let mut data_decodes = String::new();
+    let mut encodes = String::new();
+    for case in &variant.cases {
+        let case_name = to_pascal_case(&case.name);
+        let wire_name = case_wire_name(case);
+        match case.data_type.as_deref().map(str::trim) {
+            None => {
+                cases.push_str(&format!(
+                    "\n    data object {} : {}\n",
+                    case_name, type_name
+                ));
+                unit_decodes.push_str(&format!(
+                    "                {:?} -> {}.{}\n",
+                    wire_name, type_name, case_name
+                ));
+                encodes.push_str(&format!(
+                    "        is {}.{} -> JsonPrimitive({:?})\n",
+                    type_name, case_name, wire_name
+                ));
+            }
+            Some(data_type) => {
+                let serializer = if data_type.starts_with("record") {
+                    let inline_record = data_type
+                        .trim_start_matches("record")
+                        .trim()
+                        .trim_start_matches('{')
+                        .trim_end_matches('}');
+                    let fields: Vec<(String, String, String, Vec<String>)> =
+                        split_top_level(inline_record)
+                            .into_iter()
+                            .filter_map(|field| {
+                                let (name, wit_type) = field.split_once(':')?;
+                                let name = name.trim().trim_start_matches('%').to_string();
+                                let wire_name = to_snake_case(&name);
+                                Some((name, wit_type.trim().to_string(), wire_name, vec![]))
+                            })
+                            .collect();
+                    cases.push_str(&format!(
+                        "\n{}",
+                        generate_kotlin_data_class(
+                            &case_name,
+                            &fields,
+                            &[],
+                            Some(&type_name),
+                            "    "
+                        )
+                    ));
+                    format!("{}.{}.serializer()", type_name, case_name)
+                } else {
+                    cases.push_str(&format!(
+                        "\n    data class {}(val value: {}) : {}\n",
+                        case_name,
+                        wit_type_to_kotlin(data_type),
+                        type_name
+                    ));
+                    format!("serializer<{}>()", wit_type_to_kotlin(data_type))
+                };
+                let value = if data_type.starts_with("record") {
+                    "value"
+                } else {
+                    "value.value"
+                };
+                let decoded = format!("json.decodeFromJsonElement({}, data)", serializer);
+                data_decodes.push_str(&format!(
+                    "            {:?} -> {}\n",
+                    wire_name,
+                    if data_type.starts_with("record") {
+                        decoded
+                    } else {
+                        format!("{}.{}({})", type_name, case_name, decoded)
+                    }
+                ));
+                encodes.push_str(&format!(
+                    "        is {0}.{1} -> buildJsonObject {{ put({2:?}, json.encodeToJsonElement({3}, {4})) }}\n",
+                    type_name, case_name, wire_name, serializer, value
+                ));
+            }
+        }
+    }
+    let unit_decode = if unit_decodes.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "        if (element is JsonPrimitive) {{\n            return when (element.content) {{\n{}                else -> throw IllegalArgumentException(\"Unknown {} case: ${{element.content}}\")\n            }}\n        }}\n",
+            unit_decodes, type_name
+        )
+    };
+    format!(
+        "{0}@Serializable(with = {1}Serializer::class)\nsealed interface {1} {{{2}}}\n\nobject {1}Serializer : TaggedSerializer<{1}>({1:?}) {{\n    override fun toJson(value: {1}): JsonElement = when (value) {{\n{3}    }}\n\n    override fun fromJson(element: JsonElement): {1} {{\n{4}        val (tag, data) = element.jsonObject.entries.single()\n        return when (tag) {{\n{5}            else -> throw IllegalArgumentException(\"Unknown {1} case: $tag\")\n        }}\n    }}\n}}\n",
+        generate_kdoc(&variant.docs, ""),
+        type_name,
+        cases,
+        encodes,
+        unit_decode,
+        data_decodes
+    )
+}
+
+// Generate the method of a `<Hyperapp>Api` calling an HTTP handler; the body is the
+//  `{ "<Handler>": args }` the process deserializes
+fn generate_kotlin_method(signature: &SignatureStruct) -> String {
+    let args = signature
+        .args_comment
+        .as_deref()
+        .map(parse_args_comment)
+        .unwrap_or_default();
+
+    let mut params = Vec::new();
+    let mut encoded_args = Vec::new();
+    let mut return_type = "JsonElement".to_string();
+    for field in &signature.fields {
+        if field.name == "arg-types" {
+            for (i, wit_type) in parse_tuple_types(&field.wit_type).iter().enumerate() {
+                // The args comment has the richer types (maps, arrays, 128-bit integers)
+                let (param_name, wit_type) = match args.get(i) {
+                    Some((name, arg_type)) => (to_kotlin_name(name), arg_type.as_str()),
+                    None => (format!("arg{}", i), wit_type.as_str()),
+                };
+                params.push(format!("{}: {}", param_name, wit_type_to_kotlin(wit_type)));
+                encoded_args.push(format!(
+                    "json.encodeToJsonElement(serializer<{}>(), {})",
+                    wit_type_to_kotlin(wit_type),
+                    param_name
+                ));
+            }
+        } else if field.name == "returning" {
+            let wit_type = signature
+                .returns_comment
+                .as_deref()
+                .and_then(parse_returns_comment)
+                .unwrap_or_else(|| field.wit_type.clone());
+            return_type = wit_type_to_kotlin(&wit_type);
+        }
+    }
+
+    // Serde takes a single argument as is, & several as a tuple
+    let body = match encoded_args.as_slice() {
+        [] => "JsonNull".to_string(),
+        [encoded_arg] => encoded_arg.clone(),
+        encoded_args => format!(
+            "buildJsonArray {{\n            {}\n        }}",
+            encoded_args
+                .iter()
+                .map(|encoded_arg| format!("add({})", encoded_arg))
+                .collect::<Vec<_>>()
+                .join("\n            ")
+        ),
+    };
+    format!(
+        "{}    fun {}({}): {} {{\n        val args = {}\n        return client.request({:?}, {:?}, {:?}, args, serializer<{}>())\n    }}\n",
+        generate_kdoc(&signature.docs, "    "),
+        to_kotlin_name(&signature.function_name),
+        params.join(", "),
+        return_type,
+        body,
+        signature
+            .http_method
+            .as_deref()
+            .unwrap_or("POST")
+            .to_uppercase(),
+        signature.http_path.as_deref().unwrap_or("/api"),
+        to_pascal_case(&signature.function_name),
+        return_type,
+    )
+}
+
+/// Create a Kotlin source file, at `kotlin_client_path`, for calling the HTTP handlers of the
+///  package's hyperapp processes from Android (or JVM) apps, as described by the WIT files in
+///  `api_dir`: a `kotlinx.serialization` type per WIT type, and an OkHttp `Client` with an
+///  accessor per process whose methods call its handlers
+#[instrument(level = "trace", skip_all)]
+pub fn create_kotlin_client(
+    api_dir: &Path,
+    kotlin_client_path: &Path,
+    package_name: &str,
+) -> Result<()> {
+    let mut aliases = BTreeMap::new();
+    let mut type_names = BTreeSet::new();
+    let mut namespaces: Vec<(String, Vec<SignatureStruct>)> = Vec::new();
+    let mut types = Vec::new();
+
+    for (hyperapp_name, wit_files) in find_hyperapp_wit_files(api_dir) {
+        let mut signatures = Vec::new();
+        for wit_file in wit_files {
+            let wit_types = parse_wit_file(&wit_file)?;
+            signatures.extend(
+                wit_types
+                    .signatures
+                    .into_iter()
+                    .filter(|signature| signature.attr_type == "http"),
+            );
+            // Types `use`d by several interfaces are defined once
+            aliases.extend(
+                wit_types
+                    .aliases
+                    .into_iter()
+                    .filter(|(name, _)| name != "value"),
+            );
+            for enum_def in wit_types.enums {
+                if type_names.insert(enum_def.name.clone()) {
+                    types.push(generate_kotlin_enum(&enum_def));
+                }
+            }
+            for record in wit_types.records {
+                if type_names.insert(record.name.clone()) {
+                    types.push(generate_kotlin_record(&record));
+                }
+            }
+            for variant in wit_types.variants {
+                if type_names.insert(variant.name.clone()) {
+                    types.push(generate_kotlin_variant(&variant));
+                }
+            }
+        }
+        if !signatures.is_empty() {
+            namespaces.push((hyperapp_name, signatures));
+        }
+    }
+    if namespaces.is_empty() {
+        bail!(
+            "No HTTP handlers found in the WIT files of {}: nothing to generate a Kotlin client for.\n\
+             \n\
+             Suggestion: Add #[http] handlers to the hyperapp processes and build with --hyperapp.",
+            api_dir.display()
+        );
+    }
+    debug!(namespaces = namespaces.len(), "Generating Kotlin client");
+
+    let kotlin_package = format!("{}.client", to_snake_case(package_name));
+    let mut kotlin_content = KOTLIN_HEADER
+        .replace("{package_name}", package_name)
+        .replace("{kotlin_package}", &kotlin_package);
+    for (name, rhs) in &aliases {
+        kotlin_content.push_str(&format!(
+            "\ntypealias {} = {}\n",
+            to_pascal_case(name),
+            wit_type_to_kotlin(rhs)
+        ));
+    }
+    for type_def in types {
+        kotlin_content.push_str(&format!("\n{}", type_def));
+    }
+
+    let mut client_namespaces = String::new();
+    for (hyperapp_name, signatures) in &namespaces {
+        kotlin_content.push_str(&format!(
+            "\n/** HTTP handlers of {0} */\nclass {0}Api internal constructor(private val client: Client) {{\n",
+            hyperapp_name
+        ));
+        let methods: Vec<String> = signatures.iter().map(generate_kotlin_method).collect();
+        kotlin_content.push_str(&methods.join("\n"));
+        kotlin_content.push_str("}\n");
+        let mut accessor = hyperapp_name.clone();
+        if let Some(first) = accessor.get_mut(0..1) {
+            first.make_ascii_lowercase();
+        }
+        client_namespaces.push_str(&format!(
+            "\n    val {}: {}Api get() = {}Api(this)\n",
+            accessor, hyperapp_name, hyperapp_name
+        ));
+    }
+    kotlin_content.push_str(&format!(
+        "\n{}",
+        KOTLIN_CLIENT.replace("{namespaces}", &client_namespaces)
+    ));
+
+    if let Some(parent) = kotlin_client_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(kotlin_client_path, kotlin_content).with_context(|| {
+        format!(
+            "Failed to write Kotlin client: {}",
+            kotlin_client_path.display()
+        )
+    })?;
+
+    info!(
+        "Successfully created Kotlin client at {}",
+        kotlin_client_path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_kotlin_client_mirrors_http_api() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface my-app {
+    /// A node of a tree
+    record tree {
+        // serde: nodeId
+        id: u64,
+        parent: option<tree>,
+        kind: kind
+    }
+
+    variant kind {
+        leaf,
+        branch(list<tree>)
+    }
+
+    /// Get a tree
+    // Function signature for: get-tree (http)
+    // HTTP: POST /api/tree
+    // args: (id: u64, val: string)
+    record get-tree-signature-http {
+        target: string,
+        arg-types: tuple<u64, string>,
+        returning: result<tree, string>
+    }
+}
+"#;
+        fs::write(api_dir.join("my-app.wit"), wit_content).unwrap();
+
+        let kotlin_client_path = temp_dir.path().join("Client.kt");
+        create_kotlin_client(&api_dir, &kotlin_client_path, "my-package").unwrap();
+        let kotlin = fs::read_to_string(&kotlin_client_path).unwrap();
+
+        assert!(kotlin.contains("package my_package.client\n"));
+        assert!(kotlin.contains("@Serializable\ndata class Tree(\n    @SerialName(\"nodeId\") val id: ULong,\n    @SerialName(\"parent\") val parent: Tree?,\n"));
+        assert!(kotlin.contains("sealed interface Kind {\n    data object Leaf : Kind\n\n    data class Branch(val value: List<Tree>) : Kind\n}"));
+        assert!(kotlin.contains("                \"Leaf\" -> Kind.Leaf\n"));
+        assert!(kotlin.contains(
+            "    fun getTree(id: ULong, `val`: String): ApiResult<Tree, String> {\n        val args = buildJsonArray {\n            add(json.encodeToJsonElement(serializer<ULong>(), id))\n            add(json.encodeToJsonElement(serializer<String>(), `val`))\n        }\n        return client.request(\"POST\", \"/api/tree\", \"GetTree\", args, serializer<ApiResult<Tree, String>>())\n    }"
+        ));
+        assert!(kotlin.contains("    val myApp: MyAppApi get() = MyAppApi(this)\n"));
+    }
+}
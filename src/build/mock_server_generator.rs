@@ -0,0 +1,329 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use color_eyre::{eyre::WrapErr, Result};
+use serde_json::{json, Map, Value};
+use tracing::{debug, info, instrument};
+
+use super::caller_utils_ts_generator::{find_hyperapp_wit_files, parse_wit_file, to_pascal_case};
+use super::openapi_generator::{response_schema, type_schemas, wit_type_to_schema};
+
+pub const MOCK_SERVER_NAME: &str = "mock-server.mjs";
+
+/// Port the mock server listens on, unless overridden by `MOCK_PORT`
+const DEFAULT_MOCK_PORT: u16 = 8081;
+
+/// The mock server: a dependency-free Node script answering the HTTP & WebSocket handlers of the
+///  package with fake data valid against their JSON Schemas, which are substituted for
+///  `{defs}`, `{http_routes}` & `{ws_handlers}`
+const MOCK_SERVER: &str = r#"// Mock of the HTTP & WebSocket API of the package's hyperapp processes, generated by kit:
+//  answers every handler with fake data matching its return type, so that the UI can be
+//  developed without running a node. Do NOT edit this file: it is machine generated.
+//
+// Usage: `node mock-server.mjs`, then point the UI dev server at it, e.g.
+//  `VITE_NODE_URL=http://127.0.0.1:{port} vite` (set `MOCK_PORT` to listen on another port)
+import crypto from 'node:crypto';
+import http from 'node:http';
+
+const PORT = Number(process.env.MOCK_PORT || {port});
+
+// JSON Schemas of the types of the API, by name
+const DEFS = {defs};
+
+// `<METHOD> <path>` -> handler name (the key of its request body) -> response schema
+const HTTP_ROUTES = {http_routes};
+
+// hyperapp name -> schema of the messages of its WebSocket handler
+const WS_HANDLERS = {ws_handlers};
+
+const WORDS = ['alpha', 'bravo', 'charlie', 'delta', 'echo', 'foxtrot', 'golf', 'hotel'];
+// Past this depth, recursive types are cut short: options are null & lists empty
+const MAX_DEPTH = 4;
+
+const randInt = (min, max) => min + Math.floor(Math.random() * (max - min + 1));
+const pick = (items) => items[Math.floor(Math.random() * items.length)];
+
+function fake(schema, depth = 0) {
+  if (schema.$ref) {
+    return fake(DEFS[schema.$ref.slice('#/$defs/'.length)], depth + 1);
+  }
+  if (schema.const !== undefined) {
+    return schema.const;
+  }
+  if (schema.enum) {
+    return pick(schema.enum);
+  }
+  if (schema.oneOf) {
+    // Prefer cases without data once deep in a recursive type
+    const unitCases = schema.oneOf.filter((s) => s.const !== undefined);
+    return fake(depth > MAX_DEPTH && unitCases.length ? pick(unitCases) : pick(schema.oneOf), depth);
+  }
+  if (schema.anyOf) {
+    return depth > MAX_DEPTH ? null : fake(pick(schema.anyOf), depth);
+  }
+  switch (schema.type) {
+    case 'null':
+      return null;
+    case 'boolean':
+      return Math.random() < 0.5;
+    case 'integer':
+      return randInt(schema.minimum ?? -1000, 1000);
+    case 'number':
+      return Math.round(Math.random() * 100000) / 100;
+    case 'string':
+      return `${pick(WORDS)}-${randInt(0, 999)}`;
+    case 'array': {
+      if (schema.prefixItems) {
+        return schema.prefixItems.map((s) => fake(s, depth));
+      }
+      const len = schema.minItems ?? (depth > MAX_DEPTH ? 0 : randInt(1, 3));
+      return Array.from({ length: len }, () => fake(schema.items || {}, depth + 1));
+    }
+    case 'object': {
+      const object = {};
+      for (const [name, s] of Object.entries(schema.properties || {})) {
+        object[name] = fake(s, depth + 1);
+      }
+      if (schema.additionalProperties && depth <= MAX_DEPTH) {
+        for (let i = 0; i < randInt(1, 3); i++) {
+          object[`${pick(WORDS)}-${i}`] = fake(schema.additionalProperties, depth + 1);
+        }
+      }
+      return object;
+    }
+    default:
+      // `value`: arbitrary JSON
+      return { [pick(WORDS)]: randInt(0, 999) };
+  }
+}
+
+// Paths are served under `/<process>:<package>:<publisher>`, as by a node
+function splitPath(url) {
+  const path = new URL(url, 'http://localhost').pathname;
+  const [, first, ...rest] = path.split('/');
+  return first && first.includes(':')
+    ? { process: first.split(':')[0], path: `/${rest.join('/')}` }
+    : { process: undefined, path };
+}
+
+function sendJson(res, status, body) {
+  res.writeHead(status, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify(body));
+}
+
+const server = http.createServer((req, res) => {
+  let body = '';
+  req.on('data', (chunk) => (body += chunk));
+  req.on('end', () => {
+    const { path } = splitPath(req.url);
+    if (path === '/our.js') {
+      res.writeHead(200, { 'Content-Type': 'application/javascript' });
+      return res.end("window.our = { node: 'mock.os', process: 'mock:mock:mock.os' };");
+    }
+    if (path === '/our') {
+      res.writeHead(200, { 'Content-Type': 'text/plain' });
+      return res.end('mock.os');
+    }
+    const handlers = HTTP_ROUTES[`${req.method} ${path}`];
+    if (!handlers) {
+      return sendJson(res, 404, { error: `No handler bound to ${req.method} ${path}` });
+    }
+    // Handlers sharing a path & method are told apart by the key of the request body
+    let name = Object.keys(handlers)[0];
+    if (body) {
+      try {
+        name = Object.keys(JSON.parse(body))[0] ?? name;
+      } catch {
+        return sendJson(res, 400, { error: 'Request body is not JSON' });
+      }
+    }
+    if (!handlers[name]) {
+      return sendJson(res, 400, { error: `No handler ${name} bound to ${req.method} ${path}` });
+    }
+    console.log(`${req.method} ${path} ${name}`);
+    sendJson(res, 200, fake(handlers[name]));
+  });
+});
+
+// Minimal WebSocket server (RFC 6455): answers each text message with a fake message
+function sendFrame(socket, text) {
+  const payload = Buffer.from(text);
+  let header;
+  if (payload.length < 126) {
+    header = Buffer.from([0x81, payload.length]);
+  } else if (payload.length < 65536) {
+    header = Buffer.from([0x81, 126, payload.length >> 8, payload.length & 0xff]);
+  } else {
+    header = Buffer.alloc(10);
+    header[0] = 0x81;
+    header[1] = 127;
+    header.writeBigUInt64BE(BigInt(payload.length), 2);
+  }
+  socket.write(Buffer.concat([header, payload]));
+}
+
+server.on('upgrade', (req, socket) => {
+  const { process: processName, path } = splitPath(req.url);
+  // Hyperapps are named after their process, in PascalCase
+  const normalize = (name) => name?.toLowerCase().replace(/[-_]/g, '');
+  const hyperapps = Object.keys(WS_HANDLERS);
+  const hyperapp =
+    hyperapps.find((name) => normalize(name) === normalize(processName)) ?? hyperapps[0];
+  if (!hyperapp) {
+    return socket.destroy();
+  }
+  const accept = crypto
+    .createHash('sha1')
+    .update(`${req.headers['sec-websocket-key']}258EAFA5-E914-47DA-95CA-C5AB0DC85B11`)
+    .digest('base64');
+  socket.write(
+    'HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n' +
+      `Sec-WebSocket-Accept: ${accept}\r\n\r\n`,
+  );
+  console.log(`WebSocket ${path} (${hyperapp})`);
+  socket.on('data', (data) => {
+    const opcode = data[0] & 0x0f;
+    if (opcode === 0x8) {
+      return socket.end(Buffer.from([0x88, 0]));
+    }
+    if (opcode === 0x1) {
+      sendFrame(socket, JSON.stringify(fake(WS_HANDLERS[hyperapp])));
+    }
+  });
+  socket.on('error', () => socket.destroy());
+});
+
+server.listen(PORT, () => {
+  console.log(`Mock API server listening on http://127.0.0.1:${PORT}`);
+});
+"#;
+
+/// Create a mock server, at `mock_server_path`, answering the HTTP & WebSocket handlers of the
+///  package's hyperapp processes, as described by the WIT files in `api_dir`, with fake data.
+///  Returns `None` (writing nothing) if there are no such handlers
+#[instrument(level = "trace", skip_all)]
+pub fn create_mock_server(api_dir: &Path, mock_server_path: &Path) -> Result<Option<String>> {
+    const REF_PREFIX: &str = "#/$defs/";
+    let mut all_wit_types = Vec::new();
+    let mut http_routes: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    let mut ws_handlers = Map::new();
+    for (hyperapp_name, wit_files) in find_hyperapp_wit_files(api_dir) {
+        for wit_file in wit_files {
+            let wit_types = parse_wit_file(&wit_file)?;
+            for signature in &wit_types.signatures {
+                if signature.attr_type == "http" {
+                    let route = format!(
+                        "{} {}",
+                        signature.http_method.as_deref().unwrap_or("POST"),
+                        signature.http_path.as_deref().unwrap_or("/api"),
+                    );
+                    http_routes.entry(route).or_default().insert(
+                        to_pascal_case(&signature.function_name),
+                        response_schema(signature, REF_PREFIX),
+                    );
+                } else if signature.attr_type == "ws" {
+                    let message_schema = signature
+                        .fields
+                        .iter()
+                        .find(|field| field.name == "message")
+                        .map(|field| wit_type_to_schema(&field.wit_type, REF_PREFIX))
+                        .unwrap_or_else(|| json!({}));
+                    ws_handlers.insert(hyperapp_name.clone(), message_schema);
+                }
+            }
+            all_wit_types.push(wit_types);
+        }
+    }
+    if http_routes.is_empty() && ws_handlers.is_empty() {
+        debug!("No HTTP or WebSocket handlers found in WIT files, skipping mock server generation");
+        return Ok(None);
+    }
+
+    let mock_server = MOCK_SERVER
+        .replace("{port}", &DEFAULT_MOCK_PORT.to_string())
+        .replace(
+            "{defs}",
+            &serde_json::to_string_pretty(&type_schemas(&all_wit_types, REF_PREFIX))?,
+        )
+        .replace(
+            "{http_routes}",
+            &serde_json::to_string_pretty(&http_routes)?,
+        )
+        .replace(
+            "{ws_handlers}",
+            &serde_json::to_string_pretty(&ws_handlers)?,
+        );
+    if let Some(parent) = mock_server_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(mock_server_path, &mock_server).with_context(|| {
+        format!(
+            "Failed to write mock server: {}",
+            mock_server_path.display()
+        )
+    })?;
+
+    info!(
+        "Successfully created mock server at {}",
+        mock_server_path.display()
+    );
+    Ok(Some(mock_server))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_mock_server_answers_every_handler() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface my-app {
+    record item {
+        id: u64
+    }
+
+    // Function signature for: get-item (http)
+    // args: (id: u64)
+    record get-item-signature-http {
+        target: string,
+        arg-types: tuple<u64>,
+        returning: result<item, string>
+    }
+
+    // Function signature for: get-status (http)
+    // HTTP: GET /api/status
+    record get-status-signature-http {
+        target: string,
+        returning: string
+    }
+
+    // Function signature for: chat (ws)
+    record chat-signature-ws {
+        target: string,
+        message: item
+    }
+}
+"#;
+        fs::write(api_dir.join("my-app.wit"), wit_content).unwrap();
+
+        let mock_server_path = temp_dir.path().join("target/ui").join(MOCK_SERVER_NAME);
+        let mock_server = create_mock_server(&api_dir, &mock_server_path)
+            .unwrap()
+            .unwrap();
+        assert_eq!(fs::read_to_string(&mock_server_path).unwrap(), mock_server);
+
+        assert!(mock_server.contains("const PORT = Number(process.env.MOCK_PORT || 8081);"));
+        assert!(mock_server.contains(r#""POST /api": {"#));
+        assert!(mock_server.contains(r#""GET /api/status": {"#));
+        assert!(mock_server.contains(r##""$ref": "#/$defs/Item""##));
+        assert!(mock_server.contains(r#""MyApp": {"#));
+        assert!(!mock_server.contains("{http_routes}"));
+    }
+}
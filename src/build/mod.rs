@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{BufRead, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -14,7 +14,7 @@ use color_eyre::{
 use fs_err as fs;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tracing::{debug, info, instrument, warn};
+use tracing::{debug, info, info_span, instrument, warn, Instrument};
 use walkdir::WalkDir;
 use zip::write::FileOptions;
 
@@ -23,8 +23,9 @@ use hyperware_process_lib::{kernel_types::Erc721Metadata, PackageId};
 use crate::publish::make_local_file_link_path;
 use crate::run_tests::types::BroadcastRecvBool;
 use crate::setup::{
-    check_js_deps, check_py_deps, check_rust_deps, get_deps, get_newest_valid_node_version,
-    get_python_version, REQUIRED_PY_PACKAGE,
+    check_c_deps, check_go_deps, check_js_deps, check_py_deps, check_rust_deps,
+    check_rust_wasip2_deps, get_deps, get_newest_valid_node_version, get_python_version,
+    REQUIRED_PY_PACKAGE,
 };
 use crate::view_api;
 use crate::KIT_CACHE;
@@ -32,22 +33,80 @@ use crate::KIT_CACHE;
 mod rewrite;
 use rewrite::copy_and_rewrite_package;
 
+mod api_cache;
+pub use api_cache::DEFAULT_API_CACHE_TTL;
+use api_cache::{cache_api, get_cached_api};
+mod build_log;
+use build_log::{run_cargo_with_json_diagnostics, BuildStep};
+pub use build_log::{BUILD_LOG_TARGET, DEFAULT_LOG_FORMAT};
+mod build_manifest;
+mod plan;
+use plan::print_build_plan;
+mod signing;
+use signing::sign_pkg;
+pub use signing::{verify_pkg, SIGNATURE_NAME};
+mod dep_source;
+use build_manifest::write_build_manifest;
+use dep_source::get_api_from_dep_source;
+pub use dep_source::DepSource;
 mod caller_utils_generator;
+pub use caller_utils_generator::get_caller_utils_crate;
+mod caller_utils_py_generator;
 mod caller_utils_ts_generator;
+mod graphql_generator;
+mod kit_toml;
+pub use kit_toml::read_kit_toml;
+use kit_toml::{get_build_env, get_ui_build_env, run_hook, CargoConfig, ProcessConfig, WitConfig};
+mod kotlin_client_generator;
+mod mock_server_generator;
+mod openapi_generator;
+use openapi_generator::{JSON_SCHEMA_NAME, OPENAPI_JSON_NAME};
+mod rust_client_generator;
+mod swift_client_generator;
 mod wit_generator;
+pub use wit_generator::{generate_wit_files, lint_wit, write_versioned_api};
+mod workspace;
+pub use workspace::{discover_workspace_packages, get_workspace_build_order, WorkspacePackage};
 
 // Default Rust toolchain to use for builds
 pub const DEFAULT_RUST_TOOLCHAIN: &str = "+1.85.1";
+// Default compiler cache to use for Rust builds
+pub const DEFAULT_CACHE_BACKEND: &str = "none";
+pub const KIT_CARGO_ARGS_ENV: &str = "KIT_CARGO_ARGS";
+// Default way to build Rust processes into components: `cargo build` & `wasm-tools component new`
+pub const DEFAULT_BUILD_BACKEND: &str = "cargo";
+// Default cargo profile to build Rust processes with
+pub const DEFAULT_CARGO_PROFILE: &str = "release";
+// Default debug info handling: leave it to the cargo profile
+pub const DEFAULT_DEBUG_INFO: &str = "profile";
+// Default JS package manager for UI builds: detected from the UI's lockfile
+pub const DEFAULT_UI_PACKAGE_MANAGER: &str = "auto";
+const DEBUG_INFO_DIR_NAME: &str = "debug-info";
+const UI_HASHES_DIR_NAME: &str = "ui_hashes";
+// Dirs of build output & installed dependencies that do not affect a UI build
+const UI_HASH_EXCLUDE_DIRS: &[&str] = &["node_modules", "dist", "build", ".vite"];
+// Build-time constants that change with every commit or (outside git) every build: a UI whose
+//  inputs are otherwise unchanged is not rebuilt for them alone
+const UI_HASH_EXCLUDE_ENV: &[&str] = &["KIT_GIT_SHA", "KIT_BUILD_TIMESTAMP"];
 
 const PY_VENV_NAME: &str = "process_env";
+const GO_SRC_PATH: &str = "src/main.go";
+const GO_MOD_NAME: &str = "go.mod";
+const GO_BINDINGS_DIR_NAME: &str = "bindings";
+const C_SRC_PATH: &str = "src/lib.c";
+const CPP_SRC_PATH: &str = "src/lib.cpp";
 const JAVASCRIPT_SRC_PATH: &str = "src/lib.js";
 const PYTHON_SRC_PATH: &str = "src/lib.py";
 const RUST_SRC_PATH: &str = "src/lib.rs";
 const PACKAGE_JSON_NAME: &str = "package.json";
 const COMPONENTIZE_MJS_NAME: &str = "componentize.mjs";
+const HYPERWARE_WIT_REPO_URL: &str = "https://raw.githubusercontent.com/hyperware-ai/hyperware-wit";
 const HYPERWARE_WIT_1_0_0_URL: &str =
     "https://raw.githubusercontent.com/hyperware-ai/hyperware-wit/v1.0.0/hyperware.wit";
 const WASI_VERSION: &str = "33.0.0"; // TODO: un-hardcode
+const WASI_SDK_MAJOR_VERSION: &str = "25";
+const WASIP1_TARGET: &str = "wasm32-wasip1";
+const WASIP2_TARGET: &str = "wasm32-wasip2";
 const DEFAULT_WORLD_1_0_0: &str = "process-v1";
 const KINODE_PROCESS_LIB_CRATE_NAME: &str = "hyperware_process_lib";
 
@@ -105,7 +164,7 @@ pub fn zip_pkg(package_dir: &Path, pkg_publisher: &str) -> Result<(PathBuf, Stri
 }
 
 #[instrument(level = "trace", skip_all)]
-fn zip_directory(directory: &Path, zip_filename: &str) -> Result<()> {
+pub fn zip_directory(directory: &Path, zip_filename: &str) -> Result<()> {
     let file = fs::File::create(zip_filename)?;
 
     let mut zip = zip::ZipWriter::new(file);
@@ -273,13 +332,18 @@ pub fn run_command(cmd: &mut Command, verbose: bool) -> Result<Option<(String, S
     }
 }
 
-#[instrument(level = "trace", skip_all)]
-pub async fn download_file(url: &str, path: &Path) -> Result<()> {
-    fs::create_dir_all(&KIT_CACHE)?;
+/// Path within KIT_CACHE at which `download_file()` caches the contents of `url`
+pub fn get_cached_download_path(url: &str) -> PathBuf {
     let mut hasher = Sha256::new();
     hasher.update(url.as_bytes());
     let hashed_url = hasher.finalize();
-    let hashed_url_path = Path::new(KIT_CACHE).join(format!("{hashed_url:x}"));
+    Path::new(KIT_CACHE).join(format!("{hashed_url:x}"))
+}
+
+#[instrument(level = "trace", skip_all)]
+pub async fn download_file(url: &str, path: &Path) -> Result<()> {
+    fs::create_dir_all(&KIT_CACHE)?;
+    let hashed_url_path = get_cached_download_path(url);
 
     let content = if hashed_url_path.exists() {
         fs::read(hashed_url_path)?
@@ -365,6 +429,29 @@ pub fn read_and_update_metadata(package_dir: &Path) -> Result<Erc721Metadata> {
     Ok(metadata)
 }
 
+/// Get the target Rust processes are built for from metadata.json `properties.wasm_target`.
+///
+/// Defaults to `wasm32-wasip1`, whose output is adapted into a component with the
+/// wasi_snapshot_preview1 adapter. `wasm32-wasip2` produces a component directly.
+#[instrument(level = "trace", skip_all)]
+pub fn get_rust_wasm_target(package_dir: &Path) -> Result<&'static str> {
+    let metadata: serde_json::Value =
+        serde_json::from_reader(fs::File::open(package_dir.join("metadata.json"))?)?;
+    match metadata
+        .get("properties")
+        .and_then(|p| p.get("wasm_target"))
+        .and_then(|t| t.as_str())
+    {
+        None => Ok(WASIP1_TARGET),
+        Some(WASIP1_TARGET) => Ok(WASIP1_TARGET),
+        Some(WASIP2_TARGET) => Ok(WASIP2_TARGET),
+        Some(other) => Err(eyre!(
+            "Unsupported `properties.wasm_target` {other:?} in metadata.json"
+        )
+        .with_suggestion(|| format!("Use one of {WASIP1_TARGET:?} or {WASIP2_TARGET:?}."))),
+    }
+}
+
 fn replace_version_in_file(file_path: &Path, pattern: &str, new_version: &str) -> Result<()> {
     let file = fs::File::open(&file_path)?;
     let reader = std::io::BufReader::new(file);
@@ -420,6 +507,24 @@ fn extract_worlds_from_files(directory: &Path) -> Vec<String> {
     worlds
 }
 
+/// The WIT world to build a non-Rust process against
+#[derive(Debug, Clone)]
+enum ProcessWorld {
+    /// The only world in the WIT dir, if there is exactly one, else this `--world`
+    Default(String),
+    /// One of the `worlds` of the process in `kit.toml`
+    Explicit(String),
+}
+
+impl ProcessWorld {
+    fn resolve(&self, directory: &Path) -> String {
+        match self {
+            ProcessWorld::Default(default_world) => get_world_or_default(directory, default_world),
+            ProcessWorld::Explicit(world) => world.clone(),
+        }
+    }
+}
+
 fn get_world_or_default(directory: &Path, default_world: &str) -> String {
     let worlds = extract_worlds_from_files(directory);
     if worlds.len() == 1 {
@@ -433,7 +538,7 @@ fn get_world_or_default(directory: &Path, default_world: &str) -> String {
 }
 
 #[instrument(level = "trace", skip_all)]
-fn copy_dir(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<()> {
+pub fn copy_dir(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<()> {
     let src = src.as_ref();
     let dst = dst.as_ref();
     if !dst.exists() {
@@ -723,42 +828,126 @@ fn get_cargo_package_path(package: &cargo_metadata::Package) -> Result<PathBuf>
     }
 }
 
+/// Generate the TypeScript SDK (see `kit build --ts-sdk`) of the package from its `api/`
+#[instrument(level = "trace", skip_all)]
+fn create_ts_sdk(package_dir: &Path, sdk_dir: &Path) -> Result<()> {
+    let metadata = read_metadata(package_dir)?;
+    caller_utils_ts_generator::create_typescript_sdk(
+        &package_dir.join("api"),
+        sdk_dir,
+        &metadata.properties.package_name,
+        &metadata.properties.current_version,
+        &read_kit_toml(package_dir)?.typescript,
+    )
+}
+
+/// Generate the descriptions of the package's API from its `api/` into `out_dir`, for
+///  `kit api export`: its OpenAPI spec, the JSON Schema of its types and, if it has HTTP or
+///  WebSocket handlers, its TypeScript SDK in `ts/`
+#[instrument(level = "trace", skip_all)]
+pub fn generate_api_descriptions(package_dir: &Path, out_dir: &Path) -> Result<()> {
+    let metadata = read_metadata(package_dir)?;
+    let api_dir = package_dir.join("api");
+    let package_name = &metadata.properties.package_name;
+    let version = &metadata.properties.current_version;
+    openapi_generator::create_openapi_spec(
+        &api_dir,
+        &out_dir.join(OPENAPI_JSON_NAME),
+        package_name,
+        version,
+    )?;
+    openapi_generator::create_json_schema(
+        &api_dir,
+        &out_dir.join(JSON_SCHEMA_NAME),
+        package_name,
+        version,
+    )?;
+    caller_utils_ts_generator::write_typescript_sdk(
+        &api_dir,
+        &out_dir.join("ts"),
+        package_name,
+        version,
+        &read_kit_toml(package_dir)?.typescript,
+    )?;
+    Ok(())
+}
+
+/// Generate the Python client (see `kit build --py-client`) of the package from its `api/`
+#[instrument(level = "trace", skip_all)]
+fn create_py_client(package_dir: &Path, py_client_path: &Path) -> Result<()> {
+    caller_utils_py_generator::create_python_client(
+        &package_dir.join("api"),
+        py_client_path,
+        &read_metadata(package_dir)?.properties.package_name,
+    )
+}
+
+/// Generate the Rust client crate (see `kit build --rust-client`) of the package from its `api/`
+#[instrument(level = "trace", skip_all)]
+fn create_rust_client(package_dir: &Path, crate_dir: &Path) -> Result<()> {
+    let metadata = read_metadata(package_dir)?;
+    rust_client_generator::create_rust_client(
+        &package_dir.join("api"),
+        crate_dir,
+        &metadata.properties.package_name,
+        &metadata.properties.current_version,
+    )
+}
+
+/// Generate the Swift client (see `kit build --swift-client`) of the package from its `api/`
+#[instrument(level = "trace", skip_all)]
+fn create_swift_client(package_dir: &Path, swift_client_path: &Path) -> Result<()> {
+    let metadata = read_metadata(package_dir)?;
+    swift_client_generator::create_swift_client(
+        &package_dir.join("api"),
+        swift_client_path,
+        &metadata.properties.package_name,
+    )
+}
+
+/// Generate the Kotlin client (see `kit build --kotlin-client`) of the package from its `api/`
+#[instrument(level = "trace", skip_all)]
+fn create_kotlin_client(package_dir: &Path, kotlin_client_path: &Path) -> Result<()> {
+    let metadata = read_metadata(package_dir)?;
+    kotlin_client_generator::create_kotlin_client(
+        &package_dir.join("api"),
+        kotlin_client_path,
+        &metadata.properties.package_name,
+    )
+}
+
 #[instrument(level = "trace", skip_all)]
 fn is_up_to_date(
-    build_with_features_path: &Path,
-    build_with_cludes_path: &Path,
-    features: &str,
-    cludes: &str,
+    build_with: &[(PathBuf, String)],
     package_dir: &Path,
     hyperapp: bool,
 ) -> Result<bool> {
-    let old_features = fs::read_to_string(&build_with_features_path).ok();
-    let old_cludes = fs::read_to_string(&build_with_cludes_path).ok();
+    // each of `build_with` is a file recording what the last build was built with
+    let built_with_same = build_with
+        .iter()
+        .all(|(path, value)| fs::read_to_string(path).ok().as_ref() == Some(value));
 
     debug!(
         "is_up_to_date({package_dir:?}):
-    old_features == Some(features.to_string()): {}
-    old_cludes == Some(cludes.to_string()): {}
+    built_with_same: {}
     package_dir.join(\"Cargo.lock\").exists(): {}
     package_dir.join(\"pkg\").exists(): {}
     package_dir.join(\"pkg\").join(\"api.zip\").exists(): {}
     file_with_extension_exists(&package_dir.join(\"pkg\"), \"wasm\"): {}",
-        old_features == Some(features.to_string()),
-        old_cludes == Some(cludes.to_string()),
+        built_with_same,
         package_dir.join("Cargo.lock").exists(),
         package_dir.join("pkg").exists(),
         package_dir.join("pkg").join("api.zip").exists(),
         file_with_extension_exists(&package_dir.join("pkg"), "wasm"),
     );
 
-    if old_features == Some(features.to_string())
-        && old_cludes == Some(cludes.to_string())
+    if built_with_same
         && package_dir.join("Cargo.lock").exists()
         && package_dir.join("pkg").exists()
         && package_dir.join("pkg").join("api.zip").exists()
         && file_with_extension_exists(&package_dir.join("pkg"), "wasm")
     {
-        let exclude_files = HashSet::from(["Cargo.lock", "api.zip"]);
+        let exclude_files = HashSet::from(["Cargo.lock", "api.zip", SIGNATURE_NAME]);
         let exclude_extensions = HashSet::from(["wasm"]);
         let mut exclude_dirs = HashSet::from(["target", "node_modules", "dist"]);
         let mut must_exist_dirs = HashSet::from(["target"]);
@@ -862,7 +1051,8 @@ fn is_up_to_date(
 async fn compile_javascript_wasm_process(
     process_dir: &Path,
     valid_node: Option<String>,
-    world: &str,
+    world: &ProcessWorld,
+    build_env: &BTreeMap<String, String>,
     verbose: bool,
 ) -> Result<()> {
     info!(
@@ -871,7 +1061,7 @@ async fn compile_javascript_wasm_process(
     );
 
     let wasm_file_name = process_dir.file_name().and_then(|s| s.to_str()).unwrap();
-    let world_name = get_world_or_default(&process_dir.join("target").join("wit"), world);
+    let world_name = world.resolve(&process_dir.join("target").join("wit"));
 
     let install = "npm install".to_string();
     let componentize = format!("node componentize.mjs {wasm_file_name} {world_name}");
@@ -900,6 +1090,7 @@ async fn compile_javascript_wasm_process(
     run_command(
         Command::new("bash")
             .args(&["-c", &componentize])
+            .envs(build_env)
             .current_dir(process_dir),
         verbose,
     )?;
@@ -915,13 +1106,13 @@ async fn compile_javascript_wasm_process(
 async fn compile_python_wasm_process(
     process_dir: &Path,
     python: &str,
-    world: &str,
+    world: &ProcessWorld,
     verbose: bool,
 ) -> Result<()> {
     info!("Compiling Python Hyperware process in {:?}...", process_dir);
 
     let wasm_file_name = process_dir.file_name().and_then(|s| s.to_str()).unwrap();
-    let world_name = get_world_or_default(&process_dir.join("target").join("wit"), world);
+    let world_name = world.resolve(&process_dir.join("target").join("wit"));
 
     let source = format!("source ../{PY_VENV_NAME}/bin/activate");
     let install = format!("pip install {REQUIRED_PY_PACKAGE}");
@@ -950,11 +1141,465 @@ async fn compile_python_wasm_process(
     Ok(())
 }
 
+fn get_wasi_snapshot_url() -> String {
+    format!(
+        "https://github.com/bytecodealliance/wasmtime/releases/download/v{}/wasi_snapshot_preview1.reactor.wasm",
+        WASI_VERSION,
+    )
+}
+
+/// Check and download wasi_snapshot_preview1.wasm if it does not exist
+#[instrument(level = "trace", skip_all)]
+async fn download_wasi_snapshot_adapter(package_dir: &Path) -> Result<()> {
+    let wasi_snapshot_file = package_dir
+        .join("target")
+        .join("wasi_snapshot_preview1.wasm");
+    download_file(&get_wasi_snapshot_url(), &wasi_snapshot_file).await
+}
+
+/// Embed the world into a core wasm module that lacks component type
+/// information and adapt it into a component in `pkg/` using wasm-tools
+#[instrument(level = "trace", skip_all)]
+async fn componentize_core_wasm(
+    process_dir: &Path,
+    wasm_file_core: &Path,
+    world: &ProcessWorld,
+    verbose: bool,
+) -> Result<()> {
+    let Some(package_dir) = process_dir.parent() else {
+        return Err(eyre!(
+            "Could not derive package dir from process_dir ({process_dir:?}) parent"
+        ));
+    };
+    let wit_dir = package_dir.join("target").join("wit");
+    let world_name = world.resolve(&wit_dir);
+
+    download_wasi_snapshot_adapter(package_dir).await?;
+
+    // Hypermap hates `_`s and so we convert to and enforce all `-`s
+    let wasm_file_name = process_dir
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .replace("_", "-");
+    let wasm_file_embed = process_dir
+        .join("target")
+        .join(format!("{wasm_file_name}.embed.wasm"));
+    let wasm_file_pkg = package_dir
+        .join("pkg")
+        .join(format!("{wasm_file_name}.wasm"));
+    let wasi_snapshot_file = package_dir
+        .join("target")
+        .join("wasi_snapshot_preview1.wasm");
+
+    run_command(
+        Command::new("wasm-tools").args(&[
+            "component",
+            "embed",
+            wit_dir.to_str().unwrap(),
+            "--world",
+            &world_name,
+            wasm_file_core.to_str().unwrap(),
+            "-o",
+            wasm_file_embed.to_str().unwrap(),
+        ]),
+        verbose,
+    )?;
+    run_command(
+        Command::new("wasm-tools").args(&[
+            "component",
+            "new",
+            wasm_file_embed.to_str().unwrap(),
+            "-o",
+            wasm_file_pkg.to_str().unwrap(),
+            "--adapt",
+            wasi_snapshot_file.to_str().unwrap(),
+        ]),
+        verbose,
+    )?;
+    Ok(())
+}
+
+// The module path declared in the `go.mod` of the Go process at `process_dir`
+fn get_go_module_path(process_dir: &Path) -> Result<String> {
+    let go_mod_path = process_dir.join(GO_MOD_NAME);
+    if !go_mod_path.exists() {
+        return Err(eyre!("Go process {process_dir:?} has no {GO_MOD_NAME}")
+            .with_suggestion(|| "Run `go mod init <module path>` in the process dir."));
+    }
+    fs::read_to_string(&go_mod_path)?
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|module| module.trim().trim_matches('"').to_string())
+        .ok_or_else(|| eyre!("No `module` declared in {go_mod_path:?}"))
+}
+
+#[instrument(level = "trace", skip_all)]
+async fn compile_go_wasm_process(
+    process_dir: &Path,
+    world: &ProcessWorld,
+    verbose: bool,
+) -> Result<()> {
+    let Some(package_dir) = process_dir.parent() else {
+        return Err(eyre!(
+            "Could not derive package dir from process_dir ({process_dir:?}) parent"
+        ));
+    };
+    info!("Compiling Go Hyperware process in {:?}...", process_dir);
+
+    let wit_dir = package_dir.join("target").join("wit");
+    let world_name = world.resolve(&wit_dir);
+    let wasm_file_name = process_dir.file_name().and_then(|s| s.to_str()).unwrap();
+    let target_dir = process_dir.join("target");
+    fs::create_dir_all(&target_dir)?;
+    let wasm_file_core = target_dir.join(format!("{wasm_file_name}.core.wasm"));
+
+    // Generate the Go bindings of the world into `bindings/`, importable by the process
+    //  as `<module path>/bindings/<namespace>/<package>/<interface or world>`
+    let bindings_dir = process_dir.join(GO_BINDINGS_DIR_NAME);
+    if bindings_dir.exists() {
+        fs::remove_dir_all(&bindings_dir)?;
+    }
+    let package_root = format!(
+        "{}/{GO_BINDINGS_DIR_NAME}",
+        get_go_module_path(process_dir)?
+    );
+    run_command(
+        Command::new("wit-bindgen-go")
+            .args(&[
+                "generate",
+                "--world",
+                &world_name,
+                "--out",
+                bindings_dir.to_str().unwrap(),
+                "--package-root",
+                &package_root,
+                wit_dir.to_str().unwrap(),
+            ])
+            .current_dir(process_dir),
+        verbose,
+    )?;
+    // the bindings import `go.bytecodealliance.org/cm`
+    run_command(
+        Command::new("go")
+            .args(&["mod", "tidy"])
+            .current_dir(process_dir),
+        verbose,
+    )?;
+
+    // Build the core module using TinyGo: as a reactor, since processes are entered
+    //  through the `init` export rather than `_start`
+    run_command(
+        Command::new("tinygo")
+            .args(&[
+                "build",
+                "-target=wasi",
+                "-buildmode=c-shared",
+                "-no-debug",
+                "-o",
+                wasm_file_core.to_str().unwrap(),
+                "./src",
+            ])
+            .current_dir(process_dir),
+        verbose,
+    )?;
+
+    // TinyGo does not embed component type information: do so here
+    componentize_core_wasm(process_dir, &wasm_file_core, world, verbose).await?;
+
+    info!("Done compiling Go Hyperware process in {:?}.", process_dir);
+    Ok(())
+}
+
+fn get_wasi_sdk_name_and_dir() -> (String, PathBuf) {
+    let arch = match std::env::consts::ARCH {
+        "aarch64" => "arm64",
+        arch => arch,
+    };
+    let os = std::env::consts::OS;
+    let sdk_name = format!("wasi-sdk-{WASI_SDK_MAJOR_VERSION}.0-{arch}-{os}");
+    let sdk_dir = Path::new(KIT_CACHE).join(&sdk_name);
+    (sdk_name, sdk_dir)
+}
+
+/// Download and unpack the kit-managed wasi-sdk into KIT_CACHE if not already present,
+/// returning the path to the wasi-sdk root
+#[instrument(level = "trace", skip_all)]
+async fn get_wasi_sdk(verbose: bool) -> Result<PathBuf> {
+    let (sdk_name, sdk_dir) = get_wasi_sdk_name_and_dir();
+    if sdk_dir.join("bin").join("clang").exists() {
+        return Ok(sdk_dir);
+    }
+
+    info!("Getting {sdk_name}...");
+    let sdk_url = format!(
+        "https://github.com/WebAssembly/wasi-sdk/releases/download/wasi-sdk-{WASI_SDK_MAJOR_VERSION}/{sdk_name}.tar.gz",
+    );
+    let sdk_tarball = Path::new(KIT_CACHE).join(format!("{sdk_name}.tar.gz"));
+    download_file(&sdk_url, &sdk_tarball).await?;
+    run_command(
+        Command::new("tar")
+            .args(&["-xzf", sdk_tarball.to_str().unwrap()])
+            .current_dir(KIT_CACHE),
+        verbose,
+    )?;
+    if !sdk_dir.join("bin").join("clang").exists() {
+        return Err(eyre!(
+            "Unpacked wasi-sdk from {sdk_url} but did not find {:?}",
+            sdk_dir.join("bin").join("clang"),
+        ));
+    }
+    info!("Done getting {sdk_name}.");
+    Ok(sdk_dir)
+}
+
+#[instrument(level = "trace", skip_all)]
+async fn compile_c_wasm_process(
+    process_dir: &Path,
+    world: &ProcessWorld,
+    is_cpp: bool,
+    verbose: bool,
+) -> Result<()> {
+    let Some(package_dir) = process_dir.parent() else {
+        return Err(eyre!(
+            "Could not derive package dir from process_dir ({process_dir:?}) parent"
+        ));
+    };
+    info!("Compiling C/C++ Hyperware process in {:?}...", process_dir);
+
+    let sdk_dir = get_wasi_sdk(verbose).await?;
+
+    let wit_dir = package_dir.join("target").join("wit");
+    let world_name = world.resolve(&wit_dir);
+    let wasm_file_name = process_dir.file_name().and_then(|s| s.to_str()).unwrap();
+    let target_dir = process_dir.join("target");
+    let bindings_dir = target_dir.join("bindings");
+    fs::create_dir_all(&bindings_dir)?;
+    let wasm_file_core = target_dir.join(format!("{wasm_file_name}.core.wasm"));
+
+    // Generate C bindings for the world
+    run_command(
+        Command::new("wit-bindgen").args(&[
+            "c",
+            wit_dir.to_str().unwrap(),
+            "--world",
+            &world_name,
+            "--out-dir",
+            bindings_dir.to_str().unwrap(),
+        ]),
+        verbose,
+    )?;
+
+    // Compile process sources along with the generated bindings;
+    //  the component type object is omitted since the world is embedded below
+    let mut sources = vec![];
+    for dir in [process_dir.join("src"), bindings_dir.clone()] {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if let Some("c" | "cc" | "cpp") = path.extension().and_then(|e| e.to_str()) {
+                sources.push(path);
+            }
+        }
+    }
+    sources.sort();
+
+    let compiler = sdk_dir
+        .join("bin")
+        .join(if is_cpp { "clang++" } else { "clang" });
+    let sysroot = sdk_dir.join("share").join("wasi-sysroot");
+    let mut args = vec![
+        "--target=wasm32-wasip1".to_string(),
+        format!("--sysroot={}", sysroot.to_str().unwrap()),
+        "-mexec-model=reactor".to_string(),
+        "-O2".to_string(),
+        "-I".to_string(),
+        bindings_dir.to_str().unwrap().to_string(),
+        "-o".to_string(),
+        wasm_file_core.to_str().unwrap().to_string(),
+    ];
+    if is_cpp {
+        args.push("-fno-exceptions".to_string());
+    }
+    args.extend(sources.iter().map(|s| s.to_str().unwrap().to_string()));
+    run_command(
+        Command::new(compiler).args(&args).current_dir(process_dir),
+        verbose,
+    )?;
+
+    componentize_core_wasm(process_dir, &wasm_file_core, world, verbose).await?;
+
+    info!(
+        "Done compiling C/C++ Hyperware process in {:?}.",
+        process_dir
+    );
+    Ok(())
+}
+
+/// Get the dir within `target/<wasm_target>/` that cargo writes a profile's output to
+fn get_cargo_profile_dir(profile: &str) -> &str {
+    match profile {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        _ => profile,
+    }
+}
+
+/// Get the `CARGO_PROFILE_<PROFILE>_*` env vars that override the profile's debug info
+///  settings for the given `--debug-info`
+fn get_debug_info_cargo_env(
+    profile: &str,
+    debug_info: &str,
+) -> Result<Vec<(String, &'static str)>> {
+    let profile = profile.to_uppercase().replace("-", "_");
+    let (debug, strip) = match debug_info {
+        "profile" => return Ok(vec![]),
+        "keep" | "split" => ("true", "none"),
+        "strip" => ("false", "debuginfo"),
+        _ => return Err(eyre!("Unknown debug info option {debug_info:?}")),
+    };
+    Ok(vec![
+        (format!("CARGO_PROFILE_{profile}_DEBUG"), debug),
+        (format!("CARGO_PROFILE_{profile}_STRIP"), strip),
+    ])
+}
+
+/// Strip debug info from the components in `pkg/` for `--debug-info strip` or `split`;
+///  for `split`, first copy the unstripped components to `target/debug-info/` for use
+///  with wasm debuggers
+#[instrument(level = "trace", skip_all)]
+fn apply_debug_info(package_dir: &Path, debug_info: &str, verbose: bool) -> Result<()> {
+    if debug_info != "strip" && debug_info != "split" {
+        return Ok(());
+    }
+    let debug_info_dir = package_dir.join("target").join(DEBUG_INFO_DIR_NAME);
+    if debug_info == "split" {
+        if debug_info_dir.exists() {
+            fs::remove_dir_all(&debug_info_dir)?;
+        }
+        fs::create_dir_all(&debug_info_dir)?;
+    }
+    for entry in fs::read_dir(package_dir.join("pkg"))? {
+        let path = entry?.path();
+        if Some("wasm") != path.extension().and_then(|e| e.to_str()) {
+            continue;
+        }
+        if debug_info == "split" {
+            fs::copy(&path, debug_info_dir.join(path.file_name().unwrap()))?;
+        }
+        let path = path.to_str().unwrap();
+        run_command(
+            Command::new("wasm-tools").args(["strip", path, "-o", path]),
+            verbose,
+        )?;
+    }
+    if debug_info == "split" {
+        info!("Wrote components with debug info to {debug_info_dir:?}.");
+    }
+    Ok(())
+}
+
+/// Get the `RUSTC_WRAPPER` to compile Rust processes with for the given `--cache-backend`
+#[instrument(level = "trace", skip_all)]
+fn get_rustc_wrapper(cache_backend: &str) -> Result<Option<&'static str>> {
+    let is_sccache_installed = || {
+        Command::new("sccache")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    };
+    match cache_backend {
+        "none" => Ok(None),
+        "sccache" => {
+            if !is_sccache_installed() {
+                return Err(eyre!("--cache-backend sccache given but sccache not found")
+                    .with_suggestion(|| "Install sccache with `cargo install sccache --locked`."));
+            }
+            Ok(Some("sccache"))
+        }
+        "auto" => {
+            // respect a user-set wrapper
+            if std::env::var("RUSTC_WRAPPER").is_ok() || !is_sccache_installed() {
+                Ok(None)
+            } else {
+                info!("Using sccache to compile Rust processes.");
+                Ok(Some("sccache"))
+            }
+        }
+        _ => Err(eyre!("Unknown cache backend {cache_backend:?}")),
+    }
+}
+
+/// Whether to build Rust processes with cargo-component for the given `--backend`
+#[instrument(level = "trace", skip_all)]
+fn is_cargo_component_backend(backend: &str) -> Result<bool> {
+    match backend {
+        "cargo" => Ok(false),
+        "cargo-component" => {
+            let is_installed = Command::new("cargo")
+                .args(["component", "--version"])
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false);
+            if !is_installed {
+                return Err(
+                    eyre!("--backend cargo-component given but cargo-component not found")
+                        .with_suggestion(|| {
+                            "Install cargo-component with `cargo install cargo-component --locked`."
+                        }),
+                );
+            }
+            Ok(true)
+        }
+        _ => Err(eyre!("Unknown build backend {backend:?}")),
+    }
+}
+
+/// Combine extra `cargo build` args from, in order, `kit.toml`, `$KIT_CARGO_ARGS`
+/// and `kit build -- <ARGS>`
+fn get_cargo_config(mut cargo_config: CargoConfig, cargo_args: &[String]) -> CargoConfig {
+    if let Ok(env_cargo_args) = std::env::var(KIT_CARGO_ARGS_ENV) {
+        cargo_config
+            .args
+            .extend(env_cargo_args.split_whitespace().map(|a| a.to_string()));
+    }
+    cargo_config.args.extend(cargo_args.iter().cloned());
+    cargo_config
+}
+
+/// Packages nested within another package (e.g. hyperapp test packages) share the
+/// outermost package's `target/` so the dependency tree is not recompiled from scratch.
+/// If `CARGO_TARGET_DIR` is set, it is shared by all packages instead
+fn get_cargo_target_dir(package_dir: &Path) -> PathBuf {
+    if let Ok(cargo_target_dir) = std::env::var("CARGO_TARGET_DIR") {
+        return PathBuf::from(cargo_target_dir);
+    }
+    let mut cargo_target_dir = package_dir.join("target");
+    for ancestor in package_dir.ancestors().skip(1) {
+        if ancestor.join("metadata.json").exists() && ancestor.join("pkg").is_dir() {
+            cargo_target_dir = ancestor.join("target");
+        }
+    }
+    cargo_target_dir
+}
+
 #[instrument(level = "trace", skip_all)]
 async fn compile_rust_wasm_process(
     process_dir: &Path,
     features: &str,
+    wasm_target: &str,
+    offline: bool,
+    locked: bool,
+    rustc_wrapper: Option<&str>,
+    cargo_config: &CargoConfig,
+    process_config: &ProcessConfig,
+    profile: &str,
+    debug_info: &str,
+    build_env: &BTreeMap<String, String>,
+    deny_warnings: bool,
+    json_diagnostics: bool,
     verbose: bool,
+    backend: &str,
     toolchain: &str,
 ) -> Result<()> {
     let Some(package_dir) = process_dir.parent() else {
@@ -973,15 +1618,12 @@ async fn compile_rust_wasm_process(
         .join(package_dir.file_name().unwrap());
     fs::create_dir_all(&bindings_dir)?;
 
-    // Check and download wasi_snapshot_preview1.wasm if it does not exist
-    let wasi_snapshot_file = package_dir
-        .join("target")
-        .join("wasi_snapshot_preview1.wasm");
-    let wasi_snapshot_url = format!(
-        "https://github.com/bytecodealliance/wasmtime/releases/download/v{}/wasi_snapshot_preview1.reactor.wasm",
-        WASI_VERSION,
-    );
-    download_file(&wasi_snapshot_url, &wasi_snapshot_file).await?;
+    let is_wasip2 = wasm_target == WASIP2_TARGET;
+    // cargo-component adapts the module itself, with the adapter it bundles
+    let is_cargo_component = is_cargo_component_backend(backend)?;
+    if !is_wasip2 && !is_cargo_component {
+        download_wasi_snapshot_adapter(package_dir).await?;
+    }
 
     // Copy wit directory to bindings
     fs::create_dir_all(&bindings_dir.join("wit"))?;
@@ -994,19 +1636,35 @@ async fn compile_rust_wasm_process(
     }
 
     // Build the module using Cargo
-    let mut args = vec![
-        toolchain,
+    let cargo_target_dir = get_cargo_target_dir(package_dir);
+    let mut args = vec![toolchain];
+    if is_cargo_component {
+        args.push("component");
+    }
+    args.extend([
         "build",
         "-p",
         &process_name,
-        "--release",
-        "--no-default-features",
+        "--profile",
+        profile,
         "--target",
-        "wasm32-wasip1",
+        wasm_target,
         "--target-dir",
-        "target",
+        cargo_target_dir.to_str().unwrap(),
         "--color=always",
-    ];
+    ]);
+    if !process_config.default_features {
+        args.push("--no-default-features");
+    }
+    if offline {
+        args.push("--offline");
+    }
+    if locked {
+        args.push("--locked");
+    }
+    if json_diagnostics {
+        args.push("--message-format=json");
+    }
     let test_only = features == "test";
     let features: Vec<&str> = features.split(',').collect();
     let original_length = if is_only_empty_string(&features) {
@@ -1014,24 +1672,42 @@ async fn compile_rust_wasm_process(
     } else {
         features.len()
     };
-    let features = remove_missing_features(&process_dir.join("Cargo.toml"), features)?;
+    let mut features = remove_missing_features(&process_dir.join("Cargo.toml"), features)?;
     if !test_only && original_length != features.len() {
         info!(
             "process {:?} missing features; using {:?}",
             process_dir, features
         );
     };
+    for feature in &process_config.features {
+        if !features.contains(feature) {
+            features.push(feature.clone());
+        }
+    }
     let features = features.join(",");
     if !features.is_empty() {
         args.push("--features");
         args.push(&features);
     }
-    let result = run_command(
-        Command::new("cargo").args(&args).current_dir(package_dir),
-        verbose,
-    )?;
-
-    if let Some((stdout, stderr)) = result {
+    args.extend(cargo_config.args.iter().map(|a| a.as_str()));
+    let mut rustflags = get_path_remapping_rustflags(package_dir);
+    if let Some(ref extra_rustflags) = cargo_config.rustflags {
+        rustflags.extend(extra_rustflags.split_whitespace().map(|f| f.to_string()));
+    }
+    if deny_warnings {
+        rustflags.extend(["-D".to_string(), "warnings".to_string()]);
+    }
+    let mut cmd = Command::new("cargo");
+    cmd.args(&args).current_dir(package_dir);
+    add_rustflags(&mut cmd, &rustflags)?;
+    if let Some(rustc_wrapper) = rustc_wrapper {
+        cmd.env("RUSTC_WRAPPER", rustc_wrapper);
+    }
+    cmd.envs(get_debug_info_cargo_env(profile, debug_info)?);
+    cmd.envs(build_env);
+    if json_diagnostics {
+        run_cargo_with_json_diagnostics(&mut cmd, &process_name)?;
+    } else if let Some((stdout, stderr)) = run_command(&mut cmd, verbose)? {
         if stdout.contains("warning") {
             warn!("{}", stdout);
         }
@@ -1054,13 +1730,28 @@ async fn compile_rust_wasm_process(
         .replace("-", "_");
     let wasm_file_name_hep = wasm_file_name_cab.replace("_", "-");
 
-    let wasm_file_prefix = Path::new("target/wasm32-wasip1/release");
+    let wasm_file_prefix = cargo_target_dir
+        .join(wasm_target)
+        .join(get_cargo_profile_dir(profile));
     let wasm_file_cab = wasm_file_prefix.join(&format!("{wasm_file_name_cab}.wasm"));
 
     let wasm_file_pkg = format!("pkg/{wasm_file_name_hep}.wasm");
     let wasm_file_pkg = Path::new(&wasm_file_pkg);
 
-    let wasi_snapshot_file = Path::new("target/wasi_snapshot_preview1.wasm");
+    if is_wasip2 || is_cargo_component {
+        // wasm32-wasip2 & cargo-component output is already a component: no adapter needed
+        fs::copy(
+            package_dir.join(&wasm_file_cab),
+            package_dir.join(wasm_file_pkg),
+        )?;
+        info!(
+            "Done compiling Rust Hyperware process in {:?}.",
+            process_dir
+        );
+        return Ok(());
+    }
+
+    let wasi_snapshot_file = Path::new("target/wasi_snapshot_preview1.wasm");
 
     run_command(
         Command::new("wasm-tools")
@@ -1084,46 +1775,182 @@ async fn compile_rust_wasm_process(
     Ok(())
 }
 
+/// Get the JS package manager to build the UI with: `ui_package_manager` if given,
+///  else detected from the lockfile in `ui_path`, falling back to npm
+fn get_ui_package_manager(ui_path: &Path, ui_package_manager: &str) -> Result<&'static str> {
+    match ui_package_manager {
+        "npm" => Ok("npm"),
+        "pnpm" => Ok("pnpm"),
+        "yarn" => Ok("yarn"),
+        "bun" => Ok("bun"),
+        "auto" => {
+            let package_manager = if ui_path.join("pnpm-lock.yaml").exists() {
+                "pnpm"
+            } else if ui_path.join("yarn.lock").exists() {
+                "yarn"
+            } else if ui_path.join("bun.lockb").exists() || ui_path.join("bun.lock").exists() {
+                "bun"
+            } else {
+                "npm"
+            };
+            Ok(package_manager)
+        }
+        _ => Err(eyre!("Unknown UI package manager {ui_package_manager:?}")),
+    }
+}
+
+/// Get the UI lint commands `--deny-warnings` requires to pass: the `lint` script of
+///  `package.json`, if any, and `tsc --noEmit`, if the UI has a `tsconfig.json`
+fn get_ui_lint_commands(ui_path: &Path, package_manager: &str) -> Result<Vec<String>> {
+    let package_json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(ui_path.join(PACKAGE_JSON_NAME))?)?;
+    let mut commands = vec![];
+    if package_json
+        .get("scripts")
+        .and_then(|scripts| scripts.get("lint"))
+        .is_some()
+    {
+        commands.push(format!("{package_manager} run lint"));
+    }
+    if ui_path.join("tsconfig.json").exists() {
+        let exec = match package_manager {
+            "pnpm" => "pnpm exec",
+            "yarn" => "yarn",
+            "bun" => "bunx",
+            _ => "npx",
+        };
+        commands.push(format!("{exec} tsc --noEmit"));
+    }
+    Ok(commands)
+}
+
+/// Get the command to install UI dependencies; if `locked`, fail rather than update the lockfile
+fn get_ui_install_command(package_manager: &str, locked: bool) -> String {
+    match (package_manager, locked) {
+        ("npm", true) => "npm ci".to_string(),
+        (_, true) => format!("{package_manager} install --frozen-lockfile"),
+        (_, false) => format!("{package_manager} install"),
+    }
+}
+
+/// Where the hash of the last successful build of the UI at `ui_path` is stored
+fn get_ui_hash_path(ui_path: &Path) -> PathBuf {
+    ui_path
+        .parent()
+        .unwrap()
+        .join("target")
+        .join(UI_HASHES_DIR_NAME)
+        .join(format!(
+            "{}.txt",
+            ui_path.file_name().unwrap().to_str().unwrap()
+        ))
+}
+
+/// Hash the sources and lockfile of the UI at `ui_path`, along with the other inputs
+///  of its build, to tell whether it must be rebuilt
+#[instrument(level = "trace", skip_all)]
+fn hash_ui_dir(
+    ui_path: &Path,
+    package_manager: &str,
+    build_env: &BTreeMap<String, String>,
+    deny_warnings: bool,
+) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(package_manager.as_bytes());
+    hasher.update(&[deny_warnings as u8]);
+    for (key, value) in build_env {
+        if !UI_HASH_EXCLUDE_ENV.contains(&key.as_str()) {
+            hasher.update(format!("{key}={value}\n").as_bytes());
+        }
+    }
+    let mut paths: Vec<PathBuf> = WalkDir::new(ui_path)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_str().unwrap_or_default();
+            !(entry.file_type().is_dir()
+                && (name == "node_modules"
+                    || (entry.depth() == 1 && UI_HASH_EXCLUDE_DIRS.contains(&name))))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    paths.sort();
+    for path in paths {
+        hasher.update(path.strip_prefix(ui_path)?.to_string_lossy().as_bytes());
+        hasher.update(fs::read(&path)?);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 #[instrument(level = "trace", skip_all)]
 async fn compile_and_copy_ui(
     ui_path: &Path,
     valid_node: Option<String>,
+    build_env: &BTreeMap<String, String>,
+    ui_package_manager: &str,
+    locked: bool,
+    force_ui: bool,
+    deny_warnings: bool,
     verbose: bool,
 ) -> Result<()> {
-    info!("Building UI in {:?}...", ui_path);
-
     if ui_path.exists() && ui_path.is_dir() && ui_path.join("package.json").exists() {
-        info!("Running npm install...");
-
-        let install = "npm install".to_string();
-        let run = "npm run build:copy".to_string();
-        let (install, run) = valid_node
-            .map(|valid_node| {
-                (
-                    format!(
-                        "source ~/.nvm/nvm.sh && nvm use {} && {}",
-                        valid_node, install
-                    ),
-                    format!("source ~/.nvm/nvm.sh && nvm use {} && {}", valid_node, run),
-                )
-            })
-            .unwrap_or_else(|| (install, run));
+        let package_manager = get_ui_package_manager(ui_path, ui_package_manager)?;
+        let ui_hash = hash_ui_dir(ui_path, package_manager, build_env, deny_warnings)?;
+        let ui_hash_path = get_ui_hash_path(ui_path);
+        // the hash only covers the UI sources: rebuild if its `build:copy` output is gone
+        let is_copied = ui_path.parent().unwrap().join("pkg").join("ui").exists();
+        if !force_ui
+            && is_copied
+            && fs::read_to_string(&ui_hash_path).ok().as_deref() == Some(&ui_hash)
+        {
+            info!("UI in {ui_path:?} unchanged; skipping build (use `--force-ui` to rebuild).");
+            return Ok(());
+        }
+
+        info!("Building UI in {:?}...", ui_path);
+        let install = get_ui_install_command(package_manager, locked);
+        let run = format!("{package_manager} run build:copy");
+        info!("Running {install}...");
+        let with_node = |command: String| match valid_node {
+            Some(ref valid_node) => {
+                format!("source ~/.nvm/nvm.sh && nvm use {valid_node} && {command}")
+            }
+            None => command,
+        };
 
         run_command(
             Command::new("bash")
-                .args(&["-c", &install])
+                .args(&["-c", &with_node(install)])
                 .current_dir(&ui_path),
             verbose,
         )?;
 
-        info!("Running npm run build:copy...");
+        if deny_warnings {
+            for lint in get_ui_lint_commands(ui_path, package_manager)? {
+                info!("Running {lint}...");
+                run_command(
+                    Command::new("bash")
+                        .args(&["-c", &with_node(lint.clone())])
+                        .current_dir(&ui_path),
+                    verbose,
+                )
+                .wrap_err_with(|| format!("`{lint}` failed with `--deny-warnings`"))?;
+            }
+        }
+
+        info!("Running {package_manager} run build:copy...");
 
         run_command(
             Command::new("bash")
-                .args(&["-c", &run])
+                .args(&["-c", &with_node(run)])
+                .envs(get_ui_build_env(build_env))
                 .current_dir(&ui_path),
             verbose,
         )?;
+
+        fs::create_dir_all(ui_hash_path.parent().unwrap())?;
+        fs::write(&ui_hash_path, ui_hash)?;
     } else {
         return Err(eyre!("UI directory {ui_path:?} not found"));
     }
@@ -1132,20 +1959,82 @@ async fn compile_and_copy_ui(
     Ok(())
 }
 
+/// Build each UI dir in turn
+#[instrument(level = "trace", skip_all)]
+async fn compile_uis(
+    ui_dirs: Vec<PathBuf>,
+    valid_node: Option<String>,
+    build_env: BTreeMap<String, String>,
+    ui_package_manager: String,
+    locked: bool,
+    force_ui: bool,
+    deny_warnings: bool,
+    verbose: bool,
+) -> Result<()> {
+    for ui_dir in ui_dirs {
+        let step = BuildStep::start("ui", &ui_dir);
+        let result = compile_and_copy_ui(
+            &ui_dir,
+            valid_node.clone(),
+            &build_env,
+            &ui_package_manager,
+            locked,
+            force_ui,
+            deny_warnings,
+            verbose,
+        )
+        .await;
+        step.end(result)?;
+    }
+    Ok(())
+}
+
+enum WitSource {
+    Url(String),
+    Path(PathBuf),
+}
+
+/// Get where to fetch `hyperware.wit` from: `kit.toml` `[wit]` if set, else based on `wit_version`
+fn get_wit_source(
+    package_dir: &Path,
+    wit_version: Option<u32>,
+    wit_config: &WitConfig,
+) -> Result<WitSource> {
+    match (&wit_config.url, &wit_config.rev, &wit_config.path) {
+        (None, None, None) => Ok(WitSource::Url(
+            match wit_version {
+                Some(1) | _ => HYPERWARE_WIT_1_0_0_URL,
+            }
+            .to_string(),
+        )),
+        (Some(url), None, None) => Ok(WitSource::Url(url.clone())),
+        (None, Some(rev), None) => Ok(WitSource::Url(format!(
+            "{HYPERWARE_WIT_REPO_URL}/{rev}/hyperware.wit"
+        ))),
+        (None, None, Some(path)) => Ok(WitSource::Path(package_dir.join(path))),
+        _ => Err(eyre!(
+            "kit.toml `[wit]` may set only one of `url`, `rev`, or `path`"
+        )),
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
 async fn build_wit_dir(
     package_dir: &Path,
     apis: &HashMap<String, Vec<u8>>,
-    wit_version: Option<u32>,
+    wit_source: &WitSource,
 ) -> Result<()> {
     let wit_dir = package_dir.join("target").join("wit");
     if wit_dir.exists() {
         fs::remove_dir_all(&wit_dir)?;
     }
-    let wit_url = match wit_version {
-        Some(1) | _ => HYPERWARE_WIT_1_0_0_URL,
-    };
-    download_file(wit_url, &wit_dir.join("hyperware.wit")).await?;
+    match wit_source {
+        WitSource::Url(wit_url) => download_file(wit_url, &wit_dir.join("hyperware.wit")).await?,
+        WitSource::Path(wit_path) => {
+            fs::create_dir_all(&wit_dir)?;
+            fs::copy(wit_path, wit_dir.join("hyperware.wit"))?;
+        }
+    }
     for (file_name, contents) in apis {
         let destination = wit_dir.join(file_name);
         fs::write(&destination, contents)?;
@@ -1153,6 +2042,103 @@ async fn build_wit_dir(
     Ok(())
 }
 
+/// Rename the component built from `process_dir` in `pkg/` to `<process>-<world>.wasm`
+fn suffix_process_wasm(process_dir: &Path, world: &str) -> Result<()> {
+    let Some(package_dir) = process_dir.parent() else {
+        return Err(eyre!(
+            "Could not derive package dir from process_dir ({process_dir:?}) parent"
+        ));
+    };
+    let wasm_file_name = process_dir.file_name().and_then(|s| s.to_str()).unwrap();
+    let wasm_file_name_hep = wasm_file_name.replace("_", "-");
+    let pkg_dir = package_dir.join("pkg");
+    let Some(wasm_file_pkg) = [wasm_file_name, &wasm_file_name_hep]
+        .iter()
+        .map(|name| pkg_dir.join(format!("{name}.wasm")))
+        .find(|path| path.exists())
+    else {
+        return Err(eyre!(
+            "Could not find component built from {process_dir:?} for world {world} in {pkg_dir:?}"
+        ));
+    };
+    let wasm_file_world = pkg_dir.join(format!("{wasm_file_name_hep}-{world}.wasm"));
+    fs::rename(&wasm_file_pkg, &wasm_file_world)?;
+    info!("Built world {world} of {process_dir:?} into {wasm_file_world:?}.");
+    Ok(())
+}
+
+#[instrument(level = "trace", skip_all)]
+async fn compile_process_for_world(
+    path: &Path,
+    features: &str,
+    world: &ProcessWorld,
+    is_rust_process: bool,
+    is_py_process: bool,
+    is_js_process: bool,
+    is_go_process: bool,
+    is_c_process: bool,
+    wasm_target: &'static str,
+    offline: bool,
+    locked: bool,
+    rustc_wrapper: Option<&'static str>,
+    cargo_config: &CargoConfig,
+    process_config: &ProcessConfig,
+    profile: &str,
+    debug_info: &str,
+    build_env: &BTreeMap<String, String>,
+    deny_warnings: bool,
+    json_diagnostics: bool,
+    verbose: bool,
+    backend: &str,
+    toolchain: &str,
+) -> Result<()> {
+    if is_rust_process {
+        let mut process_config = process_config.clone();
+        if let ProcessWorld::Explicit(ref world) = world {
+            process_config.features.push(world.clone());
+        }
+        compile_rust_wasm_process(
+            path,
+            features,
+            wasm_target,
+            offline,
+            locked,
+            rustc_wrapper,
+            cargo_config,
+            &process_config,
+            profile,
+            debug_info,
+            build_env,
+            deny_warnings,
+            json_diagnostics,
+            verbose,
+            backend,
+            toolchain,
+        )
+        .await
+    } else if is_py_process {
+        match get_python_version(None, None) {
+            Ok(Some(python)) => compile_python_wasm_process(path, &python, world, verbose).await,
+            Ok(None) => Err(eyre!("kit requires Python 3.10 or newer")),
+            Err(e) => Err(e),
+        }
+    } else if is_js_process {
+        match get_newest_valid_node_version(None, None) {
+            Ok(valid_node) => {
+                compile_javascript_wasm_process(path, valid_node, world, build_env, verbose).await
+            }
+            Err(e) => Err(e),
+        }
+    } else if is_go_process {
+        compile_go_wasm_process(path, world, verbose).await
+    } else if is_c_process {
+        let is_cpp = path.join(CPP_SRC_PATH).exists();
+        compile_c_wasm_process(path, world, is_cpp, verbose).await
+    } else {
+        Ok(())
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
 async fn compile_package_item(
     path: PathBuf,
@@ -1161,20 +2147,74 @@ async fn compile_package_item(
     is_rust_process: bool,
     is_py_process: bool,
     is_js_process: bool,
+    is_go_process: bool,
+    is_c_process: bool,
+    wasm_target: &'static str,
+    offline: bool,
+    locked: bool,
+    rustc_wrapper: Option<&'static str>,
+    cargo_config: CargoConfig,
+    process_config: ProcessConfig,
+    profile: String,
+    debug_info: String,
+    build_env: BTreeMap<String, String>,
+    deny_warnings: bool,
+    json_diagnostics: bool,
     verbose: bool,
+    backend: String,
     toolchain: String,
 ) -> Result<()> {
-    if is_rust_process {
-        compile_rust_wasm_process(&path, &features, verbose, &toolchain).await?;
-    } else if is_py_process {
-        let python = get_python_version(None, None)?
-            .ok_or_else(|| eyre!("kit requires Python 3.10 or newer"))?;
-        compile_python_wasm_process(&path, &python, &world, verbose).await?;
-    } else if is_js_process {
-        let valid_node = get_newest_valid_node_version(None, None)?;
-        compile_javascript_wasm_process(&path, valid_node, &world, verbose).await?;
+    let step = BuildStep::start("process", &path);
+    // build any additional worlds first, moving each aside to its suffixed name,
+    //  so that the first world is built last and keeps the name the manifest refers to
+    let worlds: Vec<(ProcessWorld, Option<&str>)> = match process_config.worlds.split_first() {
+        None => vec![(ProcessWorld::Default(world), None)],
+        Some((first, rest)) => rest
+            .iter()
+            .map(|w| (ProcessWorld::Explicit(w.clone()), Some(w.as_str())))
+            .chain(std::iter::once((
+                ProcessWorld::Explicit(first.clone()),
+                None,
+            )))
+            .collect(),
+    };
+    let mut result = Ok(());
+    for (world, suffix) in worlds {
+        result = compile_process_for_world(
+            &path,
+            &features,
+            &world,
+            is_rust_process,
+            is_py_process,
+            is_js_process,
+            is_go_process,
+            is_c_process,
+            wasm_target,
+            offline,
+            locked,
+            rustc_wrapper,
+            &cargo_config,
+            &process_config,
+            &profile,
+            &debug_info,
+            &build_env,
+            deny_warnings,
+            json_diagnostics,
+            verbose,
+            &backend,
+            &toolchain,
+        )
+        .await;
+        if result.is_ok() {
+            if let Some(suffix) = suffix {
+                result = suffix_process_wasm(&path, suffix);
+            }
+        }
+        if result.is_err() {
+            break;
+        }
     }
-    Ok(())
+    step.end(result)
 }
 
 #[instrument(level = "trace", skip_all)]
@@ -1213,39 +2253,16 @@ async fn fetch_dependencies(
     dependencies: &Vec<String>,
     apis: &mut HashMap<String, Vec<u8>>,
     wasm_paths: &mut HashSet<PathBuf>,
-    url: Option<String>,
-    download_from: Option<&str>,
     mut local_dependencies: Vec<PathBuf>,
-    features: &str,
-    default_world: Option<&str>,
-    include: &HashSet<PathBuf>,
-    exclude: &HashSet<PathBuf>,
-    rewrite: bool,
-    hyperapp: bool,
-    force: bool,
-    verbose: bool,
-    toolchain: &str,
+    options: &BuildOptions<'_>,
 ) -> Result<()> {
     if let Err(e) = Box::pin(execute(
         package_dir,
-        true,
-        false,
-        include,
-        exclude,
-        true,
-        features,
-        url.clone(),
-        download_from,
-        default_world,
-        vec![], // TODO: what about deps-of-deps?
-        vec![],
-        rewrite,
-        hyperapp,
-        false,
-        force,
-        verbose,
-        true,
-        toolchain,
+        BuildOptions {
+            local_dependencies: vec![], // TODO: what about deps-of-deps?
+            ignore_deps: true,
+            ..options.clone()
+        },
     ))
     .await
     {
@@ -1267,31 +2284,14 @@ async fn fetch_dependencies(
             .collect();
         Box::pin(execute(
             local_dependency,
-            true,
-            false,
-            include,
-            exclude,
-            true,
-            features,
-            url.clone(),
-            download_from,
-            default_world,
-            local_dep_deps,
-            vec![],
-            rewrite,
-            hyperapp,
-            false,
-            force,
-            verbose,
-            false,
-            toolchain,
+            BuildOptions {
+                local_dependencies: local_dep_deps,
+                ..options.clone()
+            },
         ))
         .await?;
         fetch_local_built_dependency(apis, wasm_paths, &local_dependency)?;
     }
-    let Some(ref url) = url else {
-        return Ok(());
-    };
     local_dependencies.push(package_dir.into());
     let local_dependencies: HashSet<&str> = local_dependencies
         .iter()
@@ -1307,12 +2307,17 @@ async fn fetch_dependencies(
         if local_dependencies.contains(dep.package()) {
             continue;
         }
-        let Some(zip_dir) =
-            view_api::execute(None, Some(dependency), url, download_from, false).await?
+        let Some(zip_dir) = get_dependency_api(
+            dependency,
+            options.url.as_deref(),
+            options.download_from,
+            options.no_fetch || options.offline,
+            options.api_cache_ttl,
+            &options.dep_source,
+        )
+        .await?
         else {
-            return Err(eyre!(
-                "Got unexpected result from fetching API for {dependency}"
-            ));
+            continue;
         };
         for entry in fs::read_dir(zip_dir)? {
             let entry = entry?;
@@ -1333,6 +2338,89 @@ async fn fetch_dependencies(
     Ok(())
 }
 
+/// `metadata.json` dependencies may be paths to local packages (e.g. `../other-package`)
+///  rather than package IDs; these are built before the depending package
+pub fn is_path_dependency(dependency: &str) -> bool {
+    dependency.starts_with('.') || dependency.starts_with('/')
+}
+
+/// Resolve a path dependency relative to the dir of the package that depends on it
+pub fn resolve_path_dependency(package_dir: &Path, dependency: &str) -> Result<PathBuf> {
+    let path = package_dir.join(dependency);
+    if !(path.join("metadata.json").exists() && path.join("pkg").is_dir()) {
+        return Err(eyre!(
+            "Path dependency {dependency} of {package_dir:?} is not a package"
+        )
+        .with_suggestion(|| {
+            "Path dependencies must be dirs containing both a `metadata.json` and a `pkg/` dir."
+        }));
+    }
+    Ok(fs::canonicalize(path)?)
+}
+
+/// Get the API of a dependency: from the cache if fetched within `api_cache_ttl` seconds,
+///  else from the node at `url`, falling back to a stale cached API if that fails
+#[instrument(level = "trace", skip_all)]
+async fn get_dependency_api(
+    dependency: &str,
+    url: Option<&str>,
+    download_from: Option<&str>,
+    no_fetch: bool,
+    api_cache_ttl: u64,
+    dep_source: &DepSource,
+) -> Result<Option<PathBuf>> {
+    let ttl = if no_fetch { None } else { Some(api_cache_ttl) };
+    if let Some(api_dir) = get_cached_api(dependency, dep_source, ttl)? {
+        debug!("using cached API for {dependency} at {api_dir:?}");
+        return Ok(Some(api_dir));
+    }
+    if no_fetch {
+        return Err(
+            eyre!("API for dependency {dependency} not found in {KIT_CACHE}")
+                .with_suggestion(|| "Re-run once without `--no-fetch` to populate the cache."),
+        );
+    }
+    let source = match dep_source {
+        DepSource::Node => match url {
+            Some(url) => url.to_string(),
+            None => return Ok(None),
+        },
+        DepSource::Dir(ref dir) => format!("{dir:?}"),
+        DepSource::Git(ref source) | DepSource::Http(ref source) => source.clone(),
+    };
+    let fetched = async {
+        let (zip_dir, version) = match dep_source {
+            DepSource::Node => {
+                let Some(zip_dir) =
+                    view_api::execute(None, Some(dependency), &source, download_from, false)
+                        .await?
+                else {
+                    return Err(eyre!(
+                        "Got unexpected result from fetching API for {dependency}"
+                    ));
+                };
+                let version = view_api::get_package_version_hash(None, &source, dependency)
+                    .await
+                    .unwrap_or_default();
+                (zip_dir, version)
+            }
+            _ => get_api_from_dep_source(dep_source, dependency).await?,
+        };
+        cache_api(dependency, dep_source, &version, &zip_dir)
+    }
+    .await;
+    match fetched {
+        Ok(api_dir) => Ok(Some(api_dir)),
+        Err(e) => match get_cached_api(dependency, dep_source, None)? {
+            Some(api_dir) => {
+                warn!("Failed to fetch API for {dependency} from {source}; using stale cached API: {e}");
+                Ok(Some(api_dir))
+            }
+            None => Err(e),
+        },
+    }
+}
+
 fn extract_imports_exports_from_wit(input: &str) -> (Vec<String>, Vec<String>) {
     let import_re = regex::Regex::new(r"import\s+([^\s;]+)").unwrap();
     let export_re = regex::Regex::new(r"export\s+([^\s;]+)").unwrap();
@@ -1469,6 +2557,7 @@ fn get_ui_dirs(
 async fn check_and_populate_dependencies(
     package_dir: &Path,
     metadata: &Erc721Metadata,
+    wasm_target: &str,
     skip_deps_check: bool,
     verbose: bool,
     toolchain: &str,
@@ -1476,6 +2565,8 @@ async fn check_and_populate_dependencies(
     let mut checked_rust = false;
     let mut checked_py = false;
     let mut checked_js = false;
+    let mut checked_go = false;
+    let mut checked_c = false;
     let mut apis = HashMap::new();
     let mut dependencies = HashSet::new();
     let mut recv_kill = make_fake_kill_chan();
@@ -1487,7 +2578,10 @@ async fn check_and_populate_dependencies(
         let path = entry.path();
         if path.is_dir() {
             if path.join(RUST_SRC_PATH).exists() && !checked_rust && !skip_deps_check {
-                let deps = check_rust_deps(toolchain)?;
+                let mut deps = check_rust_deps(toolchain)?;
+                if wasm_target == WASIP2_TARGET {
+                    deps.extend(check_rust_wasip2_deps(toolchain)?);
+                }
                 get_deps(deps, &mut recv_kill, false, verbose, toolchain).await?;
                 checked_rust = true;
             } else if path.join(PYTHON_SRC_PATH).exists() && !checked_py {
@@ -1497,6 +2591,17 @@ async fn check_and_populate_dependencies(
                 let deps = check_js_deps()?;
                 get_deps(deps, &mut recv_kill, false, verbose, toolchain).await?;
                 checked_js = true;
+            } else if path.join(GO_SRC_PATH).exists() && !checked_go && !skip_deps_check {
+                let deps = check_go_deps()?;
+                get_deps(deps, &mut recv_kill, false, verbose, toolchain).await?;
+                checked_go = true;
+            } else if (path.join(C_SRC_PATH).exists() || path.join(CPP_SRC_PATH).exists())
+                && !checked_c
+                && !skip_deps_check
+            {
+                let deps = check_c_deps()?;
+                get_deps(deps, &mut recv_kill, false, verbose, toolchain).await?;
+                checked_c = true;
             } else if Some("api") == path.file_name().and_then(|s| s.to_str()) {
                 // read api files: to be used in build
                 for entry in fs::read_dir(path)? {
@@ -1558,6 +2663,101 @@ fn zip_api(
     Ok(())
 }
 
+/// Fail fast if an offline build would need to fetch something not already in KIT_CACHE
+#[instrument(level = "trace", skip_all)]
+fn check_offline_build(
+    package_dir: &Path,
+    local_dependencies: &Vec<PathBuf>,
+    ignore_deps: bool,
+    dep_source: &DepSource,
+) -> Result<()> {
+    let metadata = read_metadata(package_dir)?;
+    let wasm_target = get_rust_wasm_target(package_dir)?;
+
+    let mut needs_wit = false;
+    let mut needs_adapter = false;
+    let mut needs_wasi_sdk = false;
+    for entry in fs::read_dir(package_dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let is_rust_process = path.join(RUST_SRC_PATH).exists();
+        let is_other_process = path.join(PYTHON_SRC_PATH).exists()
+            || path.join(JAVASCRIPT_SRC_PATH).exists()
+            || path.join(GO_SRC_PATH).exists();
+        let is_c_process = path.join(C_SRC_PATH).exists() || path.join(CPP_SRC_PATH).exists();
+        if is_rust_process || is_other_process || is_c_process {
+            needs_wit = true;
+        }
+        if (is_rust_process && wasm_target != WASIP2_TARGET)
+            || path.join(GO_SRC_PATH).exists()
+            || is_c_process
+        {
+            needs_adapter = true;
+        }
+        if is_c_process {
+            needs_wasi_sdk = true;
+        }
+    }
+
+    let mut missing = vec![];
+    let wit_source = get_wit_source(
+        package_dir,
+        metadata.properties.wit_version,
+        &read_kit_toml(package_dir)?.wit,
+    )?;
+    match wit_source {
+        WitSource::Url(ref wit_url) => {
+            if needs_wit && !get_cached_download_path(wit_url).exists() {
+                missing.push(format!("hyperware WIT ({wit_url})"));
+            }
+        }
+        WitSource::Path(ref wit_path) => {
+            if needs_wit && !wit_path.exists() {
+                missing.push(format!("hyperware WIT ({wit_path:?})"));
+            }
+        }
+    }
+    let wasi_snapshot_url = get_wasi_snapshot_url();
+    if needs_adapter && !get_cached_download_path(&wasi_snapshot_url).exists() {
+        missing.push(format!("WASI adapter ({wasi_snapshot_url})"));
+    }
+    let (sdk_name, sdk_dir) = get_wasi_sdk_name_and_dir();
+    if needs_wasi_sdk && !sdk_dir.join("bin").join("clang").exists() {
+        missing.push(sdk_name);
+    }
+    if !ignore_deps {
+        let local_dependencies: HashSet<&str> = local_dependencies
+            .iter()
+            .filter_map(|p| p.file_name().and_then(|f| f.to_str()))
+            .collect();
+        for dependency in metadata.properties.dependencies.unwrap_or_default() {
+            if is_path_dependency(&dependency) {
+                continue;
+            }
+            let is_local = dependency
+                .parse::<PackageId>()
+                .map(|dep| local_dependencies.contains(dep.package()))
+                .unwrap_or(false);
+            if !is_local && get_cached_api(&dependency, dep_source, None)?.is_none() {
+                missing.push(format!("API for dependency {dependency}"));
+            }
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(eyre!(
+        "Offline build of {package_dir:?} requires resources not found in {KIT_CACHE}:\n{}",
+        missing.join("\n"),
+    )
+    .with_suggestion(|| {
+        "Re-run once without `--offline` to populate the cache, or pass dependencies with `--local-dependency`."
+    }))
+}
+
 /// is included AND is not excluded
 fn is_cluded(path: &Path, include: &HashSet<PathBuf>, exclude: &HashSet<PathBuf>) -> bool {
     (include.is_empty() || include.contains(path)) && !exclude.contains(path)
@@ -1584,31 +2784,79 @@ fn is_cluded(path: &Path, include: &HashSet<PathBuf>, exclude: &HashSet<PathBuf>
 ///   target/                             <- built
 ///     api/
 ///     wit/
+/// Process dirs in the package to compile, with which language each is written in:
+///  (path, is_rust, is_py, is_js, is_go, is_c)
+#[instrument(level = "trace", skip_all)]
+fn get_processes_to_compile(
+    package_dir: &Path,
+    include: &HashSet<PathBuf>,
+    exclude: &HashSet<PathBuf>,
+) -> Result<HashSet<(PathBuf, bool, bool, bool, bool, bool)>> {
+    let mut to_compile = HashSet::new();
+    for entry in fs::read_dir(package_dir)? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+        if !is_cluded(&path, include, exclude) {
+            continue;
+        }
+        if !path.is_dir() {
+            continue;
+        }
+
+        let is_rust_process = path.join(RUST_SRC_PATH).exists();
+        let is_py_process = path.join(PYTHON_SRC_PATH).exists();
+        let is_js_process = path.join(JAVASCRIPT_SRC_PATH).exists();
+        let is_go_process = path.join(GO_SRC_PATH).exists();
+        let is_c_process = path.join(C_SRC_PATH).exists() || path.join(CPP_SRC_PATH).exists();
+        if is_rust_process || is_py_process || is_js_process || is_go_process || is_c_process {
+            to_compile.insert((
+                path,
+                is_rust_process,
+                is_py_process,
+                is_js_process,
+                is_go_process,
+                is_c_process,
+            ));
+        }
+    }
+    Ok(to_compile)
+}
+
 #[instrument(level = "trace", skip_all)]
 async fn compile_package(
     package_dir: &Path,
     skip_deps_check: bool,
     features: &str,
-    url: Option<String>,
     default_world: Option<&str>,
-    download_from: Option<&str>,
     local_dependencies: Vec<PathBuf>,
     add_paths_to_api: &Vec<PathBuf>,
     include: &HashSet<PathBuf>,
     exclude: &HashSet<PathBuf>,
-    rewrite: bool,
-    hyperapp: bool,
-    force: bool,
     verbose: bool,
     hyperapp_processed_projects: Option<Vec<PathBuf>>,
     ignore_deps: bool, // for internal use; may cause problems when adding recursive deps
+    offline: bool,
+    cache_backend: &str,
+    locked: bool,
+    cargo_args: &[String],
+    profile: &str,
+    debug_info: &str,
+    build_env: &BTreeMap<String, String>,
+    deny_warnings: bool,
+    log_format: &str,
+    backend: &str,
     toolchain: &str,
+    dependency_options: &BuildOptions<'_>,
 ) -> Result<()> {
     let metadata = read_and_update_metadata(package_dir)?;
+    let wasm_target = get_rust_wasm_target(package_dir)?;
     let mut wasm_paths = HashSet::new();
     let (mut apis, dependencies) = check_and_populate_dependencies(
         package_dir,
         &metadata,
+        wasm_target,
         skip_deps_check,
         verbose,
         toolchain,
@@ -1618,23 +2866,26 @@ async fn compile_package(
     info!("dependencies: {dependencies:?}");
     if !ignore_deps && !dependencies.is_empty() {
         info!("fetching dependencies...");
+        // path dependencies are built locally, like `--local-dependency`s
+        let mut local_dependencies = local_dependencies.clone();
+        let mut package_id_dependencies = vec![];
+        for dependency in &dependencies {
+            if is_path_dependency(dependency) {
+                let path = resolve_path_dependency(package_dir, dependency)?;
+                if !local_dependencies.contains(&path) {
+                    local_dependencies.push(path);
+                }
+            } else {
+                package_id_dependencies.push(dependency.to_string());
+            }
+        }
         fetch_dependencies(
             package_dir,
-            &dependencies.iter().map(|s| s.to_string()).collect(),
+            &package_id_dependencies,
             &mut apis,
             &mut wasm_paths,
-            url.clone(),
-            download_from,
-            local_dependencies.clone(),
-            features,
-            default_world,
-            include,
-            exclude,
-            rewrite,
-            hyperapp,
-            force,
-            verbose,
-            toolchain,
+            local_dependencies,
+            dependency_options,
         )
         .await?
     }
@@ -1645,44 +2896,38 @@ async fn compile_package(
         })
         .to_string();
 
-    build_wit_dir(&package_dir, &apis, metadata.properties.wit_version).await?;
+    let kit_toml = read_kit_toml(package_dir)?;
+    let wit_source = get_wit_source(package_dir, metadata.properties.wit_version, &kit_toml.wit)?;
+    build_wit_dir(&package_dir, &apis, &wit_source).await?;
+
+    let rustc_wrapper = get_rustc_wrapper(cache_backend)?;
+    let cargo_config = get_cargo_config(kit_toml.cargo, cargo_args);
 
     let mut tasks = tokio::task::JoinSet::new();
     let features = features.to_string();
-    let mut to_compile = HashSet::new();
-    for entry in fs::read_dir(package_dir)? {
-        let Ok(entry) = entry else {
-            continue;
-        };
-        let path = entry.path();
-        if !is_cluded(&path, include, exclude) {
-            continue;
-        }
-        if !path.is_dir() {
-            continue;
-        }
-
-        let is_rust_process = path.join(RUST_SRC_PATH).exists();
-        let is_py_process = path.join(PYTHON_SRC_PATH).exists();
-        let is_js_process = path.join(JAVASCRIPT_SRC_PATH).exists();
-        if is_rust_process || is_py_process || is_js_process {
-            to_compile.insert((path, is_rust_process, is_py_process, is_js_process));
-        }
-    }
+    let to_compile = get_processes_to_compile(package_dir, include, exclude)?;
 
     let api_dir = package_dir.join("target").join("wit");
     //info!("{processed_project:?} {api_dir:?}");
     if let Some(ref processed_projects) = hyperapp_processed_projects {
-        caller_utils_generator::create_caller_utils(package_dir, &api_dir)?;
+        caller_utils_generator::create_caller_utils(package_dir, &api_dir, &kit_toml.caller_utils)?;
         for processed_project in processed_projects {
             caller_utils_generator::add_caller_utils_to_projects(
                 &[processed_project.clone()],
                 package_dir,
+                &kit_toml.caller_utils,
             )?;
         }
     }
 
-    for (path, is_rust_process, is_py_process, is_js_process) in to_compile {
+    for (path, is_rust_process, is_py_process, is_js_process, is_go_process, is_c_process) in
+        to_compile
+    {
+        let process_config = path
+            .file_name()
+            .and_then(|name| kit_toml.processes.get(name.to_str()?))
+            .cloned()
+            .unwrap_or_default();
         tasks.spawn(compile_package_item(
             path,
             features.clone(),
@@ -1690,7 +2935,21 @@ async fn compile_package(
             is_rust_process,
             is_py_process,
             is_js_process,
+            is_go_process,
+            is_c_process,
+            wasm_target,
+            offline,
+            locked,
+            rustc_wrapper,
+            cargo_config.clone(),
+            process_config,
+            profile.to_string(),
+            debug_info.to_string(),
+            build_env.clone(),
+            deny_warnings,
+            log_format == "json",
             verbose.clone(),
+            backend.to_string(),
             toolchain.to_string(),
         ));
     }
@@ -1747,6 +3006,8 @@ async fn compile_package(
         }
     }
 
+    apply_debug_info(package_dir, debug_info, verbose)?;
+
     if target_api_dir.exists() {
         // zip & place API inside of pkg/ to publish API
         zip_api(package_dir, &target_api_dir, add_paths_to_api, &metadata)?;
@@ -1755,47 +3016,226 @@ async fn compile_package(
     Ok(())
 }
 
+/// Remove built wasm & zips so the next build of the package starts from scratch
 #[instrument(level = "trace", skip_all)]
-pub async fn execute(
-    package_dir: &Path,
-    no_ui: bool,
-    ui_only: bool,
-    include: &HashSet<PathBuf>,
-    exclude: &HashSet<PathBuf>,
-    skip_deps_check: bool,
-    features: &str,
-    url: Option<String>,
-    download_from: Option<&str>,
-    default_world: Option<&str>,
-    local_dependencies: Vec<PathBuf>,
-    add_paths_to_api: Vec<PathBuf>,
-    rewrite: bool,
-    hyperapp: bool,
-    reproducible: bool,
-    force: bool,
-    verbose: bool,
-    ignore_deps: bool, // for internal use; may cause problems when adding recursive deps
-    toolchain: &str,
-) -> Result<()> {
-    debug!(
-        "execute:
-    package_dir={package_dir:?},
-    no_ui={no_ui},
-    ui_only={ui_only},
-    include={include:?},
-    exclude={exclude:?},
-    skip_deps_check={skip_deps_check},
-    features={features},
-    url={url:?},
-    download_from={download_from:?},
-    default_world={default_world:?},
-    local_dependencies={local_dependencies:?},
-    add_paths_to_api={add_paths_to_api:?},
-    reproducible={reproducible},
-    force={force},
-    verbose={verbose},
-    ignore_deps={ignore_deps},"
-    );
+fn remove_built_artifacts(package_dir: &Path, zip_filename: &Path) -> Result<()> {
+    if zip_filename.exists() {
+        fs::remove_file(zip_filename)?;
+    }
+    for entry in fs::read_dir(package_dir.join("pkg"))? {
+        let path = entry?.path();
+        let is_api_zip = Some("api.zip") == path.file_name().and_then(|f| f.to_str());
+        let is_wasm = Some("wasm") == path.extension().and_then(|e| e.to_str());
+        if path.is_file() && (is_api_zip || is_wasm) {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remap absolute paths out of compiled artifacts so that builds do not depend
+/// on where the package or the cargo registry live on the build machine
+fn get_path_remapping_rustflags(package_dir: &Path) -> Vec<String> {
+    let mut rustflags = vec![];
+    let cargo_home = std::env::var("CARGO_HOME")
+        .ok()
+        .or_else(|| std::env::var("HOME").ok().map(|h| format!("{h}/.cargo")));
+    if let Some(cargo_home) = cargo_home {
+        rustflags.push(format!("--remap-path-prefix={cargo_home}=/cargo"));
+    }
+    rustflags.push(format!(
+        "--remap-path-prefix={}=/package",
+        package_dir.to_str().unwrap(),
+    ));
+    rustflags
+}
+
+/// Add `rustflags` to those the `cargo build` of `cmd` compiles with. Setting `RUSTFLAGS`
+/// would override `build.rustflags` of `.cargo/config.toml`, so unless the user already set
+/// it (in which case cargo ignores the config anyway), pass them as `--config`, which cargo
+/// joins with the config's
+fn add_rustflags(cmd: &mut Command, rustflags: &[String]) -> Result<()> {
+    if rustflags.is_empty() {
+        return Ok(());
+    }
+    if let Ok(encoded) = std::env::var("CARGO_ENCODED_RUSTFLAGS") {
+        let mut all = if encoded.is_empty() {
+            vec![]
+        } else {
+            vec![encoded]
+        };
+        all.extend(rustflags.iter().cloned());
+        cmd.env("CARGO_ENCODED_RUSTFLAGS", all.join("\x1f"));
+    } else if let Ok(existing) = std::env::var("RUSTFLAGS") {
+        cmd.env("RUSTFLAGS", format!("{existing} {}", rustflags.join(" ")));
+    } else {
+        cmd.arg("--config").arg(format!(
+            "build.rustflags={}",
+            serde_json::to_string(rustflags)?
+        ));
+    }
+    Ok(())
+}
+
+/// Options of a package build; the defaults are those of a plain `kit build`
+#[derive(Debug, Clone)]
+pub struct BuildOptions<'a> {
+    pub no_ui: bool,
+    pub ui_only: bool,
+    pub include: HashSet<PathBuf>,
+    pub exclude: HashSet<PathBuf>,
+    pub skip_deps_check: bool,
+    pub features: &'a str,
+    pub url: Option<String>,
+    pub download_from: Option<&'a str>,
+    pub default_world: Option<&'a str>,
+    pub local_dependencies: Vec<PathBuf>,
+    pub add_paths_to_api: Vec<PathBuf>,
+    pub rewrite: bool,
+    pub hyperapp: bool,
+    pub reproducible: bool,
+    pub force: bool,
+    pub verbose: bool,
+    /// For internal use; may cause problems when adding recursive deps
+    pub ignore_deps: bool,
+    pub offline: bool,
+    pub cache_backend: &'a str,
+    pub locked: bool,
+    pub cargo_args: &'a [String],
+    pub no_fetch: bool,
+    pub api_cache_ttl: u64,
+    pub dep_source: DepSource,
+    pub sign: Option<&'a Path>,
+    pub profile: &'a str,
+    pub debug_info: &'a str,
+    pub ui_package_manager: &'a str,
+    pub force_ui: bool,
+    pub deny_warnings: bool,
+    pub log_format: &'a str,
+    pub dry_run: bool,
+    pub backend: &'a str,
+    pub wit_rename: bool,
+    pub toolchain: &'a str,
+    /// Build-time constants set on top of those of the package's `kit.toml` (see
+    ///  `get_build_env`); dependencies are built with those of the package depending on them
+    pub env: BTreeMap<String, String>,
+    pub ts_sdk: Option<&'a Path>,
+    pub py_client: Option<&'a Path>,
+    pub rust_client: Option<&'a Path>,
+    pub swift_client: Option<&'a Path>,
+    pub kotlin_client: Option<&'a Path>,
+}
+
+impl Default for BuildOptions<'_> {
+    fn default() -> Self {
+        BuildOptions {
+            no_ui: false,
+            ui_only: false,
+            include: HashSet::new(),
+            exclude: HashSet::new(),
+            skip_deps_check: false,
+            features: "",
+            url: None,
+            download_from: None,
+            default_world: None,
+            local_dependencies: vec![],
+            add_paths_to_api: vec![],
+            rewrite: false,
+            hyperapp: false,
+            reproducible: false,
+            force: false,
+            verbose: false,
+            ignore_deps: false,
+            offline: false,
+            cache_backend: DEFAULT_CACHE_BACKEND,
+            locked: false,
+            cargo_args: &[],
+            no_fetch: false,
+            api_cache_ttl: DEFAULT_API_CACHE_TTL,
+            dep_source: DepSource::Node,
+            sign: None,
+            profile: DEFAULT_CARGO_PROFILE,
+            debug_info: DEFAULT_DEBUG_INFO,
+            ui_package_manager: DEFAULT_UI_PACKAGE_MANAGER,
+            force_ui: false,
+            deny_warnings: false,
+            log_format: DEFAULT_LOG_FORMAT,
+            dry_run: false,
+            backend: DEFAULT_BUILD_BACKEND,
+            wit_rename: false,
+            toolchain: DEFAULT_RUST_TOOLCHAIN,
+            env: BTreeMap::new(),
+            ts_sdk: None,
+            py_client: None,
+            rust_client: None,
+            swift_client: None,
+            kotlin_client: None,
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub async fn execute(package_dir: &Path, options: BuildOptions<'_>) -> Result<()> {
+    debug!("execute: package_dir={package_dir:?}, options={options:?}");
+    // dependencies are built with the options of the package depending on them, bar its UI &
+    //  the outputs only it is built into
+    let dependency_options = BuildOptions {
+        no_ui: true,
+        ui_only: false,
+        skip_deps_check: true,
+        force_ui: false,
+        sign: None,
+        ts_sdk: None,
+        py_client: None,
+        rust_client: None,
+        swift_client: None,
+        kotlin_client: None,
+        ..options.clone()
+    };
+    let BuildOptions {
+        no_ui,
+        ui_only,
+        include,
+        exclude,
+        skip_deps_check,
+        features,
+        url,
+        download_from: _,
+        default_world,
+        local_dependencies,
+        add_paths_to_api,
+        rewrite,
+        hyperapp,
+        reproducible,
+        force,
+        verbose,
+        ignore_deps,
+        offline,
+        cache_backend,
+        locked,
+        cargo_args,
+        no_fetch,
+        api_cache_ttl,
+        dep_source,
+        sign,
+        profile,
+        debug_info,
+        ui_package_manager,
+        force_ui,
+        deny_warnings,
+        log_format,
+        dry_run,
+        backend,
+        wit_rename,
+        toolchain,
+        env,
+        ts_sdk,
+        py_client,
+        rust_client,
+        swift_client,
+        kotlin_client,
+    } = options;
+    let (include, exclude, dep_source) = (&include, &exclude, &dep_source);
     let package_dir = fs::canonicalize(package_dir)?;
     if no_ui && ui_only {
         return Err(eyre!(
@@ -1813,22 +3253,103 @@ pub async fn execute(
         )
         .with_suggestion(|| "Please re-run targeting a package."));
     }
-    let build_with_features_path = package_dir.join("target").join("build_with_features.txt");
-    let build_with_cludes_path = package_dir.join("target").join("build_with_cludes.txt");
-    let cludes = format!("include: {include:?}\nexclude: {exclude:?}");
-    if !force
-        && is_up_to_date(
-            &build_with_features_path,
-            &build_with_cludes_path,
-            features,
-            &cludes,
+    let kit_toml = read_kit_toml(&package_dir)?;
+    // the constants of `kit.toml`, & those set on top of them: the env that dependencies get
+    let env: BTreeMap<String, String> = kit_toml.env.clone().into_iter().chain(env).collect();
+    // options that change what is built: a change to any of them rebuilds the package. Not
+    //  `no_ui` or `local_dependencies`, which `fetch_dependencies` changes when it builds the
+    //  package as its own dependency: it would never again be up-to-date
+    let options = format!(
+        "rewrite: {rewrite}\n\
+         default_world: {default_world:?}\n\
+         add_paths_to_api: {add_paths_to_api:?}\n\
+         deny_warnings: {deny_warnings}\n\
+         wit_rename: {wit_rename}\n\
+         toolchain: {toolchain}\n\
+         profile: {profile}\n\
+         debug_info: {debug_info}\n\
+         backend: {backend}\n\
+         cache_backend: {cache_backend}\n\
+         cargo_args: {cargo_args:?}\n\
+         env: {env:?}"
+    );
+    let build_with = [
+        ("build_with_features.txt", features.to_string()),
+        (
+            "build_with_cludes.txt",
+            format!("include: {include:?}\nexclude: {exclude:?}"),
+        ),
+        ("build_with_options.txt", options),
+    ]
+    .map(|(file_name, value)| (package_dir.join("target").join(file_name), value));
+    let rebuild_reason = if force {
+        Some("forced by `--force`")
+    } else if is_up_to_date(&build_with, &package_dir, hyperapp)? {
+        None
+    } else {
+        Some("sources, features, include/exclude or build options changed since the last build")
+    };
+    // `--force-ui` rebuilds only the UI of an otherwise up-to-date package
+    let (rebuild_reason, ui_only) = if rebuild_reason.is_none() && force_ui && !no_ui {
+        (Some("UI forced by `--force-ui`"), true)
+    } else {
+        (rebuild_reason, ui_only)
+    };
+    if dry_run {
+        return print_build_plan(
             &package_dir,
+            rebuild_reason,
+            no_ui,
+            ui_only,
+            &include,
+            &exclude,
+            features,
+            &url,
+            &local_dependencies,
             hyperapp,
-        )?
-    {
+            ignore_deps,
+            no_fetch || offline,
+            api_cache_ttl,
+            dep_source,
+            ui_package_manager,
+            force_ui,
+            deny_warnings,
+            sign,
+            ts_sdk,
+            py_client,
+            rust_client,
+            swift_client,
+            kotlin_client,
+        );
+    }
+    if rebuild_reason.is_none() {
+        if let Some(sdk_dir) = ts_sdk {
+            create_ts_sdk(&package_dir, sdk_dir)?;
+        }
+        if let Some(py_client_path) = py_client {
+            create_py_client(&package_dir, py_client_path)?;
+        }
+        if let Some(rust_client_dir) = rust_client {
+            create_rust_client(&package_dir, rust_client_dir)?;
+        }
+        if let Some(swift_client_path) = swift_client {
+            create_swift_client(&package_dir, swift_client_path)?;
+        }
+        if let Some(kotlin_client_path) = kotlin_client {
+            create_kotlin_client(&package_dir, kotlin_client_path)?;
+        }
+        if let Some(keyfile) = sign {
+            sign_pkg(&package_dir, keyfile)?;
+        }
         return Ok(());
     }
 
+    // installing missing dependencies requires network access
+    let skip_deps_check = skip_deps_check || offline;
+    if offline {
+        check_offline_build(&package_dir, &local_dependencies, ignore_deps, dep_source)?;
+    }
+
     if reproducible {
         let version = env!("CARGO_PKG_VERSION");
         let source = package_dir.canonicalize().unwrap();
@@ -1838,22 +3359,54 @@ pub async fn execute(
             Command::new("docker").args(&["pull", &format!("nick1udwig/buildpackage:{version}")]),
             true,
         )?;
-        run_command(
-            Command::new("docker").args(&[
-                "run",
-                "--rm",
-                "--mount",
-                &format!("type=bind,source={source},target=/input"),
-                &format!("nick1udwig/buildpackage:{version}"),
-            ]),
-            true,
-        )?;
+        let pkg_publisher = make_pkg_publisher(&read_metadata(&package_dir)?);
+        let zip_filename = make_zip_filename(&package_dir, &pkg_publisher);
+        // build twice, from scratch, and verify that the builds are identical
+        let mut hashes = vec![];
+        for _ in 0..2 {
+            remove_built_artifacts(&package_dir, &zip_filename)?;
+            run_command(
+                Command::new("docker").args(&[
+                    "run",
+                    "--rm",
+                    "--mount",
+                    &format!("type=bind,source={source},target=/input"),
+                    &format!("nick1udwig/buildpackage:{version}"),
+                ]),
+                true,
+            )?;
+            hashes.push(hash_zip_pkg(&zip_filename)?);
+        }
+        if hashes[0] != hashes[1] {
+            return Err(eyre!(
+                "Reproducible build of {package_dir:?} is not reproducible: consecutive builds hashed to {} and {}",
+                hashes[0],
+                hashes[1],
+            ));
+        }
+        info!("package zip hash: {}", hashes[0]);
+        if let Some(keyfile) = sign {
+            sign_pkg(&package_dir, keyfile)?;
+        }
         return Ok(());
     }
 
+    let build_env = get_build_env(&package_dir, &env)?;
+    let dependency_options = BuildOptions {
+        env,
+        ..dependency_options
+    };
+    run_hook(
+        &package_dir,
+        "pre-build",
+        &kit_toml.hooks.pre_build,
+        verbose,
+    )?;
+
     fs::create_dir_all(package_dir.join("target"))?;
-    fs::write(&build_with_features_path, features)?;
-    fs::write(&build_with_cludes_path, &cludes)?;
+    for (path, value) in &build_with {
+        fs::write(path, value)?;
+    }
 
     check_process_lib_version(&package_dir.join("Cargo.toml"))?;
 
@@ -1870,11 +3423,67 @@ pub async fn execute(
         None
     } else {
         let api_dir = live_dir.join("api");
-        let (processed_projects, interfaces) =
-            wit_generator::generate_wit_files(&live_dir, &api_dir)?;
+        let metadata = read_metadata(&live_dir)?;
+        let shared_types_package = kit_toml
+            .api
+            .shared_types
+            .then_some(metadata.properties.package_name.as_str());
+        let (processed_projects, interfaces) = wit_generator::generate_wit_files(
+            &live_dir,
+            &api_dir,
+            wit_rename,
+            &[],
+            &kit_toml.processes,
+            shared_types_package,
+        )?;
+        if kit_toml.api.versioned && !interfaces.is_empty() {
+            write_versioned_api(
+                &api_dir,
+                &metadata.properties.package_name,
+                &metadata.properties.current_version,
+                &interfaces,
+            )?;
+        }
 
         // generate ts bindings before building ui
-        caller_utils_ts_generator::create_typescript_caller_utils(&live_dir, &api_dir)?;
+        caller_utils_ts_generator::create_typescript_caller_utils(
+            &live_dir,
+            &api_dir,
+            &kit_toml.typescript,
+        )?;
+        let openapi_spec = openapi_generator::create_openapi_spec(
+            &api_dir,
+            &live_dir.join("target").join(OPENAPI_JSON_NAME),
+            &metadata.properties.package_name,
+            &metadata.properties.current_version,
+        )?;
+        if let (true, Some(openapi_spec)) = (kit_toml.api.serve_openapi, openapi_spec) {
+            // served by the UI, at `<base URL>/openapi.json`
+            for ui_dir in get_ui_dirs(&live_dir, &include, &exclude)? {
+                let public_dir = ui_dir.join("public");
+                fs::create_dir_all(&public_dir)?;
+                fs::write(public_dir.join(OPENAPI_JSON_NAME), &openapi_spec)?;
+            }
+        }
+        mock_server_generator::create_mock_server(
+            &api_dir,
+            &live_dir
+                .join("target")
+                .join("ui")
+                .join(mock_server_generator::MOCK_SERVER_NAME),
+        )?;
+        openapi_generator::create_json_schema(
+            &api_dir,
+            &live_dir.join("target").join(JSON_SCHEMA_NAME),
+            &metadata.properties.package_name,
+            &metadata.properties.current_version,
+        )?;
+        if kit_toml.api.graphql {
+            graphql_generator::create_graphql_schema(
+                &api_dir,
+                &live_dir.join("target").join("ui"),
+            )?;
+        }
 
         if interfaces.is_empty() {
             None
@@ -1882,42 +3491,86 @@ pub async fn execute(
             Some(processed_projects)
         }
     };
+    if let Some(sdk_dir) = ts_sdk {
+        create_ts_sdk(&live_dir, sdk_dir)?;
+    }
+    if let Some(py_client_path) = py_client {
+        create_py_client(&live_dir, py_client_path)?;
+    }
+    if let Some(rust_client_dir) = rust_client {
+        create_rust_client(&live_dir, rust_client_dir)?;
+    }
+    if let Some(swift_client_path) = swift_client {
+        create_swift_client(&live_dir, swift_client_path)?;
+    }
+    if let Some(kotlin_client_path) = kotlin_client {
+        create_kotlin_client(&live_dir, kotlin_client_path)?;
+    }
 
     let ui_dirs = get_ui_dirs(&live_dir, &include, &exclude)?;
-    if !no_ui && !ui_dirs.is_empty() {
+    let ui_task = if !no_ui && !ui_dirs.is_empty() {
         if !skip_deps_check {
             let mut recv_kill = make_fake_kill_chan();
             let deps = check_js_deps()?;
             get_deps(deps, &mut recv_kill, false, verbose, DEFAULT_RUST_TOOLCHAIN).await?;
         }
         let valid_node = get_newest_valid_node_version(None, None)?;
-        for ui_dir in ui_dirs {
-            compile_and_copy_ui(&ui_dir, valid_node.clone(), verbose).await?;
-        }
-    }
+        // the UI and the processes share no inputs, so build them concurrently,
+        //  prefixing the logs of each
+        Some(tokio::spawn(
+            compile_uis(
+                ui_dirs,
+                valid_node,
+                build_env.clone(),
+                ui_package_manager.to_string(),
+                locked,
+                force || force_ui,
+                deny_warnings,
+                verbose,
+            )
+            .instrument(info_span!("ui")),
+        ))
+    } else {
+        None
+    };
 
-    if !ui_only {
-        compile_package(
+    let package_result = if ui_only {
+        Ok(())
+    } else {
+        let step = BuildStep::start("package", &package_dir);
+        let result = compile_package(
             &live_dir,
             skip_deps_check,
             features,
-            url,
             default_world.clone(),
-            download_from,
             local_dependencies,
             &add_paths_to_api,
             &include,
             &exclude,
-            rewrite,
-            hyperapp,
-            force,
             verbose,
             hyperapp_processed_projects,
             ignore_deps,
+            offline,
+            cache_backend,
+            locked,
+            cargo_args,
+            profile,
+            debug_info,
+            &build_env,
+            deny_warnings,
+            log_format,
+            backend,
             toolchain,
+            &dependency_options,
         )
-        .await?;
+        .instrument(info_span!("wasm"))
+        .await;
+        step.end(result)
+    };
+    if let Some(ui_task) = ui_task {
+        ui_task.await??;
     }
+    package_result?;
 
     if rewrite {
         if package_dir.join("pkg").exists() {
@@ -1926,10 +3579,35 @@ pub async fn execute(
         copy_dir(live_dir.join("pkg"), package_dir.join("pkg"))?;
     }
 
+    run_hook(
+        &package_dir,
+        "post-build",
+        &kit_toml.hooks.post_build,
+        verbose,
+    )?;
+
+    // a signature from a previous build would not match the new zip
+    let signature_path = package_dir.join("pkg").join(SIGNATURE_NAME);
+    if signature_path.exists() {
+        fs::remove_file(&signature_path)?;
+    }
+
     let metadata = read_metadata(&package_dir)?;
+    write_build_manifest(&package_dir, &metadata, features, toolchain)?;
     let pkg_publisher = make_pkg_publisher(&metadata);
     let (_zip_filename, hash_string) = zip_pkg(&package_dir, &pkg_publisher)?;
     info!("package zip hash: {hash_string}");
 
+    if let Some(keyfile) = sign {
+        sign_pkg(&package_dir, keyfile)?;
+    }
+
+    run_hook(
+        &package_dir,
+        "post-package",
+        &kit_toml.hooks.post_package,
+        verbose,
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,566 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use color_eyre::{eyre::WrapErr, Result};
+use serde_json::{json, Map, Value};
+use tracing::{debug, info, instrument};
+
+use super::caller_utils_ts_generator::{
+    case_wire_name, find_hyperapp_wit_files, parse_args_comment, parse_returns_comment,
+    parse_tuple_types, parse_wit_file, split_top_level, to_pascal_case, to_snake_case,
+    SignatureStruct, WitTypes,
+};
+
+pub const OPENAPI_JSON_NAME: &str = "openapi.json";
+pub const JSON_SCHEMA_NAME: &str = "schema.json";
+
+/// The JSON Schema of a WIT type (including the richer types of signature comments),
+///  referencing custom types at `ref_prefix`
+pub(super) fn wit_type_to_schema(wit_type: &str, ref_prefix: &str) -> Value {
+    match wit_type {
+        "s8" | "s16" | "s32" | "s64" | "s128" => json!({ "type": "integer" }),
+        "u8" | "u16" | "u32" | "u64" | "u128" => json!({ "type": "integer", "minimum": 0 }),
+        "f32" | "f64" => json!({ "type": "number" }),
+        "string" | "address" => json!({ "type": "string" }),
+        "bool" => json!({ "type": "boolean" }),
+        "_" => json!({ "type": "null" }),
+        t if t.starts_with("list<") => json!({
+            "type": "array",
+            "items": wit_type_to_schema(&t[5..t.len() - 1], ref_prefix),
+        }),
+        // JSON object keys are strings, even those of numbers
+        t if t.starts_with("map<") => match split_top_level(&t[4..t.len() - 1]).as_slice() {
+            [_, value_type] => json!({
+                "type": "object",
+                "additionalProperties": wit_type_to_schema(value_type, ref_prefix),
+            }),
+            _ => json!({ "type": "object" }),
+        },
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).as_slice() {
+            [inner_type, len] => {
+                let len: u64 = len.parse().unwrap_or_default();
+                json!({
+                    "type": "array",
+                    "items": wit_type_to_schema(inner_type, ref_prefix),
+                    "minItems": len,
+                    "maxItems": len,
+                })
+            }
+            _ => json!({ "type": "array" }),
+        },
+        t if t.starts_with("option<") => json!({
+            "anyOf": [wit_type_to_schema(&t[7..t.len() - 1], ref_prefix), { "type": "null" }],
+        }),
+        t if t.starts_with("result<") => {
+            let types = split_top_level(&t[7..t.len() - 1]);
+            let case = |name: &str, wit_type: Option<&String>| {
+                let schema = wit_type
+                    .map(|t| wit_type_to_schema(t, ref_prefix))
+                    .unwrap_or_else(|| json!({ "type": "null" }));
+                json!({
+                    "type": "object",
+                    "properties": { name: schema },
+                    "required": [name],
+                    "additionalProperties": false,
+                })
+            };
+            json!({ "oneOf": [case("Ok", types.first()), case("Err", types.get(1))] })
+        }
+        t if t.starts_with("tuple<") => {
+            let items: Vec<Value> = parse_tuple_types(t)
+                .iter()
+                .map(|t| wit_type_to_schema(t, ref_prefix))
+                .collect();
+            json!({
+                "type": "array",
+                "minItems": items.len(),
+                "maxItems": items.len(),
+                "prefixItems": items,
+            })
+        }
+        // `value` is arbitrary JSON
+        "value" => json!({}),
+        t => json!({ "$ref": format!("{}{}", ref_prefix, to_pascal_case(t)) }),
+    }
+}
+
+// Add the `description` of a schema from doc comments, if any
+fn with_description(mut schema: Value, docs: &[String]) -> Value {
+    if !docs.is_empty() {
+        schema["description"] = Value::String(docs.join("\n"));
+    }
+    schema
+}
+
+// The JSON Schema of the fields of a record, named by their JSON names
+fn record_schema<'a>(
+    fields: impl Iterator<Item = (String, &'a str, &'a [String])>,
+    ref_prefix: &str,
+) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for (json_name, wit_type, docs) in fields {
+        if !wit_type.starts_with("option<") {
+            required.push(Value::String(json_name.clone()));
+        }
+        properties.insert(
+            json_name,
+            with_description(wit_type_to_schema(wit_type, ref_prefix), docs),
+        );
+    }
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// The JSON Schemas of the records, variants, enums & aliases of parsed WIT files, by type
+///  name (PascalCase), referencing each other at `ref_prefix`. Types are (de)serialized by
+///  serde: records as objects of their fields' JSON names, variants as externally tagged
+pub(super) fn type_schemas(wit_types: &[WitTypes], ref_prefix: &str) -> BTreeMap<String, Value> {
+    let mut schemas = BTreeMap::new();
+    for wit_types in wit_types {
+        for (name, rhs) in &wit_types.aliases {
+            schemas.insert(to_pascal_case(name), wit_type_to_schema(rhs, ref_prefix));
+        }
+        for record in &wit_types.records {
+            let fields = record.fields.iter().map(|field| {
+                let json_name = field
+                    .wire_name
+                    .clone()
+                    .unwrap_or_else(|| to_snake_case(&field.name));
+                (json_name, field.wit_type.as_str(), field.docs.as_slice())
+            });
+            schemas.insert(
+                to_pascal_case(&record.name),
+                with_description(record_schema(fields, ref_prefix), &record.docs),
+            );
+        }
+        for enum_def in &wit_types.enums {
+            let cases: Vec<String> = enum_def.cases.iter().map(case_wire_name).collect();
+            schemas.insert(
+                to_pascal_case(&enum_def.name),
+                with_description(json!({ "type": "string", "enum": cases }), &enum_def.docs),
+            );
+        }
+        for variant in &wit_types.variants {
+            let cases: Vec<Value> = variant
+                .cases
+                .iter()
+                .map(|case| {
+                    let case_name = case_wire_name(case);
+                    let Some(ref data_type) = case.data_type else {
+                        return json!({ "const": case_name });
+                    };
+                    let data_type = data_type.trim();
+                    let data_schema = match data_type.strip_prefix("record") {
+                        Some(inline_record) => {
+                            let inline_record = inline_record
+                                .trim()
+                                .trim_start_matches('{')
+                                .trim_end_matches('}');
+                            let inline_fields: Vec<(String, String)> =
+                                split_top_level(inline_record)
+                                    .into_iter()
+                                    .filter_map(|field| {
+                                        let (name, wit_type) = field.split_once(':')?;
+                                        Some((
+                                            to_snake_case(name.trim()),
+                                            wit_type.trim().to_string(),
+                                        ))
+                                    })
+                                    .collect();
+                            record_schema(
+                                inline_fields.iter().map(|(name, wit_type)| {
+                                    (name.clone(), wit_type.as_str(), &[][..])
+                                }),
+                                ref_prefix,
+                            )
+                        }
+                        None => wit_type_to_schema(data_type, ref_prefix),
+                    };
+                    json!({
+                        "type": "object",
+                        "properties": { case_name.clone(): data_schema },
+                        "required": [case_name],
+                        "additionalProperties": false,
+                    })
+                })
+                .collect();
+            schemas.insert(
+                to_pascal_case(&variant.name),
+                with_description(json!({ "oneOf": cases }), &variant.docs),
+            );
+        }
+    }
+    schemas
+}
+
+/// The JSON Schema of the return value of a handler, referencing custom types at `ref_prefix`
+pub(super) fn response_schema(signature: &SignatureStruct, ref_prefix: &str) -> Value {
+    let Some(field) = signature.fields.iter().find(|f| f.name == "returning") else {
+        return json!({ "type": "null" });
+    };
+    // The returns comment has the richer type (maps, arrays, 128-bit integers)
+    let wit_type = signature
+        .returns_comment
+        .as_deref()
+        .and_then(parse_returns_comment)
+        .unwrap_or_else(|| field.wit_type.clone());
+    wit_type_to_schema(&wit_type, ref_prefix)
+}
+
+// The OpenAPI operation of an HTTP handler: its request body is the `{"<Handler>": <args>}`
+//  the process deserializes, and its response the handler's return type
+fn generate_operation(signature: &SignatureStruct, tag: &str, method: &str) -> Value {
+    const REF_PREFIX: &str = "#/components/schemas/";
+    let pascal_function_name = to_pascal_case(&signature.function_name);
+    let args = signature
+        .args_comment
+        .as_deref()
+        .map(parse_args_comment)
+        .unwrap_or_default();
+
+    let mut arg_schemas = Vec::new();
+    for field in &signature.fields {
+        if field.name == "arg-types" {
+            for (i, wit_type) in parse_tuple_types(&field.wit_type).iter().enumerate() {
+                // The args comment has the richer types (maps, arrays, 128-bit integers)
+                let wit_type = args.get(i).map(|(_, t)| t).unwrap_or(wit_type);
+                arg_schemas.push(wit_type_to_schema(wit_type, REF_PREFIX));
+            }
+        }
+    }
+    let response_schema = response_schema(signature, REF_PREFIX);
+    let args_schema = match arg_schemas.len() {
+        0 => json!({ "type": "null" }),
+        1 => arg_schemas.remove(0),
+        len => json!({
+            "type": "array",
+            "minItems": len,
+            "maxItems": len,
+            "prefixItems": arg_schemas,
+        }),
+    };
+
+    let mut operation = json!({
+        "operationId": to_snake_case(&signature.function_name),
+        "tags": [tag],
+        "responses": {
+            "200": {
+                "description": "The handler's return value",
+                "content": { "application/json": { "schema": response_schema } },
+            },
+        },
+    });
+    if !signature.docs.is_empty() {
+        operation["summary"] = Value::String(signature.docs[0].clone());
+        operation["description"] = Value::String(signature.docs.join("\n"));
+    }
+    // Bodies of GET requests are not sent by browsers' `fetch`
+    if method != "get" && method != "head" {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": {
+                        "type": "object",
+                        "properties": { pascal_function_name.clone(): args_schema },
+                        "required": [pascal_function_name],
+                        "additionalProperties": false,
+                    },
+                },
+            },
+        });
+    }
+    operation
+}
+
+/// Create an OpenAPI 3.1 spec of the HTTP handlers of the package's hyperapp processes, as
+///  described by the WIT files in `api_dir`, at `openapi_path`. Returns the spec, or `None`
+///  (writing nothing) if there are no HTTP handlers
+#[instrument(level = "trace", skip_all)]
+pub fn create_openapi_spec(
+    api_dir: &Path,
+    openapi_path: &Path,
+    package_name: &str,
+    version: &str,
+) -> Result<Option<String>> {
+    let mut all_wit_types = Vec::new();
+    // path -> method -> operations of the handlers bound to them
+    let mut operations: BTreeMap<String, BTreeMap<String, Vec<Value>>> = BTreeMap::new();
+    for (hyperapp_name, wit_files) in find_hyperapp_wit_files(api_dir) {
+        for wit_file in wit_files {
+            let wit_types = parse_wit_file(&wit_file)?;
+            for signature in &wit_types.signatures {
+                if signature.attr_type != "http" {
+                    continue;
+                }
+                let path = signature.http_path.as_deref().unwrap_or("/api");
+                let method = signature
+                    .http_method
+                    .as_deref()
+                    .unwrap_or("POST")
+                    .to_lowercase();
+                let operation = generate_operation(signature, &hyperapp_name, &method);
+                operations
+                    .entry(path.to_string())
+                    .or_default()
+                    .entry(method)
+                    .or_default()
+                    .push(operation);
+            }
+            all_wit_types.push(wit_types);
+        }
+    }
+    if operations.is_empty() {
+        debug!("No HTTP handlers found in WIT files, skipping OpenAPI generation");
+        return Ok(None);
+    }
+
+    let mut paths = Map::new();
+    for (path, methods) in operations {
+        let path_item: Map<String, Value> = methods
+            .into_iter()
+            .map(|(method, operations)| (method, merge_operations(operations)))
+            .collect();
+        paths.insert(path, Value::Object(path_item));
+    }
+
+    let spec = json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": package_name,
+            "version": version,
+            "description": format!("HTTP API of {package_name}, generated by kit from its WIT"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": type_schemas(&all_wit_types, "#/components/schemas/"),
+        },
+    });
+    let spec = format!("{}\n", serde_json::to_string_pretty(&spec)?);
+    if let Some(parent) = openapi_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(openapi_path, &spec)
+        .with_context(|| format!("Failed to write OpenAPI spec: {}", openapi_path.display()))?;
+
+    info!(
+        "Successfully created OpenAPI spec at {}",
+        openapi_path.display()
+    );
+    Ok(Some(spec))
+}
+
+/// Create a JSON Schema (draft 2020-12) bundle, at `schema_path`, with a definition of every
+///  record, variant & enum of the package's hyperapp processes, as described by the WIT files in
+///  `api_dir`. Returns the bundle, or `None` (writing nothing) if there are no such types
+#[instrument(level = "trace", skip_all)]
+pub fn create_json_schema(
+    api_dir: &Path,
+    schema_path: &Path,
+    package_name: &str,
+    version: &str,
+) -> Result<Option<String>> {
+    let mut all_wit_types = Vec::new();
+    for wit_files in find_hyperapp_wit_files(api_dir).into_values() {
+        for wit_file in wit_files {
+            all_wit_types.push(parse_wit_file(&wit_file)?);
+        }
+    }
+    let defs = type_schemas(&all_wit_types, "#/$defs/");
+    if defs.is_empty() {
+        debug!("No types found in WIT files, skipping JSON Schema generation");
+        return Ok(None);
+    }
+
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("{package_name} {version}"),
+        "description": format!("Types of {package_name}, generated by kit from its WIT"),
+        "$defs": defs,
+    });
+    let schema = format!("{}\n", serde_json::to_string_pretty(&schema)?);
+    if let Some(parent) = schema_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(schema_path, &schema)
+        .with_context(|| format!("Failed to write JSON Schema: {}", schema_path.display()))?;
+
+    info!(
+        "Successfully created JSON Schema at {}",
+        schema_path.display()
+    );
+    Ok(Some(schema))
+}
+
+// Merge the operations of the handlers bound to a path & method, which are served by the same
+//  endpoint (keyed by the handler's name in the request body), into one: whose request body is
+//  that of one of the handlers, & whose response is the return value of one of them
+fn merge_operations(mut operations: Vec<Value>) -> Value {
+    if operations.len() == 1 {
+        return operations.remove(0);
+    }
+    let mut merged = operations[0].clone();
+    let operation_ids: Vec<&str> = operations
+        .iter()
+        .filter_map(|operation| operation["operationId"].as_str())
+        .collect();
+    merged["operationId"] = Value::String(operation_ids.join("_or_"));
+    merged["summary"] = Value::String(format!("One of: {}", operation_ids.join(", ")));
+    if let Some(merged) = merged.as_object_mut() {
+        merged.remove("description");
+    }
+    for pointer in [
+        "/requestBody/content/application~1json/schema",
+        "/responses/200/content/application~1json/schema",
+    ] {
+        let schemas: Vec<Value> = operations
+            .iter()
+            .filter_map(|operation| operation.pointer(pointer).cloned())
+            .collect();
+        if let Some(schema) = merged.pointer_mut(pointer) {
+            *schema = json!({ "oneOf": schemas });
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_openapi_spec_describes_http_handlers() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface my-app {
+    /// An item
+    record item {
+        id: u64,
+        // serde: itemKind
+        kind: kind,
+        parent: option<item>
+    }
+
+    enum kind {
+        small,
+        large
+    }
+
+    /// Get an item
+    // Function signature for: get-item (http)
+    // HTTP: POST /api/item
+    // args: (id: u64, name: string)
+    record get-item-signature-http {
+        target: string,
+        arg-types: tuple<u64, string>,
+        returning: result<item, string>
+    }
+
+    // Function signature for: get-status (http)
+    // HTTP: GET /api/status
+    record get-status-signature-http {
+        target: string,
+        returning: string
+    }
+}
+"#;
+        fs::write(api_dir.join("my-app.wit"), wit_content).unwrap();
+
+        let openapi_path = temp_dir.path().join("target").join(OPENAPI_JSON_NAME);
+        create_openapi_spec(&api_dir, &openapi_path, "my-package", "1.2.3").unwrap();
+        let spec: Value =
+            serde_json::from_str(&fs::read_to_string(&openapi_path).unwrap()).unwrap();
+
+        assert_eq!(spec["openapi"], "3.1.0");
+        assert_eq!(spec["info"]["version"], "1.2.3");
+        let get_item = &spec["paths"]["/api/item"]["post"];
+        assert_eq!(get_item["operationId"], "get_item");
+        assert_eq!(get_item["summary"], "Get an item");
+        assert_eq!(
+            get_item["requestBody"]["content"]["application/json"]["schema"]["properties"]
+                ["GetItem"]["prefixItems"],
+            json!([{ "type": "integer", "minimum": 0 }, { "type": "string" }])
+        );
+        assert_eq!(
+            get_item["responses"]["200"]["content"]["application/json"]["schema"]["oneOf"][0]
+                ["properties"]["Ok"],
+            json!({ "$ref": "#/components/schemas/Item" })
+        );
+        assert!(spec["paths"]["/api/status"]["get"]
+            .get("requestBody")
+            .is_none());
+
+        let item = &spec["components"]["schemas"]["Item"];
+        assert_eq!(item["description"], "An item");
+        assert_eq!(item["required"], json!(["id", "itemKind"]));
+        assert_eq!(
+            spec["components"]["schemas"]["Kind"],
+            json!({ "type": "string", "enum": ["Small", "Large"] })
+        );
+    }
+
+    #[test]
+    fn test_json_schema_bundles_all_types() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface my-app {
+    record item {
+        id: u64,
+        shape: shape
+    }
+
+    variant shape {
+        point,
+        circle(f64)
+    }
+
+    // Function signature for: get-item (remote)
+    record get-item-signature-remote {
+        target: address,
+        returning: item
+    }
+}
+"#;
+        fs::write(api_dir.join("my-app.wit"), wit_content).unwrap();
+
+        let schema_path = temp_dir.path().join("target").join(JSON_SCHEMA_NAME);
+        create_json_schema(&api_dir, &schema_path, "my-package", "1.2.3").unwrap();
+        let schema: Value =
+            serde_json::from_str(&fs::read_to_string(&schema_path).unwrap()).unwrap();
+
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        let defs = schema["$defs"].as_object().unwrap();
+        // types of non-HTTP handlers are included too, but not signature records
+        assert_eq!(defs.keys().collect::<Vec<_>>(), vec!["Item", "Shape"]);
+        assert_eq!(
+            defs["Item"]["properties"]["shape"],
+            json!({ "$ref": "#/$defs/Shape" })
+        );
+
+        let empty_api_dir = temp_dir.path().join("empty-api");
+        fs::create_dir(&empty_api_dir).unwrap();
+        let empty_path = temp_dir.path().join("empty.json");
+        assert!(
+            create_json_schema(&empty_api_dir, &empty_path, "my-package", "1.2.3")
+                .unwrap()
+                .is_none()
+        );
+        assert!(!empty_path.exists());
+    }
+}
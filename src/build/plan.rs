@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use color_eyre::Result;
+use fs_err as fs;
+use tracing::{info, instrument};
+
+use super::{
+    find_non_standard, get_build_env, get_cached_api, get_processes_to_compile, get_ui_dirs,
+    get_ui_hash_path, get_ui_package_manager, hash_ui_dir, is_path_dependency, make_pkg_publisher,
+    make_zip_filename, read_kit_toml, read_metadata, resolve_path_dependency, wit_generator,
+    DepSource,
+};
+
+fn describe_process(
+    (path, is_rust, is_py, is_js, is_go, _is_c): &(PathBuf, bool, bool, bool, bool, bool),
+) -> String {
+    let language = if *is_rust {
+        "Rust"
+    } else if *is_py {
+        "Python"
+    } else if *is_js {
+        "JavaScript"
+    } else if *is_go {
+        "Go"
+    } else {
+        "C/C++"
+    };
+    format!(
+        "{} ({language})",
+        path.file_name()
+            .and_then(|f| f.to_str())
+            .unwrap_or_default()
+    )
+}
+
+/// Print what `kit build` would do for the package, in order, without doing any of it.
+///  `rebuild_reason` is `None` if the package is up-to-date and would not be rebuilt
+#[instrument(level = "trace", skip_all)]
+pub fn print_build_plan(
+    package_dir: &Path,
+    rebuild_reason: Option<&str>,
+    no_ui: bool,
+    ui_only: bool,
+    include: &HashSet<PathBuf>,
+    exclude: &HashSet<PathBuf>,
+    features: &str,
+    url: &Option<String>,
+    local_dependencies: &[PathBuf],
+    hyperapp: bool,
+    ignore_deps: bool,
+    no_fetch: bool,
+    api_cache_ttl: u64,
+    dep_source: &DepSource,
+    ui_package_manager: &str,
+    force_ui: bool,
+    deny_warnings: bool,
+    sign: Option<&Path>,
+    ts_sdk: Option<&Path>,
+    py_client: Option<&Path>,
+    rust_client: Option<&Path>,
+    swift_client: Option<&Path>,
+    kotlin_client: Option<&Path>,
+) -> Result<()> {
+    let mut plan = vec![format!("Build plan for {package_dir:?} (dry run):")];
+    let Some(rebuild_reason) = rebuild_reason else {
+        plan.push("  up-to-date: nothing to build".to_string());
+        if let Some(sdk_dir) = ts_sdk {
+            plan.push(format!("  generate TypeScript SDK in {sdk_dir:?}"));
+        }
+        if let Some(py_client_path) = py_client {
+            plan.push(format!("  generate Python client {py_client_path:?}"));
+        }
+        if let Some(rust_client_dir) = rust_client {
+            plan.push(format!(
+                "  generate Rust client crate in {rust_client_dir:?}"
+            ));
+        }
+        if let Some(swift_client_path) = swift_client {
+            plan.push(format!("  generate Swift client {swift_client_path:?}"));
+        }
+        if let Some(kotlin_client_path) = kotlin_client {
+            plan.push(format!("  generate Kotlin client {kotlin_client_path:?}"));
+        }
+        if let Some(keyfile) = sign {
+            plan.push(format!("  sign package zip with {keyfile:?}"));
+        }
+        info!("{}", plan.join("\n"));
+        return Ok(());
+    };
+    plan.push(format!("  rebuild: {rebuild_reason}"));
+
+    let kit_toml = read_kit_toml(package_dir)?;
+    if let Some(ref hook) = kit_toml.hooks.pre_build {
+        plan.push(format!("  run pre-build hook `{hook}`"));
+    }
+
+    if hyperapp {
+        let projects: Vec<String> = wit_generator::find_rust_projects(package_dir)
+            .iter()
+            .filter_map(|p| p.file_name().and_then(|f| f.to_str()).map(String::from))
+            .collect();
+        plan.push(format!(
+            "  regenerate WIT files in api/ from hyperapp processes {projects:?}"
+        ));
+        plan.push("  regenerate TypeScript caller-utils for the UI".to_string());
+        plan.push(
+            "  generate mock API server for UI development in target/ui/mock-server.mjs"
+                .to_string(),
+        );
+        plan.push("  generate OpenAPI spec of HTTP handlers in target/openapi.json".to_string());
+        plan.push("  generate JSON Schema of API types in target/schema.json".to_string());
+        if kit_toml.api.graphql {
+            plan.push(
+                "  generate GraphQL schema & resolvers of HTTP handlers in target/ui/".to_string(),
+            );
+        }
+    }
+    if let Some(sdk_dir) = ts_sdk {
+        plan.push(format!("  generate TypeScript SDK in {sdk_dir:?}"));
+    }
+    if let Some(py_client_path) = py_client {
+        plan.push(format!("  generate Python client {py_client_path:?}"));
+    }
+    if let Some(rust_client_dir) = rust_client {
+        plan.push(format!(
+            "  generate Rust client crate in {rust_client_dir:?}"
+        ));
+    }
+    if let Some(swift_client_path) = swift_client {
+        plan.push(format!("  generate Swift client {swift_client_path:?}"));
+    }
+    if let Some(kotlin_client_path) = kotlin_client {
+        plan.push(format!("  generate Kotlin client {kotlin_client_path:?}"));
+    }
+
+    let ui_dirs = get_ui_dirs(package_dir, include, exclude)?;
+    if !no_ui && !ui_dirs.is_empty() {
+        let build_env = get_build_env(package_dir, &kit_toml.env)?;
+        for ui_dir in ui_dirs {
+            let package_manager = get_ui_package_manager(&ui_dir, ui_package_manager)?;
+            let ui_hash = hash_ui_dir(&ui_dir, package_manager, &build_env, deny_warnings)?;
+            let is_unchanged = fs::read_to_string(get_ui_hash_path(&ui_dir))
+                .ok()
+                .as_deref()
+                == Some(&ui_hash);
+            if is_unchanged && !force_ui {
+                plan.push(format!("  skip UI {ui_dir:?}: unchanged since last build"));
+            } else {
+                plan.push(format!("  build UI {ui_dir:?} with {package_manager}"));
+            }
+        }
+    }
+
+    if !ui_only {
+        let metadata = read_metadata(package_dir)?;
+        let dependencies = metadata.properties.dependencies.unwrap_or_default();
+        if !ignore_deps && !dependencies.is_empty() {
+            plan.push("  fetch dependencies:".to_string());
+            for dependency in &dependencies {
+                if is_path_dependency(dependency) {
+                    let path = resolve_path_dependency(package_dir, dependency)?;
+                    plan.push(format!("    {dependency}: build local package {path:?}"));
+                    continue;
+                }
+                let ttl = if no_fetch { None } else { Some(api_cache_ttl) };
+                let source = match get_cached_api(dependency, dep_source, ttl)? {
+                    Some(api_dir) => format!("use cached API {api_dir:?}"),
+                    None => match dep_source {
+                        DepSource::Node => format!(
+                            "fetch API from node at {}",
+                            url.as_deref().unwrap_or("<no url>"),
+                        ),
+                        _ => format!("fetch API from {dep_source:?}"),
+                    },
+                };
+                plan.push(format!("    {dependency}: {source}"));
+            }
+            for local_dependency in local_dependencies {
+                plan.push(format!("    build local dependency {local_dependency:?}"));
+            }
+        }
+
+        plan.push("  fetch hyperware.wit & build target/wit/".to_string());
+        let mut processes: Vec<String> = get_processes_to_compile(package_dir, include, exclude)?
+            .iter()
+            .map(describe_process)
+            .collect();
+        processes.sort();
+        let features = if features.is_empty() {
+            String::new()
+        } else {
+            format!(" with features `{features}`")
+        };
+        plan.push(format!(
+            "  compile processes{features} (concurrently): {processes:?}"
+        ));
+
+        if !ignore_deps {
+            // compositions are determined by the imports & exports of the built components,
+            //  so report those of the last build, if any
+            let (importers, exporters, _) =
+                match find_non_standard(package_dir, &mut HashSet::new()) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        plan.push(format!("  compose: could not inspect pkg/ components: {e}"));
+                        Default::default()
+                    }
+                };
+            let mut compositions = vec![];
+            for (import, import_paths) in importers {
+                let exporter = exporters
+                    .get(&import)
+                    .map(|p| format!("{p:?}"))
+                    .unwrap_or_else(|| "MISSING".to_string());
+                for import_path in import_paths {
+                    compositions.push(format!(
+                        "    compose {import_path:?} with {exporter} for {import}"
+                    ));
+                }
+            }
+            if !compositions.is_empty() {
+                compositions.sort();
+                plan.push("  compose (based on components in pkg/ from the last build):".into());
+                plan.extend(compositions);
+            }
+        }
+        plan.push("  zip API into pkg/api.zip".to_string());
+    }
+
+    if let Some(ref hook) = kit_toml.hooks.post_build {
+        plan.push(format!("  run post-build hook `{hook}`"));
+    }
+    let pkg_publisher = make_pkg_publisher(&read_metadata(package_dir)?);
+    plan.push(format!(
+        "  zip pkg/ into {:?}",
+        make_zip_filename(package_dir, &pkg_publisher)
+    ));
+    if let Some(keyfile) = sign {
+        plan.push(format!("  sign package zip with {keyfile:?}"));
+    }
+    if let Some(ref hook) = kit_toml.hooks.post_package {
+        plan.push(format!("  run post-package hook `{hook}`"));
+    }
+
+    info!("{}", plan.join("\n"));
+    Ok(())
+}
@@ -0,0 +1,570 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use tracing::{debug, info, instrument};
+
+use super::caller_utils_py_generator::pascal_to_snake_case;
+use super::caller_utils_ts_generator::{
+    find_hyperapp_wit_files, parse_args_comment, parse_returns_comment, parse_tuple_types,
+    parse_wit_file, split_top_level, to_pascal_case, to_snake_case, SignatureStruct, WitEnum,
+    WitRecord, WitVariant,
+};
+
+const RUST_KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "super", "trait", "true", "try", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// The client, sending requests to the node & deserializing the responses, with an accessor
+///  per process substituted for `{namespaces}`
+const RS_CLIENT: &str = r#"/// An HTTP request failed, or its response was not of the handler's return type
+#[derive(Debug)]
+pub enum Error {
+    /// The request could not be sent, or its response deserialized
+    Http(reqwest::Error),
+    /// The node responded with an error status, & body
+    Status(reqwest::StatusCode, String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Http(e) => write!(f, "HTTP request failed: {e}"),
+            Error::Status(status, body) => {
+                write!(f, "HTTP request failed with status {status}: {body}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Http(e)
+    }
+}
+
+/// Client for the HTTP API at `base_url`, the URL of a process on a node,
+/// e.g. `http://localhost:8080/my-process:my-package:publisher.os`
+#[derive(Debug, Clone)]
+pub struct Client {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    /// A client sending requests with `http`, e.g. one with the node's auth cookie as a
+    /// default header
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Self { base_url, http }
+    }
+{namespaces}
+    /// Send `body` to the handler bound to `method` & `path`, deserializing its response
+    pub async fn request<B: Serialize, R: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: &B,
+    ) -> Result<R, Error> {
+        let url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
+        let mut request = self.http.request(method.clone(), url);
+        // Bodies of GET requests are not read by the node
+        if method != reqwest::Method::GET && method != reqwest::Method::HEAD {
+            request = request.json(body);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::Status(status, response.text().await.unwrap_or_default()));
+        }
+        Ok(response.json().await?)
+    }
+}
+"#;
+
+// A Rust identifier for a WIT identifier: snake_case, raw if a keyword
+fn to_rust_name(name: &str) -> String {
+    let name = to_snake_case(name);
+    if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+// Rust doc comments (each line ending in a newline) from WIT doc comments
+fn generate_rustdoc(docs: &[String], indent: &str) -> String {
+    docs.iter()
+        .map(|doc| {
+            if doc.is_empty() {
+                format!("{}///\n", indent)
+            } else {
+                format!("{}/// {}\n", indent, doc)
+            }
+        })
+        .collect()
+}
+
+// Convert a WIT type (including the richer types of signature comments) to a host-side Rust
+//  type, boxing references to `boxed`, the type being defined, which would otherwise be
+//  infinitely sized
+fn wit_type_to_rust(wit_type: &str, boxed: Option<&str>) -> String {
+    match wit_type {
+        "s8" => "i8".to_string(),
+        "s16" => "i16".to_string(),
+        "s32" => "i32".to_string(),
+        "s64" => "i64".to_string(),
+        "s128" => "i128".to_string(),
+        "u8" | "u16" | "u32" | "u64" | "u128" | "f32" | "f64" | "bool" => wit_type.to_string(),
+        "string" | "address" => "String".to_string(),
+        "_" => "()".to_string(),
+        "value" => "serde_json::Value".to_string(),
+        t if t.starts_with("list<") => {
+            format!("Vec<{}>", wit_type_to_rust(&t[5..t.len() - 1], None))
+        }
+        t if t.starts_with("option<") => {
+            format!("Option<{}>", wit_type_to_rust(&t[7..t.len() - 1], boxed))
+        }
+        t if t.starts_with("result<") => match split_top_level(&t[7..t.len() - 1]).as_slice() {
+            [ok_type, err_type] => format!(
+                "Result<{}, {}>",
+                wit_type_to_rust(ok_type, boxed),
+                wit_type_to_rust(err_type, boxed)
+            ),
+            [ok_type] => format!("Result<{}, ()>", wit_type_to_rust(ok_type, boxed)),
+            _ => "Result<(), ()>".to_string(),
+        },
+        t if t.starts_with("tuple<") => {
+            let types: Vec<String> = parse_tuple_types(t)
+                .iter()
+                .map(|t| wit_type_to_rust(t, boxed))
+                .collect();
+            format!("({})", types.join(", "))
+        }
+        t if t.starts_with("map<") => match split_top_level(&t[4..t.len() - 1]).as_slice() {
+            [key_type, value_type] => format!(
+                "std::collections::HashMap<{}, {}>",
+                wit_type_to_rust(key_type, None),
+                wit_type_to_rust(value_type, None)
+            ),
+            _ => "serde_json::Value".to_string(),
+        },
+        // Sent as lists, & so taken as `Vec`s: serde only (de)serializes short arrays
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).as_slice() {
+            [inner_type, _] => format!("Vec<{}>", wit_type_to_rust(inner_type, None)),
+            _ => "Vec<serde_json::Value>".to_string(),
+        },
+        t => {
+            let type_name = to_pascal_case(t.trim_start_matches('%'));
+            if boxed == Some(type_name.as_str()) {
+                format!("Box<{}>", type_name)
+            } else {
+                type_name
+            }
+        }
+    }
+}
+
+// The fields of a record (or record-like variant case): `(name, WIT type, wire name, docs)`
+fn generate_rust_fields(
+    fields: &[(String, String, Option<String>, Vec<String>)],
+    boxed: &str,
+    indent: &str,
+    public: bool,
+) -> String {
+    let mut rust_fields = String::new();
+    for (name, wit_type, wire_name, docs) in fields {
+        rust_fields.push_str(&generate_rustdoc(docs, indent));
+        if let Some(wire_name) = wire_name {
+            rust_fields.push_str(&format!("{}#[serde(rename = {:?})]\n", indent, wire_name));
+        }
+        rust_fields.push_str(&format!(
+            "{}{}{}: {},\n",
+            indent,
+            if public { "pub " } else { "" },
+            to_rust_name(name),
+            wit_type_to_rust(wit_type, Some(boxed))
+        ));
+    }
+    rust_fields
+}
+
+const DERIVES: &str = "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n";
+
+fn generate_rust_record(record: &WitRecord) -> String {
+    let type_name = to_pascal_case(&record.name);
+    let fields: Vec<(String, String, Option<String>, Vec<String>)> = record
+        .fields
+        .iter()
+        .map(|field| {
+            (
+                field.name.clone(),
+                field.wit_type.clone(),
+                field.wire_name.clone(),
+                field.docs.clone(),
+            )
+        })
+        .collect();
+    format!(
+        "{}{}pub struct {} {{\n{}}}\n",
+        generate_rustdoc(&record.docs, ""),
+        DERIVES,
+        type_name,
+        generate_rust_fields(&fields, &type_name, "    ", true)
+    )
+}
+
+// Variants (and enums, whose cases have no data) are externally tagged, as serde does by default
+fn generate_rust_enum(
+    name: &str,
+    cases: &[(String, Option<String>, Option<String>)],
+    docs: &[String],
+) -> String {
+    let type_name = to_pascal_case(name);
+    let mut rust_cases = String::new();
+    for (case_name, data_type, wire_name) in cases {
+        let case_name = to_pascal_case(case_name);
+        if let Some(wire_name) = wire_name.as_ref().filter(|w| **w != case_name) {
+            rust_cases.push_str(&format!("    #[serde(rename = {:?})]\n", wire_name));
+        }
+        let data_type = data_type.as_deref().map(str::trim);
+        match data_type {
+            None => rust_cases.push_str(&format!("    {},\n", case_name)),
+            Some(data_type) if data_type.starts_with("record") => {
+                let inline_record = data_type
+                    .trim_start_matches("record")
+                    .trim()
+                    .trim_start_matches('{')
+                    .trim_end_matches('}');
+                let fields: Vec<(String, String, Option<String>, Vec<String>)> =
+                    split_top_level(inline_record)
+                        .into_iter()
+                        .filter_map(|field| {
+                            let (name, wit_type) = field.split_once(':')?;
+                            Some((
+                                name.trim().to_string(),
+                                wit_type.trim().to_string(),
+                                None,
+                                vec![],
+                            ))
+                        })
+                        .collect();
+                rust_cases.push_str(&format!(
+                    "    {} {{\n{}    }},\n",
+                    case_name,
+                    generate_rust_fields(&fields, &type_name, "        ", false)
+                ));
+            }
+            Some(data_type) => rust_cases.push_str(&format!(
+                "    {}({}),\n",
+                case_name,
+                wit_type_to_rust(data_type, Some(&type_name))
+            )),
+        }
+    }
+    format!(
+        "{}{}pub enum {} {{\n{}}}\n",
+        generate_rustdoc(docs, ""),
+        DERIVES,
+        type_name,
+        rust_cases
+    )
+}
+
+fn generate_rust_variant(variant: &WitVariant) -> String {
+    let cases: Vec<(String, Option<String>, Option<String>)> = variant
+        .cases
+        .iter()
+        .map(|case| {
+            (
+                case.name.clone(),
+                case.data_type.clone(),
+                case.wire_name.clone(),
+            )
+        })
+        .collect();
+    generate_rust_enum(&variant.name, &cases, &variant.docs)
+}
+
+fn generate_rust_wit_enum(enum_def: &WitEnum) -> String {
+    let cases: Vec<(String, Option<String>, Option<String>)> = enum_def
+        .cases
+        .iter()
+        .map(|case| (case.name.clone(), None, case.wire_name.clone()))
+        .collect();
+    generate_rust_enum(&enum_def.name, &cases, &enum_def.docs)
+}
+
+// Generate the method of an `<Hyperapp>Api` calling an HTTP handler; the body is the
+//  `{"<Handler>": <args>}` the process deserializes
+fn generate_rust_method(signature: &SignatureStruct) -> String {
+    let function_name = to_rust_name(&signature.function_name);
+    let pascal_function_name = to_pascal_case(&signature.function_name);
+    let args = signature
+        .args_comment
+        .as_deref()
+        .map(parse_args_comment)
+        .unwrap_or_default();
+
+    let mut params = vec!["&self".to_string()];
+    let mut param_names = Vec::new();
+    let mut return_type = "()".to_string();
+    for field in &signature.fields {
+        if field.name == "arg-types" {
+            for (i, wit_type) in parse_tuple_types(&field.wit_type).iter().enumerate() {
+                // The args comment has the richer types (maps, arrays, 128-bit integers)
+                let (param_name, wit_type) = match args.get(i) {
+                    Some((name, arg_type)) => (to_rust_name(name), arg_type.as_str()),
+                    None => (format!("arg{}", i), wit_type.as_str()),
+                };
+                params.push(format!(
+                    "{}: {}",
+                    param_name,
+                    wit_type_to_rust(wit_type, None)
+                ));
+                param_names.push(param_name);
+            }
+        } else if field.name == "returning" {
+            let wit_type = signature
+                .returns_comment
+                .as_deref()
+                .and_then(parse_returns_comment)
+                .unwrap_or_else(|| field.wit_type.clone());
+            return_type = wit_type_to_rust(&wit_type, None);
+        }
+    }
+
+    // Serialized directly, rather than through a `serde_json::Value`, which does not fit
+    //  128-bit integers
+    let data = match param_names.as_slice() {
+        [] => "()".to_string(),
+        [param_name] => param_name.clone(),
+        param_names => format!("({})", param_names.join(", ")),
+    };
+    format!(
+        "{}    pub async fn {}({}) -> Result<{}, Error> {{\n        let body = std::collections::HashMap::from([({:?}, {})]);\n        self.client\n            .request(reqwest::Method::{}, {:?}, &body)\n            .await\n    }}\n",
+        generate_rustdoc(&signature.docs, "    "),
+        function_name,
+        params.join(", "),
+        return_type,
+        pascal_function_name,
+        data,
+        signature
+            .http_method
+            .as_deref()
+            .unwrap_or("POST")
+            .to_uppercase(),
+        signature.http_path.as_deref().unwrap_or("/api"),
+    )
+}
+
+/// Create a Rust crate at `crate_dir` for calling the HTTP handlers of the package's hyperapp
+///  processes from outside the node (e.g. from tests & scripts), as described by the WIT files
+///  in `api_dir`: a serde type per WIT type, and a `reqwest` `Client` with an accessor per
+///  process whose methods call its handlers
+#[instrument(level = "trace", skip_all)]
+pub fn create_rust_client(
+    api_dir: &Path,
+    crate_dir: &Path,
+    package_name: &str,
+    version: &str,
+) -> Result<()> {
+    let mut aliases = BTreeMap::new();
+    let mut type_names = BTreeSet::new();
+    let mut namespaces: Vec<(String, Vec<SignatureStruct>)> = Vec::new();
+    let mut types = Vec::new();
+
+    for (hyperapp_name, wit_files) in find_hyperapp_wit_files(api_dir) {
+        let mut signatures = Vec::new();
+        for wit_file in wit_files {
+            let wit_types = parse_wit_file(&wit_file)?;
+            signatures.extend(
+                wit_types
+                    .signatures
+                    .into_iter()
+                    .filter(|signature| signature.attr_type == "http"),
+            );
+            // Types `use`d by several interfaces are defined once
+            aliases.extend(
+                wit_types
+                    .aliases
+                    .into_iter()
+                    .filter(|(name, _)| name != "value"),
+            );
+            for enum_def in wit_types.enums {
+                if type_names.insert(enum_def.name.clone()) {
+                    types.push(generate_rust_wit_enum(&enum_def));
+                }
+            }
+            for record in wit_types.records {
+                if type_names.insert(record.name.clone()) {
+                    types.push(generate_rust_record(&record));
+                }
+            }
+            for variant in wit_types.variants {
+                if type_names.insert(variant.name.clone()) {
+                    types.push(generate_rust_variant(&variant));
+                }
+            }
+        }
+        if !signatures.is_empty() {
+            namespaces.push((hyperapp_name, signatures));
+        }
+    }
+    if namespaces.is_empty() {
+        bail!(
+            "No HTTP handlers found in the WIT files of {}: nothing to generate a Rust client for.\n\
+             \n\
+             Suggestion: Add #[http] handlers to the hyperapp processes and build with --hyperapp.",
+            api_dir.display()
+        );
+    }
+    debug!(namespaces = namespaces.len(), "Generating Rust client");
+
+    let mut lib_rs = format!(
+        "//! Typed client for the HTTP API of {}, generated from its WIT by `kit build --rust-client`: do not edit\n\nuse serde::{{de::DeserializeOwned, Deserialize, Serialize}};\n",
+        package_name
+    );
+    for (name, rhs) in &aliases {
+        lib_rs.push_str(&format!(
+            "\npub type {} = {};\n",
+            to_pascal_case(name),
+            wit_type_to_rust(rhs, None)
+        ));
+    }
+    for type_def in types {
+        lib_rs.push_str(&format!("\n{}", type_def));
+    }
+
+    let mut client_namespaces = String::new();
+    for (hyperapp_name, signatures) in &namespaces {
+        lib_rs.push_str(&format!(
+            "\n/// HTTP handlers of {0}\n#[derive(Debug, Clone, Copy)]\npub struct {0}Api<'a> {{\n    client: &'a Client,\n}}\n\nimpl {0}Api<'_> {{\n",
+            hyperapp_name
+        ));
+        let methods: Vec<String> = signatures.iter().map(generate_rust_method).collect();
+        lib_rs.push_str(&methods.join("\n"));
+        lib_rs.push_str("}\n");
+        client_namespaces.push_str(&format!(
+            "\n    pub fn {}(&self) -> {}Api<'_> {{\n        {}Api {{ client: self }}\n    }}\n",
+            to_rust_name(&pascal_to_snake_case(hyperapp_name)),
+            hyperapp_name,
+            hyperapp_name
+        ));
+    }
+    lib_rs.push_str(&format!(
+        "\n{}",
+        RS_CLIENT.replace("{namespaces}", &client_namespaces)
+    ));
+
+    let cargo_toml = format!(
+        r#"[package]
+name = "{}-client"
+version = "{}"
+edition = "2021"
+description = "Typed client for the HTTP API of {}"
+publish = false
+
+[dependencies]
+reqwest = {{ version = "0.12", default-features = false, features = ["json", "rustls-tls"] }}
+serde = {{ version = "1.0", features = ["derive"] }}
+serde_json = "1.0"
+"#,
+        package_name, version, package_name
+    );
+
+    fs::create_dir_all(crate_dir.join("src"))?;
+    fs::write(crate_dir.join("Cargo.toml"), cargo_toml).with_context(|| {
+        format!(
+            "Failed to write Rust client Cargo.toml in {}",
+            crate_dir.display()
+        )
+    })?;
+    fs::write(crate_dir.join("src").join("lib.rs"), lib_rs)
+        .with_context(|| format!("Failed to write Rust client in {}", crate_dir.display()))?;
+
+    info!(
+        "Successfully created Rust client in {}",
+        crate_dir.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_rust_client_mirrors_http_api() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface my-app {
+    /// A node of a tree
+    record tree {
+        // serde: nodeId
+        id: u64,
+        parent: option<tree>,
+        kind: kind
+    }
+
+    variant kind {
+        leaf,
+        branch(list<tree>)
+    }
+
+    /// Get a tree
+    // Function signature for: get-tree (http)
+    // HTTP: POST /api/tree
+    // args: (id: u64, type: string)
+    record get-tree-signature-http {
+        target: string,
+        arg-types: tuple<u64, string>,
+        returning: result<tree, string>
+    }
+
+    // Function signature for: get-count (http)
+    // HTTP: GET /api/count
+    // returns: u128
+    record get-count-signature-http {
+        target: string,
+        returning: string
+    }
+}
+"#;
+        fs::write(api_dir.join("my-app.wit"), wit_content).unwrap();
+
+        let crate_dir = temp_dir.path().join("client");
+        create_rust_client(&api_dir, &crate_dir, "my-package", "1.2.3").unwrap();
+        let cargo_toml = fs::read_to_string(crate_dir.join("Cargo.toml")).unwrap();
+        assert!(cargo_toml.contains("name = \"my-package-client\"\nversion = \"1.2.3\""));
+        let lib_rs = fs::read_to_string(crate_dir.join("src").join("lib.rs")).unwrap();
+
+        assert!(lib_rs.contains("    #[serde(rename = \"nodeId\")]\n    pub id: u64,\n"));
+        // recursive types are boxed
+        assert!(lib_rs.contains("    pub parent: Option<Box<Tree>>,\n"));
+        assert!(lib_rs.contains("pub enum Kind {\n    Leaf,\n    Branch(Vec<Tree>),\n}"));
+        assert!(lib_rs.contains(
+            "    pub async fn get_tree(&self, id: u64, r#type: String) -> Result<Result<Tree, String>, Error> {\n        let body = std::collections::HashMap::from([(\"GetTree\", (id, r#type))]);\n        self.client\n            .request(reqwest::Method::POST, \"/api/tree\", &body)"
+        ));
+        assert!(lib_rs.contains("pub async fn get_count(&self) -> Result<u128, Error> {"));
+        assert!(lib_rs.contains("    pub fn my_app(&self) -> MyAppApi<'_> {"));
+    }
+}
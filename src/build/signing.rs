@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result, Section,
+};
+use ed25519_dalek::{pkcs8::DecodePrivateKey, Signature, Signer, SigningKey, VerifyingKey};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use super::{hash_zip_pkg, make_pkg_publisher, make_zip_filename, read_metadata};
+
+pub const SIGNATURE_NAME: &str = "signature.json";
+const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// Signature over the package zip, written to `pkg/signature.json`
+///  (after the zip is made, so it is not itself part of the zip)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageSignature {
+    pub package: String,
+    pub algorithm: String,
+    pub zip_sha256: String,
+    /// hex
+    pub public_key: String,
+    /// hex; signs the bytes of the zip
+    pub signature: String,
+}
+
+/// Read an ed25519 key: either a PKCS#8 PEM file (e.g. from
+///  `openssl genpkey -algorithm ed25519`) or a hex-encoded 32-byte seed
+fn read_signing_key(keyfile: &Path) -> Result<SigningKey> {
+    let contents = fs::read_to_string(keyfile)?;
+    let contents = contents.trim();
+    if contents.starts_with("-----BEGIN") {
+        return SigningKey::from_pkcs8_pem(contents)
+            .map_err(|e| eyre!("Failed to parse PEM key {keyfile:?}: {e}"));
+    }
+    let seed: [u8; 32] = hex::decode(contents.trim_start_matches("0x"))
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| {
+            eyre!("Failed to parse key {keyfile:?}").with_suggestion(|| {
+                "Key must be a PKCS#8 PEM file or a hex-encoded 32-byte ed25519 seed."
+            })
+        })?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+fn parse_hex_array<const N: usize>(name: &str, s: &str) -> Result<[u8; N]> {
+    hex::decode(s.trim_start_matches("0x"))
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| eyre!("{name} must be {N} hex-encoded bytes; got {s}"))
+}
+
+/// Sign the package zip, writing `pkg/signature.json`
+#[instrument(level = "trace", skip_all)]
+pub fn sign_pkg(package_dir: &Path, keyfile: &Path) -> Result<()> {
+    let pkg_publisher = make_pkg_publisher(&read_metadata(package_dir)?);
+    let zip_filename = make_zip_filename(package_dir, &pkg_publisher);
+    let signing_key = read_signing_key(keyfile)?;
+
+    let zip_bytes = fs::read(&zip_filename)?;
+    let signature = PackageSignature {
+        package: pkg_publisher,
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        zip_sha256: hash_zip_pkg(&zip_filename)?,
+        public_key: hex::encode(signing_key.verifying_key().to_bytes()),
+        signature: hex::encode(signing_key.sign(&zip_bytes).to_bytes()),
+    };
+    let signature_path = package_dir.join("pkg").join(SIGNATURE_NAME);
+    fs::write(&signature_path, serde_json::to_string_pretty(&signature)?)?;
+    info!(
+        "Signed {zip_filename:?} with public key {}.",
+        signature.public_key
+    );
+    Ok(())
+}
+
+/// Verify `signature_path` signs `zip_path`; if `public_key` is given, also check the
+///  signature was made by that key
+#[instrument(level = "trace", skip_all)]
+pub fn verify_pkg(zip_path: &Path, signature_path: &Path, public_key: Option<&str>) -> Result<()> {
+    let signature: PackageSignature = serde_json::from_str(&fs::read_to_string(signature_path)?)
+        .wrap_err_with(|| format!("Failed to parse {signature_path:?}"))?;
+    if signature.algorithm != SIGNATURE_ALGORITHM {
+        return Err(eyre!(
+            "Unsupported signature algorithm {}; expected {SIGNATURE_ALGORITHM}",
+            signature.algorithm,
+        ));
+    }
+    if let Some(public_key) = public_key {
+        if public_key.trim_start_matches("0x") != signature.public_key {
+            return Err(eyre!(
+                "{signature_path:?} was signed by {}, not {public_key}",
+                signature.public_key,
+            ));
+        }
+    }
+
+    let zip_sha256 = hash_zip_pkg(zip_path)?;
+    if zip_sha256 != signature.zip_sha256 {
+        return Err(eyre!(
+            "{zip_path:?} has hash {zip_sha256} but {signature_path:?} is for {}",
+            signature.zip_sha256,
+        ));
+    }
+
+    let verifying_key =
+        VerifyingKey::from_bytes(&parse_hex_array("public_key", &signature.public_key)?)
+            .map_err(|e| eyre!("Invalid public key {}: {e}", signature.public_key))?;
+    let sig = Signature::from_bytes(&parse_hex_array("signature", &signature.signature)?);
+    verifying_key
+        .verify_strict(&fs::read(zip_path)?, &sig)
+        .map_err(|e| eyre!("Signature verification of {zip_path:?} failed: {e}"))?;
+    info!(
+        "{zip_path:?} ({}) is validly signed by {}.",
+        signature.package, signature.public_key,
+    );
+    Ok(())
+}
@@ -0,0 +1,689 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use tracing::{debug, info, instrument};
+
+use super::caller_utils_ts_generator::{
+    case_wire_name, find_hyperapp_wit_files, parse_args_comment, parse_returns_comment,
+    parse_tuple_types, parse_wit_file, split_top_level, to_camel_case, to_pascal_case,
+    to_snake_case, SignatureStruct, WitEnum, WitRecord, WitVariant,
+};
+
+const SWIFT_KEYWORDS: [&str; 45] = [
+    "Any",
+    "as",
+    "associatedtype",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "continue",
+    "default",
+    "defer",
+    "deinit",
+    "do",
+    "else",
+    "enum",
+    "extension",
+    "fallthrough",
+    "false",
+    "fileprivate",
+    "for",
+    "func",
+    "guard",
+    "if",
+    "import",
+    "in",
+    "init",
+    "inout",
+    "internal",
+    "is",
+    "let",
+    "nil",
+    "open",
+    "operator",
+    "private",
+    "protocol",
+    "public",
+    "repeat",
+    "rethrows",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "subscript",
+    "super",
+    "switch",
+    "throw",
+];
+
+const SWIFT_HEADER: &str = r#"// Typed client for the HTTP API of {package_name}, generated from its WIT by
+//  `kit build --swift-client`. Do NOT edit this file: it is machine generated.
+//
+// Types are `Codable` as serde (de)serializes them: variants are externally tagged. Requires
+//  iOS 15 / macOS 12.
+import Foundation
+
+/// Any JSON value: for `value`s & tuples, which have no Swift `Codable` type of their own
+public enum JSONValue: Codable, Equatable {
+    case null
+    case bool(Bool)
+    case number(Double)
+    case string(String)
+    case array([JSONValue])
+    case object([String: JSONValue])
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        if container.decodeNil() {
+            self = .null
+        } else if let value = try? container.decode(Bool.self) {
+            self = .bool(value)
+        } else if let value = try? container.decode(Double.self) {
+            self = .number(value)
+        } else if let value = try? container.decode(String.self) {
+            self = .string(value)
+        } else if let value = try? container.decode([JSONValue].self) {
+            self = .array(value)
+        } else {
+            self = .object(try container.decode([String: JSONValue].self))
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        switch self {
+        case .null: try container.encodeNil()
+        case .bool(let value): try container.encode(value)
+        case .number(let value): try container.encode(value)
+        case .string(let value): try container.encode(value)
+        case .array(let value): try container.encode(value)
+        case .object(let value): try container.encode(value)
+        }
+    }
+}
+
+/// The key of an externally tagged value: `{ "<Tag>": value }`
+struct TagKey: CodingKey {
+    var stringValue: String
+    var intValue: Int? { nil }
+
+    init(_ stringValue: String) { self.stringValue = stringValue }
+    init?(stringValue: String) { self.stringValue = stringValue }
+    init?(intValue: Int) { return nil }
+}
+
+/// A WIT `result`: `{ "Ok": value }` or `{ "Err": error }`
+public indirect enum ApiResult<T: Codable & Equatable, E: Codable & Equatable>: Codable, Equatable {
+    case ok(T)
+    case err(E)
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: TagKey.self)
+        if container.contains(TagKey("Ok")) {
+            self = .ok(try container.decode(T.self, forKey: TagKey("Ok")))
+        } else {
+            self = .err(try container.decode(E.self, forKey: TagKey("Err")))
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: TagKey.self)
+        switch self {
+        case .ok(let value): try container.encode(value, forKey: TagKey("Ok"))
+        case .err(let error): try container.encode(error, forKey: TagKey("Err"))
+        }
+    }
+}
+
+/// Boxes a field of a record of its own type, which a struct cannot hold directly
+public final class Indirect<T: Codable & Equatable>: Codable, Equatable {
+    public let value: T
+
+    public init(_ value: T) { self.value = value }
+    public required init(from decoder: Decoder) throws { value = try T(from: decoder) }
+    public func encode(to encoder: Encoder) throws { try value.encode(to: encoder) }
+    public static func == (lhs: Indirect<T>, rhs: Indirect<T>) -> Bool { lhs.value == rhs.value }
+}
+"#;
+
+/// The client, sending requests to the node & decoding the responses, with an accessor per
+///  process substituted for `{namespaces}`
+const SWIFT_CLIENT: &str = r#"/// The node responded with an error status
+public enum ApiError: Error {
+    case status(Int, String)
+}
+
+/// The body of a request: `{ "<Handler>": args }`, with args as serde takes them
+struct RequestBody: Encodable {
+    let name: String
+    let args: [any Encodable]
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: TagKey.self)
+        switch args.count {
+        case 0: try container.encodeNil(forKey: TagKey(name))
+        case 1: try container.encode(args[0], forKey: TagKey(name))
+        default:
+            var tuple = container.nestedUnkeyedContainer(forKey: TagKey(name))
+            for arg in args {
+                try tuple.encode(arg)
+            }
+        }
+    }
+}
+
+/// Client for the HTTP API at `baseURL`, the URL of a process on a node,
+/// e.g. `http://localhost:8080/my-process:my-package:publisher.os`
+public final class Client {
+    public let baseURL: URL
+    public let session: URLSession
+
+    /// A client sending requests with `session`, e.g. one with the node's auth cookie
+    public init(baseURL: URL, session: URLSession = .shared) {
+        self.baseURL = baseURL
+        self.session = session
+    }
+{namespaces}
+    /// Send `args` to the handler `name` bound to `method` & `path`, decoding its response
+    public func request<R: Decodable>(
+        _ method: String, _ path: String, _ name: String, _ args: [any Encodable]
+    ) async throws -> R {
+        let url = baseURL.appendingPathComponent(String(path.drop(while: { $0 == "/" })))
+        var request = URLRequest(url: url)
+        request.httpMethod = method
+        // Bodies of GET requests are not read by the node
+        if method != "GET" && method != "HEAD" {
+            request.setValue("application/json", forHTTPHeaderField: "Content-Type")
+            request.httpBody = try JSONEncoder().encode(RequestBody(name: name, args: args))
+        }
+        let (data, response) = try await session.data(for: request)
+        if let response = response as? HTTPURLResponse, !(200..<300).contains(response.statusCode) {
+            throw ApiError.status(response.statusCode, String(decoding: data, as: UTF8.self))
+        }
+        return try JSONDecoder().decode(R.self, from: data)
+    }
+}
+"#;
+
+// A Swift identifier for a WIT identifier: lowerCamelCase, in backticks if a keyword
+fn to_swift_name(name: &str) -> String {
+    let name = to_camel_case(name);
+    if SWIFT_KEYWORDS.contains(&name.as_str()) {
+        format!("`{}`", name)
+    } else {
+        name
+    }
+}
+
+// Swift doc comments (each line ending in a newline) from WIT doc comments
+fn generate_swift_doc(docs: &[String], indent: &str) -> String {
+    docs.iter()
+        .map(|doc| {
+            if doc.is_empty() {
+                format!("{}///\n", indent)
+            } else {
+                format!("{}/// {}\n", indent, doc)
+            }
+        })
+        .collect()
+}
+
+// Convert a WIT type (including the richer types of signature comments) to a Swift type,
+//  boxing references to `boxed`, the record being defined, which a struct cannot hold directly
+fn wit_type_to_swift(wit_type: &str, boxed: Option<&str>) -> String {
+    match wit_type {
+        "s8" => "Int8".to_string(),
+        "s16" => "Int16".to_string(),
+        "s32" => "Int32".to_string(),
+        "s64" => "Int64".to_string(),
+        "u8" => "UInt8".to_string(),
+        "u16" => "UInt16".to_string(),
+        "u32" => "UInt32".to_string(),
+        "u64" => "UInt64".to_string(),
+        // JSON numbers past 64 bits
+        "s128" | "u128" => "Decimal".to_string(),
+        "f32" => "Float".to_string(),
+        "f64" => "Double".to_string(),
+        "bool" => "Bool".to_string(),
+        "string" | "char" | "address" => "String".to_string(),
+        "_" | "value" => "JSONValue".to_string(),
+        t if t.starts_with("list<") => {
+            format!("[{}]", wit_type_to_swift(&t[5..t.len() - 1], None))
+        }
+        t if t.starts_with("array<") => match split_top_level(&t[6..t.len() - 1]).as_slice() {
+            [inner_type, _] => format!("[{}]", wit_type_to_swift(inner_type, None)),
+            _ => "[JSONValue]".to_string(),
+        },
+        t if t.starts_with("option<") => {
+            format!("{}?", wit_type_to_swift(&t[7..t.len() - 1], boxed))
+        }
+        t if t.starts_with("result<") => match split_top_level(&t[7..t.len() - 1]).as_slice() {
+            [ok_type, err_type] => format!(
+                "ApiResult<{}, {}>",
+                wit_type_to_swift(ok_type, None),
+                wit_type_to_swift(err_type, None)
+            ),
+            [ok_type] => format!("ApiResult<{}, JSONValue>", wit_type_to_swift(ok_type, None)),
+            _ => "ApiResult<JSONValue, JSONValue>".to_string(),
+        },
+        // Maps are JSON objects, whatever their keys
+        t if t.starts_with("map<") => match split_top_level(&t[4..t.len() - 1]).as_slice() {
+            [_, value_type] => format!("[String: {}]", wit_type_to_swift(value_type, None)),
+            _ => "JSONValue".to_string(),
+        },
+        t if t.starts_with("tuple<") => "JSONValue".to_string(),
+        t => {
+            let type_name = to_pascal_case(t.trim_start_matches('%'));
+            if boxed == Some(type_name.as_str()) {
+                format!("Indirect<{}>", type_name)
+            } else {
+                type_name
+            }
+        }
+    }
+}
+
+// A struct of `(name, WIT type, wire name, docs)` fields, with a public memberwise init
+fn generate_swift_struct(
+    type_name: &str,
+    fields: &[(String, String, String, Vec<String>)],
+    docs: &[String],
+    indent: &str,
+) -> String {
+    let mut properties = String::new();
+    let mut params = Vec::new();
+    let mut assignments = String::new();
+    let mut coding_keys = String::new();
+    for (name, wit_type, wire_name, field_docs) in fields {
+        let swift_name = to_swift_name(name);
+        let swift_type = wit_type_to_swift(wit_type, Some(type_name));
+        properties.push_str(&generate_swift_doc(field_docs, &format!("{}    ", indent)));
+        properties.push_str(&format!(
+            "{}    public var {}: {}\n",
+            indent, swift_name, swift_type
+        ));
+        params.push(format!("{}: {}", swift_name, swift_type));
+        assignments.push_str(&format!(
+            "{}        self.{} = {}\n",
+            indent, swift_name, swift_name
+        ));
+        coding_keys.push_str(&format!(
+            "{}        case {} = {:?}\n",
+            indent, swift_name, wire_name
+        ));
+    }
+    format!(
+        "{0}{1}public struct {2}: Codable, Equatable {{\n{3}\n{1}    public init({4}) {{\n{5}{1}    }}\n\n{1}    enum CodingKeys: String, CodingKey {{\n{6}{1}    }}\n{1}}}\n",
+        generate_swift_doc(docs, indent),
+        indent,
+        type_name,
+        properties,
+        params.join(", "),
+        assignments,
+        coding_keys
+    )
+}
+
+fn generate_swift_record(record: &WitRecord) -> String {
+    let fields: Vec<(String, String, String, Vec<String>)> = record
+        .fields
+        .iter()
+        .map(|field| {
+            (
+                field.name.clone(),
+                field.wit_type.clone(),
+                field
+                    .wire_name
+                    .clone()
+                    .unwrap_or_else(|| to_snake_case(&field.name)),
+                field.docs.clone(),
+            )
+        })
+        .collect();
+    generate_swift_struct(&to_pascal_case(&record.name), &fields, &record.docs, "")
+}
+
+// Enums, and variants without data, are strings
+fn generate_swift_string_enum(name: &str, cases: &[(String, String)], docs: &[String]) -> String {
+    let cases: String = cases
+        .iter()
+        .map(|(case_name, wire_name)| {
+            format!("    case {} = {:?}\n", to_swift_name(case_name), wire_name)
+        })
+        .collect();
+    format!(
+        "{}public enum {}: String, Codable, Equatable {{\n{}}}\n",
+        generate_swift_doc(docs, ""),
+        to_pascal_case(name),
+        cases
+    )
+}
+
+fn generate_swift_enum(enum_def: &WitEnum) -> String {
+    let cases: Vec<(String, String)> = enum_def
+        .cases
+        .iter()
+        .map(|case| (case.name.clone(), case_wire_name(case)))
+        .collect();
+    generate_swift_string_enum(&enum_def.name, &cases, &enum_def.docs)
+}
+
+// Variants with data are externally tagged: `{ "<Case>": data }`, or `"<Case>"` for cases
+//  without data. Inline records become structs nested in the enum
+fn generate_swift_variant(variant: &WitVariant) -> String {
+    if variant.cases.iter().all(|case| case.data_type.is_none()) {
+        let cases: Vec<(String, String)> = variant
+            .cases
+            .iter()
+            .map(|case| (case.name.clone(), case_wire_name(case)))
+            .collect();
+        return generate_swift_string_enum(&variant.name, &cases, &variant.docs);
+    }
+
+    let type_name = to_pascal_case(&variant.name);
+    let mut cases = String::new();
+    let mut nested_structs = String::new();
+    let mut unit_decodes = String::new();
+    let mut data_decodes = String::new();
+    let mut encodes = String::new();
+    for case in &variant.cases {
+        let case_name = to_swift_name(&case.name);
+        let wire_name = case_wire_name(case);
+        match case.data_type.as_deref().map(str::trim) {
+            None => {
+                cases.push_str(&format!("    case {}\n", case_name));
+                unit_decodes.push_str(&format!(
+                    "            case {:?}: self = .{}; return\n",
+                    wire_name, case_name
+                ));
+                encodes.push_str(&format!(
+                    "        case .{}:\n            var container = encoder.singleValueContainer()\n            try container.encode({:?})\n",
+                    case_name, wire_name
+                ));
+            }
+            Some(data_type) => {
+                let data_swift_type = if data_type.starts_with("record") {
+                    let struct_name = to_pascal_case(&case.name);
+                    let inline_record = data_type
+                        .trim_start_matches("record")
+                        .trim()
+                        .trim_start_matches('{')
+                        .trim_end_matches('}');
+                    let fields: Vec<(String, String, String, Vec<String>)> =
+                        split_top_level(inline_record)
+                            .into_iter()
+                            .filter_map(|field| {
+                                let (name, wit_type) = field.split_once(':')?;
+                                let name = name.trim().trim_start_matches('%').to_string();
+                                let wire_name = to_snake_case(&name);
+                                Some((name, wit_type.trim().to_string(), wire_name, vec![]))
+                            })
+                            .collect();
+                    nested_structs.push_str(&format!(
+                        "\n{}",
+                        generate_swift_struct(&struct_name, &fields, &[], "    ")
+                    ));
+                    struct_name
+                } else {
+                    wit_type_to_swift(data_type, None)
+                };
+                cases.push_str(&format!("    case {}({})\n", case_name, data_swift_type));
+                data_decodes.push_str(&format!(
+                    "        case {:?}: self = .{}(try container.decode({}.self, forKey: key))\n",
+                    wire_name, case_name, data_swift_type
+                ));
+                encodes.push_str(&format!(
+                    "        case .{}(let value):\n            var container = encoder.container(keyedBy: TagKey.self)\n            try container.encode(value, forKey: TagKey({:?}))\n",
+                    case_name, wire_name
+                ));
+            }
+        }
+    }
+    let unit_decode = if unit_decodes.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "        if let tag = try? decoder.singleValueContainer().decode(String.self) {{\n            switch tag {{\n{}            default: break\n            }}\n        }}\n",
+            unit_decodes
+        )
+    };
+    format!(
+        "{0}public indirect enum {1}: Codable, Equatable {{\n{2}{3}\n    public init(from decoder: Decoder) throws {{\n{4}        let container = try decoder.container(keyedBy: TagKey.self)\n        guard let key = container.allKeys.first else {{\n            throw DecodingError.dataCorrupted(.init(codingPath: decoder.codingPath, debugDescription: \"Expected a {1}\"))\n        }}\n        switch key.stringValue {{\n{5}        default:\n            throw DecodingError.dataCorruptedError(forKey: key, in: container, debugDescription: \"Unknown {1} case\")\n        }}\n    }}\n\n    public func encode(to encoder: Encoder) throws {{\n        switch self {{\n{6}        }}\n    }}\n}}\n",
+        generate_swift_doc(&variant.docs, ""),
+        type_name,
+        cases,
+        nested_structs,
+        unit_decode,
+        data_decodes,
+        encodes
+    )
+}
+
+// Generate the method of a `<Hyperapp>Api` calling an HTTP handler
+fn generate_swift_method(signature: &SignatureStruct) -> String {
+    let args = signature
+        .args_comment
+        .as_deref()
+        .map(parse_args_comment)
+        .unwrap_or_default();
+
+    let mut params = Vec::new();
+    let mut param_names = Vec::new();
+    let mut return_type = "JSONValue".to_string();
+    for field in &signature.fields {
+        if field.name == "arg-types" {
+            for (i, wit_type) in parse_tuple_types(&field.wit_type).iter().enumerate() {
+                // The args comment has the richer types (maps, arrays, 128-bit integers)
+                let (param_name, wit_type) = match args.get(i) {
+                    Some((name, arg_type)) => (to_swift_name(name), arg_type.as_str()),
+                    None => (format!("arg{}", i), wit_type.as_str()),
+                };
+                params.push(format!(
+                    "{}: {}",
+                    param_name,
+                    wit_type_to_swift(wit_type, None)
+                ));
+                param_names.push(param_name);
+            }
+        } else if field.name == "returning" {
+            let wit_type = signature
+                .returns_comment
+                .as_deref()
+                .and_then(parse_returns_comment)
+                .unwrap_or_else(|| field.wit_type.clone());
+            return_type = wit_type_to_swift(&wit_type, None);
+        }
+    }
+
+    format!(
+        "{}    public func {}({}) async throws -> {} {{\n        try await client.request({:?}, {:?}, {:?}, [{}])\n    }}\n",
+        generate_swift_doc(&signature.docs, "    "),
+        to_swift_name(&signature.function_name),
+        params.join(", "),
+        return_type,
+        signature
+            .http_method
+            .as_deref()
+            .unwrap_or("POST")
+            .to_uppercase(),
+        signature.http_path.as_deref().unwrap_or("/api"),
+        to_pascal_case(&signature.function_name),
+        param_names.join(", "),
+    )
+}
+
+/// Create a Swift source file, at `swift_client_path`, for calling the HTTP handlers of the
+///  package's hyperapp processes from native apps, as described by the WIT files in `api_dir`:
+///  a `Codable` type per WIT type, and a `URLSession` `Client` with an accessor per process
+///  whose methods call its handlers
+#[instrument(level = "trace", skip_all)]
+pub fn create_swift_client(
+    api_dir: &Path,
+    swift_client_path: &Path,
+    package_name: &str,
+) -> Result<()> {
+    let mut aliases = BTreeMap::new();
+    let mut type_names = BTreeSet::new();
+    let mut namespaces: Vec<(String, Vec<SignatureStruct>)> = Vec::new();
+    let mut types = Vec::new();
+
+    for (hyperapp_name, wit_files) in find_hyperapp_wit_files(api_dir) {
+        let mut signatures = Vec::new();
+        for wit_file in wit_files {
+            let wit_types = parse_wit_file(&wit_file)?;
+            signatures.extend(
+                wit_types
+                    .signatures
+                    .into_iter()
+                    .filter(|signature| signature.attr_type == "http"),
+            );
+            // Types `use`d by several interfaces are defined once
+            aliases.extend(
+                wit_types
+                    .aliases
+                    .into_iter()
+                    .filter(|(name, _)| name != "value"),
+            );
+            for enum_def in wit_types.enums {
+                if type_names.insert(enum_def.name.clone()) {
+                    types.push(generate_swift_enum(&enum_def));
+                }
+            }
+            for record in wit_types.records {
+                if type_names.insert(record.name.clone()) {
+                    types.push(generate_swift_record(&record));
+                }
+            }
+            for variant in wit_types.variants {
+                if type_names.insert(variant.name.clone()) {
+                    types.push(generate_swift_variant(&variant));
+                }
+            }
+        }
+        if !signatures.is_empty() {
+            namespaces.push((hyperapp_name, signatures));
+        }
+    }
+    if namespaces.is_empty() {
+        bail!(
+            "No HTTP handlers found in the WIT files of {}: nothing to generate a Swift client for.\n\
+             \n\
+             Suggestion: Add #[http] handlers to the hyperapp processes and build with --hyperapp.",
+            api_dir.display()
+        );
+    }
+    debug!(namespaces = namespaces.len(), "Generating Swift client");
+
+    let mut swift_content = SWIFT_HEADER.replace("{package_name}", package_name);
+    for (name, rhs) in &aliases {
+        swift_content.push_str(&format!(
+            "\npublic typealias {} = {}\n",
+            to_pascal_case(name),
+            wit_type_to_swift(rhs, None)
+        ));
+    }
+    for type_def in types {
+        swift_content.push_str(&format!("\n{}", type_def));
+    }
+
+    let mut client_namespaces = String::new();
+    for (hyperapp_name, signatures) in &namespaces {
+        swift_content.push_str(&format!(
+            "\n/// HTTP handlers of {0}\npublic struct {0}Api {{\n    let client: Client\n",
+            hyperapp_name
+        ));
+        for signature in signatures {
+            swift_content.push_str(&format!("\n{}", generate_swift_method(signature)));
+        }
+        swift_content.push_str("}\n");
+        let mut accessor = hyperapp_name.clone();
+        if let Some(first) = accessor.get_mut(0..1) {
+            first.make_ascii_lowercase();
+        }
+        client_namespaces.push_str(&format!(
+            "\n    public var {}: {}Api {{ {}Api(client: self) }}\n",
+            accessor, hyperapp_name, hyperapp_name
+        ));
+    }
+    swift_content.push_str(&format!(
+        "\n{}",
+        SWIFT_CLIENT.replace("{namespaces}", &client_namespaces)
+    ));
+
+    if let Some(parent) = swift_client_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(swift_client_path, swift_content).with_context(|| {
+        format!(
+            "Failed to write Swift client: {}",
+            swift_client_path.display()
+        )
+    })?;
+
+    info!(
+        "Successfully created Swift client at {}",
+        swift_client_path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_swift_client_mirrors_http_api() {
+        let temp_dir = tempdir().unwrap();
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir(&api_dir).unwrap();
+
+        let wit_content = r#"
+interface my-app {
+    /// A node of a tree
+    record tree {
+        // serde: nodeId
+        id: u64,
+        parent: option<tree>,
+        kind: kind
+    }
+
+    variant kind {
+        leaf,
+        branch(list<tree>)
+    }
+
+    /// Get a tree
+    // Function signature for: get-tree (http)
+    // HTTP: POST /api/tree
+    // args: (id: u64, default: string)
+    record get-tree-signature-http {
+        target: string,
+        arg-types: tuple<u64, string>,
+        returning: result<tree, string>
+    }
+}
+"#;
+        fs::write(api_dir.join("my-app.wit"), wit_content).unwrap();
+
+        let swift_client_path = temp_dir.path().join("Client.swift");
+        create_swift_client(&api_dir, &swift_client_path, "my-package").unwrap();
+        let swift = fs::read_to_string(&swift_client_path).unwrap();
+
+        assert!(swift.contains("public struct Tree: Codable, Equatable {\n    public var id: UInt64\n    public var parent: Indirect<Tree>?\n"));
+        assert!(swift.contains("        case id = \"nodeId\"\n"));
+        assert!(swift.contains("public indirect enum Kind: Codable, Equatable {\n    case leaf\n    case branch([Tree])\n"));
+        assert!(swift.contains("            case \"Leaf\": self = .leaf; return\n"));
+        assert!(swift.contains(
+            "    public func getTree(id: UInt64, `default`: String) async throws -> ApiResult<Tree, String> {\n        try await client.request(\"POST\", \"/api/tree\", \"GetTree\", [id, `default`])\n    }"
+        ));
+        assert!(swift.contains("    public var myApp: MyAppApi { MyAppApi(client: self) }\n"));
+    }
+}
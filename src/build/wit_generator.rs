@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -7,16 +7,13 @@ use color_eyre::{
     eyre::{bail, eyre, WrapErr},
     Result,
 };
-use syn::{
-    self,
-    parse::{Parse, ParseStream},
-    punctuated::Punctuated,
-    Attribute, Ident, ImplItem, Item, LitStr, Token, Type,
-};
+use syn::{self, Attribute, Ident, ImplItem, Item, LitStr, Token, Type};
 use toml::Value;
 use tracing::{debug, info, instrument, warn};
 use walkdir::WalkDir;
 
+use super::kit_toml::ProcessConfig;
+
 // List of WIT keywords that need to be prefixed with %
 fn is_wit_keyword(s: &str) -> bool {
     matches!(
@@ -117,9 +114,9 @@ fn kebab_to_snake_case(s: &str) -> String {
 }
 
 // Validates a name doesn't contain numbers or "stream"
-fn validate_name(name: &str, kind: &str) -> Result<()> {
-    // Check for numbers
-    if name.chars().any(|c| c.is_digit(10)) {
+fn validate_name(name: &str, kind: &str, wit_rename: bool) -> Result<()> {
+    // Check for numbers, unless they are to be renamed (see `rename_digit_idents()`)
+    if !wit_rename && name.chars().any(|c| c.is_digit(10)) {
         bail!(
             "{} name '{}' contains numbers, which are not allowed in WIT identifiers.\n\
              \n\
@@ -128,7 +125,8 @@ fn validate_name(name: &str, kind: &str) -> Result<()> {
              - Numbers are not permitted in identifiers\n\
              \n\
              Suggestion: Rename '{}' to use descriptive words instead of numbers.\n\
-             Examples: 'field1' → 'first_field', 'level2' → 'level_two', 'data3' → 'third_data'",
+             Examples: 'field1' → 'first_field', 'level2' → 'level_two', 'data3' → 'third_data'\n\
+             Alternatively, build with --wit-rename to rewrite the numbers as words in the generated WIT.",
             kind,
             name,
             name
@@ -164,6 +162,26 @@ fn check_and_strip_leading_underscore(field_name: String) -> String {
     }
 }
 
+// Validate an interface name from kit.toml `wit-interface`, which is used as-is
+fn validate_interface_name_override(kebab_name: &str, wit_rename: bool) -> Result<()> {
+    validate_name(kebab_name, "Interface", wit_rename)?;
+    let is_kebab_case = kebab_name.split('-').all(|word| {
+        !word.is_empty()
+            && word
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    });
+    if !is_kebab_case || kebab_name.starts_with(|c: char| c.is_ascii_digit()) {
+        bail!(
+            "Interface name '{}' from kit.toml `wit-interface` is not a valid WIT identifier.\n\
+             \n\
+             Suggestion: Use kebab-case, e.g. 'my-api'.",
+            kebab_name
+        );
+    }
+    Ok(())
+}
+
 // Remove "State" suffix from a name
 fn remove_state_suffix(name: &str) -> String {
     if name.ends_with("State") {
@@ -185,36 +203,65 @@ fn is_hyperapp_attr(attr: &Attribute) -> bool {
     segments.len() == 2 && segments[0].ident == "hyperapp_macro" && segments[1].ident == "hyperapp"
 }
 
-// Extract wit_world from the #[hyperapp] attribute using the format in the debug representation
+// Convert a syn error into a report locating it in the source file being parsed
+fn syn_error_report(error: syn::Error, context: &str) -> color_eyre::eyre::Report {
+    let start = error.span().start();
+    eyre!(
+        "{} at line {}, column {}: {}",
+        context,
+        start.line,
+        start.column + 1,
+        error
+    )
+}
+
+// Extract wit_world from the #[hyperapp] attribute; its other arguments are not needed here,
+//  so their values are parsed as expressions & skipped
 #[instrument(level = "trace", skip_all)]
 fn extract_wit_world(attrs: &[Attribute]) -> Result<String> {
     for attr in attrs {
-        if is_hyperapp_attr(attr) {
-            // Convert attribute to string representation
-            let attr_str = format!("{:?}", attr);
-            debug!(attr_str = %attr_str, "Attribute string");
-
-            // Look for wit_world in the attribute string
-            if let Some(pos) = attr_str.find("wit_world") {
-                debug!(pos = %pos, "Found wit_world");
-
-                // Find the literal value after wit_world by looking for lit: "value"
-                let lit_pattern = "lit: \"";
-                if let Some(lit_pos) = attr_str[pos..].find(lit_pattern) {
-                    let start_pos = pos + lit_pos + lit_pattern.len();
-
-                    // Find the closing quote of the literal
-                    if let Some(quote_pos) = attr_str[start_pos..].find('\"') {
-                        let world_name = &attr_str[start_pos..(start_pos + quote_pos)];
-                        debug!(wit_world = %world_name, "Extracted wit_world");
-                        return Ok(world_name.to_string());
-                    }
-                }
+        if !is_hyperapp_attr(attr) {
+            continue;
+        }
+        let mut wit_world = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("wit_world") {
+                wit_world = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.input.parse::<proc_macro2::Group>()?;
             }
+            Ok(())
+        })
+        .map_err(|e| syn_error_report(e, "Failed to parse #[hyperapp] attribute"))?;
+        if let Some(world_name) = wit_world {
+            debug!(wit_world = %world_name, "Extracted wit_world");
+            return Ok(world_name);
         }
     }
-    bail!("wit_world not found in hyperapp attribute")
+    bail!(
+        "wit_world not found in hyperapp attribute\n\
+         Suggestion: Add it, e.g. #[hyperapp(wit_world = \"my-world-dot-os-v0\", ...)]"
+    )
 }
+// Common ecosystem types, by Rust type name, and the WIT types they are aliased to (as they are
+//  serialized by serde) when not defined in the project: (Rust type, WIT type, description)
+const ECOSYSTEM_TYPE_MAPPINGS: [(&str, &str, &str); 4] = [
+    ("Uuid", "string", "UUID, hyphenated (uuid::Uuid)"),
+    (
+        "DateTime",
+        "string",
+        "Date & time, RFC 3339 (chrono::DateTime)",
+    ),
+    ("Url", "string", "URL (url::Url)"),
+    (
+        "Decimal",
+        "string",
+        "Decimal number, as a string to preserve precision (rust_decimal::Decimal)",
+    ),
+];
+
 // Helper function to check if a WIT type name is a primitive or known built-in
 fn is_wit_primitive_or_builtin(type_name: &str) -> bool {
     matches!(
@@ -240,7 +287,88 @@ fn is_wit_primitive_or_builtin(type_name: &str) -> bool {
 
 // Convert Rust type to WIT type, including downstream types
 #[instrument(level = "trace", skip_all)]
-fn rust_type_to_wit(ty: &Type, used_types: &mut HashSet<String>) -> Result<String> {
+fn rust_type_to_wit(
+    ty: &Type,
+    used_types: &mut HashSet<String>,
+    wit_rename: bool,
+) -> Result<String> {
+    Ok(lower_maps(&rust_type_to_wit_with_maps(
+        ty, used_types, wit_rename,
+    )?))
+}
+
+// Lower each `map<k, v>` (a Rust map) to WIT's `list<tuple<k, v>>`, each `array<t, n>`
+//  (a Rust array) to WIT's `list<t>`, and each 128-bit `u128`/`s128` to WIT's `string`
+fn lower_maps(wit_type: &str) -> String {
+    let mut lowered = String::with_capacity(wit_type.len());
+    // what each open `<` is that of: `map`, `array`, or anything else
+    let mut opened_stack = Vec::new();
+    let mut rest = wit_type;
+    while let Some(c) = rest.chars().next() {
+        let is_type_start = !lowered.ends_with(|c: char| c.is_alphanumeric() || c == '-');
+        if rest.starts_with("map<") && is_type_start {
+            lowered.push_str("list<tuple<");
+            opened_stack.push("map");
+            rest = &rest["map<".len()..];
+            continue;
+        }
+        if rest.starts_with("array<") && is_type_start {
+            lowered.push_str("list<");
+            opened_stack.push("array");
+            rest = &rest["array<".len()..];
+            continue;
+        }
+        let type_name = rest
+            .split(|c: char| !c.is_alphanumeric() && c != '-')
+            .next();
+        if is_type_start && is_128_bit_int(type_name) {
+            lowered.push_str("string");
+            rest = &rest["u128".len()..];
+            continue;
+        }
+        match c {
+            '<' => opened_stack.push(""),
+            // drop the length of an array
+            ',' if opened_stack.last() == Some(&"array") => {
+                rest = &rest[rest.find('>').unwrap_or(rest.len())..];
+                continue;
+            }
+            '>' => {
+                if opened_stack.pop() == Some("map") {
+                    lowered.push('>');
+                }
+            }
+            _ => {}
+        }
+        lowered.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    lowered
+}
+
+// Whether a (kebab-case) WIT type name is one of the 128-bit integers that signature comments
+//  carry, but that are lowered to `string` in WIT
+fn is_128_bit_int(wit_type: Option<&str>) -> bool {
+    matches!(wit_type, Some("u128" | "s128"))
+}
+
+// Whether a WIT type, before lowering, contains a 128-bit integer
+fn has_128_bit_int(wit_type: &str) -> bool {
+    wit_type
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .any(|t| is_128_bit_int(Some(t)))
+}
+
+// Convert Rust type to WIT type, but with Rust maps as `map<k, v>`, arrays as `array<t, n>`
+//  & 128-bit integers as `u128`/`s128` rather than lowered: used in signature comments so that
+//  caller-utils can (de)serialize them as maps, check the lengths of arrays, & send and receive
+//  128-bit integers as the JSON numbers that serde (de)serializes them as
+#[instrument(level = "trace", skip_all)]
+fn rust_type_to_wit_with_maps(
+    ty: &Type,
+    used_types: &mut HashSet<String>,
+    wit_rename: bool,
+) -> Result<String> {
     match ty {
         Type::Path(type_path) => {
             if type_path.path.segments.is_empty() {
@@ -259,22 +387,45 @@ fn rust_type_to_wit(ty: &Type, used_types: &mut HashSet<String>) -> Result<Strin
                 "u32" => Ok("u32".to_string()),
                 "i64" => Ok("s64".to_string()),
                 "u64" => Ok("u64".to_string()),
-                // WIT 1.0 does not support 128-bit integers. Represent these as strings
-                // to preserve full precision across language boundaries.
-                "i128" => Ok("string".to_string()),
-                "u128" => Ok("string".to_string()),
+                // WIT 1.0 does not support 128-bit integers: these are lowered to strings
+                //  (of decimal digits) to preserve full precision across language boundaries
+                "i128" => Ok("s128".to_string()),
+                "u128" => Ok("u128".to_string()),
                 "f32" => Ok("f32".to_string()),
                 "f64" => Ok("f64".to_string()),
                 "usize" => Ok("u64".to_string()),
                 "isize" => Ok("s64".to_string()),
                 "String" => Ok("string".to_string()),
+                // Borrowed `&str`: the reference itself is handled below
+                "str" => Ok("string".to_string()),
                 "bool" => Ok("bool".to_string()),
+                // Smart pointers & Cow<'_, T> are (de)serialized as their T, so are transparent
+                "Box" | "Arc" | "Rc" | "Cow" => {
+                    if let syn::PathArguments::AngleBracketed(args) =
+                        &type_path.path.segments.last().unwrap().arguments
+                    {
+                        // Skip the lifetime of a Cow
+                        let inner_ty = args.args.iter().find_map(|arg| match arg {
+                            syn::GenericArgument::Type(inner_ty) => Some(inner_ty),
+                            _ => None,
+                        });
+                        match inner_ty {
+                            Some(inner_ty) => {
+                                rust_type_to_wit_with_maps(inner_ty, used_types, wit_rename)
+                            }
+                            None => Err(eyre!("Failed to parse {} inner type", type_name)),
+                        }
+                    } else {
+                        Err(eyre!("Failed to parse {} inner type!", type_name))
+                    }
+                }
                 "Vec" => {
                     if let syn::PathArguments::AngleBracketed(args) =
                         &type_path.path.segments.last().unwrap().arguments
                     {
                         if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
-                            let inner_type = rust_type_to_wit(inner_ty, used_types)?;
+                            let inner_type =
+                                rust_type_to_wit_with_maps(inner_ty, used_types, wit_rename)?;
                             Ok(format!("list<{}>", inner_type))
                         } else {
                             Err(eyre!("Failed to parse Vec inner type"))
@@ -288,7 +439,8 @@ fn rust_type_to_wit(ty: &Type, used_types: &mut HashSet<String>) -> Result<Strin
                         &type_path.path.segments.last().unwrap().arguments
                     {
                         if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
-                            let inner_type = rust_type_to_wit(inner_ty, used_types)?;
+                            let inner_type =
+                                rust_type_to_wit_with_maps(inner_ty, used_types, wit_rename)?;
                             Ok(format!("option<{}>", inner_type))
                         } else {
                             Err(eyre!("Failed to parse Option inner type"))
@@ -308,8 +460,10 @@ fn rust_type_to_wit(ty: &Type, used_types: &mut HashSet<String>) -> Result<Strin
                                 Some(syn::GenericArgument::Type(err_ty)),
                             ) = (args.args.first(), args.args.get(1))
                             {
-                                let ok_type_str = rust_type_to_wit(ok_ty, used_types)?;
-                                let err_type_str = rust_type_to_wit(err_ty, used_types)?;
+                                let ok_type_str =
+                                    rust_type_to_wit_with_maps(ok_ty, used_types, wit_rename)?;
+                                let err_type_str =
+                                    rust_type_to_wit_with_maps(err_ty, used_types, wit_rename)?;
 
                                 // Map Rust's () (represented as "_") to WIT's _ in result<...>
                                 let final_ok = if ok_type_str == "_" {
@@ -357,13 +511,15 @@ fn rust_type_to_wit(ty: &Type, used_types: &mut HashSet<String>) -> Result<Strin
                                 Some(syn::GenericArgument::Type(val_ty)),
                             ) = (args.args.first(), args.args.get(1))
                             {
-                                let key_type = rust_type_to_wit(key_ty, used_types)?;
-                                let val_type = rust_type_to_wit(val_ty, used_types)?;
+                                let key_type =
+                                    rust_type_to_wit_with_maps(key_ty, used_types, wit_rename)?;
+                                let val_type =
+                                    rust_type_to_wit_with_maps(val_ty, used_types, wit_rename)?;
                                 // Defer alias/string validation to later verification.
-                                // Generate a tuple-backed representation using the key type name
-                                // (which may be an alias like `node-id`) and let the alias
-                                // definition resolve to `string`.
-                                Ok(format!("list<tuple<{}, {}>>", key_type, val_type))
+                                // Generate a map (lowered to a list of tuples) using the key
+                                // type name (which may be an alias like `node-id`) and let the
+                                // alias definition resolve to `string`.
+                                Ok(format!("map<{}, {}>", key_type, val_type))
                             } else {
                                 bail!("Failed to parse HashMap generic arguments");
                             }
@@ -379,7 +535,8 @@ fn rust_type_to_wit(ty: &Type, used_types: &mut HashSet<String>) -> Result<Strin
                         &type_path.path.segments.last().unwrap().arguments
                     {
                         if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
-                            let inner = rust_type_to_wit(inner_ty, used_types)?;
+                            let inner =
+                                rust_type_to_wit_with_maps(inner_ty, used_types, wit_rename)?;
                             Ok(format!("list<{}>", inner))
                         } else {
                             bail!("Failed to parse HashSet inner type");
@@ -390,18 +547,62 @@ fn rust_type_to_wit(ty: &Type, used_types: &mut HashSet<String>) -> Result<Strin
                 }
                 custom => {
                     // Validate custom type name
-                    validate_name(custom, "Type")?;
+                    validate_name(custom, "Type", wit_rename)?;
 
                     // Convert custom type to kebab-case and add to used types
                     let kebab_custom = to_kebab_case(custom);
-                    used_types.insert(kebab_custom.clone());
-                    Ok(kebab_custom)
+
+                    // Ecosystem types are aliased whatever their type arguments
+                    //  (e.g. the time zone of a `DateTime<Utc>`)
+                    if ECOSYSTEM_TYPE_MAPPINGS
+                        .iter()
+                        .any(|(rust_type, _, _)| *rust_type == custom)
+                    {
+                        used_types.insert(kebab_custom.clone());
+                        return Ok(kebab_custom);
+                    }
+
+                    // Generic types are monomorphized: add the instantiation (e.g.
+                    // `paginated<todo-item>`) to used types, to be defined with its
+                    // type arguments substituted, and name it after them
+                    let mut type_args = Vec::new();
+                    if let syn::PathArguments::AngleBracketed(args) =
+                        &type_path.path.segments.last().unwrap().arguments
+                    {
+                        for arg in &args.args {
+                            if let syn::GenericArgument::Type(arg_ty) = arg {
+                                let arg_type =
+                                    rust_type_to_wit_with_maps(arg_ty, used_types, wit_rename)?;
+                                if arg_type == "_" {
+                                    bail!(
+                                        "Generic type '{}' has a '()' type argument, which is not supported in WIT.",
+                                        custom
+                                    );
+                                }
+                                type_args.push(arg_type);
+                            }
+                        }
+                    }
+                    if type_args.is_empty() {
+                        used_types.insert(kebab_custom.clone());
+                        return Ok(kebab_custom);
+                    }
+                    let instantiation = format!("{}<{}>", kebab_custom, type_args.join(", "));
+                    let monomorphized = monomorphized_name(&instantiation);
+                    used_types.insert(instantiation);
+                    Ok(monomorphized)
                 }
             }
         }
         Type::Reference(type_ref) => {
-            // Handle references by using the underlying type
-            rust_type_to_wit(&type_ref.elem, used_types)
+            // Handle references (e.g. `&str`, `&[u8]`) by using the underlying type: callers
+            //  send it owned, as serde (de)serializes both the same
+            rust_type_to_wit_with_maps(&type_ref.elem, used_types, wit_rename)
+        }
+        // [T]: slices, borrowed as `&[T]`, are lists like Vecs
+        Type::Slice(type_slice) => {
+            let inner_type = rust_type_to_wit_with_maps(&type_slice.elem, used_types, wit_rename)?;
+            Ok(format!("list<{}>", inner_type))
         }
         // fn () -> Result<(), Error>
         // tuple<>
@@ -414,15 +615,372 @@ fn rust_type_to_wit(ty: &Type, used_types: &mut HashSet<String>) -> Result<Strin
                 // Create a tuple representation in WIT
                 let mut elem_types = Vec::new();
                 for elem in &type_tuple.elems {
-                    elem_types.push(rust_type_to_wit(elem, used_types)?);
+                    elem_types.push(rust_type_to_wit_with_maps(elem, used_types, wit_rename)?);
                 }
                 Ok(format!("tuple<{}>", elem_types.join(", ")))
             }
         }
+        // [T; N]: fixed-size arrays (e.g. hashes & signatures) are lists in WIT
+        Type::Array(type_array) => {
+            let inner_type = rust_type_to_wit_with_maps(&type_array.elem, used_types, wit_rename)?;
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(len),
+                ..
+            }) = &type_array.len
+            else {
+                bail!(
+                    "Array of {} has a length that is not an integer literal, which is not supported in WIT signatures. \
+                     Suggestion: write the length as a literal (e.g. `[u8; 32]`), or use a Vec.",
+                    inner_type
+                );
+            };
+            Ok(format!("array<{}, {}>", inner_type, len.base10_digits()))
+        }
         _ => return Err(eyre!("Failed to parse type: {ty:?}")),
     }
 }
 
+// The identifiers in the type positions of a WIT type definition (after a field's `:`, within a
+//  variant case's `(...)`, or after an alias's `=`), i.e. the types it references; doc comments
+//  & other comments are skipped
+fn referenced_wit_types(wit_def: &str) -> HashSet<String> {
+    wit_def
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("//"))
+        .filter_map(|line| {
+            line.find([':', '(', '='])
+                .map(|type_start| &line[type_start + 1..])
+        })
+        .flat_map(|types| types.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '%')))
+        .map(|ident| ident.trim_start_matches('%').to_string())
+        .filter(|ident| !ident.is_empty())
+        .collect()
+}
+
+// Find a cycle among the types left over by the topological sort, each of which has a dependent
+//  that is also left over: following dependents from the first must come back around.
+//  Returns the cycle as a path of dependencies, starting & ending with the same type
+fn find_type_cycle(
+    remaining: &[String],
+    type_dependencies: &HashMap<String, Vec<String>>,
+) -> Vec<String> {
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (type_name, deps) in type_dependencies {
+        if !remaining.contains(type_name) {
+            continue;
+        }
+        for dep in deps {
+            dependents.entry(dep).or_default().push(type_name);
+        }
+    }
+    for type_dependents in dependents.values_mut() {
+        type_dependents.sort();
+    }
+
+    let Some(start) = remaining.first() else {
+        return Vec::new();
+    };
+    let mut path: Vec<&str> = vec![start];
+    loop {
+        let current = path.last().unwrap();
+        let Some(next) = dependents.get(current).and_then(|d| d.first()) else {
+            // Not reachable given the topological sort, but don't loop forever
+            return remaining.to_vec();
+        };
+        if let Some(cycle_start) = path.iter().position(|t| t == next) {
+            // `path` is of dependents, so reverse it into dependencies
+            let mut cycle: Vec<String> = path[cycle_start..]
+                .iter()
+                .rev()
+                .map(|t| t.to_string())
+                .collect();
+            cycle.insert(0, next.to_string());
+            return cycle;
+        }
+        path.push(next);
+    }
+}
+
+// Find the (1-based) line of the Rust struct, enum or type alias whose WIT name is `kebab_name`
+fn find_type_definition_line(file_path: &Path, kebab_name: &str) -> Option<usize> {
+    let content = fs::read_to_string(file_path).ok()?;
+    content
+        .lines()
+        .position(|line| {
+            let mut tokens = line
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .filter(|token| !token.is_empty());
+            while let Some(token) = tokens.next() {
+                if matches!(token, "struct" | "enum" | "type") {
+                    return tokens
+                        .next()
+                        .is_some_and(|name| to_kebab_case(name) == kebab_name);
+                }
+            }
+            false
+        })
+        .map(|index| index + 1)
+}
+
+// Name a generic type instantiation after its type arguments,
+//  e.g. `paginated<todo-item>` as `paginated-todo-item`
+fn monomorphized_name(instantiation: &str) -> String {
+    instantiation
+        .split(['<', '>', ',', ' '])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Split a comma-separated list of WIT types at its top level (i.e. not within nested generics)
+fn split_top_level(inner: &str) -> Vec<String> {
+    let mut types = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    for c in inner.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                types.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        types.push(current.trim().to_string());
+    }
+    types
+}
+
+// Split a type to collect into its name and, for a generic type instantiation
+//  like `paginated<todo-item>`, its type arguments
+fn split_generic_instantiation(ty: &str) -> (&str, Vec<String>) {
+    match ty.split_once('<') {
+        Some((name, args)) => (
+            name,
+            split_top_level(args.strip_suffix('>').unwrap_or(args)),
+        ),
+        None => (ty, Vec::new()),
+    }
+}
+
+// Pair the type parameters of a generic type with the type arguments it is instantiated with:
+//  returns the (monomorphized) name of the instantiation and the argument for each parameter
+fn instantiate_generics(
+    generics: &syn::Generics,
+    name: &str,
+    kebab_name: &str,
+    type_args: &[String],
+) -> Result<(String, HashMap<String, String>)> {
+    let params: Vec<String> = generics
+        .type_params()
+        .map(|p| to_kebab_case(&p.ident.to_string()))
+        .collect();
+    if params.len() != type_args.len() {
+        bail!(
+            "Type '{}' has {} generic type parameter(s) but is used with {} type argument(s) {:?}.\n\
+             \n\
+             Suggestion: Use generic types in WIT signatures only with concrete type arguments \
+             (e.g., '{}<MyItem>'), so they can be generated as one WIT type per instantiation.",
+            name,
+            params.len(),
+            type_args.len(),
+            type_args,
+            name
+        );
+    }
+    if type_args.is_empty() {
+        return Ok((kebab_name.to_string(), HashMap::new()));
+    }
+    let instantiation = format!("{}<{}>", kebab_name, type_args.join(", "));
+    Ok((
+        monomorphized_name(&instantiation),
+        params.into_iter().zip(type_args.iter().cloned()).collect(),
+    ))
+}
+
+// Substitute WIT types for the identifiers in type positions of a WIT definition
+//  (i.e. after `:`, `(` or `=`, or within `<...>`), leaving names of fields & cases alone
+fn substitute_type_idents(wit_def: &str, substitutions: &HashMap<String, String>) -> String {
+    fn flush(
+        substituted: &mut String,
+        ident: &mut String,
+        depth: usize,
+        substitutions: &HashMap<String, String>,
+    ) {
+        if ident.is_empty() {
+            return;
+        }
+        let is_type_position = depth > 0
+            || matches!(
+                substituted.trim_end().chars().last(),
+                Some(':') | Some('(') | Some('=')
+            );
+        match substitutions.get(ident.as_str()) {
+            Some(wit_type) if is_type_position => substituted.push_str(wit_type),
+            _ => substituted.push_str(ident),
+        }
+        ident.clear();
+    }
+
+    let mut substituted = String::with_capacity(wit_def.len());
+    let mut ident = String::new();
+    let mut depth = 0;
+    for c in wit_def.chars() {
+        if c.is_alphanumeric() || c == '-' || c == '%' {
+            ident.push(c);
+            continue;
+        }
+        flush(&mut substituted, &mut ident, depth, substitutions);
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        substituted.push(c);
+    }
+    flush(&mut substituted, &mut ident, depth, substitutions);
+    substituted
+}
+
+// Substitute the type arguments of a generic type instantiation into its WIT definition,
+//  and add its dependencies (with the same substitution) to `dependencies`
+fn substitute_generics(
+    wit_def: &str,
+    type_params: &HashMap<String, String>,
+    type_dependencies: HashSet<String>,
+    dependencies: &mut HashSet<String>,
+) -> String {
+    if type_params.is_empty() {
+        dependencies.extend(type_dependencies);
+        return wit_def.to_string();
+    }
+    // within the definition, type parameters are replaced by their (lowered) arguments...
+    let mut substitutions: HashMap<String, String> = type_params
+        .iter()
+        .map(|(param, arg)| (param.clone(), lower_maps(arg)))
+        .collect();
+    for dep in type_dependencies {
+        if type_params.contains_key(&dep) {
+            continue;
+        }
+        if dep.contains('<') {
+            // ...and generic types instantiated with them by their instantiations with the arguments
+            let instantiation = substitute_type_idents(&dep, type_params);
+            substitutions.insert(monomorphized_name(&dep), monomorphized_name(&instantiation));
+            dependencies.insert(instantiation);
+        } else {
+            dependencies.insert(dep);
+        }
+    }
+    // doc comments are left alone
+    wit_def
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.trim_start().starts_with("//") {
+                line.to_string()
+            } else {
+                substitute_type_idents(line, &substitutions)
+            }
+        })
+        .collect()
+}
+
+// Comment recording, for --wit-rename, that an identifier was renamed:
+//  `// wit-rename: <renamed> = <original>`
+const WIT_RENAME_COMMENT: &str = "wit-rename:";
+
+// Rewrite the digits of a kebab-case identifier as words, e.g. `secret-v2` as `secret-v-two`
+fn mangle_digits(ident: &str) -> String {
+    const DIGIT_WORDS: [&str; 10] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut words = Vec::new();
+    for word in ident.split('-') {
+        let mut current = String::new();
+        for c in word.chars() {
+            match c.to_digit(10) {
+                Some(digit) => {
+                    if !current.is_empty() {
+                        words.push(std::mem::take(&mut current));
+                    }
+                    words.push(DIGIT_WORDS[digit as usize].to_string());
+                }
+                None => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+    }
+    words.join("-")
+}
+
+// Rename the identifiers containing digits in generated WIT by rewriting their digits as words
+//  (see `mangle_digits()`), adding each to `renames` (renamed -> original); comments are left
+//  alone, except the `args` & `returns` comments of signatures, which caller-utils parse
+fn rename_digit_idents(wit: &str, renames: &mut BTreeMap<String, String>) -> Result<String> {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '-';
+    let idents: HashSet<&str> = wit
+        .split(|c: char| !is_ident_char(c))
+        .filter(|ident| !ident.is_empty())
+        .collect();
+
+    let mut renamed = String::with_capacity(wit.len());
+    for line in wit.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("//")
+            && !trimmed.starts_with("// args:")
+            && !trimmed.starts_with("// returns:")
+        {
+            renamed.push_str(line);
+            continue;
+        }
+        let mut ident = String::new();
+        for c in line.chars().chain(std::iter::once('\0')) {
+            if is_ident_char(c) {
+                ident.push(c);
+                continue;
+            }
+            if ident.chars().any(|c| c.is_ascii_digit())
+                && ident.starts_with(|c: char| c.is_alphabetic())
+                && !is_wit_keyword(&ident)
+            {
+                let mangled = mangle_digits(&ident);
+                if idents.contains(mangled.as_str())
+                    || renames
+                        .get(&mangled)
+                        .is_some_and(|original| original != &ident)
+                {
+                    bail!(
+                        "Cannot rename WIT identifier '{}' to '{}' for --wit-rename: '{}' is already in use.\n\
+                         \n\
+                         Suggestion: Rename '{}' to use descriptive words instead of numbers.",
+                        ident,
+                        mangled,
+                        mangled,
+                        ident
+                    );
+                }
+                renamed.push_str(&mangled);
+                renames.insert(mangled, std::mem::take(&mut ident));
+            } else {
+                renamed.push_str(&ident);
+                ident.clear();
+            }
+            if c != '\0' {
+                renamed.push(c);
+            }
+        }
+    }
+    Ok(renamed)
+}
+
 // Find all Rust files in a crate directory
 fn find_rust_files(crate_path: &Path) -> Vec<PathBuf> {
     let mut rust_files = Vec::new();
@@ -435,7 +993,11 @@ fn find_rust_files(crate_path: &Path) -> Vec<PathBuf> {
         return rust_files;
     }
 
-    for entry in WalkDir::new(src_dir).into_iter().filter_map(Result::ok) {
+    for entry in WalkDir::new(src_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+    {
         let path = entry.path();
         if path.is_file() && path.extension().map_or(false, |ext| ext == "rs") {
             debug!(path = %path.display(), "Found Rust file");
@@ -447,13 +1009,75 @@ fn find_rust_files(crate_path: &Path) -> Vec<PathBuf> {
     rust_files
 }
 
+// Find the workspace root of a crate: the nearest ancestor whose Cargo.toml has a [workspace]
+fn find_workspace_root(crate_path: &Path) -> Option<(PathBuf, Value)> {
+    crate_path.ancestors().skip(1).find_map(|dir| {
+        let content = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+        let cargo_data = content.parse::<Value>().ok()?;
+        cargo_data
+            .get("workspace")
+            .is_some()
+            .then(|| (dir.to_path_buf(), cargo_data))
+    })
+}
+
+// Find the Rust files of the crates a crate depends on by path, either directly
+//  (`shared-types = { path = "../shared-types" }`) or via the workspace
+//  (`shared-types = { workspace = true }`), so that types shared between processes
+//  can be defined once in, e.g., a sibling workspace crate
+fn find_path_dependency_rust_files(crate_path: &Path) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(crate_path.join("Cargo.toml")) else {
+        return vec![];
+    };
+    let Ok(cargo_data) = content.parse::<Value>() else {
+        return vec![];
+    };
+    let Some(dependencies) = cargo_data.get("dependencies").and_then(|d| d.as_table()) else {
+        return vec![];
+    };
+    let workspace_root = find_workspace_root(crate_path);
+
+    let mut dependency_paths = Vec::new();
+    for (name, spec) in dependencies {
+        if let Some(path) = spec.get("path").and_then(|p| p.as_str()) {
+            dependency_paths.push(crate_path.join(path));
+        } else if spec.get("workspace").and_then(|w| w.as_bool()) == Some(true) {
+            let Some((ref root, ref workspace_data)) = workspace_root else {
+                continue;
+            };
+            let path = workspace_data
+                .get("workspace")
+                .and_then(|w| w.get("dependencies"))
+                .and_then(|d| d.get(name))
+                .and_then(|spec| spec.get("path"))
+                .and_then(|p| p.as_str());
+            if let Some(path) = path {
+                dependency_paths.push(root.join(path));
+            }
+        }
+    }
+
+    let mut rust_files = Vec::new();
+    let mut seen = HashSet::new();
+    for dependency_path in dependency_paths {
+        let dependency_path = dependency_path.canonicalize().unwrap_or(dependency_path);
+        if !seen.insert(dependency_path.clone()) {
+            continue;
+        }
+        debug!(path = %dependency_path.display(), "Scanning path dependency for types");
+        rust_files.extend(find_rust_files(&dependency_path));
+    }
+    rust_files
+}
+
 // Find all relevant Rust projects
-fn find_rust_projects(base_dir: &Path) -> Vec<PathBuf> {
+pub fn find_rust_projects(base_dir: &Path) -> Vec<PathBuf> {
     let mut projects = Vec::new();
     debug!(base_dir = %base_dir.display(), "Scanning for Rust projects");
 
     for entry in WalkDir::new(base_dir)
         .max_depth(1)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(Result::ok)
     {
@@ -508,14 +1132,17 @@ fn generate_signature_struct(
     attr_type: &str,
     method: &syn::ImplItemFn,
     used_types: &mut HashSet<String>,
+    wit_rename: bool,
 ) -> Result<String> {
     // Create signature struct name with attribute type
     let signature_struct_name = format!("{}-signature-{}", kebab_name, attr_type);
 
-    // Generate comment for this specific function
+    // Generate comment for this specific function, preceded by its doc comments
     let mut comment = format!(
-        "    // Function signature for: {} ({})",
-        kebab_name, attr_type
+        "{}    // Function signature for: {} ({})",
+        format_wit_docs(&extract_docs(&method.attrs), "    "),
+        kebab_name,
+        attr_type
     );
 
     // For HTTP endpoints, try to extract method and path from attribute
@@ -562,7 +1189,7 @@ fn generate_signature_struct(
                 let param_orig_name = pat_ident.ident.to_string();
 
                 // Validate parameter name
-                match validate_name(&param_orig_name, "Parameter") {
+                match validate_name(&param_orig_name, "Parameter", wit_rename) {
                     Ok(_) => {
                         let stripped_param_name =
                             check_and_strip_leading_underscore(param_orig_name.clone());
@@ -570,7 +1197,7 @@ fn generate_signature_struct(
                         let param_wit_ident = to_wit_ident(&param_name);
 
                         // Rust type to WIT type
-                        match rust_type_to_wit(&pat_type.ty, used_types) {
+                        match rust_type_to_wit_with_maps(&pat_type.ty, used_types, wit_rename) {
                             Ok(param_type) => {
                                 param_names_and_types.push((param_wit_ident, param_type));
                             }
@@ -595,9 +1222,9 @@ fn generate_signature_struct(
             .map(|(name, ty)| format!("{}: {}", name, ty))
             .collect();
 
-        let tuple_types: Vec<&str> = param_names_and_types
+        let tuple_types: Vec<String> = param_names_and_types
             .iter()
-            .map(|(_, ty)| ty.as_str())
+            .map(|(_, ty)| lower_maps(ty))
             .collect();
 
         // Add args comment above the record (appended to the function comment)
@@ -640,29 +1267,35 @@ fn generate_signature_struct(
 
     // Add return type field
     match &method.sig.output {
-        syn::ReturnType::Type(_, ty) => match rust_type_to_wit(&*ty, used_types) {
-            Ok(return_type) => {
-                // Check if the return type is "_", which signifies a standalone () return type.
-                if return_type == "_" {
-                    let method_name = method.sig.ident.to_string();
-                    bail!(
+        syn::ReturnType::Type(_, ty) => {
+            match rust_type_to_wit_with_maps(&*ty, used_types, wit_rename) {
+                Ok(return_type) => {
+                    // Check if the return type is "_", which signifies a standalone () return type.
+                    if return_type == "_" {
+                        let method_name = method.sig.ident.to_string();
+                        bail!(
                         "Function '{}' returns '()', which is not directly supported in WIT signatures. \
                          Consider returning a Result<(), YourErrorType> or another meaningful type.",
                         method_name
                     );
+                    }
+                    // Document maps, arrays & 128-bit integers, which are lowered in the field
+                    if return_type != lower_maps(&return_type) {
+                        comment.push_str(&format!("\n    // returns: {}", return_type));
+                    }
+                    // Add the valid return type field
+                    struct_fields.push(format!("        returning: {}", lower_maps(&return_type)));
+                }
+                Err(e) => {
+                    // Propagate *other* errors from return type conversion, wrapping them.
+                    let method_name = method.sig.ident.to_string();
+                    return Err(e.wrap_err(format!(
+                        "Failed to convert return type for function '{}'",
+                        method_name
+                    )));
                 }
-                // Add the valid return type field
-                struct_fields.push(format!("        returning: {}", return_type));
-            }
-            Err(e) => {
-                // Propagate *other* errors from return type conversion, wrapping them.
-                let method_name = method.sig.ident.to_string();
-                return Err(e.wrap_err(format!(
-                    "Failed to convert return type for function '{}'",
-                    method_name
-                )));
             }
-        },
+        }
         syn::ReturnType::Default => {
             // Functions exposed via WIT must have an explicit return type.
             let method_name = method.sig.ident.to_string();
@@ -683,80 +1316,344 @@ fn generate_signature_struct(
     Ok(record_def)
 }
 
-#[derive(Default, Debug, Clone)]
-struct HttpAttrInfo {
-    method: Option<String>,
-    path: Option<String>,
-}
+// Generate the signature struct of a [ws] handler: its messages, of the Rust type `ws_message`
+#[instrument(level = "trace", skip_all)]
+fn generate_ws_signature_struct(
+    kebab_name: &str,
+    method: &syn::ImplItemFn,
+    ws_message: &str,
+    used_types: &mut HashSet<String>,
+    wit_rename: bool,
+) -> Result<String> {
+    let message_type = syn::parse_str::<Type>(ws_message).map_err(|e| {
+        eyre!(
+            "Invalid `ws-message` type '{}' in kit.toml: {}. \
+             Suggestion: set it to the Rust type of the messages, e.g. `ws-message = \"WsMessage\"`.",
+            ws_message,
+            e
+        )
+    })?;
+    let message_wit_type = rust_type_to_wit_with_maps(&message_type, used_types, wit_rename)
+        .wrap_err_with(|| format!("Failed to convert `ws-message` type '{}'", ws_message))?;
+
+    let mut comment = format!(
+        "{}    // Function signature for: {} (ws)",
+        format_wit_docs(&extract_docs(&method.attrs), "    "),
+        kebab_name,
+    );
+    // Document maps, which are lowered to lists of tuples in the field
+    if message_wit_type != lower_maps(&message_wit_type) {
+        comment.push_str(&format!("\n    // message: {}", message_wit_type));
+    }
 
-struct HttpKeyValue {
-    key: Ident,
-    _eq_token: Token![=],
-    value: LitStr,
+    Ok(format!(
+        "{}\n    record {}-signature-ws {{\n        message: {}\n    }}",
+        comment,
+        kebab_name,
+        lower_maps(&message_wit_type)
+    ))
 }
 
-impl Parse for HttpKeyValue {
-    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
-        Ok(Self {
-            key: input.parse()?,
-            _eq_token: input.parse()?,
-            value: input.parse()?,
-        })
+// Generate the signature struct of the events a hyperapp process pushes to its WebSocket
+//  clients, of the Rust type `ws_push`
+#[instrument(level = "trace", skip_all)]
+fn generate_ws_push_signature_struct(
+    ws_push: &str,
+    used_types: &mut HashSet<String>,
+    wit_rename: bool,
+) -> Result<String> {
+    let event_type = syn::parse_str::<Type>(ws_push).map_err(|e| {
+        eyre!(
+            "Invalid `ws-push` type '{}' in kit.toml: {}. \
+             Suggestion: set it to the Rust type of the pushed events, e.g. `ws-push = \"ChatEvent\"`.",
+            ws_push,
+            e
+        )
+    })?;
+    let event_wit_type = rust_type_to_wit_with_maps(&event_type, used_types, wit_rename)
+        .wrap_err_with(|| format!("Failed to convert `ws-push` type '{}'", ws_push))?;
+
+    let mut comment =
+        "    /// Events pushed to WebSocket clients\n    // Function signature for: push (ws-push)"
+            .to_string();
+    // Document maps, which are lowered to lists of tuples in the field
+    if event_wit_type != lower_maps(&event_wit_type) {
+        comment.push_str(&format!("\n    // event: {}", event_wit_type));
     }
+
+    Ok(format!(
+        "{}\n    record push-signature-ws-push {{\n        event: {}\n    }}",
+        comment,
+        lower_maps(&event_wit_type)
+    ))
+}
+
+#[derive(Default, Debug, Clone)]
+struct HttpAttrInfo {
+    method: Option<String>,
+    path: Option<String>,
 }
 
 // Helper function to extract HTTP method and path from [http] attribute
 #[instrument(level = "trace", skip_all)]
 fn extract_http_info(attrs: &[Attribute]) -> Result<Option<HttpAttrInfo>> {
     for attr in attrs {
-        if attr.path().is_ident("http") {
-            match &attr.meta {
-                syn::Meta::Path(_) => {
-                    return Ok(Some(HttpAttrInfo::default()));
-                }
-                syn::Meta::List(list) => {
-                    let parser = Punctuated::<HttpKeyValue, Token![,]>::parse_terminated;
-                    let parsed = list.parse_args_with(parser);
-
-                    match parsed {
-                        Ok(args) => {
-                            let mut info = HttpAttrInfo::default();
-                            for kv in args {
-                                let key = kv.key.to_string();
-                                let value = kv.value.value();
-                                match key.as_str() {
-                                    "method" => info.method = Some(value.to_uppercase()),
-                                    "path" => info.path = Some(value),
-                                    other => {
-                                        warn!(key = %other, "Unknown parameter in #[http] attribute")
-                                    }
-                                }
-                            }
-                            return Ok(Some(info));
-                        }
-                        Err(err) => {
-                            return Err(err)
-                                .wrap_err("Failed to parse #[http] attribute arguments");
+        if !attr.path().is_ident("http") {
+            continue;
+        }
+        let mut info = HttpAttrInfo::default();
+        if let syn::Meta::NameValue(_) = attr.meta {
+            warn!("Unexpected name-value form for #[http] attribute");
+            return Ok(Some(info));
+        }
+        if let syn::Meta::List(_) = attr.meta {
+            attr.parse_nested_meta(|meta| {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map(|ident| ident.to_string())
+                    .unwrap_or_default();
+                match key.as_str() {
+                    "method" => {
+                        let method = meta.value()?.parse::<LitStr>()?.value();
+                        info.method = Some(method.to_uppercase());
+                    }
+                    "path" => info.path = Some(meta.value()?.parse::<LitStr>()?.value()),
+                    other => {
+                        warn!(key = %other, "Unknown parameter in #[http] attribute");
+                        if meta.input.peek(Token![=]) {
+                            meta.value()?.parse::<syn::Expr>()?;
                         }
                     }
                 }
-                syn::Meta::NameValue(_) => {
-                    warn!("Unexpected name-value form for #[http] attribute");
-                    return Ok(Some(HttpAttrInfo::default()));
-                }
-            }
+                Ok(())
+            })
+            .map_err(|e| syn_error_report(e, "Failed to parse #[http] attribute arguments"))?;
         }
+        return Ok(Some(info));
     }
     Ok(None)
 }
 
-// Helper trait to get TypePath from Type
-trait AsTypePath {
-    fn as_type_path(&self) -> Option<&syn::TypePath>;
-}
-
-impl AsTypePath for syn::Type {
-    fn as_type_path(&self) -> Option<&syn::TypePath> {
+// Extract the doc comments (`///` or `#[doc = "..."]`) from attributes, one entry per line
+fn extract_docs(attrs: &[Attribute]) -> Vec<String> {
+    let mut docs = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("doc") {
+            continue;
+        }
+        if let syn::Meta::NameValue(nv) = &attr.meta {
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) = &nv.value
+            {
+                for line in s.value().split('\n') {
+                    // `/// doc` is `#[doc = " doc"]`
+                    let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+                    docs.push(line.to_string());
+                }
+            }
+        }
+    }
+    docs
+}
+
+// Format doc comments as WIT doc comments (each line ending in a newline), so that they are
+//  carried by wit-bindgen into the generated bindings
+fn format_wit_docs(docs: &[String], indent: &str) -> String {
+    docs.iter()
+        .map(|line| {
+            if line.is_empty() {
+                format!("{}///\n", indent)
+            } else {
+                format!("{}/// {}\n", indent, line)
+            }
+        })
+        .collect()
+}
+
+// Comment recording the serde (JSON) name of the following field or case, when the clients'
+//  default naming of its WIT identifier would not reproduce it: `// serde: <wire name>`
+const SERDE_WIRE_NAME_COMMENT: &str = "serde:";
+
+// The `#[serde(...)]` attributes of a type, field or variant that change its JSON form
+#[derive(Debug, Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    rename_all: Option<String>,
+    rename_all_fields: Option<String>,
+    skip: bool,
+    // Whether (de)serialization is customized, by `with`, `serialize_with` or `deserialize_with`
+    custom_with: bool,
+}
+
+// Extract the `#[serde(...)]` attributes that change the JSON names of fields & variants, or
+//  skip them; of `rename(serialize = "..", deserialize = "..")` forms, the serialized name is used
+fn extract_serde_attrs(attrs: &[Attribute]) -> SerdeAttrs {
+    let mut serde_attrs = SerdeAttrs::default();
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        let parsed = attr.parse_nested_meta(|meta| {
+            let key = meta
+                .path
+                .get_ident()
+                .map(|ident| ident.to_string())
+                .unwrap_or_default();
+            let name = if meta.input.peek(Token![=]) {
+                let value = meta.value()?;
+                match key.as_str() {
+                    "rename" | "rename_all" | "rename_all_fields" => {
+                        Some(value.parse::<LitStr>()?.value())
+                    }
+                    _ => {
+                        // e.g. `default = "..."`, `with = "..."`
+                        if matches!(key.as_str(), "with" | "serialize_with" | "deserialize_with") {
+                            serde_attrs.custom_with = true;
+                        }
+                        value.parse::<syn::Expr>()?;
+                        None
+                    }
+                }
+            } else if meta.input.peek(syn::token::Paren) {
+                let mut serialized = None;
+                meta.parse_nested_meta(|inner| {
+                    let value = inner.value()?.parse::<LitStr>()?.value();
+                    if inner.path.is_ident("serialize") {
+                        serialized = Some(value);
+                    }
+                    Ok(())
+                })?;
+                serialized
+            } else {
+                if key == "skip" {
+                    serde_attrs.skip = true;
+                }
+                None
+            };
+            match key.as_str() {
+                "rename" if name.is_some() => serde_attrs.rename = name,
+                "rename_all" if name.is_some() => serde_attrs.rename_all = name,
+                "rename_all_fields" if name.is_some() => serde_attrs.rename_all_fields = name,
+                _ => {}
+            }
+            Ok(())
+        });
+        if let Err(e) = parsed {
+            warn!(error = %e, "Failed to parse #[serde] attribute; ignoring it for WIT generation");
+        }
+    }
+    serde_attrs
+}
+
+// Apply a `#[serde(rename_all = "...")]` rule to a field (snake_case) or variant (PascalCase)
+//  name, as serde does. Returns `None` for unknown rules
+fn apply_serde_rename_all(rule: &str, name: &str, is_variant: bool) -> Option<String> {
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+    let uncapitalize = |word: &str| {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map(|first| first.to_lowercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+    let snake = if is_variant {
+        let mut snake = String::with_capacity(name.len() + 4);
+        for (i, c) in name.char_indices() {
+            if c.is_uppercase() && i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        }
+        snake
+    } else {
+        name.to_string()
+    };
+    let pascal = if is_variant {
+        name.to_string()
+    } else {
+        name.split('_').map(capitalize).collect()
+    };
+    Some(match rule {
+        "lowercase" if is_variant => name.to_lowercase(),
+        "UPPERCASE" if is_variant => name.to_uppercase(),
+        "lowercase" | "snake_case" => snake,
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => snake.to_uppercase(),
+        "PascalCase" => pascal,
+        "camelCase" => uncapitalize(&pascal),
+        "kebab-case" => snake.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake.to_uppercase().replace('_', "-"),
+        _ => return None,
+    })
+}
+
+// The name a field or variant has in JSON: its `rename`, else its name under the container's
+//  `rename_all` rule, else its Rust name
+fn serde_wire_name(
+    name: &str,
+    serde_attrs: &SerdeAttrs,
+    rename_all: Option<&str>,
+    is_variant: bool,
+) -> String {
+    if let Some(rename) = &serde_attrs.rename {
+        return rename.clone();
+    }
+    let name = name.trim_start_matches("r#");
+    match rename_all {
+        Some(rule) => apply_serde_rename_all(rule, name, is_variant).unwrap_or_else(|| {
+            warn!(rule = %rule, "Unknown #[serde(rename_all)] rule; ignoring it for WIT generation");
+            name.to_string()
+        }),
+        None => name.to_string(),
+    }
+}
+
+// The kebab-case WIT identifier for a JSON name, if it can be written as one
+fn wire_name_to_kebab(wire_name: &str) -> Option<String> {
+    let kebab = to_kebab_case(wire_name).to_lowercase();
+    kebab
+        .split('-')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then_some(kebab)
+}
+
+// Format the `// serde: <wire name>` comment (ending in a newline) for a field or case whose
+//  JSON name is not the default the clients derive from its WIT identifier: snake_case for
+//  fields, PascalCase for cases
+fn format_serde_wire_name_comment(wire_name: &str, kebab_name: &str, is_variant: bool) -> String {
+    let default_name = if is_variant {
+        to_pascal_case(kebab_name)
+    } else {
+        kebab_to_snake_case(kebab_name)
+    };
+    if default_name == wire_name {
+        return String::new();
+    }
+    warn!(
+        wire_name = %wire_name,
+        "      Warning: '{}' is (de)serialized by the process as '{}', which the TypeScript caller-utils follow, but the Rust caller-utils types (generated by wit-bindgen) use '{}'.",
+        kebab_name,
+        wire_name,
+        default_name
+    );
+    format!("// {} {}\n", SERDE_WIRE_NAME_COMMENT, wire_name)
+}
+
+// Helper trait to get TypePath from Type
+trait AsTypePath {
+    fn as_type_path(&self) -> Option<&syn::TypePath>;
+}
+
+impl AsTypePath for syn::Type {
+    fn as_type_path(&self) -> Option<&syn::TypePath> {
         match self {
             syn::Type::Path(tp) => Some(tp),
             _ => None,
@@ -770,6 +1667,7 @@ impl AsTypePath for syn::Type {
 fn collect_single_type_definition(
     file_path: &Path,
     target_type_kebab: &str, // The kebab-case type name we're looking for
+    wit_rename: bool,
 ) -> Result<Option<(String, HashSet<String>)>> {
     // Returns (WIT definition, dependencies)
     debug!(file_path = %file_path.display(), target_type = %target_type_kebab, "Looking for type in file");
@@ -780,9 +1678,28 @@ fn collect_single_type_definition(
     let ast = syn::parse_file(&content)
         .with_context(|| format!("Failed to parse file: {}", file_path.display()))?;
 
+    let (target_type_kebab, type_args) = split_generic_instantiation(target_type_kebab);
     let mut dependencies = HashSet::new();
+    Ok(find_type_definition_in_items(
+        &ast.items,
+        target_type_kebab,
+        &type_args,
+        &mut dependencies,
+        wit_rename,
+    )?
+    .map(|wit_def| (wit_def, dependencies)))
+}
 
-    for item in &ast.items {
+// Find the type among the items of a file, including within its inline modules,
+//  and generate its WIT definition (instantiated with `type_args` if it is generic)
+fn find_type_definition_in_items(
+    items: &[Item],
+    target_type_kebab: &str,
+    type_args: &[String],
+    dependencies: &mut HashSet<String>,
+    wit_rename: bool,
+) -> Result<Option<String>> {
+    for item in items {
         match item {
             Item::Struct(s) => {
                 let name = s.ident.to_string();
@@ -798,8 +1715,22 @@ fn collect_single_type_definition(
                 }
 
                 // Found the type! Generate its WIT definition
-                return generate_struct_wit_definition(s, &name, &kebab_name, &mut dependencies)
-                    .map(|wit_def| Some((wit_def, dependencies)));
+                let (wit_name, type_params) =
+                    instantiate_generics(&s.generics, &name, &kebab_name, type_args)?;
+                let mut type_dependencies = HashSet::new();
+                let wit_def = generate_struct_wit_definition(
+                    s,
+                    &name,
+                    &wit_name,
+                    &mut type_dependencies,
+                    wit_rename,
+                )?;
+                return Ok(Some(substitute_generics(
+                    &wit_def,
+                    &type_params,
+                    type_dependencies,
+                    dependencies,
+                )));
             }
             Item::Enum(e) => {
                 let name = e.ident.to_string();
@@ -814,8 +1745,22 @@ fn collect_single_type_definition(
                 }
 
                 // Found the type! Generate its WIT definition
-                return generate_enum_wit_definition(e, &name, &kebab_name, &mut dependencies)
-                    .map(|wit_def| Some((wit_def, dependencies)));
+                let (wit_name, type_params) =
+                    instantiate_generics(&e.generics, &name, &kebab_name, type_args)?;
+                let mut type_dependencies = HashSet::new();
+                let wit_def = generate_enum_wit_definition(
+                    e,
+                    &name,
+                    &wit_name,
+                    &mut type_dependencies,
+                    wit_rename,
+                )?;
+                return Ok(Some(substitute_generics(
+                    &wit_def,
+                    &type_params,
+                    type_dependencies,
+                    dependencies,
+                )));
             }
             Item::Type(t) => {
                 let alias_name = t.ident.to_string();
@@ -829,16 +1774,175 @@ fn collect_single_type_definition(
                     continue;
                 }
 
+                validate_name(&alias_name, "Type alias", wit_rename)?;
+                let (wit_name, type_params) =
+                    instantiate_generics(&t.generics, &alias_name, &kebab_name, type_args)?;
+
                 // Build alias: type <kebab> = <rhs>
-                let rhs = rust_type_to_wit(&t.ty, &mut dependencies)?;
-                let def = format!("type {} = {};", to_wit_ident(&kebab_name), rhs);
-                return Ok(Some((def, dependencies)));
+                let mut type_dependencies = HashSet::new();
+                let rhs = rust_type_to_wit(&t.ty, &mut type_dependencies, wit_rename)?;
+                let def = format!(
+                    "{}type {} = {};",
+                    format_wit_docs(&extract_docs(&t.attrs), ""),
+                    to_wit_ident(&wit_name),
+                    rhs
+                );
+                return Ok(Some(substitute_generics(
+                    &def,
+                    &type_params,
+                    type_dependencies,
+                    dependencies,
+                )));
+            }
+            Item::Use(u) if type_args.is_empty() => {
+                // `use path::Type as Alias;` renames a type: emit it as an alias
+                if let Some(def) =
+                    find_use_rename(&u.tree, target_type_kebab, dependencies, wit_rename)?
+                {
+                    return Ok(Some(def));
+                }
+            }
+            Item::Mod(m) => {
+                if let Some((_, ref mod_items)) = m.content {
+                    if let Some(def) = find_type_definition_in_items(
+                        mod_items,
+                        target_type_kebab,
+                        type_args,
+                        dependencies,
+                        wit_rename,
+                    )? {
+                        return Ok(Some(def));
+                    }
+                }
             }
             _ => {}
         }
     }
 
-    Ok(None) // Type not found in this file
+    Ok(None) // Type not found in these items
+}
+
+// Find `Type as Alias` for the target type in a use tree and generate a WIT alias for it
+fn find_use_rename(
+    tree: &syn::UseTree,
+    target_type_kebab: &str,
+    dependencies: &mut HashSet<String>,
+    wit_rename: bool,
+) -> Result<Option<String>> {
+    match tree {
+        syn::UseTree::Path(p) => {
+            find_use_rename(&p.tree, target_type_kebab, dependencies, wit_rename)
+        }
+        syn::UseTree::Group(g) => {
+            for tree in &g.items {
+                if let Some(def) =
+                    find_use_rename(tree, target_type_kebab, dependencies, wit_rename)?
+                {
+                    return Ok(Some(def));
+                }
+            }
+            Ok(None)
+        }
+        syn::UseTree::Rename(r) => {
+            let alias_name = r.rename.to_string();
+            let kebab_name = to_kebab_case(&alias_name);
+            if kebab_name != target_type_kebab || r.ident == alias_name {
+                return Ok(None);
+            }
+            validate_name(&alias_name, "Type alias", wit_rename)?;
+            let aliased: Type = syn::parse_str(&r.ident.to_string())?;
+            let rhs = rust_type_to_wit(&aliased, dependencies, wit_rename)?;
+            Ok(Some(format!(
+                "type {} = {};",
+                to_wit_ident(&kebab_name),
+                rhs
+            )))
+        }
+        syn::UseTree::Name(_) | syn::UseTree::Glob(_) => Ok(None),
+    }
+}
+
+// Generate the WIT fields (each preceded by its doc comments) for named Rust fields, named as
+//  they are in JSON (per their `#[serde(rename)]` or the container's `rename_all`, if any)
+//  and leaving out `#[serde(skip)]` fields
+fn generate_named_fields_wit(
+    fields: &syn::FieldsNamed,
+    rename_all: Option<&str>,
+    dependencies: &mut HashSet<String>,
+    wit_rename: bool,
+) -> Result<Vec<String>> {
+    let mut field_strings = Vec::new();
+    for f in &fields.named {
+        if let Some(field_ident) = &f.ident {
+            let serde_attrs = extract_serde_attrs(&f.attrs);
+            if serde_attrs.skip {
+                continue;
+            }
+            let field_orig_name = field_ident.to_string();
+            let wire_name = serde_wire_name(&field_orig_name, &serde_attrs, rename_all, false);
+
+            // Name the field after its JSON name where possible, so the clients' types match it
+            let (field_name, field_kebab_name) = match wire_name_to_kebab(&wire_name) {
+                Some(kebab_name) => (wire_name.clone(), kebab_name),
+                None => {
+                    let stripped_field_orig_name =
+                        check_and_strip_leading_underscore(field_orig_name.clone());
+                    let kebab_name = to_kebab_case(&stripped_field_orig_name);
+                    (stripped_field_orig_name, kebab_name)
+                }
+            };
+
+            if let Err(e) = validate_name(&field_name, "Field", wit_rename) {
+                // Return the validation error directly to preserve the helpful message
+                return Err(e);
+            }
+
+            let wit_type = rust_type_to_wit_with_maps(&f.ty, dependencies, wit_rename)?;
+            if has_128_bit_int(&wit_type) && !serde_attrs.custom_with {
+                warn!(
+                    "Field '{}' is a 128-bit integer, which is a string in WIT but a JSON number \
+                     when serialized by serde: callers using the generated types will fail to \
+                     (de)serialize it. Serialize it as a string, e.g. with \
+                     #[serde(with = \"...\")], or use it only as a handler argument or return type",
+                    field_orig_name
+                );
+            }
+            let wit_type = lower_maps(&wit_type);
+            field_strings.push(format!(
+                "{}{}{}: {}",
+                format_wit_docs(&extract_docs(&f.attrs), ""),
+                format_serde_wire_name_comment(&wire_name, &field_kebab_name, false),
+                to_wit_ident(&field_kebab_name),
+                wit_type
+            ));
+        }
+    }
+    Ok(field_strings)
+}
+
+// Generate a WIT record definition from its (already generated) fields
+fn format_record_wit_definition(docs: &str, kebab_name: &str, fields: &[String]) -> String {
+    if fields.is_empty() {
+        format!("{}record {} {{}}", docs, to_wit_ident(kebab_name))
+    } else {
+        // Fields may span several lines, preceded by their doc comments
+        let indented_fields = fields
+            .iter()
+            .map(|f| {
+                f.lines()
+                    .map(|line| format!("    {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+        format!(
+            "{}record {} {{\n{}\n}}",
+            docs,
+            to_wit_ident(kebab_name),
+            indented_fields
+        )
+    }
 }
 
 // Helper function to generate WIT definition for a struct
@@ -847,45 +1951,29 @@ fn generate_struct_wit_definition(
     name: &str,
     kebab_name: &str,
     dependencies: &mut HashSet<String>,
+    wit_rename: bool,
 ) -> Result<String> {
     // Validate name
-    if let Err(e) = validate_name(&name, "Struct") {
+    if let Err(e) = validate_name(&name, "Struct", wit_rename) {
         return Err(e);
     }
 
     // Generate WIT definition for this struct
     let fields_result: Result<Vec<String>> = match &s.fields {
-        syn::Fields::Named(fields) => {
-            let mut field_strings = Vec::new();
-            for f in &fields.named {
-                if let Some(field_ident) = &f.ident {
-                    let field_orig_name = field_ident.to_string();
-                    let stripped_field_orig_name =
-                        check_and_strip_leading_underscore(field_orig_name.clone());
-
-                    if let Err(e) = validate_name(&stripped_field_orig_name, "Field") {
-                        // Return the validation error directly to preserve the helpful message
-                        return Err(e);
-                    }
-
-                    let field_kebab_name = to_kebab_case(&stripped_field_orig_name);
-                    let wit_type = rust_type_to_wit(&f.ty, dependencies)?;
-                    field_strings.push(format!(
-                        "{}: {}",
-                        to_wit_ident(&field_kebab_name),
-                        wit_type
-                    ));
-                }
-            }
-            Ok(field_strings)
-        }
+        syn::Fields::Named(fields) => generate_named_fields_wit(
+            fields,
+            extract_serde_attrs(&s.attrs).rename_all.as_deref(),
+            dependencies,
+            wit_rename,
+        ),
         syn::Fields::Unnamed(fields) => {
             // Support 1-tuple (newtype) structs by emitting a WIT type alias.
             if fields.unnamed.len() == 1 {
                 let inner = &fields.unnamed[0];
-                let wit_type = rust_type_to_wit(&inner.ty, dependencies)?;
+                let wit_type = rust_type_to_wit(&inner.ty, dependencies, wit_rename)?;
                 return Ok(format!(
-                    "type {} = {};",
+                    "{}type {} = {};",
+                    format_wit_docs(&extract_docs(&s.attrs), ""),
                     to_wit_ident(&kebab_name),
                     wit_type
                 ));
@@ -905,21 +1993,8 @@ fn generate_struct_wit_definition(
     };
 
     let fields = fields_result?;
-
-    if fields.is_empty() {
-        Ok(format!("record {} {{}}", to_wit_ident(&kebab_name)))
-    } else {
-        let indented_fields = fields
-            .iter()
-            .map(|f| format!("    {}", f))
-            .collect::<Vec<_>>()
-            .join(",\n");
-        Ok(format!(
-            "record {} {{\n{}\n}}",
-            to_wit_ident(&kebab_name),
-            indented_fields
-        ))
-    }
+    let docs = format_wit_docs(&extract_docs(&s.attrs), "");
+    Ok(format_record_wit_definition(&docs, kebab_name, &fields))
 }
 
 // Helper function to generate WIT definition for an enum
@@ -928,45 +2003,91 @@ fn generate_enum_wit_definition(
     name: &str,
     kebab_name: &str,
     dependencies: &mut HashSet<String>,
+    wit_rename: bool,
 ) -> Result<String> {
     // Validate name
-    if let Err(e) = validate_name(&name, "Enum") {
+    if let Err(e) = validate_name(&name, "Enum", wit_rename) {
         return Err(e);
     }
 
+    let enum_serde_attrs = extract_serde_attrs(&e.attrs);
     let mut wit_fields = Vec::new();
+    // Records synthesized for the data of struct-like variants
+    let mut variant_records = Vec::new();
     let mut is_simple_enum = true;
 
     for v in &e.variants {
+        let serde_attrs = extract_serde_attrs(&v.attrs);
+        if serde_attrs.skip {
+            continue;
+        }
         let variant_orig_name = v.ident.to_string();
+        let wire_name = serde_wire_name(
+            &variant_orig_name,
+            &serde_attrs,
+            enum_serde_attrs.rename_all.as_deref(),
+            true,
+        );
+
+        // Name the case after its JSON name where possible, so the clients' types match it
+        let (variant_name, variant_kebab_name) = match wire_name_to_kebab(&wire_name) {
+            Some(kebab_name) => (wire_name.clone(), kebab_name),
+            None => (variant_orig_name.clone(), to_kebab_case(&variant_orig_name)),
+        };
 
-        if let Err(e) = validate_name(&variant_orig_name, "Variant") {
+        if let Err(e) = validate_name(&variant_name, "Variant", wit_rename) {
             return Err(e);
         }
 
-        let variant_kebab_name = to_kebab_case(&variant_orig_name);
+        let variant_docs = format!(
+            "{}{}",
+            format_wit_docs(&extract_docs(&v.attrs), ""),
+            format_serde_wire_name_comment(&wire_name, &variant_kebab_name, true)
+        );
 
         match &v.fields {
             syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
                 is_simple_enum = false;
                 let field = fields.unnamed.first().unwrap();
-                let wit_type = rust_type_to_wit(&field.ty, dependencies)?;
+                let wit_type = rust_type_to_wit(&field.ty, dependencies, wit_rename)?;
                 wit_fields.push(format!(
-                    "{}({})",
+                    "{}{}({})",
+                    variant_docs,
                     to_wit_ident(&variant_kebab_name),
                     wit_type
                 ));
             }
             syn::Fields::Unit => {
-                wit_fields.push(to_wit_ident(&variant_kebab_name));
+                wit_fields.push(format!(
+                    "{}{}",
+                    variant_docs,
+                    to_wit_ident(&variant_kebab_name)
+                ));
             }
-            syn::Fields::Named(_) => {
-                bail!(
-                    "Enum '{}' has variant '{}' with struct-like fields {{ ... }}, which is not supported in WIT. \
-                     WIT variants can only have unnamed single-value data or no data at all. \
-                     Consider refactoring to use a separate struct type or a single unnamed field.",
-                    name, variant_orig_name
-                );
+            syn::Fields::Named(fields) => {
+                // WIT variant cases can only carry a single type, so lower
+                //  `Variant { .. }` to `variant(<enum>-<variant>-data)` with a record of its fields
+                is_simple_enum = false;
+                let record_kebab_name = format!("{}-{}-data", kebab_name, variant_kebab_name);
+                // Fields of struct-like variants are renamed by `rename_all` on the variant or,
+                //  for all variants, by `rename_all_fields` on the enum
+                let rename_all = serde_attrs
+                    .rename_all
+                    .as_deref()
+                    .or(enum_serde_attrs.rename_all_fields.as_deref());
+                let record_fields =
+                    generate_named_fields_wit(fields, rename_all, dependencies, wit_rename)?;
+                variant_records.push(format_record_wit_definition(
+                    "",
+                    &record_kebab_name,
+                    &record_fields,
+                ));
+                wit_fields.push(format!(
+                    "{}{}({})",
+                    variant_docs,
+                    to_wit_ident(&variant_kebab_name),
+                    to_wit_ident(&record_kebab_name)
+                ));
             }
             syn::Fields::Unnamed(fields) => {
                 bail!(
@@ -980,17 +2101,35 @@ fn generate_enum_wit_definition(
     }
 
     let keyword = if is_simple_enum { "enum" } else { "variant" };
+    // The synthesized records are defined along with (before) the variant & its docs
+    let mut docs: String = variant_records
+        .iter()
+        .map(|record| format!("{}\n\n", record))
+        .collect();
+    docs.push_str(&format_wit_docs(&extract_docs(&e.attrs), ""));
 
     if wit_fields.is_empty() {
-        Ok(format!("{} {} {{}}", keyword, to_wit_ident(&kebab_name)))
+        Ok(format!(
+            "{}{} {} {{}}",
+            docs,
+            keyword,
+            to_wit_ident(&kebab_name)
+        ))
     } else {
+        // Cases may span several lines, preceded by their doc comments
         let indented_fields = wit_fields
             .iter()
-            .map(|f| format!("    {}", f))
+            .map(|f| {
+                f.lines()
+                    .map(|line| format!("    {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
             .collect::<Vec<_>>()
             .join(",\n");
         Ok(format!(
-            "{} {} {{\n{}\n}}",
+            "{}{} {} {{\n{}\n}}",
+            docs,
             keyword,
             to_wit_ident(&kebab_name),
             indented_fields
@@ -1026,7 +2165,7 @@ fn _collect_type_definitions_from_file(
                 }
 
                 // Validate name
-                if let Err(e) = validate_name(&name, "Struct") {
+                if let Err(e) = validate_name(&name, "Struct", false) {
                     return Err(e.wrap_err(format!("Invalid struct name '{}'", name)));
                 }
 
@@ -1042,7 +2181,9 @@ fn _collect_type_definitions_from_file(
                                 let stripped_field_orig_name =
                                     check_and_strip_leading_underscore(field_orig_name.clone());
 
-                                if let Err(e) = validate_name(&stripped_field_orig_name, "Field") {
+                                if let Err(e) =
+                                    validate_name(&stripped_field_orig_name, "Field", false)
+                                {
                                     return Err(e);
                                 }
 
@@ -1052,7 +2193,7 @@ fn _collect_type_definitions_from_file(
                                 }
 
                                 // Convert field type
-                                match rust_type_to_wit(&f.ty, &mut temp_used_types) {
+                                match rust_type_to_wit(&f.ty, &mut temp_used_types, false) {
                                     Ok(field_wit_type) => {
                                         let field_wit_ident = to_wit_ident(&field_kebab_name);
                                         field_strings.push(format!(
@@ -1109,7 +2250,7 @@ fn _collect_type_definitions_from_file(
                 }
 
                 // Validate name
-                if let Err(e) = validate_name(&name, "Enum") {
+                if let Err(e) = validate_name(&name, "Enum", false) {
                     return Err(e.wrap_err(format!("Invalid enum name '{}'", name)));
                 }
 
@@ -1118,7 +2259,7 @@ fn _collect_type_definitions_from_file(
 
                 for v in &e.variants {
                     let variant_orig_name = v.ident.to_string();
-                    if let Err(e) = validate_name(&variant_orig_name, "Enum variant") {
+                    if let Err(e) = validate_name(&variant_orig_name, "Enum variant", false) {
                         return Err(e);
                     }
 
@@ -1130,6 +2271,7 @@ fn _collect_type_definitions_from_file(
                             match rust_type_to_wit(
                                 &fields.unnamed.first().unwrap().ty,
                                 &mut temp_used_types,
+                                false,
                             ) {
                                 Ok(type_result) => {
                                     variants_wit.push(format!(
@@ -1186,10 +2328,152 @@ fn _collect_type_definitions_from_file(
     Ok(())
 }
 
+// With `[api] shared-types`, the type definitions of all generated interfaces, which are
+//  written to a single `types-<package>` interface that the others `use`
+#[derive(Debug, Default)]
+struct SharedTypes {
+    interface: String,
+    // (type name, definition), in the order they were collected
+    definitions: Vec<(String, String)>,
+    // The project each type was first collected from, to locate conflicting definitions
+    projects: HashMap<String, PathBuf>,
+}
+
+impl SharedTypes {
+    // Add a type definition, which must be the same as any other of the same name
+    fn insert(&mut self, project_path: &Path, type_name: &str, definition: String) -> Result<()> {
+        match self.definitions.iter().find(|(name, _)| name == type_name) {
+            Some((_, existing)) if *existing != definition => bail!(
+                "WIT Generation Error in project '{}': Type '{}' is defined differently than in project '{}', \
+                 so cannot be defined once in the shared types interface '{}' (`[api] shared-types` in kit.toml).\n\
+                 Suggestion: Define the type in a single crate that both processes depend on by path, \
+                 or rename one of the two types.",
+                project_path.display(),
+                type_name,
+                self.projects
+                    .get(type_name)
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default(),
+                self.interface
+            ),
+            Some(_) => {}
+            None => {
+                self.projects
+                    .insert(type_name.to_string(), project_path.to_path_buf());
+                self.definitions.push((type_name.to_string(), definition));
+            }
+        }
+        Ok(())
+    }
+}
+
+// The name of the shared types interface of a package, e.g. `types-my-app`
+fn shared_types_interface_name(package_name: &str) -> String {
+    format!("types-{}", package_name.replace('_', "-").to_lowercase())
+}
+
+// Indent type definitions by 4 spaces, for an interface, separating them by blank lines
+fn indent_wit_definitions(definitions: &[String]) -> String {
+    definitions
+        .iter()
+        .map(|def| {
+            def.lines()
+                .map(|line| {
+                    if line.is_empty() {
+                        line.to_string()
+                    } else {
+                        format!("    {}", line)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// With --wit-rename, rewrite the digits of an interface's identifiers as words, recording the
+//  renames at the top of the interface so caller-utils can use the original names
+fn rename_interface_digits(content: &str) -> Result<String> {
+    let mut renames = BTreeMap::new();
+    let content = rename_digit_idents(content, &mut renames)?;
+    if renames.is_empty() {
+        return Ok(content);
+    }
+    let rename_comments: String = renames
+        .iter()
+        .map(|(renamed, original)| {
+            format!("    // {} {} = {}\n", WIT_RENAME_COMMENT, renamed, original)
+        })
+        .collect();
+    Ok(content.replacen("{\n", &format!("{{\n{}\n", rename_comments), 1))
+}
+
+// Write the shared types interface to `api_dir`, returning its (possibly renamed) name
+fn write_shared_types_interface(
+    api_dir: &Path,
+    shared_types: &SharedTypes,
+    wit_rename: bool,
+) -> Result<String> {
+    let definitions: Vec<String> = shared_types
+        .definitions
+        .iter()
+        .map(|(_, def)| def.clone())
+        .collect();
+    let mut content = format!(
+        "interface {} {{\n    use standard.{{address}};\n\n{}\n}}\n",
+        to_wit_ident(&shared_types.interface),
+        indent_wit_definitions(&definitions)
+    );
+    let mut kebab_name = shared_types.interface.clone();
+    if wit_rename {
+        content = rename_interface_digits(&content)?;
+        if kebab_name.chars().any(|c| c.is_ascii_digit()) {
+            kebab_name = mangle_digits(&kebab_name);
+        }
+    }
+
+    let interface_file = api_dir.join(format!("{}.wit", kebab_name));
+    fs::write(&interface_file, content).with_context(|| {
+        format!(
+            "Failed to write WIT interface file: {}",
+            interface_file.display()
+        )
+    })?;
+    Ok(kebab_name)
+}
+
+// How to generate the WIT of a single Rust project: from `kit build --wit-rename`, the
+//  process's kit.toml config & `[api] shared-types`
+#[derive(Debug, Default)]
+struct ProjectOptions<'a> {
+    wit_rename: bool,
+    interface_name_override: Option<&'a str>,
+    handler_interfaces: BTreeMap<String, Vec<String>>,
+    ws_message: Option<&'a str>,
+    ws_push: Option<&'a str>,
+    type_mappings: BTreeMap<String, String>,
+    shared_types: Option<&'a mut SharedTypes>,
+}
+
 // Process a single Rust project and generate WIT files
 #[instrument(level = "trace", skip_all)]
-fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(String, String)>> {
+fn process_rust_project(
+    project_path: &Path,
+    api_dir: &Path,
+    options: ProjectOptions<'_>,
+) -> Result<Option<(Vec<String>, String)>> {
     debug!(project_path = %project_path.display(), "Processing project");
+    let ProjectOptions {
+        wit_rename,
+        interface_name_override,
+        handler_interfaces,
+        ws_message,
+        ws_push,
+        type_mappings,
+        mut shared_types,
+    } = options;
+    let (handler_interfaces, type_mappings) = (&handler_interfaces, &type_mappings);
 
     // --- 0. Setup & Find Project Files ---
     let lib_rs = project_path.join("src").join("lib.rs");
@@ -1197,11 +2481,13 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
         warn!(project_path = %project_path.display(), "No lib.rs found, skipping project");
         return Ok(None);
     }
-    let rust_files = find_rust_files(project_path);
+    let mut rust_files = find_rust_files(project_path);
     if rust_files.is_empty() {
         warn!(project_path=%project_path.display(), "No Rust files found in src/, skipping project");
         return Ok(None);
     }
+    // Types not defined in the project itself are looked for in its path dependencies
+    rust_files.extend(find_path_dependency_rust_files(project_path));
     let lib_content = fs::read_to_string(&lib_rs).with_context(|| {
         format!(
             "Failed to read lib.rs for project: {}",
@@ -1253,10 +2539,16 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
 
                 if let Some(ref name) = interface_name {
                     // Validate original name first
-                    match validate_name(name, "Interface") {
+                    match validate_name(name, "Interface", wit_rename) {
                         Ok(_) => {
                             let base_name = remove_state_suffix(name);
-                            kebab_interface_name = Some(to_kebab_case(&base_name));
+                            kebab_interface_name = match interface_name_override {
+                                Some(kebab_name) => {
+                                    validate_interface_name_override(kebab_name, wit_rename)?;
+                                    Some(kebab_name.to_string())
+                                }
+                                None => Some(to_kebab_case(&base_name)),
+                            };
                             debug!(interface_name = %name, base_name = %base_name, kebab_name = ?kebab_interface_name, "Interface details");
                             impl_item_with_hyperapp = Some(impl_item.clone());
                             break; // Found the target impl block
@@ -1290,10 +2582,49 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
     // These unwraps are safe due to the checks above ensuring we error or break successfully
     let kebab_name = kebab_interface_name.as_ref().unwrap();
     let current_wit_world = wit_world.as_ref().unwrap();
+    let interface_name = interface_name.as_ref().unwrap();
+
+    // Handlers listed in kit.toml `wit-interfaces` go into that interface; the rest stay in the
+    // default one
+    let mut handler_to_interface: HashMap<String, String> = HashMap::new();
+    for (split_name, handlers) in handler_interfaces {
+        validate_interface_name_override(split_name, wit_rename)?;
+        if split_name == kebab_name {
+            bail!(
+                "WIT interface '{}' in kit.toml `wit-interfaces` of project '{}' is the name of its default interface. \
+                 Suggestion: choose another name for the split interface.",
+                split_name,
+                project_path.display()
+            );
+        }
+        for handler in handlers {
+            let is_handler = impl_item.items.iter().any(
+                |item| matches!(item, ImplItem::Fn(method) if method.sig.ident == handler.as_str()),
+            );
+            if !is_handler {
+                bail!(
+                    "Handler '{}' listed under WIT interface '{}' in kit.toml `wit-interfaces` is not in the #[hyperapp] impl block of project '{}'",
+                    handler,
+                    split_name,
+                    project_path.display()
+                );
+            }
+            if let Some(previous) = handler_to_interface.insert(handler.clone(), split_name.clone())
+            {
+                bail!(
+                    "Handler '{}' is listed under both WIT interfaces '{}' and '{}' in kit.toml `wit-interfaces` of project '{}'",
+                    handler,
+                    previous,
+                    split_name,
+                    project_path.display()
+                );
+            }
+        }
+    }
 
     // --- PASS 2: Process signatures and collect directly used types ---
-    let mut signature_structs = Vec::new(); // Stores WIT string for each signature record
-    let mut global_used_types = HashSet::new(); // All custom WIT types encountered (kebab-case)
+    // Per interface: WIT string for each signature record & all custom WIT types encountered (kebab-case)
+    let mut interface_signatures: HashMap<String, (Vec<String>, HashSet<String>)> = HashMap::new();
 
     debug!("Pass 2: Analyzing functions in hyperapp impl block");
     for item in &impl_item.items {
@@ -1313,7 +2644,7 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
             {
                 debug!(remote=%has_remote, local=%has_local, http=%has_http, init=%has_init, ws=%has_ws, ws_client=%has_ws_client, "Method attributes found");
                 // Validate original Rust function name
-                validate_name(&method_name, "Function")?; // Error early if name invalid
+                validate_name(&method_name, "Function", wit_rename)?; // Error early if name invalid
                 let func_kebab_name = to_kebab_case(&method_name);
 
                 if has_init {
@@ -1321,8 +2652,8 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
                     continue;
                 }
 
-                if has_ws {
-                    debug!(method_name = %method_name, "Found [ws] function, skipping signature generation (websocket handlers are ignored by WIT generator)");
+                if has_ws && ws_message.is_none() {
+                    debug!(method_name = %method_name, "Found [ws] function, skipping signature generation (websocket handlers are ignored by WIT generator unless `ws-message` is set in kit.toml)");
                     continue;
                 }
 
@@ -1336,6 +2667,28 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
                     continue;
                 }
 
+                let (signature_structs, global_used_types) = interface_signatures
+                    .entry(
+                        handler_to_interface
+                            .get(&method_name)
+                            .unwrap_or(kebab_name)
+                            .clone(),
+                    )
+                    .or_default();
+
+                // WebSocket handlers are typed by the message type declared in kit.toml
+                if let (true, Some(ws_message)) = (has_ws, ws_message) {
+                    let sig_struct = generate_ws_signature_struct(
+                        &func_kebab_name,
+                        method,
+                        ws_message,
+                        global_used_types,
+                        wit_rename,
+                    )?;
+                    signature_structs.push(sig_struct);
+                    continue;
+                }
+
                 // Generate signature structs. `generate_signature_struct` calls `rust_type_to_wit`,
                 // which populates `global_used_types` with all custom types found in parameters/return types.
                 if has_remote {
@@ -1343,7 +2696,8 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
                         &func_kebab_name,
                         "remote",
                         method,
-                        &mut global_used_types,
+                        global_used_types,
+                        wit_rename,
                     )?;
                     signature_structs.push(sig_struct);
                 }
@@ -1352,7 +2706,8 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
                         &func_kebab_name,
                         "local",
                         method,
-                        &mut global_used_types,
+                        global_used_types,
+                        wit_rename,
                     )?;
                     signature_structs.push(sig_struct);
                 }
@@ -1361,7 +2716,8 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
                         &func_kebab_name,
                         "http",
                         method,
-                        &mut global_used_types,
+                        global_used_types,
+                        wit_rename,
                     )?;
                     signature_structs.push(sig_struct);
                 }
@@ -1374,7 +2730,61 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
             }
         }
     }
-    debug!(signature_count = %signature_structs.len(), initial_used_types = ?global_used_types, "Completed signature analysis");
+    // Events pushed to WebSocket clients are typed by the event type declared in kit.toml
+    if let Some(ws_push) = ws_push {
+        let (signature_structs, global_used_types) =
+            interface_signatures.entry(kebab_name.clone()).or_default();
+        signature_structs.push(generate_ws_push_signature_struct(
+            ws_push,
+            global_used_types,
+            wit_rename,
+        )?);
+    }
+    debug!(interfaces = ?interface_signatures.keys(), "Completed signature analysis");
+
+    // The default interface first, then the split ones
+    let mut interfaces = Vec::new();
+    for split_name in std::iter::once(kebab_name).chain(handler_interfaces.keys()) {
+        let (signature_structs, global_used_types) =
+            interface_signatures.remove(split_name).unwrap_or_default();
+        if let Some(interface) = generate_interface(
+            project_path,
+            &rust_files,
+            api_dir,
+            interface_name,
+            split_name,
+            signature_structs,
+            global_used_types,
+            type_mappings,
+            shared_types.as_deref_mut(),
+            wit_rename,
+        )? {
+            interfaces.push(interface);
+        }
+    }
+
+    // Return the world name even if no interface content is generated,
+    // so the world file can still be updated/created if necessary.
+    debug!(interfaces = ?interfaces, wit_world=%current_wit_world, "Returning import statement info");
+    Ok(Some((interfaces, current_wit_world.to_string())))
+}
+
+// Generate the WIT interface `kebab_name` of a project from its signatures and the types they use,
+// writing it to `api_dir`. Returns the (possibly renamed) interface name, or None if it would be empty
+#[instrument(level = "trace", skip_all)]
+fn generate_interface(
+    project_path: &Path,
+    rust_files: &[PathBuf],
+    api_dir: &Path,
+    interface_name: &str,
+    kebab_name: &str,
+    mut signature_structs: Vec<String>,
+    global_used_types: HashSet<String>,
+    type_mappings: &BTreeMap<String, String>,
+    shared_types: Option<&mut SharedTypes>,
+    wit_rename: bool,
+) -> Result<Option<String>> {
+    debug!(signature_count = %signature_structs.len(), initial_used_types = ?global_used_types, "Generating interface");
 
     // --- PASS 3: Collect type definitions ONLY for used types and their dependencies ---
     debug!("Pass 3: Collecting type definitions for used types");
@@ -1387,6 +2797,9 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
         .cloned()
         .collect::<HashSet<String>>();
     let mut collected_types = HashSet::new();
+    // The file each type is defined in (by monomorphized name), with its (non-generic) name,
+    //  to locate types in errors
+    let mut type_files: HashMap<String, (PathBuf, String)> = HashMap::new();
     // Track every custom type referenced directly or via dependencies
     let mut transitively_used_types: HashSet<String> = HashSet::new();
 
@@ -1404,12 +2817,18 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
 
             // Try to find and collect this type definition from the source files
             let mut found = false;
-            for file_path in &rust_files {
-                match collect_single_type_definition(file_path, &type_name) {
+            for file_path in rust_files {
+                match collect_single_type_definition(file_path, &type_name, wit_rename) {
                     Ok(Some((wit_def, dependencies))) => {
                         found = true;
-                        all_type_definitions.insert(type_name.clone(), wit_def);
+                        // Generic type instantiations are defined under their monomorphized names
+                        all_type_definitions.insert(monomorphized_name(&type_name), wit_def);
                         collected_types.insert(type_name.clone());
+                        let base_name = type_name.split('<').next().unwrap_or_default();
+                        type_files.insert(
+                            monomorphized_name(&type_name),
+                            (file_path.clone(), base_name.to_string()),
+                        );
 
                         // Add dependencies to be collected
                         for dep in dependencies {
@@ -1441,21 +2860,73 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
 
     debug!(collected_count = %all_type_definitions.len(), "Collected type definitions in Pass 3");
 
+    // From here on, refer to generic type instantiations by their monomorphized names
+    let global_used_types: HashSet<String> = global_used_types
+        .iter()
+        .map(|ty| monomorphized_name(ty))
+        .collect();
+
     // Merge direct and transitive used type sets for alias inference and verification
     let mut all_used_types: HashSet<String> = global_used_types.clone();
-    all_used_types.extend(transitively_used_types.into_iter());
+    all_used_types.extend(
+        transitively_used_types
+            .iter()
+            .map(|ty| monomorphized_name(ty)),
+    );
 
     // Minimal inference: only add alias for `value` when used.
-    let mut inferred_aliases: Vec<String> = Vec::new();
+    // (type name, definition) of each inferred alias
+    let mut inferred_aliases: Vec<(String, String)> = Vec::new();
     let mut inferred_types: HashSet<String> = HashSet::new();
     if all_used_types.contains("value") && !all_type_definitions.contains_key("value") {
-        inferred_aliases.push(
-            "// Arbitrary JSON value; encoded as string for WIT 1.0 (TS: unknown, Rust: serde_json::Value)"
-                .to_string(),
-        );
-        inferred_aliases.push(format!("type {} = string;", to_wit_ident("value")));
+        inferred_aliases.push((
+            "value".to_string(),
+            format!(
+                "// Arbitrary JSON value; encoded as string for WIT 1.0 (TS: unknown, Rust: serde_json::Value)\ntype {} = string;",
+                to_wit_ident("value")
+            ),
+        ));
         inferred_types.insert("value".to_string());
     }
+    // Types mapped in kit.toml, then common ecosystem types, are aliased when used but not
+    //  defined in the project
+    let mut mappings: Vec<(String, String, String)> = type_mappings
+        .iter()
+        .map(|(rust_type, wit_type)| {
+            (
+                rust_type.clone(),
+                wit_type.clone(),
+                format!("{} (mapped in kit.toml)", rust_type),
+            )
+        })
+        .collect();
+    for (rust_type, wit_type, description) in ECOSYSTEM_TYPE_MAPPINGS {
+        if !type_mappings.contains_key(rust_type) {
+            mappings.push((
+                rust_type.to_string(),
+                wit_type.to_string(),
+                description.to_string(),
+            ));
+        }
+    }
+    for (rust_type, wit_type, description) in mappings {
+        let kebab_type = to_kebab_case(&rust_type);
+        if all_used_types.contains(&kebab_type)
+            && !all_type_definitions.contains_key(&kebab_type)
+            && !inferred_types.contains(&kebab_type)
+        {
+            inferred_aliases.push((
+                kebab_type.clone(),
+                format!(
+                    "// {}\ntype {} = {};",
+                    description,
+                    to_wit_ident(&kebab_type),
+                    wit_type
+                ),
+            ));
+            inferred_types.insert(kebab_type);
+        }
+    }
 
     // --- 4. Build dependency graph and topologically sort types ---
     debug!("Pass 4: Building type dependency graph");
@@ -1482,12 +2953,13 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
             needed_types.insert(type_name.clone());
             let mut deps = Vec::new();
 
-            // Extract nested type dependencies from the WIT definition
-            // Look for other custom types referenced in this definition
+            // Extract nested type dependencies from the WIT definition:
+            // the custom types referenced in this definition, including itself if recursive
+            let referenced_types = referenced_wit_types(wit_def);
             let mut type_def_keys: Vec<_> = all_type_definitions.keys().collect();
             type_def_keys.sort();
             for other_type_name in type_def_keys {
-                if other_type_name != &type_name && wit_def.contains(other_type_name) {
+                if referenced_types.contains(other_type_name) {
                     deps.push(other_type_name.clone());
                     if !needed_types.contains(other_type_name)
                         && !to_process.contains(other_type_name)
@@ -1552,7 +3024,7 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
         }
     }
 
-    // Check for cycles
+    // Check for cycles, which WIT cannot express
     if sorted_types.len() != needed_types.len() {
         let mut missing: Vec<String> = needed_types
             .iter()
@@ -1561,13 +3033,31 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
             .collect();
         // Sort for deterministic output order
         missing.sort();
-        warn!(missing = ?missing, "Circular dependency detected in type definitions");
-        // Add remaining types anyway (WIT might still work)
-        for t in missing {
-            sorted_types.push(t);
-        }
-    }
-
+        let cycle = find_type_cycle(&missing, &type_dependencies);
+        let cycle_path: Vec<String> = cycle
+            .iter()
+            .map(|type_name| match type_files.get(type_name) {
+                Some((file_path, base_name)) => {
+                    match find_type_definition_line(file_path, base_name) {
+                        Some(line) => {
+                            format!("{} ({}:{})", type_name, file_path.display(), line)
+                        }
+                        None => format!("{} ({})", type_name, file_path.display()),
+                    }
+                }
+                None => type_name.clone(),
+            })
+            .collect();
+        bail!(
+            "WIT Generation Error in project '{}': Found a circular type dependency, which WIT cannot express:\n  {}\n\
+             Suggestion: WIT types cannot refer to themselves, even through a `Box`, `Vec` or `Option`, \
+             so break the cycle with indirection: e.g. refer to one of these types by an id (a `u64` or `string` key \
+             into a map or list held elsewhere) rather than containing it.",
+            project_path.display(),
+            cycle_path.join("\n  -> ")
+        );
+    }
+
     debug!(sorted_count = %sorted_types.len(), "Completed topological sort");
 
     // --- 5. Verify All Used Types Have Definitions ---
@@ -1589,8 +3079,9 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
         let project_display = project_path.display();
         bail!(
             "WIT Generation Error in project '{}': Found types used (directly or indirectly) in function signatures \
-             that are neither WIT built-ins nor defined locally within the scanned project files: {:?}. \
-             Ensure definitions for these types (structs/enums) are present in the project's source code \
+             that are neither WIT built-ins nor defined within the scanned project files or its path dependencies: {:?}. \
+             Ensure definitions for these types (structs/enums) are present in the source code of the project \
+             or of a crate it depends on by path (e.g., a shared types crate in the workspace) \
              (and not skipped due to errors/complexity), or adjust the function/type definitions.",
              project_display,
              undefined_types
@@ -1609,29 +3100,55 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
     // No need to sort again - already in topological order
     signature_structs.sort(); // Sort signature records for consistency
 
-    if signature_structs.is_empty() && relevant_defs.is_empty() {
-        // Use the original interface name if available, otherwise fallback
-        let name_for_warning = interface_name.as_deref().unwrap_or("<unknown>");
-        warn!(interface_name = %name_for_warning, "No attributed functions or used types requiring definitions found. No WIT interface file generated for this project.");
+    // With `[api] shared-types`, the types are defined once, in the shared types interface,
+    //  which this interface `use`s instead
+    let mut shared_types_use = None;
+    if let Some(shared_types) = shared_types {
+        let mut type_names = Vec::new();
+        let definitions = inferred_aliases
+            .drain(..)
+            .chain(sorted_types.iter().filter_map(|type_name| {
+                all_type_definitions
+                    .get(type_name)
+                    .map(|def| (type_name.clone(), def.clone()))
+            }));
+        for (type_name, definition) in definitions {
+            shared_types.insert(project_path, &type_name, definition)?;
+            type_names.push(to_wit_ident(&type_name));
+        }
+        if !type_names.is_empty() {
+            type_names.sort();
+            shared_types_use = Some(format!(
+                "use {}.{{{}}};",
+                to_wit_ident(&shared_types.interface),
+                type_names.join(", ")
+            ));
+        }
+        relevant_defs.clear();
+    }
 
-        // Return the world name even if no interface content is generated,
-        // so the world file can still be updated/created if necessary.
-        // But signal that no *interface* was generated by returning None for the interface name part.
-        return Ok(Some((String::new(), current_wit_world.to_string()))); // Return empty string for interface name
+    if signature_structs.is_empty() && relevant_defs.is_empty() && shared_types_use.is_none() {
+        warn!(interface_name = %interface_name, kebab_name = %kebab_name, "No attributed functions or used types requiring definitions found. No WIT interface file generated.");
+        Ok(None)
     } else {
         debug!(kebab_name=%kebab_name, "Generating final WIT content");
         let mut content = String::new();
 
         // Add standard imports (can be refined based on actual needs)
         content.push_str("    use standard.{address};\n"); // Assuming world includes 'standard'
+        if let Some(shared_types_use) = &shared_types_use {
+            content.push_str(&format!("    {}\n", shared_types_use));
+        }
 
         // Add inferred aliases (if any)
         if !inferred_aliases.is_empty() {
             content.push('\n');
-            for line in &inferred_aliases {
-                content.push_str("    ");
-                content.push_str(line);
-                content.push('\n');
+            for (_, definition) in &inferred_aliases {
+                for line in definition.lines() {
+                    content.push_str("    ");
+                    content.push_str(line);
+                    content.push('\n');
+                }
             }
         }
 
@@ -1639,23 +3156,7 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
         if !relevant_defs.is_empty() {
             content.push('\n'); // Separator
             debug!(count=%relevant_defs.len(), "Adding type definitions to interface");
-            // Indent each type definition by 4 spaces
-            let indented_defs: Vec<String> = relevant_defs
-                .iter()
-                .map(|def| {
-                    def.lines()
-                        .map(|line| {
-                            if line.is_empty() {
-                                line.to_string()
-                            } else {
-                                format!("    {}", line)
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                })
-                .collect();
-            content.push_str(&indented_defs.join("\n\n"));
+            content.push_str(&indent_wit_definitions(&relevant_defs));
             content.push('\n');
         }
 
@@ -1669,12 +3170,21 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
 
         // Wrap in interface block
         let interface_wit_ident = to_wit_ident(kebab_name);
-        let final_content = format!(
+        let mut final_content = format!(
             "interface {} {{\n{}\n}}\n",
             interface_wit_ident,
             content.trim()
         ); // Trim any trailing whitespace
-        debug!(interface_name = %interface_name.as_ref().unwrap(), signature_count = %signature_structs.len(), type_def_count = %relevant_defs.len(), "Generated interface content");
+
+        // With --wit-rename, rewrite the digits of identifiers as words
+        let mut kebab_name = kebab_name.to_string();
+        if wit_rename {
+            final_content = rename_interface_digits(&final_content)?;
+            if kebab_name.chars().any(|c| c.is_ascii_digit()) {
+                kebab_name = mangle_digits(&kebab_name);
+            }
+        }
+        debug!(interface_name = %interface_name, signature_count = %signature_structs.len(), type_def_count = %relevant_defs.len(), "Generated interface content");
 
         // Write the interface file
         let interface_file = api_dir.join(format!("{}.wit", kebab_name));
@@ -1688,25 +3198,31 @@ fn process_rust_project(project_path: &Path, api_dir: &Path) -> Result<Option<(S
         debug!("Successfully wrote WIT file");
 
         // If content was generated, return the kebab name for the import statement
-        debug!(interface = %kebab_name, wit_world=%current_wit_world, "Returning import statement info");
-        Ok(Some((
-            kebab_name.to_string(),
-            current_wit_world.to_string(),
-        )))
+        Ok(Some(kebab_name))
     }
 }
 
+// The `include` lines of a world for the worlds it includes
+fn format_include_lines(includes: &[String]) -> HashSet<String> {
+    includes
+        .iter()
+        .map(|include| format!("include {};", include.trim().trim_end_matches(';')))
+        .collect()
+}
+
 #[instrument(level = "trace", skip_all)]
 fn rewrite_wit(
     api_dir: &Path,
     new_imports: &Vec<String>,
     wit_worlds: &mut HashSet<String>,
+    world_includes: &HashMap<String, Vec<String>>,
     updated_world: &mut bool,
 ) -> Result<()> {
     debug!(api_dir = %api_dir.display(), "Rewriting WIT world files");
     // handle existing api files
     for entry in WalkDir::new(api_dir)
         .max_depth(1)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(Result::ok)
     {
@@ -1749,6 +3265,11 @@ fn rewrite_wit(
 
             debug!(world_name = %world_name, "Extracted world name");
 
+            // Includes configured in kit.toml replace those of the world
+            if let Some(includes) = world_includes.get(&world_name) {
+                include_lines = format_include_lines(includes);
+            }
+
             // Check if this world name matches the one we're looking for
             if wit_worlds.remove(&world_name) || wit_worlds.contains(&world_name[6..]) {
                 let world_content = generate_wit_file(
@@ -1776,8 +3297,12 @@ fn rewrite_wit(
     for wit_world in wit_worlds_sorted {
         for prefix in ["", "types-"] {
             let wit_world = format!("{prefix}{wit_world}");
+            let mut include_lines = world_includes
+                .get(&wit_world)
+                .map(|includes| format_include_lines(includes))
+                .unwrap_or_default();
             let world_content =
-                generate_wit_file(&wit_world, new_imports, &Vec::new(), &mut HashSet::new())?;
+                generate_wit_file(&wit_world, new_imports, &Vec::new(), &mut include_lines)?;
 
             let path = api_dir.join(format!("{wit_world}.wit"));
             debug!(path = %path.display(), wit_world = %wit_world, "Writing new world definition");
@@ -1800,13 +3325,29 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    const TEST_CARGO_TOML: &str = r#"
+[package]
+name = "test-project"
+version = "0.1.0"
+
+[package.metadata.component]
+package = "test:component"
+"#;
+
+    // Write a `test-project` crate with `lib_rs` as its `src/lib.rs`, & an empty `api/` dir
+    fn write_test_project(lib_rs: &str) -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(src_dir.join("lib.rs"), lib_rs).unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), TEST_CARGO_TOML).unwrap();
+        fs::create_dir_all(temp_dir.path().join("api")).unwrap();
+        temp_dir
+    }
+
     #[test]
     fn test_only_collects_used_types() -> Result<()> {
         // Create a temporary directory for the test
-        let temp_dir = TempDir::new()?;
-        let src_dir = temp_dir.path().join("src");
-        fs::create_dir_all(&src_dir)?;
-
         // Create a lib.rs with a handler that uses SimpleStruct but not UnusedStruct
         let lib_content = r#"
 use hyperware_macros::hyperapp;
@@ -1818,7 +3359,7 @@ pub struct SimpleStruct {
 
 // This struct has incompatible enum variant but shouldn't be processed
 pub enum UnusedEnum {
-    Variant1 { data: Vec<u8> },  // Struct-like variant - would fail if processed
+    Variant1(Vec<u8>, u32),  // Multi-field tuple variant - would fail if processed
     Variant2(String),
 }
 
@@ -1832,25 +3373,12 @@ impl ProcessState {
     }
 }
 "#;
-        fs::write(src_dir.join("lib.rs"), lib_content)?;
-
-        // Create a Cargo.toml
-        let cargo_content = r#"
-[package]
-name = "test-project"
-version = "0.1.0"
-
-[package.metadata.component]
-package = "test:component"
-"#;
-        fs::write(temp_dir.path().join("Cargo.toml"), cargo_content)?;
-
-        // Create the api directory
+        let temp_dir = write_test_project(lib_content);
+        let src_dir = temp_dir.path().join("src");
         let api_dir = temp_dir.path().join("api");
-        fs::create_dir_all(&api_dir)?;
 
         // Run the WIT generator
-        let result = process_rust_project(temp_dir.path(), &api_dir);
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
 
         // Debug: Check what files were created
         eprintln!("Test directory: {:?}", temp_dir.path());
@@ -1904,10 +3432,6 @@ package = "test:component"
 
     #[test]
     fn test_collects_recursive_dependencies() -> Result<()> {
-        let temp_dir = TempDir::new()?;
-        let src_dir = temp_dir.path().join("src");
-        fs::create_dir_all(&src_dir)?;
-
         // Create a lib.rs with nested type dependencies
         let lib_content = r#"
 use hyperware_macros::hyperapp;
@@ -1938,23 +3462,10 @@ impl ProcessState {
     }
 }
 "#;
-        fs::write(src_dir.join("lib.rs"), lib_content)?;
-
-        let cargo_content = r#"
-[package]
-name = "test-project"
-version = "0.1.0"
-
-[package.metadata.component]
-package = "test:component"
-"#;
-        fs::write(temp_dir.path().join("Cargo.toml"), cargo_content)?;
-
-        // Create the api directory
+        let temp_dir = write_test_project(lib_content);
         let api_dir = temp_dir.path().join("api");
-        fs::create_dir_all(&api_dir)?;
 
-        let result = process_rust_project(temp_dir.path(), &api_dir);
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
 
         assert!(
             result.is_ok(),
@@ -2006,16 +3517,12 @@ package = "test:component"
 
     #[test]
     fn test_fails_on_incompatible_used_type() -> Result<()> {
-        let temp_dir = TempDir::new()?;
-        let src_dir = temp_dir.path().join("src");
-        fs::create_dir_all(&src_dir)?;
-
         // Create a lib.rs with a handler that uses an incompatible enum
         let lib_content = r#"
 use hyperware_macros::hyperapp;
 
 pub enum BadEnum {
-    Variant { name: String, count: u32 },  // Struct-like variant - should fail
+    Variant(String, u32),  // Multi-field tuple variant - should fail
 }
 
 pub struct ProcessState;
@@ -2028,23 +3535,10 @@ impl ProcessState {
     }
 }
 "#;
-        fs::write(src_dir.join("lib.rs"), lib_content)?;
-
-        let cargo_content = r#"
-[package]
-name = "test-project"
-version = "0.1.0"
-
-[package.metadata.component]
-package = "test:component"
-"#;
-        fs::write(temp_dir.path().join("Cargo.toml"), cargo_content)?;
-
-        // Create the api directory
+        let temp_dir = write_test_project(lib_content);
         let api_dir = temp_dir.path().join("api");
-        fs::create_dir_all(&api_dir)?;
 
-        let result = process_rust_project(temp_dir.path(), &api_dir);
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
 
         // Should fail because BadEnum is used and has incompatible variant
         assert!(
@@ -2054,8 +3548,8 @@ package = "test:component"
 
         let error_msg = result.unwrap_err().to_string();
         assert!(
-            error_msg.contains("struct-like fields"),
-            "Error should mention struct-like fields"
+            error_msg.contains("2 unnamed fields"),
+            "Error should mention the unnamed fields"
         );
         assert!(
             error_msg.contains("BadEnum"),
@@ -2066,18 +3560,19 @@ package = "test:component"
     }
 
     #[test]
-    fn test_clear_error_message_for_illegal_field_names() -> Result<()> {
-        let temp_dir = TempDir::new()?;
-        let src_dir = temp_dir.path().join("src");
-        fs::create_dir_all(&src_dir)?;
-
-        // Create a lib.rs with a struct that has fields with numbers
+    fn test_lowers_struct_like_variants() -> Result<()> {
         let lib_content = r#"
 use hyperware_macros::hyperapp;
 
-pub struct TestStruct {
-    pub field1: String,  // This will trigger the error
-    pub data2: u32,      // This too
+pub enum Shape {
+    Circle { radius: f64 },
+    Rect { width: f64, height: f64 },
+    Point(Label),
+    Empty,
+}
+
+pub struct Label {
+    pub text: String,
 }
 
 pub struct ProcessState;
@@ -2085,74 +3580,53 @@ pub struct ProcessState;
 #[hyperapp(wit_world = "test-world")]
 impl ProcessState {
     #[remote]
-    pub fn handler(&self, input: TestStruct) -> Result<(), String> {
-        Ok(())
+    pub fn area(&self, shape: Shape) -> Result<f64, String> {
+        Ok(0.0)
     }
 }
 "#;
-        fs::write(src_dir.join("lib.rs"), lib_content)?;
-
-        let cargo_content = r#"
-[package]
-name = "test-project"
-version = "0.1.0"
-
-[package.metadata.component]
-package = "test:component"
-"#;
-        fs::write(temp_dir.path().join("Cargo.toml"), cargo_content)?;
-
-        // Create the api directory
+        let temp_dir = write_test_project(lib_content);
         let api_dir = temp_dir.path().join("api");
-        fs::create_dir_all(&api_dir)?;
 
-        let result = process_rust_project(temp_dir.path(), &api_dir);
-
-        // Should fail with our improved error message
-        assert!(
-            result.is_err(),
-            "Should fail when field names contain numbers"
-        );
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
 
-        let error_msg = result.unwrap_err().to_string();
+        let interface_content = fs::read_to_string(api_dir.join("process.wit"))?;
 
-        // Check that the error message contains our helpful information
-        assert!(
-            error_msg.contains("contains numbers, which are not allowed in WIT identifiers"),
-            "Error should explain that numbers are not allowed"
-        );
         assert!(
-            error_msg.contains("WIT (WebAssembly Interface Types) has strict naming rules"),
-            "Error should mention WIT naming rules"
+            interface_content.contains("record shape-circle-data {\n        radius: f64\n    }"),
+            "Struct-like variant should get a record of its fields, got:\n{}",
+            interface_content
         );
         assert!(
-            error_msg.contains("Suggestion: Rename"),
-            "Error should provide suggestions"
+            interface_content.contains(
+                "record shape-rect-data {\n        width: f64,\n        height: f64\n    }"
+            ),
+            "Struct-like variant should get a record of its fields, got:\n{}",
+            interface_content
         );
         assert!(
-            error_msg.contains("field1"),
-            "Error should mention the problematic field name"
+            interface_content.contains(
+                "variant shape {\n        circle(shape-circle-data),\n        rect(shape-rect-data),\n        point(label),\n        empty\n    }"
+            ),
+            "Struct-like variants should carry their records, got:\n{}",
+            interface_content
         );
         assert!(
-            error_msg.contains("Examples:"),
-            "Error should provide examples of how to fix"
+            interface_content.contains("record label {"),
+            "Types used by other variants should still be collected"
         );
 
         Ok(())
     }
 
     #[test]
-    fn test_clear_error_message_for_stream_keyword() -> Result<()> {
-        let temp_dir = TempDir::new()?;
-        let src_dir = temp_dir.path().join("src");
-        fs::create_dir_all(&src_dir)?;
-
-        // Create a lib.rs with a struct that has 'stream' in the name
+    fn test_lowers_maps_and_documents_them() -> Result<()> {
         let lib_content = r#"
+use std::collections::{BTreeMap, HashMap};
 use hyperware_macros::hyperapp;
 
-pub struct DataStream {  // This will trigger the error
-    pub data: String,
+pub struct Inventory {
+    pub counts: BTreeMap<String, u64>,
 }
 
 pub struct ProcessState;
@@ -2160,124 +3634,1846 @@ pub struct ProcessState;
 #[hyperapp(wit_world = "test-world")]
 impl ProcessState {
     #[remote]
-    pub fn handler(&self, input: DataStream) -> Result<(), String> {
-        Ok(())
+    pub fn restock(&self, deltas: HashMap<String, u64>) -> Result<HashMap<String, u64>, String> {
+        Ok(deltas)
     }
-}
-"#;
-        fs::write(src_dir.join("lib.rs"), lib_content)?;
-
-        let cargo_content = r#"
-[package]
-name = "test-project"
-version = "0.1.0"
 
-[package.metadata.component]
-package = "test:component"
+    #[remote]
+    pub fn inventory(&self) -> Inventory {
+        Inventory { counts: BTreeMap::new() }
+    }
+}
 "#;
-        fs::write(temp_dir.path().join("Cargo.toml"), cargo_content)?;
-
-        // Create the api directory
+        let temp_dir = write_test_project(lib_content);
         let api_dir = temp_dir.path().join("api");
-        fs::create_dir_all(&api_dir)?;
-
-        let result = process_rust_project(temp_dir.path(), &api_dir);
 
-        // Should fail with our improved error message
-        assert!(result.is_err(), "Should fail when name contains 'stream'");
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
 
-        let error_msg = result.unwrap_err().to_string();
+        let mut interface_content = String::new();
+        for entry in fs::read_dir(&api_dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if name.ends_with(".wit") && name != "test-world.wit" && name != "types-test-world.wit"
+            {
+                interface_content.push_str(&fs::read_to_string(&path)?);
+            }
+        }
 
-        // Check that the error message contains our helpful information
+        // WIT has no maps: they are lowered to lists of tuples...
         assert!(
-            error_msg.contains("contains 'stream', which is a reserved keyword in WIT"),
-            "Error should explain that 'stream' is reserved"
+            interface_content.contains("counts: list<tuple<string, u64>>"),
+            "Map field should be lowered to a list of tuples"
         );
         assert!(
-            error_msg.contains("'stream' is reserved for future WIT streaming functionality"),
-            "Error should explain why stream is reserved"
+            interface_content.contains("returning: result<list<tuple<string, u64>>, string>"),
+            "Map return type should be lowered to a list of tuples"
         );
         assert!(
-            error_msg.contains("Suggestion: Use an alternative term"),
-            "Error should provide alternatives"
+            !interface_content.contains("returning: result<map<"),
+            "Maps should not appear in WIT types"
         );
+        // ...but documented as maps so caller-utils can (de)serialize them as such
         assert!(
-            error_msg.contains("DataStream"),
-            "Error should mention the problematic type name"
+            interface_content.contains("// args: (deltas: map<string, u64>)"),
+            "Map arg should be documented as a map"
         );
         assert!(
-            error_msg.contains("flow"),
-            "Error should suggest 'flow' as an alternative"
+            interface_content.contains("// returns: result<map<string, u64>, string>"),
+            "Map return type should be documented as a map"
         );
 
         Ok(())
     }
+
+    #[test]
+    fn test_resolves_type_aliases() -> Result<()> {
+        // Aliases at the top level, within an inline module, and as a `use` rename
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+use types::Item as Entry;
+
+pub type Id = String;
+
+mod types {
+    pub type Quantity = u64;
+
+    pub struct Item {
+        pub id: super::Id,
+        pub quantity: Quantity,
+    }
 }
 
-fn generate_wit_file(
-    world_name: &str,
-    new_imports: &Vec<String>,
-    existing_imports: &Vec<String>,
-    include_lines: &mut HashSet<String>,
-) -> Result<String> {
-    // Determine the include line based on world name
-    // If world name starts with "types-", use "include lib;" instead
-    if world_name.starts_with("types-") {
-        if !include_lines.contains("include lib;") {
-            include_lines.insert("include lib;".to_string());
-        }
-    } else {
-        // Keep existing include or default to process-v1
-        if include_lines.is_empty() {
-            include_lines.insert("include process-v1;".to_string());
-        }
+pub struct ProcessState;
+
+#[hyperapp(wit_world = "test-world")]
+impl ProcessState {
+    #[remote]
+    pub fn get_entry(&self, id: Id) -> Result<Entry, String> {
+        Err(id)
     }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
 
-    // Combine existing imports with new imports
-    let mut all_imports = existing_imports.clone();
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
 
-    for import in new_imports {
-        let import_stmt = import.trim();
-        if !all_imports.iter().any(|i| i.trim() == import_stmt) {
-            all_imports.push(import_stmt.to_string());
+        let mut interface_content = String::new();
+        for entry in fs::read_dir(&api_dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if name.ends_with(".wit") && name != "test-world.wit" && name != "types-test-world.wit"
+            {
+                interface_content.push_str(&fs::read_to_string(&path)?);
+            }
         }
+
+        assert!(
+            interface_content.contains("type id = string;"),
+            "Top-level alias should be emitted"
+        );
+        assert!(
+            interface_content.contains("type quantity = u64;"),
+            "Alias within an inline module should be emitted"
+        );
+        assert!(
+            interface_content.contains("type entry = item;"),
+            "`use` rename should be emitted as an alias"
+        );
+        assert!(
+            interface_content.contains("record item {"),
+            "Renamed type should be defined"
+        );
+
+        Ok(())
     }
 
-    // Make sure all imports have proper indentation
-    let all_imports_with_indent: Vec<String> = all_imports
-        .iter()
-        .map(|import| {
-            if import.starts_with("    ") {
-                import.clone()
-            } else {
-                format!("    {}", import.trim())
-            }
-        })
-        .collect();
+    #[test]
+    fn test_monomorphizes_generic_types() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
 
-    let imports_section = all_imports_with_indent.join("\n");
+pub struct TodoItem {
+    pub title: String,
+}
 
-    // Create updated world content with proper indentation
-    let mut include_lines_sorted: Vec<_> = include_lines.iter().collect();
-    include_lines_sorted.sort();
-    let include_lines: String = include_lines_sorted
-        .iter()
-        .map(|l| format!("    {l}\n"))
-        .collect();
-    let world_content = format!("world {world_name} {{\n{imports_section}\n{include_lines}}}");
+pub struct Cursor<T> {
+    pub after: Option<T>,
+}
 
-    return Ok(world_content);
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub next: Option<Cursor<T>>,
+}
+
+pub enum ApiResponse<T> {
+    Found(T),
+    Missing,
+}
+
+pub type Listing<T> = Vec<T>;
+
+pub struct ProcessState;
+
+#[hyperapp(wit_world = "test-world")]
+impl ProcessState {
+    #[remote]
+    pub fn list_items(&self, cursor: Cursor<TodoItem>) -> Paginated<TodoItem> {
+        todo!()
+    }
+
+    #[remote]
+    pub fn get_item(&self, title: String) -> ApiResponse<TodoItem> {
+        todo!()
+    }
+
+    #[remote]
+    pub fn list_titles(&self) -> Listing<String> {
+        todo!()
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+
+        let interface_content = fs::read_to_string(api_dir.join("process.wit"))?;
+        assert!(
+            interface_content
+                .contains("record cursor-todo-item {\n        after: option<todo-item>\n    }"),
+            "Generic struct should be instantiated with its type argument"
+        );
+        assert!(
+            interface_content.contains(
+                "record paginated-todo-item {\n        items: list<todo-item>,\n        next: option<cursor-todo-item>\n    }"
+            ),
+            "Generic types used within a generic type should be instantiated with its type argument"
+        );
+        assert!(
+            interface_content.contains(
+                "variant api-response-todo-item {\n        found(todo-item),\n        missing\n    }"
+            ),
+            "Generic enum should be instantiated with its type argument"
+        );
+        assert!(
+            interface_content.contains("type listing-string = list<string>;"),
+            "Generic alias should be instantiated with its type argument"
+        );
+        assert!(
+            interface_content.contains("returning: paginated-todo-item"),
+            "Signatures should refer to the instantiation by its monomorphized name"
+        );
+        assert!(
+            !interface_content.contains("record paginated {"),
+            "Generic type should not be emitted uninstantiated"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolves_types_from_path_dependencies() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let workspace_dir = temp_dir.path();
+
+        fs::write(
+            workspace_dir.join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["process", "shared-types", "common"]
+
+[workspace.dependencies]
+common = { path = "common" }
+"#,
+        )?;
+
+        // A crate depended on directly by path...
+        let shared_types_src = workspace_dir.join("shared-types").join("src");
+        fs::create_dir_all(&shared_types_src)?;
+        fs::write(
+            shared_types_src.join("lib.rs"),
+            r#"
+pub struct Profile {
+    pub name: String,
+    pub visibility: common::Visibility,
+}
+"#,
+        )?;
+
+        // ...and one depended on via the workspace
+        let common_src = workspace_dir.join("common").join("src");
+        fs::create_dir_all(&common_src)?;
+        fs::write(
+            common_src.join("lib.rs"),
+            r#"
+pub enum Visibility {
+    Public,
+    Private,
+}
+"#,
+        )?;
+
+        let project_dir = workspace_dir.join("process");
+        let src_dir = project_dir.join("src");
+        fs::create_dir_all(&src_dir)?;
+        fs::write(
+            src_dir.join("lib.rs"),
+            r#"
+use hyperware_macros::hyperapp;
+use shared_types::Profile;
+
+pub struct ProcessState;
+
+#[hyperapp(wit_world = "test-world")]
+impl ProcessState {
+    #[remote]
+    pub fn get_profile(&self) -> Result<Profile, String> {
+        todo!()
+    }
+}
+"#,
+        )?;
+        fs::write(
+            project_dir.join("Cargo.toml"),
+            r#"
+[package]
+name = "process"
+version = "0.1.0"
+
+[dependencies]
+shared-types = { path = "../shared-types" }
+common = { workspace = true }
+
+[package.metadata.component]
+package = "test:component"
+"#,
+        )?;
+
+        let api_dir = workspace_dir.join("api");
+        fs::create_dir_all(&api_dir)?;
+
+        process_rust_project(&project_dir, &api_dir, ProjectOptions::default())?;
+
+        let interface_content = fs::read_to_string(api_dir.join("process.wit"))?;
+        assert!(
+            interface_content.contains("record profile {"),
+            "Type from a path dependency should be defined"
+        );
+        assert!(
+            interface_content.contains("enum visibility {"),
+            "Type from a workspace path dependency should be defined"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wit_rename_rewrites_digits() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct SecretV2 {
+    pub field1: String,
+    pub size: u64,
+}
+
+pub struct ProcessState;
+
+#[hyperapp(wit_world = "test-world")]
+impl ProcessState {
+    #[remote]
+    pub fn get_secret_v2(&self, key1: String) -> SecretV2 {
+        todo!()
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        // Without --wit-rename, digits are an error
+        assert!(
+            process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default()).is_err()
+        );
+
+        process_rust_project(
+            temp_dir.path(),
+            &api_dir,
+            ProjectOptions {
+                wit_rename: true,
+                ..Default::default()
+            },
+        )?;
+
+        let interface_content = fs::read_to_string(api_dir.join("process.wit"))?;
+        assert!(
+            interface_content.contains("record secret-v-two {")
+                && interface_content.contains("field-one: string")
+                && interface_content.contains("size: u64"),
+            "Digits in identifiers should be rewritten as words, leaving WIT types alone"
+        );
+        assert!(
+            interface_content.contains("// args: (key-one: string)")
+                && interface_content.contains("record get-secret-v-two-signature-remote {"),
+            "Signatures should use the renamed identifiers"
+        );
+        assert!(
+            interface_content.contains("// json fmt: {\"GetSecretV2\": key1}"),
+            "Other comments should be left alone"
+        );
+        for rename in [
+            "// wit-rename: field-one = field1",
+            "// wit-rename: key-one = key1",
+            "// wit-rename: secret-v-two = secret-v2",
+            "// wit-rename: get-secret-v-two-signature-remote = get-secret-v2-signature-remote",
+        ] {
+            assert!(
+                interface_content.contains(rename),
+                "Rename should be recorded: {rename}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_propagates_doc_comments() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+/// A thing to do.
+///
+/// Marked done once finished.
+pub struct TodoItem {
+    /// Unique id: assigned on creation
+    pub id: u64,
+    pub done: bool,
+}
+
+/// How urgent an item is
+pub enum Priority {
+    /// Whenever
+    Low,
+    High,
+}
+
+pub struct ProcessState;
+
+#[hyperapp(wit_world = "test-world")]
+impl ProcessState {
+    /// Get an item by its id
+    #[remote]
+    pub fn get_item(&self, id: u64, priority: Priority) -> Result<TodoItem, String> {
+        Err(String::new())
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+
+        let interface_content = fs::read_to_string(api_dir.join("process.wit"))?;
+
+        assert!(
+            interface_content.contains(
+                "    /// A thing to do.\n    ///\n    /// Marked done once finished.\n    record todo-item {\n        /// Unique id: assigned on creation\n        id: u64,\n        done: bool\n    }"
+            ),
+            "Struct & field docs should precede the record & its fields, got:\n{}",
+            interface_content
+        );
+        assert!(
+            interface_content.contains(
+                "    /// How urgent an item is\n    enum priority {\n        /// Whenever\n        low,\n        high\n    }"
+            ),
+            "Enum & case docs should precede the enum & its cases, got:\n{}",
+            interface_content
+        );
+        assert!(
+            interface_content.contains(
+                "    /// Get an item by its id\n    // Function signature for: get-item (remote)"
+            ),
+            "Handler docs should precede its signature, got:\n{}",
+            interface_content
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_overrides_interface_name() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct AppStateV;
+
+#[hyperapp(wit_world = "test-world")]
+impl AppStateV {
+    #[remote]
+    pub fn ping(&self) -> Result<String, String> {
+        Ok(String::new())
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        let result = process_rust_project(
+            temp_dir.path(),
+            &api_dir,
+            ProjectOptions {
+                interface_name_override: Some("My_Api"),
+                ..Default::default()
+            },
+        );
+        assert!(
+            result.is_err(),
+            "Interface name that is not kebab-case should be rejected"
+        );
+
+        let generated = process_rust_project(
+            temp_dir.path(),
+            &api_dir,
+            ProjectOptions {
+                interface_name_override: Some("my-api"),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            generated.map(|(interfaces, _)| interfaces),
+            Some(vec!["my-api".to_string()])
+        );
+        let interface_content = fs::read_to_string(api_dir.join("my-api.wit"))?;
+        assert!(
+            interface_content.starts_with("interface my-api {"),
+            "Interface should have the overridden name, got:\n{}",
+            interface_content
+        );
+        assert!(!api_dir.join("app-state-v.wit").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_splits_handlers_into_interfaces() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+pub struct Item {
+    pub id: u64,
+}
+
+pub struct Config {
+    pub limit: u32,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[remote]
+    pub fn get_item(&self, id: u64) -> Result<Item, String> {
+        Ok(Item { id })
+    }
+
+    #[local]
+    pub fn set_config(&mut self, config: Config) -> Result<(), String> {
+        Ok(())
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        let unknown_handler =
+            BTreeMap::from([("app-admin".to_string(), vec!["ban_user".to_string()])]);
+        let result = process_rust_project(
+            temp_dir.path(),
+            &api_dir,
+            ProjectOptions {
+                handler_interfaces: unknown_handler.clone(),
+                ..Default::default()
+            },
+        );
+        assert!(
+            result.is_err(),
+            "Handler not in the impl block should be rejected"
+        );
+
+        let handler_interfaces =
+            BTreeMap::from([("app-admin".to_string(), vec!["set_config".to_string()])]);
+        let generated = process_rust_project(
+            temp_dir.path(),
+            &api_dir,
+            ProjectOptions {
+                handler_interfaces: handler_interfaces.clone(),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            generated.map(|(interfaces, _)| interfaces),
+            Some(vec!["app".to_string(), "app-admin".to_string()])
+        );
+
+        let default_content = fs::read_to_string(api_dir.join("app.wit"))?;
+        assert!(default_content.contains("record get-item-signature-remote"));
+        assert!(default_content.contains("record item {"));
+        assert!(!default_content.contains("set-config-signature-local"));
+        assert!(!default_content.contains("record config {"));
+
+        let admin_content = fs::read_to_string(api_dir.join("app-admin.wit"))?;
+        assert!(
+            admin_content.starts_with("interface app-admin {"),
+            "Split interface should have its own name, got:\n{}",
+            admin_content
+        );
+        assert!(admin_content.contains("record set-config-signature-local"));
+        assert!(admin_content.contains("record config {"));
+        assert!(!admin_content.contains("get-item-signature-remote"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_types_ws_handlers_by_message() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct ChatState;
+
+pub enum ChatMessage {
+    Join(String),
+    Leave,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl ChatState {
+    #[remote]
+    pub fn ping(&self) -> Result<String, String> {
+        Ok(String::new())
+    }
+
+    /// Chat with other users
+    #[ws]
+    pub fn chat(&mut self, channel_id: u32, message_type: WsMessageType, blob: LazyLoadBlob) {}
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        // Without a message type, [ws] handlers are left out
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+        let interface_content = fs::read_to_string(api_dir.join("chat.wit"))?;
+        assert!(!interface_content.contains("chat-signature-ws"));
+        assert!(!interface_content.contains("chat-message"));
+
+        process_rust_project(
+            temp_dir.path(),
+            &api_dir,
+            ProjectOptions {
+                ws_message: Some("ChatMessage"),
+                ..Default::default()
+            },
+        )?;
+        let interface_content = fs::read_to_string(api_dir.join("chat.wit"))?;
+        assert!(
+            interface_content.contains(
+                "    /// Chat with other users\n    // Function signature for: chat (ws)\n    record chat-signature-ws {\n        message: chat-message\n    }"
+            ),
+            "[ws] handler should get a signature carrying its messages, got:\n{}",
+            interface_content
+        );
+        assert!(interface_content.contains("variant chat-message {"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_types_ws_push_events() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct ChatState;
+
+pub enum ChatEvent {
+    Joined(String),
+    Cleared,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl ChatState {
+    #[remote]
+    pub fn ping(&self) -> Result<String, String> {
+        Ok(String::new())
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(
+            temp_dir.path(),
+            &api_dir,
+            ProjectOptions {
+                ws_push: Some("ChatEvent"),
+                ..Default::default()
+            },
+        )?;
+        let interface_content = fs::read_to_string(api_dir.join("chat.wit"))?;
+        assert!(
+            interface_content.contains(
+                "    /// Events pushed to WebSocket clients\n    // Function signature for: push (ws-push)\n    record push-signature-ws-push {\n        event: chat-event\n    }"
+            ),
+            "pushed events should get a signature carrying them, got:\n{}",
+            interface_content
+        );
+        assert!(interface_content.contains("variant chat-event {"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lowers_fixed_size_arrays() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+pub struct Block {
+    pub hash: [u8; 32],
+    pub roots: [[u8; 32]; 2],
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[remote]
+    pub fn verify(&self, block: Block, signature: [u8; 64]) -> Result<[u64; 4], String> {
+        Ok([0; 4])
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let src_dir = temp_dir.path().join("src");
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+        let interface_content = fs::read_to_string(api_dir.join("app.wit"))?;
+        assert!(
+            interface_content.contains("hash: list<u8>,\n        roots: list<list<u8>>"),
+            "Array fields should be lowered to lists, got:\n{}",
+            interface_content
+        );
+        assert!(
+            interface_content.contains("// args: (block: block, signature: array<u8, 64>)"),
+            "Array lengths should be documented in the args comment, got:\n{}",
+            interface_content
+        );
+        assert!(interface_content.contains("// returns: result<array<u64, 4>, string>"));
+        assert!(interface_content.contains("arg-types: tuple<block, list<u8>>"));
+        assert!(interface_content.contains("returning: result<list<u64>, string>"));
+
+        fs::write(
+            src_dir.join("lib.rs"),
+            lib_content.replace("[u8; 64]", "[u8; SIGNATURE_LEN]"),
+        )?;
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
+        assert!(
+            result.is_err(),
+            "Array length that is not a literal should be rejected"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lowers_128_bit_integers() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+pub struct Transfer {
+    pub amount: u128,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[remote]
+    pub fn transfer(&mut self, transfer: Transfer, fee: u128) -> Result<i128, String> {
+        Ok(0)
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+        let interface_content = fs::read_to_string(api_dir.join("app.wit"))?;
+        assert!(
+            interface_content.contains("amount: string"),
+            "128-bit fields should be lowered to strings, got:\n{}",
+            interface_content
+        );
+        assert!(
+            interface_content.contains("// args: (transfer: transfer, fee: u128)"),
+            "128-bit args should be documented in the args comment, got:\n{}",
+            interface_content
+        );
+        assert!(interface_content.contains("// returns: result<s128, string>"));
+        assert!(interface_content.contains("arg-types: tuple<transfer, string>"));
+        assert!(interface_content.contains("returning: result<string, string>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lowers_borrowed_params() -> Result<()> {
+        let lib_content = r#"
+use std::borrow::Cow;
+
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[local]
+    pub fn store(&mut self, key: &str, data: &[u8], note: Cow<'_, str>) -> bool {
+        true
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+        let interface_content = fs::read_to_string(api_dir.join("app.wit"))?;
+        assert!(
+            interface_content.contains("arg-types: tuple<string, list<u8>, string>"),
+            "Borrowed params should be lowered to their owned WIT types, got:\n{}",
+            interface_content
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aliases_newtypes() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+/// A node's name
+pub struct NodeId(String);
+
+pub struct Peer {
+    pub id: NodeId,
+    pub scores: Vec<Score>,
+}
+
+pub struct Score(pub u64);
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[local]
+    pub fn add_peer(&mut self, peer: Peer) -> NodeId {
+        peer.id
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+        let interface_content = fs::read_to_string(api_dir.join("app.wit"))?;
+        assert!(
+            interface_content.contains("/// A node's name\n    type node-id = string;"),
+            "Newtypes should be aliases of their inner type, got:\n{}",
+            interface_content
+        );
+        assert!(interface_content.contains("type score = u64;"));
+        assert!(interface_content.contains("id: node-id,\n        scores: list<score>"));
+        assert!(interface_content.contains("returning: node-id"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sees_through_smart_pointers() -> Result<()> {
+        let lib_content = r#"
+use std::rc::Rc;
+use std::sync::Arc;
+
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+pub struct Config {
+    pub name: Arc<str>,
+    pub limits: Box<Limits>,
+}
+
+pub struct Limits {
+    pub max: Option<Box<u64>>,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[local]
+    pub fn configure(&mut self, config: Box<Config>, tags: Rc<Vec<String>>) -> Arc<Limits> {
+        Arc::new(*config.limits)
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+        let interface_content = fs::read_to_string(api_dir.join("app.wit"))?;
+        assert!(
+            interface_content.contains("name: string,\n        limits: limits"),
+            "Smart pointer fields should be their inner type, got:\n{}",
+            interface_content
+        );
+        assert!(interface_content.contains("max: option<u64>"));
+        assert!(interface_content.contains("arg-types: tuple<config, list<string>>"));
+        assert!(interface_content.contains("returning: limits"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aliases_ecosystem_and_mapped_types() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+pub struct Order {
+    pub id: uuid::Uuid,
+    pub placed_at: chrono::DateTime<chrono::Utc>,
+    pub total: Decimal,
+    pub store: ObjectId,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[remote]
+    pub fn get_order(&self, id: Uuid) -> Result<Order, String> {
+        Err(String::new())
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
+        assert!(result.is_err(), "Unmapped external type should be rejected");
+
+        let type_mappings = BTreeMap::from([
+            ("ObjectId".to_string(), "string".to_string()),
+            ("Decimal".to_string(), "f64".to_string()),
+        ]);
+        process_rust_project(
+            temp_dir.path(),
+            &api_dir,
+            ProjectOptions {
+                type_mappings: type_mappings.clone(),
+                ..Default::default()
+            },
+        )?;
+        let interface_content = fs::read_to_string(api_dir.join("app.wit"))?;
+        for alias in [
+            "type uuid = string;",
+            "type date-time = string;",
+            "type object-id = string;",
+            "type decimal = f64;",
+        ] {
+            assert!(
+                interface_content.contains(alias),
+                "Expected alias `{}`, got:\n{}",
+                alias,
+                interface_content
+            );
+        }
+        assert!(
+            interface_content.contains("placed-at: date-time,"),
+            "Type arguments of ecosystem types should be dropped, got:\n{}",
+            interface_content
+        );
+        assert!(!interface_content.contains("utc"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_respects_serde_attributes() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub display_name: String,
+    #[serde(rename = "user_handle")]
+    pub handle: String,
+    #[serde(skip)]
+    pub cache: Cache,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    InProgress,
+    #[serde(rename = "Finished")]
+    Done,
+    #[serde(skip)]
+    Internal,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[remote]
+    pub fn get_profile(&self, status: Status) -> Profile {
+        todo!()
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default())?;
+        let interface_content = fs::read_to_string(api_dir.join("app.wit"))?;
+        for expected in [
+            "// serde: displayName\n        display-name: string,",
+            "        user-handle: string\n",
+            "// serde: in_progress\n        in-progress,",
+            "        finished\n",
+        ] {
+            assert!(
+                interface_content.contains(expected),
+                "Expected `{}`, got:\n{}",
+                expected,
+                interface_content
+            );
+        }
+        assert!(
+            !interface_content.contains("cache") && !interface_content.contains("internal"),
+            "Skipped fields & variants should be left out, got:\n{}",
+            interface_content
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reports_type_cycles() -> Result<()> {
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+pub struct Item {
+    pub id: u64,
+}
+
+// `order` contains the name `item` in `order-item`, but does not depend on it
+pub struct Order {
+    pub order_item: OrderItem,
+}
+
+pub struct OrderItem {
+    pub parent: Option<Parent>,
+}
+
+pub struct Parent {
+    pub children: Vec<OrderItem>,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[remote]
+    pub fn get_item(&self) -> Result<Item, String> {
+        Ok(Item { id: 0 })
+    }
+
+    #[remote]
+    pub fn get_order(&self) -> Result<Order, String> {
+        Err(String::new())
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let src_dir = temp_dir.path().join("src");
+        let api_dir = temp_dir.path().join("api");
+
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
+        let error = format!("{:?}", result.expect_err("Type cycle should be rejected"));
+        let lib_rs = src_dir.join("lib.rs");
+        let expected_cycle = format!(
+            "order-item ({0}:15)\n  -> parent ({0}:19)\n  -> order-item ({0}:15)",
+            lib_rs.display()
+        );
+        assert!(
+            error.contains(&expected_cycle),
+            "Error should report the cycle with its locations, got:\n{}",
+            error
+        );
+        assert!(!error.contains("-> item"));
+
+        // Recursion of a type into itself is a cycle too
+        fs::write(
+            src_dir.join("lib.rs"),
+            lib_content.replace(
+                "pub parent: Option<Parent>,",
+                "pub children: Vec<OrderItem>,",
+            ),
+        )?;
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
+        let error = format!(
+            "{:?}",
+            result.expect_err("Recursive type should be rejected")
+        );
+        assert!(
+            error.contains(&format!(
+                "order-item ({0}:15)\n  -> order-item ({0}:15)",
+                lib_rs.display()
+            )),
+            "Error should report the recursive type, got:\n{}",
+            error
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_writes_versioned_api() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir_all(api_dir.join("versions").join("1.0.0"))?;
+        fs::write(
+            api_dir.join("versions").join("1.0.0").join("app.wit"),
+            "package my-app:api@1.0.0;\n\ninterface app {}\n",
+        )?;
+        fs::write(
+            api_dir.join("app.wit"),
+            "interface app {\n    use standard.{address};\n}\n",
+        )?;
+
+        let version_dir = write_versioned_api(&api_dir, "my_app", "1.2.3", &["app".to_string()])?;
+        assert_eq!(version_dir, api_dir.join("versions").join("1.2.3"));
+        assert_eq!(
+            fs::read_to_string(version_dir.join("app.wit"))?,
+            "package my-app:api@1.2.3;\n\ninterface app {\n    use standard.{address};\n}\n"
+        );
+        assert!(
+            api_dir
+                .join("versions")
+                .join("1.0.0")
+                .join("app.wit")
+                .exists(),
+            "Previous versions should be kept"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_shares_types_between_interfaces() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir_all(&api_dir)?;
+
+        let profile = "pub struct Profile {\n    pub name: String,\n}\n";
+        let write_project = |name: &str, state: &str, profile: &str| -> Result<PathBuf> {
+            let project_dir = temp_dir.path().join(name);
+            fs::create_dir_all(project_dir.join("src"))?;
+            fs::write(project_dir.join("Cargo.toml"), TEST_CARGO_TOML)?;
+            let lib_content = format!(
+                "{}\npub struct {};\n\n#[hyperapp(wit_world = \"test-world\")]\nimpl {} {{\n    #[remote]\n    pub fn get_profile(&self) -> Profile {{\n        todo!()\n    }}\n}}\n",
+                profile, state, state
+            );
+            fs::write(project_dir.join("src").join("lib.rs"), lib_content)?;
+            Ok(project_dir)
+        };
+        let chat_dir = write_project("chat", "ChatState", profile)?;
+        let feed_dir = write_project("feed", "FeedState", profile)?;
+
+        let mut shared_types = SharedTypes {
+            interface: shared_types_interface_name("my_app"),
+            ..Default::default()
+        };
+        for project_dir in [&chat_dir, &feed_dir] {
+            process_rust_project(
+                project_dir,
+                &api_dir,
+                ProjectOptions {
+                    shared_types: Some(&mut shared_types),
+                    ..Default::default()
+                },
+            )?;
+        }
+        for interface in ["chat", "feed"] {
+            let interface_content = fs::read_to_string(api_dir.join(format!("{}.wit", interface)))?;
+            assert!(
+                interface_content.contains("use types-my-app.{profile};")
+                    && !interface_content.contains("record profile"),
+                "Interfaces should use the shared types, got:\n{}",
+                interface_content
+            );
+        }
+        assert_eq!(
+            write_shared_types_interface(&api_dir, &shared_types, false)?,
+            "types-my-app"
+        );
+        let types_content = fs::read_to_string(api_dir.join("types-my-app.wit"))?;
+        assert_eq!(types_content.matches("record profile {").count(), 1);
+
+        // The same type defined differently by two processes cannot be shared
+        let other_dir = write_project(
+            "other",
+            "OtherState",
+            "pub struct Profile {\n    pub id: u64,\n}\n",
+        )?;
+        let result = process_rust_project(
+            &other_dir,
+            &api_dir,
+            ProjectOptions {
+                shared_types: Some(&mut shared_types),
+                ..Default::default()
+            },
+        );
+        assert!(
+            result.is_err()
+                && result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("defined differently"),
+            "Conflicting shared types should be rejected"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_world_includes_are_configurable() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir_all(&api_dir)?;
+        fs::write(
+            api_dir.join("old-world.wit"),
+            "world old-world {\n    import app;\n    include process-v1;\n}",
+        )?;
+
+        let world_includes = HashMap::from([
+            ("old-world".to_string(), vec!["my-runtime-v0".to_string()]),
+            (
+                "new-world".to_string(),
+                vec!["process-v1".to_string(), "my-runtime-v0".to_string()],
+            ),
+        ]);
+        let mut wit_worlds = HashSet::from(["old-world".to_string(), "new-world".to_string()]);
+        let mut updated_world = false;
+        rewrite_wit(
+            &api_dir,
+            &vec!["    import app;".to_string()],
+            &mut wit_worlds,
+            &world_includes,
+            &mut updated_world,
+        )?;
+
+        let old_world = fs::read_to_string(api_dir.join("old-world.wit"))?;
+        assert!(
+            old_world.contains("include my-runtime-v0;") && !old_world.contains("process-v1"),
+            "Configured includes should replace existing ones, got:\n{}",
+            old_world
+        );
+        let new_world = fs::read_to_string(api_dir.join("new-world.wit"))?;
+        assert!(
+            new_world.contains("include my-runtime-v0;")
+                && new_world.contains("include process-v1;"),
+            "New worlds should have the configured includes, got:\n{}",
+            new_world
+        );
+        let types_world = fs::read_to_string(api_dir.join("types-new-world.wit"))?;
+        assert!(
+            types_world.contains("include lib;") && !types_world.contains("my-runtime-v0"),
+            "types- worlds should still include lib, got:\n{}",
+            types_world
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parses_hyperapp_and_http_attributes() -> Result<()> {
+        let file = syn::parse_file(
+            r##"
+#[hyperapp(
+    name = "Test App",
+    ui = Some(HttpBindingConfig::default()),
+    endpoints = vec![Binding::Http { path: "/api", config: HttpBindingConfig::default() }],
+    wit_world = r#"test-world"#
+)]
+impl TestState {
+    #[http(method = "post", path = r"/items")]
+    async fn add_item(&mut self, item: String) -> bool { true }
+
+    #[http(method = POST)]
+    async fn broken(&mut self) -> bool { true }
+}
+"##,
+        )?;
+        let Some(Item::Impl(impl_item)) = file.items.first() else {
+            panic!("Expected an impl block");
+        };
+        assert_eq!(extract_wit_world(&impl_item.attrs)?, "test-world");
+
+        let methods: Vec<_> = impl_item
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ImplItem::Fn(method) => Some(method),
+                _ => None,
+            })
+            .collect();
+        let info = extract_http_info(&methods[0].attrs)?.expect("Expected #[http] info");
+        assert_eq!(info.method.as_deref(), Some("POST"));
+        assert_eq!(info.path.as_deref(), Some("/items"));
+
+        let error = extract_http_info(&methods[1].attrs)
+            .expect_err("Expected an unquoted method to fail")
+            .to_string();
+        assert!(
+            error.contains("line 12, column 21"),
+            "Error should locate the bad argument, got: {}",
+            error
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_reports_all_issues_with_locations() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let src_dir = temp_dir.path().join("my-process").join("src");
+        fs::create_dir_all(&src_dir)?;
+
+        let lib_content = r#"use hyperware_macros::hyperapp;
+
+pub struct AppState;
+
+pub struct Item {
+    pub field1: u64,
+    pub data_stream: Vec<Kind>,
+}
+
+pub enum Kind {
+    Level2,
+}
+
+#[hyperapp(wit_world = "test-world")]
+impl AppState {
+    #[remote]
+    pub fn get_item(&self, _id: u64) -> Item {
+        todo!()
+    }
+
+    #[local]
+    pub fn reset2(&mut self) {}
+}
+"#;
+        fs::write(src_dir.join("lib.rs"), lib_content)?;
+
+        let cargo_content = r#"
+[package]
+name = "my-process"
+version = "0.1.0"
+
+[package.metadata.component]
+package = "hyperware:process"
+"#;
+        fs::write(
+            temp_dir.path().join("my-process").join("Cargo.toml"),
+            cargo_content,
+        )?;
+
+        let issues = lint_wit(temp_dir.path(), &[], false)?;
+        let lib_rs = Path::new("my-process").join("src").join("lib.rs");
+        let expected = [
+            (17, 28, "Parameter name '_id' starts with an underscore"),
+            (22, 12, "Function name 'reset2' contains numbers"),
+            (
+                22,
+                12,
+                "Function 'reset2' must have an explicit return type",
+            ),
+            (6, 9, "Field name 'field1' contains numbers"),
+            (7, 9, "Field name 'data_stream' contains 'stream'"),
+            (11, 5, "Variant name 'Level2' contains numbers"),
+        ];
+        for (line, column, message) in expected {
+            let location = format!("{}:{}:{}: ", lib_rs.display(), line, column);
+            assert!(
+                issues
+                    .iter()
+                    .any(|issue| issue.starts_with(&location) && issue.contains(message)),
+                "Expected '{}{}' among issues: {:#?}",
+                location,
+                message,
+                issues
+            );
+        }
+        assert_eq!(issues.len(), expected.len(), "Got issues: {:#?}", issues);
+
+        assert_eq!(lint_wit(temp_dir.path(), &[], true)?.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_world_imports_are_sorted() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let api_dir = temp_dir.path().join("api");
+        fs::create_dir_all(&api_dir)?;
+        fs::write(
+            api_dir.join("my-world.wit"),
+            "world my-world {\n    import zeta;\n    include process-v1;\n}",
+        )?;
+
+        let new_imports = vec![
+            "    import beta;".to_string(),
+            "    import alpha;".to_string(),
+        ];
+        let mut wit_worlds = HashSet::from(["my-world".to_string()]);
+        let mut updated_world = false;
+        rewrite_wit(
+            &api_dir,
+            &new_imports,
+            &mut wit_worlds,
+            &HashMap::new(),
+            &mut updated_world,
+        )?;
+        let world = fs::read_to_string(api_dir.join("my-world.wit"))?;
+        assert!(
+            world.contains("    import alpha;\n    import beta;\n    import zeta;\n"),
+            "World imports should be sorted, got:\n{}",
+            world
+        );
+
+        // Regenerating with the same imports, in any order, changes nothing
+        let mut wit_worlds = HashSet::from(["my-world".to_string()]);
+        rewrite_wit(
+            &api_dir,
+            &new_imports.into_iter().rev().collect(),
+            &mut wit_worlds,
+            &HashMap::new(),
+            &mut updated_world,
+        )?;
+        assert_eq!(fs::read_to_string(api_dir.join("my-world.wit"))?, world);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clear_error_message_for_illegal_field_names() -> Result<()> {
+        // Create a lib.rs with a struct that has fields with numbers
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct TestStruct {
+    pub field1: String,  // This will trigger the error
+    pub data2: u32,      // This too
+}
+
+pub struct ProcessState;
+
+#[hyperapp(wit_world = "test-world")]
+impl ProcessState {
+    #[remote]
+    pub fn handler(&self, input: TestStruct) -> Result<(), String> {
+        Ok(())
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
+
+        // Should fail with our improved error message
+        assert!(
+            result.is_err(),
+            "Should fail when field names contain numbers"
+        );
+
+        let error_msg = result.unwrap_err().to_string();
+
+        // Check that the error message contains our helpful information
+        assert!(
+            error_msg.contains("contains numbers, which are not allowed in WIT identifiers"),
+            "Error should explain that numbers are not allowed"
+        );
+        assert!(
+            error_msg.contains("WIT (WebAssembly Interface Types) has strict naming rules"),
+            "Error should mention WIT naming rules"
+        );
+        assert!(
+            error_msg.contains("Suggestion: Rename"),
+            "Error should provide suggestions"
+        );
+        assert!(
+            error_msg.contains("field1"),
+            "Error should mention the problematic field name"
+        );
+        assert!(
+            error_msg.contains("Examples:"),
+            "Error should provide examples of how to fix"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clear_error_message_for_stream_keyword() -> Result<()> {
+        // Create a lib.rs with a struct that has 'stream' in the name
+        let lib_content = r#"
+use hyperware_macros::hyperapp;
+
+pub struct DataStream {  // This will trigger the error
+    pub data: String,
+}
+
+pub struct ProcessState;
+
+#[hyperapp(wit_world = "test-world")]
+impl ProcessState {
+    #[remote]
+    pub fn handler(&self, input: DataStream) -> Result<(), String> {
+        Ok(())
+    }
+}
+"#;
+        let temp_dir = write_test_project(lib_content);
+        let api_dir = temp_dir.path().join("api");
+
+        let result = process_rust_project(temp_dir.path(), &api_dir, ProjectOptions::default());
+
+        // Should fail with our improved error message
+        assert!(result.is_err(), "Should fail when name contains 'stream'");
+
+        let error_msg = result.unwrap_err().to_string();
+
+        // Check that the error message contains our helpful information
+        assert!(
+            error_msg.contains("contains 'stream', which is a reserved keyword in WIT"),
+            "Error should explain that 'stream' is reserved"
+        );
+        assert!(
+            error_msg.contains("'stream' is reserved for future WIT streaming functionality"),
+            "Error should explain why stream is reserved"
+        );
+        assert!(
+            error_msg.contains("Suggestion: Use an alternative term"),
+            "Error should provide alternatives"
+        );
+        assert!(
+            error_msg.contains("DataStream"),
+            "Error should mention the problematic type name"
+        );
+        assert!(
+            error_msg.contains("flow"),
+            "Error should suggest 'flow' as an alternative"
+        );
+
+        Ok(())
+    }
+}
+
+fn generate_wit_file(
+    world_name: &str,
+    new_imports: &Vec<String>,
+    existing_imports: &Vec<String>,
+    include_lines: &mut HashSet<String>,
+) -> Result<String> {
+    // Determine the include line based on world name
+    // If world name starts with "types-", use "include lib;" instead
+    if world_name.starts_with("types-") {
+        if !include_lines.contains("include lib;") {
+            include_lines.insert("include lib;".to_string());
+        }
+    } else {
+        // Keep existing include or default to process-v1
+        if include_lines.is_empty() {
+            include_lines.insert("include process-v1;".to_string());
+        }
+    }
+
+    // Combine existing imports with new imports, sorted so that the world does not depend on the
+    //  order in which processes were found
+    let mut all_imports = existing_imports.clone();
+
+    for import in new_imports {
+        let import_stmt = import.trim();
+        if !all_imports.iter().any(|i| i.trim() == import_stmt) {
+            all_imports.push(import_stmt.to_string());
+        }
+    }
+    all_imports.sort_by(|a, b| a.trim().cmp(b.trim()));
+
+    // Make sure all imports have proper indentation
+    let all_imports_with_indent: Vec<String> = all_imports
+        .iter()
+        .map(|import| {
+            if import.starts_with("    ") {
+                import.clone()
+            } else {
+                format!("    {}", import.trim())
+            }
+        })
+        .collect();
+
+    let imports_section = all_imports_with_indent.join("\n");
+
+    // Create updated world content with proper indentation
+    let mut include_lines_sorted: Vec<_> = include_lines.iter().collect();
+    include_lines_sorted.sort();
+    let include_lines: String = include_lines_sorted
+        .iter()
+        .map(|l| format!("    {l}\n"))
+        .collect();
+    let world_content = format!("world {world_name} {{\n{imports_section}\n{include_lines}}}");
+
+    return Ok(world_content);
+}
+
+// Find all relevant Rust projects, or, if `processes` are given, only those
+fn find_selected_rust_projects(base_dir: &Path, processes: &[String]) -> Result<Vec<PathBuf>> {
+    let mut projects = find_rust_projects(base_dir);
+    if !processes.is_empty() {
+        let project_name = |p: &PathBuf| {
+            p.file_name()
+                .and_then(|f| f.to_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        for process in processes {
+            if !projects.iter().any(|p| &project_name(p) == process) {
+                let available: Vec<String> = projects.iter().map(project_name).collect();
+                bail!(
+                    "Process '{}' not found among the Rust processes of {}; available processes: {:?}",
+                    process,
+                    base_dir.display(),
+                    available
+                );
+            }
+        }
+        projects.retain(|p| processes.contains(&project_name(p)));
+    }
+    Ok(projects)
+}
+
+// Collects the identifiers of the paths in a type (e.g. `Vec`, `Item` in `Vec<Item>`), i.e. the
+//  types it may reference
+#[derive(Default)]
+struct TypeIdentCollector {
+    idents: Vec<String>,
+}
+
+impl<'ast> syn::visit::Visit<'ast> for TypeIdentCollector {
+    fn visit_path_segment(&mut self, segment: &'ast syn::PathSegment) {
+        self.idents.push(segment.ident.to_string());
+        syn::visit::visit_path_segment(self, segment);
+    }
+}
+
+fn collect_type_idents(ty: &Type, idents: &mut Vec<String>) {
+    let mut collector = TypeIdentCollector::default();
+    syn::visit::Visit::visit_type(&mut collector, ty);
+    idents.extend(collector.idents);
+}
+
+// Lints the names in a project's source that WIT generation would fail on (or rewrite), at
+//  their locations, collecting all of them rather than stopping at the first
+struct ProjectLinter<'a> {
+    base_dir: &'a Path,
+    wit_rename: bool,
+    issues: Vec<String>,
+}
+
+impl ProjectLinter<'_> {
+    fn report(&mut self, file: &Path, span: proc_macro2::Span, message: &str) {
+        let start = span.start();
+        let file = file.strip_prefix(self.base_dir).unwrap_or(file);
+        self.issues.push(format!(
+            "{}:{}:{}: {}",
+            file.display(),
+            start.line,
+            start.column + 1,
+            message
+        ));
+    }
+
+    // Report the first line of `validate_name()`'s error, if any
+    fn lint_name(&mut self, file: &Path, ident: &Ident, kind: &str) {
+        if let Err(e) = validate_name(&ident.to_string(), kind, self.wit_rename) {
+            let message = e.to_string();
+            let message = message.lines().next().unwrap_or_default().to_string();
+            self.report(file, ident.span(), &message);
+        }
+    }
+
+    fn lint_underscore_prefix(&mut self, file: &Path, ident: &Ident, kind: &str) {
+        let name = ident.to_string();
+        if name.starts_with('_') {
+            self.report(
+                file,
+                ident.span(),
+                &format!(
+                    "{} name '{}' starts with an underscore, which is invalid in WIT and is stripped",
+                    kind, name
+                ),
+            );
+        }
+    }
+
+    // Lint the handlers of the #[hyperapp] impl in `lib.rs`, returning the idents of the types
+    //  that their signatures reference
+    fn lint_handlers(&mut self, lib_rs: &Path, ast: &syn::File) -> Vec<String> {
+        let mut type_idents = Vec::new();
+        for item in &ast.items {
+            let Item::Impl(impl_item) = item else {
+                continue;
+            };
+            let Some(attr) = impl_item.attrs.iter().find(|a| is_hyperapp_attr(a)) else {
+                continue;
+            };
+            if let Err(e) = extract_wit_world(std::slice::from_ref(attr)) {
+                let message = e.to_string();
+                let message = message.lines().next().unwrap_or_default().to_string();
+                self.report(lib_rs, attr.pound_token.span, &message);
+            }
+            for item in &impl_item.items {
+                let ImplItem::Fn(method) = item else {
+                    continue;
+                };
+                let is_handler = ["remote", "local", "http", "init", "ws", "ws_client", "eth"]
+                    .iter()
+                    .any(|attr| method.attrs.iter().any(|a| a.path().is_ident(attr)));
+                if !is_handler {
+                    continue;
+                }
+                self.lint_name(lib_rs, &method.sig.ident, "Function");
+                let has_signature = ["remote", "local", "http"]
+                    .iter()
+                    .any(|attr| method.attrs.iter().any(|a| a.path().is_ident(attr)));
+                if !has_signature {
+                    continue;
+                }
+                for input in &method.sig.inputs {
+                    let syn::FnArg::Typed(pat_type) = input else {
+                        continue;
+                    };
+                    if let syn::Pat::Ident(pat_ident) = &*pat_type.pat {
+                        self.lint_name(lib_rs, &pat_ident.ident, "Parameter");
+                        self.lint_underscore_prefix(lib_rs, &pat_ident.ident, "Parameter");
+                    }
+                    collect_type_idents(&pat_type.ty, &mut type_idents);
+                }
+                match &method.sig.output {
+                    syn::ReturnType::Default => self.report(
+                        lib_rs,
+                        method.sig.ident.span(),
+                        &format!(
+                            "Function '{}' must have an explicit return type to be exposed via WIT",
+                            method.sig.ident
+                        ),
+                    ),
+                    syn::ReturnType::Type(arrow, ty) => {
+                        if matches!(&**ty, Type::Tuple(t) if t.elems.is_empty()) {
+                            self.report(
+                                lib_rs,
+                                arrow.spans[0],
+                                &format!(
+                                    "Function '{}' returns '()', which is not supported in WIT signatures",
+                                    method.sig.ident
+                                ),
+                            );
+                        }
+                        collect_type_idents(ty, &mut type_idents);
+                    }
+                }
+            }
+        }
+        type_idents
+    }
+
+    // Lint the types (& their fields & variants) that the handlers reference, transitively
+    fn lint_types(&mut self, rust_files: &[PathBuf], mut type_idents: Vec<String>) {
+        let mut definitions: HashMap<String, (PathBuf, Item)> = HashMap::new();
+        for file in rust_files {
+            let Ok(content) = fs::read_to_string(file) else {
+                continue;
+            };
+            let Ok(ast) = syn::parse_file(&content) else {
+                continue;
+            };
+            for item in ast.items {
+                let name = match &item {
+                    Item::Struct(s) => s.ident.to_string(),
+                    Item::Enum(e) => e.ident.to_string(),
+                    _ => continue,
+                };
+                definitions.entry(name).or_insert((file.clone(), item));
+            }
+        }
+
+        let mut linted = HashSet::new();
+        while let Some(type_ident) = type_idents.pop() {
+            if !linted.insert(type_ident.clone()) {
+                continue;
+            }
+            let Some((file, item)) = definitions.get(&type_ident) else {
+                continue;
+            };
+            match item {
+                Item::Struct(s) => {
+                    self.lint_name(file, &s.ident, "Struct");
+                    for field in &s.fields {
+                        if let Some(ident) = &field.ident {
+                            self.lint_name(file, ident, "Field");
+                        }
+                        collect_type_idents(&field.ty, &mut type_idents);
+                    }
+                }
+                Item::Enum(e) => {
+                    self.lint_name(file, &e.ident, "Enum");
+                    for variant in &e.variants {
+                        self.lint_name(file, &variant.ident, "Variant");
+                        for field in &variant.fields {
+                            if let Some(ident) = &field.ident {
+                                self.lint_name(file, ident, "Field");
+                            }
+                            collect_type_idents(&field.ty, &mut type_idents);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Lint the hyperapp processes of a package (or only `processes`, if given) for the naming
+//  problems that WIT generation fails on: digits & "stream" in names, underscore-prefixed
+//  parameters & missing return types. Returns all issues found, as `<file>:<line>:<column>: <issue>`
+#[instrument(level = "trace", skip_all)]
+pub fn lint_wit(base_dir: &Path, processes: &[String], wit_rename: bool) -> Result<Vec<String>> {
+    let mut linter = ProjectLinter {
+        base_dir,
+        wit_rename,
+        issues: Vec::new(),
+    };
+    for project_path in find_selected_rust_projects(base_dir, processes)? {
+        let lib_rs = project_path.join("src").join("lib.rs");
+        let Ok(content) = fs::read_to_string(&lib_rs) else {
+            continue;
+        };
+        let ast = syn::parse_file(&content)
+            .map_err(|e| syn_error_report(e, &format!("Failed to parse {}", lib_rs.display())))?;
+        let type_idents = linter.lint_handlers(&lib_rs, &ast);
+
+        let mut rust_files = find_rust_files(&project_path);
+        rust_files.extend(find_path_dependency_rust_files(&project_path));
+        linter.lint_types(&rust_files, type_idents);
+    }
+    Ok(linter.issues)
 }
 
 // Generate WIT files from Rust code
 #[instrument(level = "trace", skip_all)]
-pub fn generate_wit_files(base_dir: &Path, api_dir: &Path) -> Result<(Vec<PathBuf>, Vec<String>)> {
+pub fn generate_wit_files(
+    base_dir: &Path,
+    api_dir: &Path,
+    wit_rename: bool,
+    processes: &[String],
+    process_configs: &HashMap<String, ProcessConfig>,
+    shared_types_package: Option<&str>,
+) -> Result<(Vec<PathBuf>, Vec<String>)> {
     // Keep INFO for start
     info!("Generating WIT files...");
     fs::create_dir_all(&api_dir)?;
 
-    // Find all relevant Rust projects
-    let projects = find_rust_projects(base_dir);
+    let projects = find_selected_rust_projects(base_dir, processes)?;
     let mut processed_projects = Vec::new();
 
     if projects.is_empty() {
@@ -2290,18 +5486,70 @@ pub fn generate_wit_files(base_dir: &Path, api_dir: &Path) -> Result<(Vec<PathBu
     let mut interfaces = Vec::new(); // Kebab-case interface names
 
     let mut wit_worlds = HashSet::new(); // Collect all unique world names encountered
+                                         // World name -> the worlds it includes, if configured in kit.toml
+    let mut world_includes: HashMap<String, Vec<String>> = HashMap::new();
+    // With `[api] shared-types`, the types of all projects are collected into one interface
+    let mut shared_types = shared_types_package.map(|package| SharedTypes {
+        interface: shared_types_interface_name(package),
+        ..Default::default()
+    });
     for project_path in &projects {
-        match process_rust_project(project_path, api_dir) {
-            // Project processed successfully, yielding an interface name and world name
-            Ok(Some((interface, wit_world))) => {
-                // Only add import if an interface name was actually generated
-                if !interface.is_empty() {
+        // Interface names may be overridden, and handlers split into further interfaces,
+        // per-process in kit.toml
+        let process_config = project_path
+            .file_name()
+            .and_then(|f| f.to_str())
+            .and_then(|process| process_configs.get(process));
+        let interface_name_override =
+            process_config.and_then(|config| config.wit_interface.as_deref());
+        let handler_interfaces = process_config
+            .map(|config| config.wit_interfaces.clone())
+            .unwrap_or_default();
+        let ws_message = process_config.and_then(|config| config.ws_message.as_deref());
+        let ws_push = process_config.and_then(|config| config.ws_push.as_deref());
+        let type_mappings = process_config
+            .map(|config| config.wit_type_mappings.clone())
+            .unwrap_or_default();
+        let options = ProjectOptions {
+            wit_rename,
+            interface_name_override,
+            handler_interfaces,
+            ws_message,
+            ws_push,
+            type_mappings,
+            shared_types: shared_types.as_mut(),
+        };
+        match process_rust_project(project_path, api_dir, options) {
+            // Project processed successfully, yielding its interface names and world name
+            Ok(Some((project_interfaces, wit_world))) => {
+                if project_interfaces.is_empty() {
+                    // Log if processing succeeded but generated no interface content
+                    debug!(project = %project_path.display(), world = %wit_world, "Project processed but generated no interface content (only types/no functions?)");
+                }
+                for interface in project_interfaces {
                     let import_wit_ident = to_wit_ident(&interface);
                     new_imports.push(format!("    import {};", import_wit_ident));
                     interfaces.push(interface); // Add to list of generated interfaces
-                } else {
-                    // Log if processing succeeded but generated no interface content
-                    debug!(project = %project_path.display(), world = %wit_world, "Project processed but generated no interface content (only types/no functions?)");
+                }
+                if let Some(includes) = process_config.and_then(|c| c.world_includes.as_ref()) {
+                    if includes.iter().any(|include| include.trim().is_empty()) {
+                        bail!(
+                            "Empty world name in `world-includes` of process {} in kit.toml",
+                            project_path.display()
+                        );
+                    }
+                    match world_includes.get(&wit_world) {
+                        Some(other_includes) if other_includes != includes => bail!(
+                            "Processes with world '{}' have different `world-includes` in kit.toml: {:?} and {:?}.\n\
+                             Suggestion: Give every process of a world the same `world-includes`.",
+                            wit_world,
+                            other_includes,
+                            includes
+                        ),
+                        _ => {
+                            world_includes.insert(wit_world.clone(), includes.clone());
+                        }
+                    }
                 }
                 // Always record the project path and the target world
                 processed_projects.push(project_path.clone());
@@ -2321,6 +5569,12 @@ pub fn generate_wit_files(base_dir: &Path, api_dir: &Path) -> Result<(Vec<PathBu
         }
     }
 
+    if let Some(shared_types) = shared_types.filter(|s| !s.definitions.is_empty()) {
+        let interface = write_shared_types_interface(api_dir, &shared_types, wit_rename)?;
+        new_imports.insert(0, format!("    import {};", to_wit_ident(&interface)));
+        interfaces.insert(0, interface);
+    }
+
     debug!(count = %new_imports.len(), "Collected number of new imports");
     if new_imports.is_empty() && wit_worlds.is_empty() {
         info!(
@@ -2343,6 +5597,7 @@ pub fn generate_wit_files(base_dir: &Path, api_dir: &Path) -> Result<(Vec<PathBu
         api_dir,
         &new_imports,
         &mut wit_worlds.clone(),
+        &world_includes,
         &mut updated_world,
     )?; // Pass a clone as rewrite_wit might modify it
 
@@ -2387,3 +5642,56 @@ pub fn generate_wit_files(base_dir: &Path, api_dir: &Path) -> Result<(Vec<PathBu
     info!("WIT file generation process completed.");
     Ok((processed_projects, interfaces)) // Return list of successfully processed projects and generated interfaces
 }
+
+// The package of the versioned WIT API of a package, e.g. `my-app:api@1.2.3`
+fn versioned_api_package(package_name: &str, version: &str) -> String {
+    format!(
+        "{}:api@{}",
+        package_name.replace('_', "-").to_lowercase(),
+        version
+    )
+}
+
+// Write the generated WIT `interfaces` of `api_dir`, under a versioned package header, to
+//  `api_dir/versions/<version>/`; the interfaces of other versions are left as they are
+#[instrument(level = "trace", skip_all)]
+pub fn write_versioned_api(
+    api_dir: &Path,
+    package_name: &str,
+    version: &str,
+    interfaces: &[String],
+) -> Result<PathBuf> {
+    let package = versioned_api_package(package_name, version);
+    let versions_dir = api_dir.join("versions");
+    let version_dir = versions_dir.join(version);
+    if version_dir.exists() {
+        fs::remove_dir_all(&version_dir)?;
+    }
+    fs::create_dir_all(&version_dir)?;
+
+    for interface in interfaces {
+        let file_name = format!("{}.wit", interface);
+        let content = fs::read_to_string(api_dir.join(&file_name)).with_context(|| {
+            format!("Failed to read generated WIT interface file: {}", file_name)
+        })?;
+        fs::write(
+            version_dir.join(&file_name),
+            format!("package {};\n\n{}", package, content),
+        )?;
+    }
+
+    let mut versions: Vec<String> = fs::read_dir(&versions_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .collect();
+    versions.sort();
+    info!(
+        "Wrote WIT API {} to {}; versions in {}: {:?}",
+        package,
+        version_dir.display(),
+        versions_dir.display(),
+        versions
+    );
+    Ok(version_dir)
+}
@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use color_eyre::{eyre::eyre, Result, Section};
+use fs_err as fs;
+use tracing::{debug, instrument};
+
+use hyperware_process_lib::PackageId;
+
+use super::{is_path_dependency, make_pkg_publisher, read_metadata, resolve_path_dependency};
+
+const SKIP_DIRS: &[&str] = &["target", "node_modules", ".git"];
+
+/// A package found under a workspace root
+#[derive(Debug, Clone)]
+pub struct WorkspacePackage {
+    pub dir: PathBuf,
+    /// `package:publisher`
+    pub package_id: String,
+    /// `package:publisher` of each dependency in `metadata.json`, including path dependencies
+    pub dependencies: Vec<String>,
+}
+
+/// Package dirs are those containing both `metadata.json` and `pkg/`;
+///  do not descend into package dirs (e.g. to find nested test packages)
+fn find_package_dirs(dir: &Path, package_dirs: &mut Vec<PathBuf>) -> Result<()> {
+    if dir.join("metadata.json").exists() && dir.join("pkg").is_dir() {
+        package_dirs.push(dir.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let Some(file_name) = path.file_name().and_then(|f| f.to_str()) else {
+            continue;
+        };
+        if file_name.starts_with('.') || SKIP_DIRS.contains(&file_name) {
+            continue;
+        }
+        find_package_dirs(&path, package_dirs)?;
+    }
+    Ok(())
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn discover_workspace_packages(root_dir: &Path) -> Result<Vec<WorkspacePackage>> {
+    let root_dir = fs::canonicalize(root_dir)?;
+    let mut package_dirs = vec![];
+    find_package_dirs(&root_dir, &mut package_dirs)?;
+    package_dirs.sort();
+
+    let mut packages = vec![];
+    for dir in package_dirs {
+        let metadata = read_metadata(&dir)?;
+        let dependencies = metadata
+            .properties
+            .dependencies
+            .clone()
+            .unwrap_or_default()
+            .iter()
+            .map(|dependency| {
+                if is_path_dependency(dependency) {
+                    let path = resolve_path_dependency(&dir, dependency)?;
+                    return Ok(make_pkg_publisher(&read_metadata(&path)?));
+                }
+                let dep = dependency.parse::<PackageId>().map_err(|_| {
+                    eyre!(
+                        "Dependencies must be PackageIds (e.g. `package:publisher.os`); given {dependency} in {dir:?}.",
+                    )
+                })?;
+                Ok(format!("{}:{}", dep.package(), dep.publisher()))
+            })
+            .collect::<Result<Vec<String>>>()?;
+        packages.push(WorkspacePackage {
+            package_id: make_pkg_publisher(&metadata),
+            dir,
+            dependencies,
+        });
+    }
+    if packages.is_empty() {
+        return Err(
+            eyre!("No packages found in workspace {root_dir:?}").with_suggestion(|| {
+                "Packages are dirs that contain both a `metadata.json` and a `pkg/` dir."
+            }),
+        );
+    }
+    debug!("workspace packages: {packages:?}");
+    Ok(packages)
+}
+
+/// Order the packages under `root_dir` so that each comes after the workspace packages it
+///  depends on; returns each package dir along with the dirs of all workspace packages it
+///  (transitively) depends on
+#[instrument(level = "trace", skip_all)]
+pub fn get_workspace_build_order(root_dir: &Path) -> Result<Vec<(PathBuf, Vec<PathBuf>)>> {
+    let mut packages: BTreeMap<String, WorkspacePackage> = BTreeMap::new();
+    for package in discover_workspace_packages(root_dir)? {
+        if let Some(other) = packages.get(&package.package_id) {
+            return Err(eyre!(
+                "Workspace packages {:?} and {:?} are both {}",
+                other.dir,
+                package.dir,
+                package.package_id,
+            ));
+        }
+        packages.insert(package.package_id.clone(), package);
+    }
+
+    let mut order: Vec<String> = vec![];
+    let mut transitive_deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut remaining: BTreeSet<String> = packages.keys().cloned().collect();
+    while !remaining.is_empty() {
+        let ready: Vec<String> = remaining
+            .iter()
+            .filter(|id| {
+                packages[*id]
+                    .dependencies
+                    .iter()
+                    .all(|dep| !remaining.contains(dep))
+            })
+            .cloned()
+            .collect();
+        if ready.is_empty() {
+            return Err(eyre!(
+                "Workspace packages have a dependency cycle among {:?}",
+                remaining,
+            ));
+        }
+        for id in ready {
+            let mut deps = BTreeSet::new();
+            for dep in &packages[&id].dependencies {
+                if packages.contains_key(dep) {
+                    deps.insert(dep.clone());
+                    deps.extend(transitive_deps[dep].iter().cloned());
+                }
+            }
+            transitive_deps.insert(id.clone(), deps);
+            remaining.remove(&id);
+            order.push(id);
+        }
+    }
+
+    Ok(order
+        .into_iter()
+        .map(|id| {
+            let deps = transitive_deps[&id]
+                .iter()
+                .map(|dep| packages[dep].dir.clone())
+                .collect();
+            (packages[&id].dir.clone(), deps)
+        })
+        .collect())
+}
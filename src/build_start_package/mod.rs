@@ -1,5 +1,4 @@
-use std::collections::HashSet;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use color_eyre::Result;
 use tracing::instrument;
@@ -7,47 +6,19 @@ use tracing::instrument;
 use crate::build;
 use crate::start_package;
 
+/// Build the package at `package_dir` with `options` & start it on the node at `url`
 #[instrument(level = "trace", skip_all)]
 pub async fn execute(
     package_dir: &Path,
-    no_ui: bool,
-    ui_only: bool,
-    include: &HashSet<PathBuf>,
-    exclude: &HashSet<PathBuf>,
     url: &str,
-    skip_deps_check: bool,
-    features: &str,
-    download_from: Option<&str>,
-    default_world: Option<&str>,
-    local_dependencies: Vec<PathBuf>,
-    add_paths_to_api: Vec<PathBuf>,
-    rewrite: bool,
-    hyperapp: bool,
-    reproducible: bool,
-    force: bool,
-    verbose: bool,
-    toolchain: &str,
+    options: build::BuildOptions<'_>,
 ) -> Result<()> {
     build::execute(
         package_dir,
-        no_ui,
-        ui_only,
-        include,
-        exclude,
-        skip_deps_check,
-        features,
-        Some(url.into()),
-        download_from,
-        default_world,
-        local_dependencies,
-        add_paths_to_api,
-        rewrite,
-        hyperapp,
-        reproducible,
-        force,
-        verbose,
-        false,
-        toolchain,
+        build::BuildOptions {
+            url: Some(url.into()),
+            ..options
+        },
     )
     .await?;
     start_package::execute(package_dir, url).await?;
@@ -0,0 +1,108 @@
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use reqwest::Client;
+use tracing::{info, instrument};
+
+use super::{check_chain_running, fakechain_url, rpc_request};
+
+const WEI_DECIMALS: usize = 18;
+
+// Parse an amount of ETH, e.g. `10` or `0.5`, to wei
+fn parse_eth(amount: &str) -> Result<u128> {
+    let invalid = || {
+        eyre!("Invalid amount of ETH {amount:?}")
+            .with_suggestion(|| "Give a decimal amount of ETH, e.g. `10` or `0.5`.")
+    };
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > WEI_DECIMALS
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let whole: u128 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let fraction: u128 = format!("{fraction:0<WEI_DECIMALS$}")
+        .parse()
+        .map_err(|_| invalid())?;
+    whole
+        .checked_mul(10u128.pow(WEI_DECIMALS as u32))
+        .and_then(|wei| wei.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+// Format an amount of wei as ETH, trimming trailing zeros
+fn format_eth(wei: u128) -> String {
+    let unit = 10u128.pow(WEI_DECIMALS as u32);
+    let fraction = format!("{:0>WEI_DECIMALS$}", wei % unit);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}", wei / unit)
+    } else {
+        format!("{}.{fraction}", wei / unit)
+    }
+}
+
+fn parse_quantity(quantity: &serde_json::Value) -> Result<u128> {
+    let hex = quantity
+        .as_str()
+        .ok_or_else(|| eyre!("unexpected quantity: {quantity}"))?;
+    Ok(u128::from_str_radix(hex.trim_start_matches("0x"), 16)?)
+}
+
+/// kit chain accounts: list the accounts of the chain on `port` with their balances
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_accounts(port: u16) -> Result<()> {
+    check_chain_running(port).await?;
+
+    let url = fakechain_url(port);
+    let client = Client::new();
+    let accounts = rpc_request(&url, &client, "eth_accounts", serde_json::json!([])).await?;
+    let accounts = accounts
+        .as_array()
+        .ok_or_else(|| eyre!("unexpected eth_accounts result: {accounts}"))?;
+    let mut lines = vec![];
+    for (i, account) in accounts.iter().enumerate() {
+        let balance = rpc_request(
+            &url,
+            &client,
+            "eth_getBalance",
+            serde_json::json!([account, "latest"]),
+        )
+        .await?;
+        lines.push(format!(
+            "({i}) {} ({} ETH)",
+            account.as_str().unwrap_or_default(),
+            format_eth(parse_quantity(&balance)?),
+        ));
+    }
+    info!("Accounts on port {port}:\n{}", lines.join("\n"));
+    Ok(())
+}
+
+/// kit chain fund: set the balance of `address` on the chain on `port` to `amount` ETH
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_fund(port: u16, address: &str, amount: &str) -> Result<()> {
+    check_chain_running(port).await?;
+    let wei = parse_eth(amount)?;
+
+    let url = fakechain_url(port);
+    let client = Client::new();
+    rpc_request(
+        &url,
+        &client,
+        "anvil_setBalance",
+        serde_json::json!([address, format!("0x{wei:x}")]),
+    )
+    .await?;
+    info!("Set the balance of {address} to {} ETH.", format_eth(wei));
+    Ok(())
+}
@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use alloy::dyn_abi::{FunctionExt, JsonAbiExt, Specifier};
+use alloy::json_abi::{Function, JsonAbi};
+use alloy::primitives::Address;
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use reqwest::Client;
+use tokio::time::Duration;
+use tracing::{info, instrument};
+
+use super::logs::{format_value, read_deployment_abi};
+use super::mint::hypermap_get;
+use super::signer::Signer;
+use super::state::read_deployments_on;
+use super::{
+    check_chain_running, fakechain_url, resolve_rpc_url, send_impersonated, wait_for_receipt,
+    DEFAULT_RECEIPT_TIMEOUT_SECS, OWNER_ADDRESS,
+};
+
+/// Resolve `target` (an address, a contract `kit chain deploy` deployed to the chain at
+///  `rpc_url` or else on `port`, or a Hypermap name, for its TBA on the chain at `url`) to an
+///  address & the name it was deployed as, if any
+#[instrument(level = "trace", skip_all)]
+pub async fn resolve_target(
+    port: u16,
+    rpc_url: Option<&str>,
+    url: &str,
+    client: &Client,
+    target: &str,
+) -> Result<(Address, Option<String>)> {
+    if target.starts_with("0x") {
+        let address =
+            Address::from_str(target).map_err(|e| eyre!("Invalid address {target}: {e}"))?;
+        return Ok((address, None));
+    }
+    if let Some(address) = read_deployments_on(port, rpc_url)?.get(target) {
+        return Ok((Address::from_str(address)?, Some(target.to_string())));
+    }
+    let (tba, _) = hypermap_get(url, client, target).await?;
+    if tba == Address::ZERO {
+        return Err(eyre!(
+            "{target} is neither an address, a deployed contract, nor a minted Hypermap name"
+        )
+        .with_suggestion(|| {
+            "Deploy it with `kit chain deploy`, or mint it with `kit chain mint`."
+        }));
+    }
+    Ok((tba, None))
+}
+
+/// Resolve `signature`: a full signature (`balanceOf(address) returns (uint256)`), or a function
+///  name looked up in `abi`
+pub fn resolve_function(signature: &str, abi: Option<&JsonAbi>) -> Result<Function> {
+    if signature.contains('(') {
+        return Function::parse(signature)
+            .map_err(|e| eyre!("Invalid function signature {signature:?}: {e}"));
+    }
+    let Some(abi) = abi else {
+        return Err(eyre!("Cannot find function {signature} without an ABI").with_suggestion(
+            || "Give a full signature, e.g. `balanceOf(address) returns (uint256)`, or the deployed contract & its `--project`.",
+        ));
+    };
+    match abi.function(signature).map(|f| f.as_slice()) {
+        Some([function]) => Ok(function.clone()),
+        Some(overloads) => Err(eyre!(
+            "Function {signature} is overloaded: {}",
+            overloads
+                .iter()
+                .map(|f| f.signature())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+        .with_suggestion(|| "Give the full signature of the overload.")),
+        None => Err(eyre!("The ABI has no function {signature}")),
+    }
+}
+
+/// Resolve & ABI-encode a call of `signature` on `target` with `args`, as a `kit-deploy.toml`
+///  gives them, without a chain: `target` is an address, a contract in `listed` (whose ABI is
+///  read from the foundry project at `project_dir`), or a Hypermap name
+pub fn encode_manifest_call(
+    project_dir: &Path,
+    listed: &BTreeMap<String, String>,
+    target: &str,
+    signature: &str,
+    args: &[String],
+) -> Result<(Function, Vec<u8>)> {
+    let abi = if target.starts_with("0x") {
+        Address::from_str(target).map_err(|e| eyre!("Invalid address {target}: {e}"))?;
+        None
+    } else if listed.contains_key(target) {
+        Some(read_deployment_abi(project_dir, target)?)
+    } else {
+        None
+    };
+    let function = resolve_function(signature, abi.as_ref())?;
+    let data = encode_call(&function, args)?;
+    Ok((function, data))
+}
+
+/// ABI-encode a call of `function` with `args`, as given on the command line
+pub fn encode_call(function: &Function, args: &[String]) -> Result<Vec<u8>> {
+    if function.inputs.len() != args.len() {
+        return Err(eyre!(
+            "{} takes {} argument(s), but {} given",
+            function.signature(),
+            function.inputs.len(),
+            args.len(),
+        ));
+    }
+    let values = function
+        .inputs
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| {
+            let ty = param.resolve()?;
+            ty.coerce_str(arg)
+                .map_err(|e| eyre!("Invalid {} argument {arg:?}: {e}", param.ty))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(function.abi_encode_input(&values)?)
+}
+
+/// Readable revert reason: an error of `abi`, `Error(string)` or `Panic(uint256)`, or else hex
+pub fn decode_revert(data: &[u8], abi: Option<&JsonAbi>) -> String {
+    if data.len() >= 4 {
+        for error in abi.iter().flat_map(|abi| abi.errors()) {
+            if data[..4] != error.selector()[..] {
+                continue;
+            }
+            if let Ok(values) = error.abi_decode_input(&data[4..], true) {
+                return format!(
+                    "{}({})",
+                    error.name,
+                    values
+                        .iter()
+                        .map(format_value)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                );
+            }
+        }
+    }
+    alloy::sol_types::decode_revert_reason(data)
+        .unwrap_or_else(|| format!("0x{}", hex::encode(data)))
+}
+
+/// eth_call `data` to `to`: Ok with its output, or Err with its revert data
+#[instrument(level = "trace", skip_all)]
+pub async fn eth_call(
+    url: &str,
+    client: &Client,
+    from: &str,
+    to: Address,
+    data: &[u8],
+) -> Result<std::result::Result<Vec<u8>, Vec<u8>>> {
+    let request_body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "eth_call",
+        "params": [
+            {"from": from, "to": to.to_string(), "data": format!("0x{}", hex::encode(data))},
+            "latest",
+        ],
+        "id": 1
+    });
+    let res: serde_json::Value = client
+        .post(url)
+        .json(&request_body)
+        .send()
+        .await?
+        .json()
+        .await?;
+    if let Some(result) = res["result"].as_str() {
+        return Ok(Ok(hex::decode(result.trim_start_matches("0x"))?));
+    }
+    if let Some(error) = res.get("error") {
+        // anvil gives revert data as a hex string
+        if let Some(revert) = error["data"].as_str() {
+            return Ok(Err(hex::decode(revert.trim_start_matches("0x"))?));
+        }
+        return Err(eyre!("eth_call failed: {error}"));
+    }
+    Err(eyre!("unexpected response: {res}"))
+}
+
+/// kit chain call: call `signature` on `target` with `args` on the chain on `port`, without
+///  sending a transaction, & print its decoded return values
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_call(
+    port: u16,
+    target: &str,
+    signature: &str,
+    args: &[String],
+    from: Option<&str>,
+    project_dir: Option<&Path>,
+) -> Result<()> {
+    check_chain_running(port).await?;
+    let url = fakechain_url(port);
+    let client = Client::new();
+    let (to, deployment) = resolve_target(port, None, &url, &client, target).await?;
+    let abi = match (project_dir, deployment) {
+        (Some(project_dir), Some(name)) => Some(read_deployment_abi(project_dir, &name)?),
+        _ => None,
+    };
+    let function = resolve_function(signature, abi.as_ref())?;
+    let data = encode_call(&function, args)?;
+
+    let output = eth_call(&url, &client, from.unwrap_or(OWNER_ADDRESS), to, &data)
+        .await?
+        .map_err(|revert| {
+            eyre!(
+                "{} reverted: {}",
+                function.signature(),
+                decode_revert(&revert, abi.as_ref())
+            )
+        })?;
+    if function.outputs.is_empty() {
+        info!("0x{}", hex::encode(&output));
+        return Ok(());
+    }
+    let values = function
+        .abi_decode_output(&output, true)
+        .map_err(|e| eyre!("Failed to decode output 0x{}: {e}", hex::encode(&output)))?;
+    info!(
+        "{}",
+        values
+            .iter()
+            .map(format_value)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    Ok(())
+}
+
+/// kit chain send: send a transaction calling `signature` on `target` with `args` on the chain
+///  on `port`, or at `rpc_url`, from the (impersonated) `from` or signed by `signer`, after
+///  checking it will not revert
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_send(
+    port: u16,
+    rpc_url: Option<&str>,
+    target: &str,
+    signature: &str,
+    args: &[String],
+    from: Option<&str>,
+    project_dir: Option<&Path>,
+    signer: Option<&Signer>,
+) -> Result<()> {
+    let url = resolve_rpc_url(port, rpc_url).await?;
+    let client = Client::new();
+    let (to, deployment) = resolve_target(port, rpc_url, &url, &client, target).await?;
+    let abi = match (project_dir, deployment) {
+        (Some(project_dir), Some(name)) => Some(read_deployment_abi(project_dir, &name)?),
+        _ => None,
+    };
+    let function = resolve_function(signature, abi.as_ref())?;
+    let data = encode_call(&function, args)?;
+    let from = match signer {
+        Some(signer) => {
+            signer.check_sender("The transaction", from)?;
+            signer.address.to_string()
+        }
+        None => from.unwrap_or(OWNER_ADDRESS).to_string(),
+    };
+
+    if let Err(revert) = eth_call(&url, &client, &from, to, &data).await? {
+        return Err(eyre!(
+            "{} would revert: {}",
+            function.signature(),
+            decode_revert(&revert, abi.as_ref())
+        ));
+    }
+
+    let tx_hash = match signer {
+        Some(signer) => signer.send(&url, &client, Some(to), data).await?,
+        None => {
+            send_impersonated(
+                &url,
+                &client,
+                &from,
+                serde_json::json!({
+                    "to": to.to_string(),
+                    "data": format!("0x{}", hex::encode(&data)),
+                }),
+            )
+            .await?
+        }
+    };
+
+    let receipt = wait_for_receipt(
+        &url,
+        &client,
+        &tx_hash,
+        Duration::from_secs(DEFAULT_RECEIPT_TIMEOUT_SECS),
+    )
+    .await?;
+    if receipt["status"].as_str() != Some("0x1") {
+        return Err(eyre!("Transaction {tx_hash} reverted"));
+    }
+    info!(
+        "Sent {} to {to}: {tx_hash} (gas used {})",
+        function.signature(),
+        u64::from_str_radix(
+            receipt["gasUsed"]
+                .as_str()
+                .unwrap_or("0x0")
+                .trim_start_matches("0x"),
+            16,
+        )?,
+    );
+    Ok(())
+}
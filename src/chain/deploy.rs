@@ -0,0 +1,582 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use fs_err as fs;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::time::Duration;
+use tracing::{info, instrument};
+
+use crate::build::run_command;
+
+use super::call::{encode_manifest_call, execute_send};
+use super::signer::Signer;
+use super::state::{read_deployments_on, write_deployments_on};
+use super::verify::check_assertion_config;
+use super::{resolve_rpc_url, send_impersonated, wait_for_receipt, OWNER_ADDRESS};
+
+/// Name of the manifest `kit chain deploy` reads from the foundry project dir
+pub const DEPLOY_MANIFEST_NAME: &str = "kit-deploy.toml";
+
+const PLACEHOLDER_ADDRESS: &str = "0x0000000000000000000000000000000000000001";
+
+/// Contracts to deploy, in order, read from `kit-deploy.toml`; `libraries` are deployed first &
+///  linked into the contracts (& libraries listed after them) that use them:
+///
+/// ```toml
+/// [[libraries]]
+/// name = "MathLib"
+///
+/// [[contracts]]
+/// name = "Token"
+/// args = ["Test Token", "TT"]
+///
+/// [[contracts]]
+/// name = "Vault"
+/// # only needed if more than one source file defines a `Vault`
+/// file = "Vault.sol"
+/// # `@<name>` is the address of a contract deployed before
+/// args = ["@Token"]
+/// # deploy from this (impersonated) address rather than anvil's first account
+/// from = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8"
+///
+/// # sent, in order, once the contracts are deployed
+/// [[transactions]]
+/// target = "Token"
+/// call = "transfer(address,uint256)"
+/// args = ["@Vault", "1000"]
+/// from = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8"
+///
+/// # checked by `kit chain verify`
+/// [[assertions]]
+/// target = "Token"
+/// call = "symbol() returns (string)"
+/// expect = "TT"
+///
+/// [[assertions]]
+/// target = "Vault"
+/// slot = "0x0"
+/// expect = "0x1"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeployManifest {
+    #[serde(default)]
+    pub libraries: Vec<ContractDeployment>,
+    #[serde(default)]
+    pub contracts: Vec<ContractDeployment>,
+    #[serde(default)]
+    pub transactions: Vec<ManifestTransaction>,
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractDeployment {
+    pub name: String,
+    /// Source file defining the contract, as named in `out/`
+    pub file: Option<String>,
+    /// Constructor arguments, as `cast abi-encode` takes them
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Address to deploy from, impersonated; else anvil's first account
+    pub from: Option<String>,
+}
+
+/// A transaction `kit chain deploy` sends once the contracts are deployed
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestTransaction {
+    /// Address, name of a deployed contract, or Hypermap name (for its TBA)
+    pub target: String,
+    /// Function to call, as a full signature or (for a deployed contract) a name
+    pub call: String,
+    /// Arguments of `call`; `@<name>` is the address of a deployed contract
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Address to send from, impersonated; else anvil's first account
+    pub from: Option<String>,
+}
+
+/// A check `kit chain verify` runs against the deployed contracts: either a `call`, whose return
+///  value(s) must equal `expect`, or a storage `slot`, whose contents must equal `expect`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Assertion {
+    /// Address, name of a deployed contract, or Hypermap name (for its TBA)
+    pub target: String,
+    /// Function to call, as a full signature or (given the target's ABI) a name
+    pub call: Option<String>,
+    /// Arguments of `call`
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Storage slot to read, in decimal or 0x-prefixed hex
+    pub slot: Option<String>,
+    /// Expected return value (as a tuple, e.g. `(1,true)`, for several) or slot contents
+    pub expect: String,
+}
+
+impl Assertion {
+    /// Short description of what is checked, e.g. `Token.symbol()` or `Vault slot 0x0`
+    pub fn describe(&self) -> String {
+        match (&self.call, &self.slot) {
+            (Some(call), _) => format!("{}.{call}", self.target),
+            (None, Some(slot)) => format!("{} slot {slot}", self.target),
+            (None, None) => self.target.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Artifact {
+    abi: Vec<AbiItem>,
+    bytecode: ArtifactBytecode,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactBytecode {
+    object: String,
+    /// Placeholders of libraries in `object`, by source file & library name
+    #[serde(default)]
+    link_references: BTreeMap<String, BTreeMap<String, Vec<LinkReference>>>,
+}
+
+/// Byte range of a `__$<hash>$__` library placeholder in bytecode
+#[derive(Debug, Deserialize)]
+struct LinkReference {
+    start: usize,
+    length: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbiItem {
+    #[serde(rename = "type")]
+    item_type: String,
+    #[serde(default)]
+    inputs: Vec<AbiParam>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbiParam {
+    #[serde(rename = "type")]
+    param_type: String,
+    #[serde(default)]
+    components: Vec<AbiParam>,
+}
+
+// Canonical type of an ABI param, expanding tuples (e.g. `(uint256,address)[]`)
+fn canonical_type(param: &AbiParam) -> String {
+    match param.param_type.strip_prefix("tuple") {
+        Some(suffix) => format!(
+            "({}){suffix}",
+            param
+                .components
+                .iter()
+                .map(canonical_type)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        None => param.param_type.clone(),
+    }
+}
+
+/// Find the forge artifact of `contract` in `out_dir`, i.e. `out/<file>/<name>.json`
+pub fn find_artifact(out_dir: &Path, contract: &ContractDeployment) -> Result<PathBuf> {
+    let artifact_name = format!("{}.json", contract.name);
+    if let Some(ref file) = contract.file {
+        let path = out_dir.join(file).join(&artifact_name);
+        if !path.exists() {
+            return Err(eyre!("No artifact {path:?} for contract {}", contract.name));
+        }
+        return Ok(path);
+    }
+    let mut paths = vec![];
+    for entry in fs::read_dir(out_dir)? {
+        let path = entry?.path().join(&artifact_name);
+        if path.exists() {
+            paths.push(path);
+        }
+    }
+    match paths.len() {
+        0 => Err(
+            eyre!("No artifact for contract {} in {out_dir:?}", contract.name)
+                .with_suggestion(|| "Check the contract name matches its Solidity name."),
+        ),
+        1 => Ok(paths.pop().unwrap()),
+        _ => Err(eyre!(
+            "Contract {} is defined in more than one file: {paths:?}",
+            contract.name
+        )
+        .with_suggestion(|| {
+            format!(
+                "Set `file` for {} in {DEPLOY_MANIFEST_NAME}.",
+                contract.name
+            )
+        })),
+    }
+}
+
+// Resolve the `@<name>` references among the `args` of `user` to the addresses in `deployments`
+fn resolve_references(
+    user: &str,
+    args: &[String],
+    deployments: &BTreeMap<String, String>,
+) -> Result<Vec<String>> {
+    args.iter()
+        .map(|arg| match arg.strip_prefix('@') {
+            Some(reference) => deployments.get(reference).cloned().ok_or_else(|| {
+                eyre!("{user} references {reference}, which is not deployed before it")
+                    .with_suggestion(|| {
+                        format!("Deploy {reference} before {user} in {DEPLOY_MANIFEST_NAME}.")
+                    })
+            }),
+            None => Ok(arg.clone()),
+        })
+        .collect()
+}
+
+// Fail unless `from` is an address
+fn check_sender(user: &str, from: &str) -> Result<()> {
+    let hex = from.strip_prefix("0x").unwrap_or_default();
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(eyre!(
+            "{user} is sent from {from:?}, which is not an address"
+        ));
+    }
+    Ok(())
+}
+
+// Bytecode of `contract` from its `artifact`, with the addresses of the libraries it uses, as
+//  deployed before it in `libraries`, substituted for their placeholders
+fn link_bytecode(
+    artifact: &Artifact,
+    contract: &ContractDeployment,
+    libraries: &BTreeMap<String, String>,
+) -> Result<String> {
+    let mut bytecode = artifact
+        .bytecode
+        .object
+        .trim_start_matches("0x")
+        .to_string();
+    for (file, linked) in &artifact.bytecode.link_references {
+        for (library, references) in linked {
+            let address = libraries.get(library).ok_or_else(|| {
+                eyre!(
+                    "{} uses library {library} ({file}), which is not deployed before it",
+                    contract.name
+                )
+                .with_suggestion(|| {
+                    format!("List {library} under `libraries` in {DEPLOY_MANIFEST_NAME}.")
+                })
+            })?;
+            let address = address.trim_start_matches("0x").to_lowercase();
+            for reference in references {
+                let start = reference.start * 2;
+                let end = start + reference.length * 2;
+                if end > bytecode.len() || address.len() != reference.length * 2 {
+                    return Err(eyre!(
+                        "Invalid link reference to {library} in the artifact of {}",
+                        contract.name
+                    ));
+                }
+                bytecode.replace_range(start..end, &address);
+            }
+        }
+    }
+    if bytecode.contains("__$") {
+        return Err(eyre!(
+            "Contract {} has library placeholders its artifact has no link references for",
+            contract.name
+        )
+        .with_suggestion(|| "Rebuild the project with `forge build`."));
+    }
+    Ok(bytecode)
+}
+
+// Bytecode deploying `contract` from its `artifact`, linked against `libraries` & with its
+//  constructor args, referencing `deployments`, appended
+fn make_deploy_data(
+    artifact: &Artifact,
+    contract: &ContractDeployment,
+    libraries: &BTreeMap<String, String>,
+    deployments: &BTreeMap<String, String>,
+) -> Result<String> {
+    if artifact.bytecode.object.trim_start_matches("0x").is_empty() {
+        return Err(eyre!(
+            "Contract {} has no bytecode: is it abstract or an interface?",
+            contract.name
+        ));
+    }
+    let bytecode = link_bytecode(artifact, contract, libraries)?;
+    let inputs = artifact
+        .abi
+        .iter()
+        .find(|item| item.item_type == "constructor")
+        .map(|item| item.inputs.as_slice())
+        .unwrap_or_default();
+    if inputs.len() != contract.args.len() {
+        return Err(eyre!(
+            "Contract {}'s constructor takes {} argument(s), but {} given",
+            contract.name,
+            inputs.len(),
+            contract.args.len(),
+        ));
+    }
+    if inputs.is_empty() {
+        return Ok(format!("0x{bytecode}"));
+    }
+
+    let args = resolve_references(&contract.name, &contract.args, deployments)?;
+    let signature = format!(
+        "constructor({})",
+        inputs
+            .iter()
+            .map(canonical_type)
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    let Some((encoded, _)) = run_command(
+        Command::new("cast")
+            .args(["abi-encode", &signature])
+            .args(&args),
+        false,
+    )?
+    else {
+        return Err(eyre!("`cast abi-encode` gave no output"));
+    };
+    Ok(format!(
+        "0x{bytecode}{}",
+        encoded.trim().trim_start_matches("0x")
+    ))
+}
+
+// Deploy `data` signed by `signer`, or else from the (impersonated) `from`, returning the address
+//  of the deployed contract once its deployment is mined, or failing if it is not within
+//  `receipt_timeout`
+#[instrument(level = "trace", skip_all)]
+async fn deploy_contract(
+    url: &str,
+    client: &Client,
+    from: &str,
+    signer: Option<&Signer>,
+    data: &str,
+    receipt_timeout: Duration,
+) -> Result<String> {
+    let tx_hash = match signer {
+        Some(signer) => {
+            let data = hex::decode(data.trim_start_matches("0x"))?;
+            signer.send(url, client, None, data).await?
+        }
+        None => send_impersonated(url, client, from, serde_json::json!({"data": data})).await?,
+    };
+    let receipt = wait_for_receipt(url, client, &tx_hash, receipt_timeout).await?;
+    if receipt["status"].as_str() != Some("0x1") {
+        return Err(eyre!("Deployment transaction {tx_hash} reverted"));
+    }
+    receipt["contractAddress"]
+        .as_str()
+        .map(|address| address.to_string())
+        .ok_or_else(|| eyre!("unexpected receipt: {receipt}"))
+}
+
+/// kit chain deploy: build the foundry project in `project_dir` with forge & deploy the libraries
+///  of its `kit-deploy.toml`, then its `contracts` (or else those of its `kit-deploy.toml`, & then
+///  send its transactions) to the fakechain on `port`, or the chain at `rpc_url`, recording their
+///  addresses; fail on the first whose deployment is not mined within `receipt_timeout`.
+///  Transactions are signed by `signer`, if given, else sent from impersonated senders
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_deploy(
+    port: u16,
+    rpc_url: Option<&str>,
+    project_dir: &Path,
+    contracts: &[String],
+    receipt_timeout: Duration,
+    signer: Option<&Signer>,
+    verbose: bool,
+) -> Result<()> {
+    let url = resolve_rpc_url(port, rpc_url).await?;
+
+    let manifest_path = project_dir.join(DEPLOY_MANIFEST_NAME);
+    let mut manifest: DeployManifest = if manifest_path.exists() {
+        toml::from_str(&fs::read_to_string(&manifest_path)?)?
+    } else {
+        DeployManifest::default()
+    };
+    if !contracts.is_empty() {
+        manifest.contracts = contracts
+            .iter()
+            .map(|name| ContractDeployment {
+                name: name.clone(),
+                file: None,
+                args: vec![],
+                from: None,
+            })
+            .collect();
+        // they may reference the manifest's contracts
+        manifest.transactions.clear();
+    }
+    if manifest.contracts.is_empty() {
+        return Err(
+            eyre!("No contracts to deploy from {project_dir:?}").with_suggestion(|| {
+                format!("Name them with `--contract`, or list them in {DEPLOY_MANIFEST_NAME}.")
+            }),
+        );
+    }
+
+    info!("Building {project_dir:?} with forge...");
+    run_command(
+        Command::new("forge").arg("build").current_dir(project_dir),
+        verbose,
+    )?;
+    let out_dir = project_dir.join("out");
+
+    let client = Client::new();
+    let mut deployments = read_deployments_on(port, rpc_url)?;
+    // libraries are linked only as deployed by this run: a recorded one may be out of date
+    let mut deployed = BTreeMap::new();
+    for contract in manifest.libraries.iter().chain(&manifest.contracts) {
+        let artifact_path = find_artifact(&out_dir, contract)?;
+        let artifact: Artifact = serde_json::from_slice(&fs::read(&artifact_path)?)?;
+        let data = make_deploy_data(&artifact, contract, &deployed, &deployments)?;
+        let from = contract.from.as_deref().unwrap_or(OWNER_ADDRESS);
+        check_sender(&contract.name, from)?;
+        if let Some(signer) = signer {
+            signer.check_sender(&contract.name, contract.from.as_deref())?;
+        }
+        let address = deploy_contract(&url, &client, from, signer, &data, receipt_timeout)
+            .await
+            .map_err(|e| eyre!("Failed to deploy {}: {e}", contract.name))
+            .with_suggestion(|| {
+                "If its deployment was not mined in time, check the chain is mining blocks, or raise `--receipt-timeout`."
+            })?;
+        info!("Deployed {} at {address}", contract.name);
+        deployed.insert(contract.name.clone(), address.clone());
+        deployments.insert(contract.name.clone(), address);
+        write_deployments_on(port, rpc_url, &deployments)?;
+    }
+
+    let count = manifest.transactions.len();
+    for (i, tx) in manifest.transactions.iter().enumerate() {
+        let user = format!("transaction {}", i + 1);
+        let args = resolve_references(&user, &tx.args, &deployments)?;
+        if let Some(ref from) = tx.from {
+            check_sender(&user, from)?;
+        }
+        execute_send(
+            port,
+            rpc_url,
+            &tx.target,
+            &tx.call,
+            &args,
+            tx.from.as_deref(),
+            Some(project_dir),
+            signer,
+        )
+        .await
+        .map_err(|e| {
+            eyre!(
+                "Transaction {}/{count} ({}.{}) failed: {e}",
+                i + 1,
+                tx.target,
+                tx.call
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// kit chain check-config: check a `kit-deploy.toml` (or the foundry project dir holding it)
+///  against its project's built artifacts, without deploying, reporting every problem found
+#[instrument(level = "trace", skip_all)]
+pub fn execute_check_config(path: &Path) -> Result<()> {
+    let (project_dir, manifest_path) = if path.is_dir() {
+        (path.to_path_buf(), path.join(DEPLOY_MANIFEST_NAME))
+    } else {
+        let project_dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        (project_dir, path.to_path_buf())
+    };
+    if !manifest_path.exists() {
+        return Err(eyre!("No {manifest_path:?}"));
+    }
+    let manifest: DeployManifest = toml::from_str(&fs::read_to_string(&manifest_path)?)
+        .map_err(|e| eyre!("{manifest_path:?} is not a valid {DEPLOY_MANIFEST_NAME}: {e}"))?;
+
+    let out_dir = project_dir.join("out");
+    if !out_dir.exists() {
+        return Err(eyre!("No artifacts at {out_dir:?}")
+            .with_suggestion(|| "Build the project with `forge build`."));
+    }
+
+    let mut problems = vec![];
+    // references resolve to contracts listed before; encode them with a placeholder address
+    let mut listed = BTreeMap::new();
+    for contract in manifest.libraries.iter().chain(&manifest.contracts) {
+        if listed.contains_key(&contract.name) {
+            problems.push(format!("{} is listed more than once", contract.name));
+        }
+        if let Some(ref from) = contract.from {
+            if let Err(e) = check_sender(&contract.name, from) {
+                problems.push(e.to_string());
+            }
+        }
+        let checked = find_artifact(&out_dir, contract).and_then(|artifact_path| {
+            let artifact: Artifact = serde_json::from_slice(&fs::read(&artifact_path)?)
+                .map_err(|e| eyre!("Artifact {artifact_path:?} is invalid: {e}"))?;
+            make_deploy_data(&artifact, contract, &listed, &listed)
+        });
+        if let Err(e) = checked {
+            problems.push(format!("{}: {e}", contract.name));
+        }
+        listed.insert(contract.name.clone(), PLACEHOLDER_ADDRESS.to_string());
+    }
+    // each call is encoded as it would be sent, against the listed contracts' ABIs
+    for (i, tx) in manifest.transactions.iter().enumerate() {
+        let user = format!("transaction {}", i + 1);
+        if let Some(ref from) = tx.from {
+            if let Err(e) = check_sender(&user, from) {
+                problems.push(e.to_string());
+            }
+        }
+        let checked = resolve_references(&user, &tx.args, &listed).and_then(|args| {
+            encode_manifest_call(&project_dir, &listed, &tx.target, &tx.call, &args)
+                .map_err(|e| eyre!("Transaction {} ({}.{}): {e}", i + 1, tx.target, tx.call))
+        });
+        if let Err(e) = checked {
+            problems.push(e.to_string());
+        }
+    }
+    for (i, assertion) in manifest.assertions.iter().enumerate() {
+        if let Err(e) = check_assertion_config(&project_dir, &listed, assertion) {
+            problems.push(format!(
+                "Assertion {} ({}): {e}",
+                i + 1,
+                assertion.describe()
+            ));
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(eyre!(
+            "{} problem(s) in {manifest_path:?}:\n{}",
+            problems.len(),
+            problems
+                .iter()
+                .map(|p| format!(" - {p}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ));
+    }
+    info!(
+        "{manifest_path:?} is valid: {} contract(s) to deploy, after {} librar(ies).",
+        manifest.contracts.len(),
+        manifest.libraries.len(),
+    );
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use alloy::dyn_abi::{DynSolValue, EventExt};
+use alloy::json_abi::{Event, JsonAbi};
+use alloy::primitives::{Address, B256};
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use fs_err as fs;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, instrument};
+
+use super::deploy::{find_artifact, ContractDeployment};
+use super::state::read_deployments;
+use super::{check_chain_running, fakechain_url, rpc_request, HYPERMAP_PROXY};
+
+const HYPERMAP_EVENTS: &[&str] = &[
+    "event Mint(bytes32 indexed parenthash, bytes32 indexed childhash, bytes indexed labelhash, bytes label)",
+    "event Fact(bytes32 indexed parenthash, bytes32 indexed facthash, bytes indexed labelhash, bytes label, bytes data)",
+    "event Note(bytes32 indexed parenthash, bytes32 indexed notehash, bytes indexed labelhash, bytes label, bytes data)",
+    "event Gene(bytes32 indexed entry, address indexed gene)",
+    "event Zero(address indexed zeroTba)",
+    "event Transfer(address indexed from, address indexed to, uint256 indexed id)",
+];
+
+const POLL_INTERVAL_MS: u64 = 500;
+
+#[derive(Debug, Deserialize)]
+struct ArtifactAbi {
+    abi: JsonAbi,
+}
+
+// A contract whose events `kit chain logs` decodes
+struct WatchedContract {
+    name: String,
+    events: HashMap<B256, Event>,
+}
+
+fn parse_quantity(quantity: &serde_json::Value) -> Result<u64> {
+    let hex = quantity
+        .as_str()
+        .ok_or_else(|| eyre!("unexpected quantity: {quantity}"))?;
+    Ok(u64::from_str_radix(hex.trim_start_matches("0x"), 16)?)
+}
+
+/// Readable form of a decoded ABI value: labels as text, everything else as hex or decimal
+pub fn format_value(value: &DynSolValue) -> String {
+    match value {
+        DynSolValue::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) if !text.is_empty() && !text.chars().any(|c| c.is_control()) => {
+                format!("{text:?}")
+            }
+            _ => format!("0x{}", hex::encode(bytes)),
+        },
+        DynSolValue::FixedBytes(word, size) => format!("0x{}", hex::encode(&word[..*size])),
+        DynSolValue::Address(address) => address.to_string(),
+        DynSolValue::Uint(value, _) => value.to_string(),
+        DynSolValue::Int(value, _) => value.to_string(),
+        DynSolValue::Bool(value) => value.to_string(),
+        DynSolValue::String(value) => format!("{value:?}"),
+        DynSolValue::Array(values)
+        | DynSolValue::FixedArray(values)
+        | DynSolValue::Tuple(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => format!("{other:?}"),
+    }
+}
+
+/// ABI of the contract `name`, from the artifacts of the foundry project it was deployed from
+pub fn read_deployment_abi(project_dir: &Path, name: &str) -> Result<JsonAbi> {
+    let contract = ContractDeployment {
+        name: name.to_string(),
+        file: None,
+        args: vec![],
+        from: None,
+    };
+    let artifact_path = find_artifact(&project_dir.join("out"), &contract)?;
+    let artifact: ArtifactAbi = serde_json::from_slice(&fs::read(&artifact_path)?)?;
+    Ok(artifact.abi)
+}
+
+// The contracts to decode events of: Hypermap, & (given the foundry project they were deployed
+//  from) those `kit chain deploy` deployed
+fn get_watched_contracts(
+    port: u16,
+    project_dir: Option<&Path>,
+) -> Result<HashMap<Address, WatchedContract>> {
+    let mut watched = HashMap::new();
+    let mut events = HashMap::new();
+    for signature in HYPERMAP_EVENTS {
+        let event = Event::parse(signature)?;
+        events.insert(event.selector(), event);
+    }
+    watched.insert(
+        Address::from_str(HYPERMAP_PROXY)?,
+        WatchedContract {
+            name: "Hypermap".to_string(),
+            events,
+        },
+    );
+
+    let Some(project_dir) = project_dir else {
+        return Ok(watched);
+    };
+    for (name, address) in read_deployments(port)? {
+        let abi = match read_deployment_abi(project_dir, &name) {
+            Ok(abi) => abi,
+            Err(e) => {
+                debug!("not decoding events of {name}: {e}");
+                continue;
+            }
+        };
+        let events = abi
+            .events()
+            .map(|event| (event.selector(), event.clone()))
+            .collect();
+        watched.insert(
+            Address::from_str(&address)?,
+            WatchedContract { name, events },
+        );
+    }
+    Ok(watched)
+}
+
+// One line describing `log`, decoded if it was emitted by a watched contract
+fn format_log(
+    log: &serde_json::Value,
+    watched: &HashMap<Address, WatchedContract>,
+) -> Result<String> {
+    let address = Address::from_str(log["address"].as_str().unwrap_or_default())?;
+    let block = parse_quantity(&log["blockNumber"])?;
+    let topics = log["topics"]
+        .as_array()
+        .map(|topics| {
+            topics
+                .iter()
+                .map(|t| B256::from_str(t.as_str().unwrap_or_default()))
+                .collect::<std::result::Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let data = hex::decode(
+        log["data"]
+            .as_str()
+            .unwrap_or("0x")
+            .trim_start_matches("0x"),
+    )?;
+
+    let contract = watched.get(&address);
+    let event = contract.and_then(|c| topics.first().and_then(|t| c.events.get(t)));
+    let (Some(contract), Some(event)) = (contract, event) else {
+        return Ok(format!(
+            "[{block}] {address}: {} topic(s), {} byte(s) of data",
+            topics.len(),
+            data.len(),
+        ));
+    };
+
+    let decoded = event.decode_log_parts(topics, &data, false)?;
+    let mut indexed = decoded.indexed.iter();
+    let mut body = decoded.body.iter();
+    let params = event
+        .inputs
+        .iter()
+        .map(|input| {
+            let value = if input.indexed {
+                indexed.next()
+            } else {
+                body.next()
+            };
+            format!(
+                "{}={}",
+                input.name,
+                value.map(format_value).unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(format!(
+        "[{block}] {}.{}({params})",
+        contract.name, event.name
+    ))
+}
+
+/// kit chain logs: print the events emitted on the chain on `port` as blocks are mined,
+///  decoding those of Hypermap & of contracts deployed from the foundry project at `project_dir`
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_logs(
+    port: u16,
+    from_block: Option<u64>,
+    project_dir: Option<&Path>,
+) -> Result<()> {
+    check_chain_running(port).await?;
+    let watched = get_watched_contracts(port, project_dir)
+        .with_suggestion(|| "Check the foundry project has been built with `forge build`.")?;
+
+    let url = fakechain_url(port);
+    let client = Client::new();
+    let latest = parse_quantity(
+        &rpc_request(&url, &client, "eth_blockNumber", serde_json::json!([])).await?,
+    )?;
+    let mut next_block = from_block.unwrap_or(latest + 1);
+    info!("Printing events on port {port} from block {next_block} (Ctrl-C to stop)...");
+    loop {
+        let latest = parse_quantity(
+            &rpc_request(&url, &client, "eth_blockNumber", serde_json::json!([])).await?,
+        )?;
+        if latest >= next_block {
+            let logs = rpc_request(
+                &url,
+                &client,
+                "eth_getLogs",
+                serde_json::json!([{
+                    "fromBlock": format!("0x{next_block:x}"),
+                    "toBlock": format!("0x{latest:x}"),
+                }]),
+            )
+            .await?;
+            for log in logs.as_array().map(|l| l.as_slice()).unwrap_or_default() {
+                match format_log(log, &watched) {
+                    Ok(line) => info!("{line}"),
+                    Err(e) => debug!("failed to decode log {log}: {e}"),
+                }
+            }
+            next_block = latest + 1;
+        }
+        sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+    }
+}
@@ -0,0 +1,211 @@
+use std::str::FromStr;
+
+use alloy::primitives::{Address, Bytes, U256};
+use alloy_sol_macro::sol;
+use alloy_sol_types::SolCall;
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use reqwest::Client;
+use tokio::time::Duration;
+use tracing::{info, instrument};
+
+use crate::new::is_hypermap_safe;
+use crate::publish::namehash;
+
+use super::signer::Signer;
+use super::{
+    resolve_rpc_url, rpc_request, send_impersonated, wait_for_receipt,
+    DEFAULT_RECEIPT_TIMEOUT_SECS, HYPERMAP_PROXY, HYPER_ACCOUNT, HYPER_ACCOUNT_MINTER,
+    OWNER_ADDRESS,
+};
+
+sol! {
+    function mint (
+        address who,
+        bytes calldata label,
+        bytes calldata initialization,
+        address implementation
+    ) external returns (
+        address tba
+    );
+
+    function get (
+        bytes32 node
+    ) external view returns (
+        address tba,
+        address owner,
+        bytes data,
+    );
+
+    // tba account
+    function execute(
+        address to,
+        uint256 value,
+        bytes calldata data,
+        uint8 operation
+    ) external payable returns (bytes memory returnData);
+
+    // HyperAccountMinter
+    function initialize() external;
+}
+
+/// Account implementations `kit chain mint --impl` knows by name
+const ACCOUNT_IMPLEMENTATIONS: &[(&str, &str)] = &[
+    ("hyper-account", HYPER_ACCOUNT),
+    ("hyper-account-minter", HYPER_ACCOUNT_MINTER),
+];
+
+fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address).map_err(|e| eyre!("Invalid address {address}: {e}"))
+}
+
+// Resolve `--impl` (a name from ACCOUNT_IMPLEMENTATIONS or an address) to an address & the
+//  initialization call for accounts using it
+fn resolve_implementation(implementation: &str) -> Result<(Address, Vec<u8>)> {
+    let address = ACCOUNT_IMPLEMENTATIONS
+        .iter()
+        .find(|(name, _)| *name == implementation)
+        .map(|(_, address)| *address)
+        .unwrap_or(implementation);
+    let address = parse_address(address).with_suggestion(|| {
+        format!(
+            "Use an address or one of: {}.",
+            ACCOUNT_IMPLEMENTATIONS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    })?;
+    // minters must be initialized so that anyone may mint beneath them
+    let initialization = if address == parse_address(HYPER_ACCOUNT_MINTER)? {
+        initializeCall {}.abi_encode()
+    } else {
+        vec![]
+    };
+    Ok((address, initialization))
+}
+
+/// The TBA & owner of `name` on Hypermap (both zero if it is not minted)
+#[instrument(level = "trace", skip_all)]
+pub async fn hypermap_get(url: &str, client: &Client, name: &str) -> Result<(Address, Address)> {
+    let call = getCall {
+        node: namehash(name).into(),
+    }
+    .abi_encode();
+    let result = rpc_request(
+        url,
+        client,
+        "eth_call",
+        serde_json::json!([
+            {"to": HYPERMAP_PROXY, "data": format!("0x{}", hex::encode(call))},
+            "latest",
+        ]),
+    )
+    .await?;
+    let result = result
+        .as_str()
+        .ok_or_else(|| eyre!("unexpected eth_call result: {result}"))?;
+    let result = hex::decode(result.trim_start_matches("0x"))?;
+    let decoded = getCall::abi_decode_returns(&result, false)?;
+    Ok((decoded.tba, decoded.owner))
+}
+
+/// kit chain mint: mint `name` (e.g. `sub.label.os`) to `owner` on the fakechain on `port`, or
+///  the chain at `rpc_url`, sending the mint from the TBA of its parent as the parent's owner:
+///  signed by `signer`, which must be that owner, if given, else impersonated
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_mint(
+    port: u16,
+    rpc_url: Option<&str>,
+    name: &str,
+    owner: Option<&str>,
+    implementation: &str,
+    signer: Option<&Signer>,
+) -> Result<()> {
+    let url = resolve_rpc_url(port, rpc_url).await?;
+
+    let (label, parent) = name.split_once('.').unwrap_or((name, ""));
+    if !is_hypermap_safe(label, false) || !(parent.is_empty() || is_hypermap_safe(parent, true)) {
+        return Err(eyre!(
+            "Cannot mint {name}: Hypermap names have only lowercase letters, digits, `-`s, and `.`s"
+        ));
+    }
+    let owner = match (owner, signer) {
+        (Some(owner), _) => parse_address(owner)?,
+        (None, Some(signer)) => signer.address,
+        (None, None) => parse_address(OWNER_ADDRESS)?,
+    };
+    let (implementation, initialization) = resolve_implementation(implementation)?;
+
+    let client = Client::new();
+    let (tba, _) = hypermap_get(&url, &client, name).await?;
+    if tba != Address::ZERO {
+        return Err(eyre!("{name} is already minted (TBA {tba})"));
+    }
+    let (parent_tba, parent_owner) = hypermap_get(&url, &client, parent).await?;
+    if parent_tba == Address::ZERO {
+        return Err(
+            eyre!("Cannot mint {name}: its parent {parent} is not minted")
+                .with_suggestion(|| format!("Mint it first with `kit chain mint {parent}`.")),
+        );
+    }
+
+    let mint_call = mintCall {
+        who: owner,
+        label: Bytes::copy_from_slice(label.as_bytes()),
+        initialization: initialization.into(),
+        implementation,
+    }
+    .abi_encode();
+    let call = executeCall {
+        to: parse_address(HYPERMAP_PROXY)?,
+        value: U256::from(0),
+        data: mint_call.into(),
+        operation: 0,
+    }
+    .abi_encode();
+
+    let sent = match signer {
+        Some(signer) => {
+            if signer.address != parent_owner {
+                return Err(eyre!(
+                    "Cannot mint {name}: its parent {parent} is owned by {parent_owner}, not the signer {}",
+                    signer.address
+                ));
+            }
+            signer.send(&url, &client, Some(parent_tba), call).await
+        }
+        None => {
+            send_impersonated(
+                &url,
+                &client,
+                &parent_owner.to_string(),
+                serde_json::json!({
+                    "to": parent_tba.to_string(),
+                    "data": format!("0x{}", hex::encode(call)),
+                }),
+            )
+            .await
+        }
+    };
+    let tx_hash = sent.map_err(|e| eyre!("Failed to mint {name}: {e}"))?;
+    let receipt = wait_for_receipt(
+        &url,
+        &client,
+        &tx_hash,
+        Duration::from_secs(DEFAULT_RECEIPT_TIMEOUT_SECS),
+    )
+    .await?;
+    if receipt["status"].as_str() != Some("0x1") {
+        return Err(eyre!(
+            "Failed to mint {name}: transaction {tx_hash} reverted"
+        ));
+    }
+
+    let (tba, _) = hypermap_get(&url, &client, name).await?;
+    info!("Minted {name} to {owner}: TBA {tba}");
+    Ok(())
+}
@@ -1,12 +1,15 @@
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 
 use color_eyre::{
     eyre::{eyre, Result},
     Section,
 };
+use fs_err as fs;
 use reqwest::Client;
+use sha2::{Digest, Sha256};
 use tokio::time::{sleep, Duration};
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 
 use crate::build;
 use crate::run_tests::cleanup::{clean_process_by_pid, cleanup_on_signal};
@@ -14,6 +17,31 @@ use crate::run_tests::types::BroadcastRecvBool;
 use crate::setup::{check_foundry_deps, get_deps};
 use crate::KIT_CACHE;
 
+mod accounts;
+mod call;
+mod deploy;
+mod logs;
+mod mint;
+mod replay;
+mod signer;
+mod snapshot;
+mod state;
+mod verify;
+pub use accounts::{execute_accounts, execute_fund};
+pub use call::{execute_call, execute_send};
+pub use deploy::{execute_check_config, execute_deploy};
+pub use logs::execute_logs;
+pub use mint::execute_mint;
+pub use replay::execute_replay;
+pub use signer::{read_signer, Signer};
+pub use snapshot::{execute_restore, execute_snapshot};
+use state::{
+    find_chain_state_by_name, get_named_state_dir, is_pid_alive, load_deployments, now,
+    remove_chain_state, save_deployments, write_chain_state, ANVIL_STATE_FILE_NAME,
+};
+pub use state::{read_chain_state, read_deployments, ChainState};
+pub use verify::execute_verify;
+
 // important contract addresses:
 //  https://gist.github.com/nick1udwig/273292fdfe94dd1c563f302df8bdfb74
 
@@ -27,7 +55,7 @@ const CREATE2: &str = "0x4e59b44847b379578588920cA78FbF26c0B4956C";
 const HYPERMAP_PROXY: &str = "0x000000000044C6B8Cb4d8f0F889a3E47664EAeda";
 const HYPERMAP: &str = "0x000000000013a0486EBDc2DB1D7B4d1f7fCA92eD";
 const HYPER_ACCOUNT: &str = "0x0000000000EDAd72076CBe7b9Cfa3751D5a85C97";
-//const HYPER_ACCOUNT_MINTER: &str = "0xE01dCbD3Ed5f709874A1eA7a25677de18C8661c9";
+const HYPER_ACCOUNT_MINTER: &str = "0xE01dCbD3Ed5f709874A1eA7a25677de18C8661c9";
 
 const DOT_OS_TBA: &str = "0x9b3853358ede717fc7D4806cF75d7A4d4517A9C9";
 const ZEROTH_TBA: &str = "0x809A598d9883f2Fb6B77382eBfC9473Fd6A857c9";
@@ -37,6 +65,19 @@ const HYPERMAP_PROXY_LONG: &str =
 const HYPERMAP_LONG: &str = "0x000000000000000000000000000000000013a0486EBDc2DB1D7B4d1f7fCA92eD";
 
 const DEFAULT_MAX_ATTEMPTS: u16 = 16;
+// forks fetch state from their RPC before answering
+const FORK_MAX_ATTEMPTS: u16 = 120;
+// anvil started with `--state` dumps its (possibly large) state before exiting
+const STATE_DUMP_MAX_ATTEMPTS: u16 = 480;
+
+/// Default time to wait for a transaction to be mined before giving up, in seconds
+pub const DEFAULT_RECEIPT_TIMEOUT_SECS: u64 = 60;
+// receipts are polled after 50ms, then after doubling intervals up to 2s
+const RECEIPT_POLL_INITIAL_MS: u64 = 50;
+const RECEIPT_POLL_MAX_MS: u64 = 2_000;
+
+// 1 ETH
+const GAS_FUNDS: &str = "0xde0b6b3a7640000";
 
 const PREDEPLOY_CONTRACTS: &[(&str, &str)] = &[
     (
@@ -96,9 +137,219 @@ const TRANSACTIONS: &[(&str, &str)] = &[
     (ZEROTH_TBA, include_str!("./bytecode/mint-os.txt")),
 ];
 
+/// Whether a fakechain is running on a port
+#[derive(Debug)]
+pub enum ChainStatus {
+    /// Started by kit, & initialized as this version of kit initializes chains
+    Running(ChainState),
+    /// Started by kit, but initialized with other contracts or transactions (e.g. by another
+    ///  version of kit)
+    Outdated(ChainState),
+    /// A chain not started by kit answers on the port
+    Unmanaged,
+    Stopped,
+}
+
+/// Hash of the contracts & transactions a fakechain is initialized with
+pub fn config_fingerprint() -> String {
+    let mut hasher = Sha256::new();
+    for (address, bytecode) in PREDEPLOY_CONTRACTS {
+        hasher.update(address.as_bytes());
+        hasher.update(bytecode.trim().as_bytes());
+    }
+    for (address, slot, value) in STORAGE_SLOTS {
+        hasher.update(address.as_bytes());
+        hasher.update(slot.as_bytes());
+        hasher.update(value.as_bytes());
+    }
+    for (to, data) in TRANSACTIONS {
+        hasher.update(to.as_bytes());
+        hasher.update(data.trim().as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Whether a fakechain is running on `port`: from the state recorded in `KIT_CACHE` by the kit
+///  command that started it, or else by probing the port
+#[instrument(level = "trace", skip_all)]
+pub async fn get_chain_status(port: u16) -> Result<ChainStatus> {
+    if let Some(state) = read_chain_state(port)? {
+        if is_pid_alive(state.pid) {
+            return Ok(if state.fingerprint == config_fingerprint() {
+                ChainStatus::Running(state)
+            } else {
+                ChainStatus::Outdated(state)
+            });
+        }
+        // The kit command that started it exited without cleaning up
+        debug!("removing state of exited fakechain (pid {})", state.pid);
+        remove_chain_state(port)?;
+    }
+    if wait_for_anvil(port, 1, None).await.is_ok() {
+        Ok(ChainStatus::Unmanaged)
+    } else {
+        Ok(ChainStatus::Stopped)
+    }
+}
+
+/// JSON-RPC endpoint of the fakechain on `port`
+fn fakechain_url(port: u16) -> String {
+    format!("http://localhost:{port}")
+}
+
+/// Send a JSON-RPC request to the chain at `url`, returning its result
 #[instrument(level = "trace", skip_all)]
-async fn get_nonce(port: u16, client: &Client, address: &str) -> Result<u64> {
-    let url = format!("http://localhost:{}", port);
+async fn rpc_request(
+    url: &str,
+    client: &Client,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<serde_json::Value> {
+    let request_body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+        "id": 1
+    });
+    let res: serde_json::Value = client
+        .post(url)
+        .json(&request_body)
+        .send()
+        .await?
+        .json()
+        .await?;
+    if let Some(error) = res.get("error") {
+        return Err(eyre!("{method} failed: {error}"));
+    }
+    res.get("result")
+        .cloned()
+        .ok_or_else(|| eyre!("unexpected response: {res}"))
+}
+
+/// Receipt of `tx_hash`, polling with exponential backoff until it is mined or `timeout` passes
+#[instrument(level = "trace", skip_all)]
+async fn wait_for_receipt(
+    url: &str,
+    client: &Client,
+    tx_hash: &str,
+    timeout: Duration,
+) -> Result<serde_json::Value> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut interval = Duration::from_millis(RECEIPT_POLL_INITIAL_MS);
+    loop {
+        let receipt = rpc_request(
+            url,
+            client,
+            "eth_getTransactionReceipt",
+            serde_json::json!([tx_hash]),
+        )
+        .await?;
+        if !receipt.is_null() {
+            return Ok(receipt);
+        }
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return Err(eyre!(
+                "Transaction {tx_hash} was not mined within {}s",
+                timeout.as_secs()
+            )
+            .with_suggestion(|| "Check the chain is mining blocks."));
+        }
+        debug!("{tx_hash} not yet mined; retrying in {interval:?}");
+        sleep(interval.min(deadline - now)).await;
+        interval = (interval * 2).min(Duration::from_millis(RECEIPT_POLL_MAX_MS));
+    }
+}
+
+/// Fail unless a chain is running on `port`
+#[instrument(level = "trace", skip_all)]
+async fn check_chain_running(port: u16) -> Result<()> {
+    if let ChainStatus::Stopped = get_chain_status(port).await? {
+        return Err(eyre!("No chain is running on port {port}")
+            .with_suggestion(|| "Start one with `kit chain start`, or pass its `--port`."));
+    }
+    Ok(())
+}
+
+/// JSON-RPC endpoint to send to: `rpc_url`, if given, else that of the fakechain on `port`, which
+///  must be running
+#[instrument(level = "trace", skip_all)]
+async fn resolve_rpc_url(port: u16, rpc_url: Option<&str>) -> Result<String> {
+    match rpc_url {
+        Some(rpc_url) => Ok(rpc_url.to_string()),
+        None => {
+            check_chain_running(port).await?;
+            Ok(fakechain_url(port))
+        }
+    }
+}
+
+/// Give `address` 1 ETH if it has none, so that it can pay for gas when impersonated
+#[instrument(level = "trace", skip_all)]
+async fn fund_for_gas(url: &str, client: &Client, address: &str) -> Result<()> {
+    let balance = rpc_request(
+        url,
+        client,
+        "eth_getBalance",
+        serde_json::json!([address, "latest"]),
+    )
+    .await?;
+    if balance.as_str() == Some("0x0") {
+        debug!("funding {address} for gas");
+        rpc_request(
+            url,
+            client,
+            "anvil_setBalance",
+            serde_json::json!([address, GAS_FUNDS]),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Send the transaction `request` from `from`, impersonating it & funding it for gas, returning
+///  its hash; `from` is no longer impersonated afterwards, even if sending fails
+#[instrument(level = "trace", skip_all)]
+async fn send_impersonated(
+    url: &str,
+    client: &Client,
+    from: &str,
+    mut request: serde_json::Value,
+) -> Result<String> {
+    rpc_request(
+        url,
+        client,
+        "anvil_impersonateAccount",
+        serde_json::json!([from]),
+    )
+    .await?;
+    let sent = async {
+        fund_for_gas(url, client, from).await?;
+        request["from"] = serde_json::json!(from);
+        rpc_request(
+            url,
+            client,
+            "eth_sendTransaction",
+            serde_json::json!([request]),
+        )
+        .await
+    }
+    .await;
+    rpc_request(
+        url,
+        client,
+        "anvil_stopImpersonatingAccount",
+        serde_json::json!([from]),
+    )
+    .await?;
+    let sent = sent?;
+    sent.as_str()
+        .map(|tx_hash| tx_hash.to_string())
+        .ok_or_else(|| eyre!("unexpected transaction hash: {sent}"))
+}
+
+#[instrument(level = "trace", skip_all)]
+async fn get_nonce(url: &str, client: &Client, address: &str) -> Result<u64> {
     let request_body = serde_json::json!({
         "jsonrpc": "2.0",
         "method": "eth_getTransactionCount",
@@ -106,7 +357,7 @@ async fn get_nonce(port: u16, client: &Client, address: &str) -> Result<u64> {
         "id": 1
     });
     let response: serde_json::Value = client
-        .post(&url)
+        .post(url)
         .json(&request_body)
         .send()
         .await?
@@ -124,14 +375,13 @@ async fn get_nonce(port: u16, client: &Client, address: &str) -> Result<u64> {
 
 #[instrument(level = "trace", skip_all)]
 async fn execute_transaction(
-    port: u16,
+    url: &str,
     client: &Client,
     from: &str,
     to: &str,
     data: &str,
     nonce: u64,
 ) -> Result<String> {
-    let url = format!("http://localhost:{}", port);
     let request_body = serde_json::json!({
         "jsonrpc": "2.0",
         "method": "eth_sendTransaction",
@@ -146,7 +396,7 @@ async fn execute_transaction(
     });
 
     let res: serde_json::Value = client
-        .post(&url)
+        .post(url)
         .json(&request_body)
         .send()
         .await?
@@ -166,10 +416,55 @@ async fn execute_transaction(
     return Err(eyre!("unexpected response: {res}"));
 }
 
+// Readable revert reason from revert data: `Error(string)`, `Panic(uint256)`, or else hex
+fn decode_revert_reason(data: &[u8]) -> String {
+    // Error(string): selector, offset, length, string
+    if data.len() >= 68 && data[..4] == [0x08, 0xc3, 0x79, 0xa0] {
+        let length = u64::from_be_bytes(data[60..68].try_into().unwrap()) as usize;
+        if let Some(reason) = data.get(68..68 + length) {
+            return String::from_utf8_lossy(reason).to_string();
+        }
+    }
+    // Panic(uint256): selector, code
+    if data.len() == 36 && data[..4] == [0x4e, 0x48, 0x7b, 0x71] {
+        return format!("panic 0x{:x}", data[35]);
+    }
+    if data.is_empty() {
+        return "no reason given".to_string();
+    }
+    format!("0x{}", hex::encode(data))
+}
+
+// Why sending `data` to `to` from `from` reverts, found by calling it
+#[instrument(level = "trace", skip_all)]
+async fn get_revert_reason(url: &str, client: &Client, from: &str, to: &str, data: &str) -> String {
+    let request_body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "eth_call",
+        "params": [{"from": from, "to": to, "data": data.trim()}, "latest"],
+        "id": 1
+    });
+    let res: serde_json::Value = match client.post(url).json(&request_body).send().await {
+        Ok(response) => response.json().await.unwrap_or_default(),
+        Err(e) => return format!("unknown ({e})"),
+    };
+    match res["error"]["data"].as_str() {
+        Some(revert) => match hex::decode(revert.trim_start_matches("0x")) {
+            Ok(revert) => decode_revert_reason(&revert),
+            Err(_) => revert.to_string(),
+        },
+        None => match res.get("error") {
+            Some(error) => error.to_string(),
+            // does not revert when called alone: it depended on state at the time
+            None => "unknown".to_string(),
+        },
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
 async fn initialize_contracts(port: u16) -> Result<()> {
     let client = Client::new();
-    let url = format!("http://localhost:{}", port);
+    let url = fakechain_url(port);
 
     // impersonate owner account
     let request_body = serde_json::json!({
@@ -203,16 +498,47 @@ async fn initialize_contracts(port: u16) -> Result<()> {
             .await?;
     }
 
-    let mut nonce = get_nonce(port, &client, OWNER_ADDRESS).await?;
+    let ran: Result<()> = async {
+        let mut nonce = get_nonce(&url, &client, OWNER_ADDRESS).await?;
 
-    // execute all transactions
-    for (to, data) in TRANSACTIONS {
-        match execute_transaction(port, &client, OWNER_ADDRESS, to, data, nonce).await {
-            Ok(result) => debug!("Transaction to {to}:  {result}"),
-            Err(e) => info!("Transaction failed: {e:?}"),
+        // execute all transactions, failing on the first that reverts: later ones depend on it
+        let mut total_gas_used = 0;
+        for (i, (to, data)) in TRANSACTIONS.iter().enumerate() {
+            let description = format!(
+                "Config transaction {}/{} to {to}",
+                i + 1,
+                TRANSACTIONS.len()
+            );
+            let tx_hash = execute_transaction(&url, &client, OWNER_ADDRESS, to, data, nonce)
+                .await
+                .map_err(|e| eyre!("{description} failed: {e}"))?;
+            nonce += 1;
+            let receipt = wait_for_receipt(
+                &url,
+                &client,
+                &tx_hash,
+                Duration::from_secs(DEFAULT_RECEIPT_TIMEOUT_SECS),
+            )
+            .await
+            .map_err(|e| eyre!("{description}: {e}"))?;
+            if receipt["status"].as_str() != Some("0x1") {
+                let reason = get_revert_reason(&url, &client, OWNER_ADDRESS, to, data).await;
+                return Err(eyre!("{description} reverted ({tx_hash}): {reason}"));
+            }
+            let gas_used = receipt["gasUsed"]
+                .as_str()
+                .and_then(|g| u64::from_str_radix(g.trim_start_matches("0x"), 16).ok())
+                .unwrap_or_default();
+            total_gas_used += gas_used;
+            debug!("{description}: {tx_hash}, gas used {gas_used}");
         }
-        nonce += 1;
+        info!(
+            "Ran {} config transactions, using {total_gas_used} gas.",
+            TRANSACTIONS.len()
+        );
+        Ok(())
     }
+    .await;
 
     // stop impersonating
     let request_body = serde_json::json!({
@@ -229,13 +555,26 @@ async fn initialize_contracts(port: u16) -> Result<()> {
         .json()
         .await?;
 
-    Ok(())
+    ran
+}
+
+/// Whether contract code is deployed at `address` on the chain at `url`
+#[instrument(level = "trace", skip_all)]
+async fn has_code(url: &str, client: &Client, address: &str) -> Result<bool> {
+    let code = rpc_request(
+        url,
+        client,
+        "eth_getCode",
+        serde_json::json!([address, "latest"]),
+    )
+    .await?;
+    Ok(code.as_str().is_some_and(|code| code != "0x"))
 }
 
 #[instrument(level = "trace", skip_all)]
 async fn check_dot_os_tba(port: u16) -> Result<bool> {
     let client = Client::new();
-    let url = format!("http://localhost:{}", port);
+    let url = fakechain_url(port);
 
     let request_body = serde_json::json!({
         "jsonrpc": "2.0",
@@ -250,13 +589,67 @@ async fn check_dot_os_tba(port: u16) -> Result<bool> {
     Ok(code != "0x")
 }
 
+/// Port of the running fakechain named `name`
+#[instrument(level = "trace", skip_all)]
+pub fn resolve_chain_port(name: &str) -> Result<u16> {
+    match find_chain_state_by_name(name)? {
+        Some(state) => Ok(state.port),
+        None => Err(
+            eyre!("No fakechain named {name} is running").with_suggestion(|| {
+                format!("Start it with `kit chain start --name {name} --port <port>`.")
+            }),
+        ),
+    }
+}
+
+/// Port of the running fakechain named `name`, if any, else `port` (to start it on)
+#[instrument(level = "trace", skip_all)]
+pub fn get_chain_port(port: u16, name: Option<&str>) -> Result<u16> {
+    let Some(name) = name else {
+        return Ok(port);
+    };
+    Ok(find_chain_state_by_name(name)?
+        .map(|state| state.port)
+        .unwrap_or(port))
+}
+
+// A named fakechain keeps its state in its own dir unless given another
+fn resolve_state_dir(name: Option<&str>, state_dir: Option<&Path>) -> Option<PathBuf> {
+    state_dir
+        .map(|d| d.to_path_buf())
+        .or_else(|| name.map(get_named_state_dir))
+}
+
 #[instrument(level = "trace", skip_all)]
 pub async fn start_chain(
     port: u16,
+    name: Option<&str>,
+    fork_url: Option<&str>,
+    fork_block_number: Option<u64>,
+    state_dir: Option<&Path>,
     mut recv_kill: BroadcastRecvBool,
     verbose: bool,
     tracing: bool,
 ) -> Result<Option<Child>> {
+    // anvil args the chain must be started with
+    let mut chain_args = vec![];
+    if let Some(fork_url) = fork_url {
+        chain_args.extend(["--fork-url".to_string(), fork_url.to_string()]);
+        if let Some(fork_block_number) = fork_block_number {
+            chain_args.extend([
+                "--fork-block-number".to_string(),
+                fork_block_number.to_string(),
+            ]);
+        }
+    }
+    let state_dir = resolve_state_dir(name, state_dir);
+    if let Some(ref state_dir) = state_dir {
+        fs::create_dir_all(state_dir)?;
+        // anvil loads its state from here on start, & dumps it here on exit
+        let state_path = fs::canonicalize(state_dir)?.join(ANVIL_STATE_FILE_NAME);
+        chain_args.extend(["--state".to_string(), state_path.display().to_string()]);
+    }
+
     let deps = check_foundry_deps()?;
     get_deps(
         deps,
@@ -267,21 +660,63 @@ pub async fn start_chain(
     )
     .await?;
 
+    if let Some(name) = name {
+        if let Some(state) = find_chain_state_by_name(name)? {
+            if state.port != port {
+                return Err(eyre!(
+                    "The fakechain {name} is already running on port {}",
+                    state.port
+                )
+                .with_suggestion(|| format!("Use `--port {}`.", state.port)));
+            }
+        }
+    }
+
     info!("Checking for Anvil on port {}...", port);
-    if wait_for_anvil(port, 1, None).await.is_ok() {
-        if !check_dot_os_tba(port).await? {
-            predeploy_contracts(port).await?;
-            initialize_contracts(port).await?;
+    match get_chain_status(port).await? {
+        ChainStatus::Running(state) => {
+            if name.is_some() && state.name.as_deref() != name {
+                return Err(eyre!(
+                    "Port {port} is in use by the fakechain {}",
+                    state.name.as_deref().unwrap_or("(unnamed)"),
+                )
+                .with_suggestion(|| "Use another port with `--port`."));
+            }
+            if !chain_args.is_empty()
+                && !state
+                    .args
+                    .windows(chain_args.len())
+                    .any(|w| w == chain_args)
+            {
+                return Err(eyre!(
+                    "The fakechain running on port {port} was not started with `{}`",
+                    chain_args.join(" "),
+                )
+                .with_suggestion(|| "Replace it with `kit chain restart`, passing them."));
+            }
+            info!(
+                "Using the fakechain already running on port {port} (pid {}).",
+                state.pid
+            );
+            return Ok(None);
+        }
+        ChainStatus::Outdated(_) | ChainStatus::Unmanaged => {
+            if !check_dot_os_tba(port).await? {
+                predeploy_contracts(port).await?;
+                initialize_contracts(port).await?;
+            }
+            return Ok(None);
         }
-        return Ok(None);
+        ChainStatus::Stopped => {}
     }
 
     let mut args = vec!["--port".to_string(), port.to_string()];
+    args.extend(chain_args);
     if tracing {
         args.push("--tracing".to_string());
     }
     let mut child = Command::new("anvil")
-        .args(args)
+        .args(&args)
         .current_dir(KIT_CACHE)
         .stdout(if verbose {
             Stdio::inherit()
@@ -291,12 +726,26 @@ pub async fn start_chain(
         .spawn()?;
 
     info!("Waiting for Anvil to be ready on port {}...", port);
-    if let Err(e) = wait_for_anvil(port, DEFAULT_MAX_ATTEMPTS, Some(recv_kill)).await {
+    let max_attempts = if fork_url.is_some() {
+        FORK_MAX_ATTEMPTS
+    } else {
+        DEFAULT_MAX_ATTEMPTS
+    };
+    if let Err(e) = wait_for_anvil(port, max_attempts, Some(recv_kill)).await {
         let _ = child.kill();
         return Err(e);
     }
 
-    if !check_dot_os_tba(port).await? {
+    // a fork of a chain Hypermap is deployed on (e.g. Base) has the real contracts
+    let is_deployed = if fork_url.is_some()
+        && has_code(&fakechain_url(port), &Client::new(), HYPERMAP_PROXY).await?
+    {
+        info!("Hypermap exists on the forked chain: skipping its local deployment.");
+        true
+    } else {
+        check_dot_os_tba(port).await?
+    };
+    if !is_deployed {
         if let Err(e) = predeploy_contracts(port).await {
             let _ = child.kill();
             return Err(e.wrap_err("Failed to pre-deploy contracts"));
@@ -308,6 +757,18 @@ pub async fn start_chain(
         }
     }
 
+    write_chain_state(&ChainState {
+        pid: child.id(),
+        port,
+        name: name.map(|n| n.to_string()),
+        args,
+        fingerprint: config_fingerprint(),
+        started_at: now(),
+    })?;
+    if let Some(ref state_dir) = state_dir {
+        load_deployments(port, state_dir)?;
+    }
+
     Ok(Some(child))
 }
 
@@ -318,7 +779,7 @@ async fn wait_for_anvil(
     mut recv_kill: Option<BroadcastRecvBool>,
 ) -> Result<()> {
     let client = Client::new();
-    let url = format!("http://localhost:{}", port);
+    let url = fakechain_url(port);
 
     for _ in 0..max_attempts {
         let request_body = serde_json::json!({
@@ -366,7 +827,7 @@ async fn wait_for_anvil(
 #[instrument(level = "trace", skip_all)]
 async fn predeploy_contracts(port: u16) -> Result<()> {
     let client = Client::new();
-    let url = format!("http://localhost:{}", port);
+    let url = fakechain_url(port);
 
     for (address, bytecode) in PREDEPLOY_CONTRACTS {
         let request_body = serde_json::json!({
@@ -401,9 +862,122 @@ async fn predeploy_contracts(port: u16) -> Result<()> {
     Ok(())
 }
 
-/// kit chain, alias to anvil
+/// Stop the fakechain of `state`, waiting for its anvil to exit
+#[instrument(level = "trace", skip_all)]
+async fn stop_chain(state: &ChainState) -> Result<()> {
+    let pid = nix::unistd::Pid::from_raw(state.pid as i32);
+    nix::sys::signal::kill(pid, nix::sys::signal::Signal::SIGINT)?;
+    let max_attempts = if state.args.iter().any(|arg| arg == "--state") {
+        info!("Waiting for anvil to save its state...");
+        STATE_DUMP_MAX_ATTEMPTS
+    } else {
+        DEFAULT_MAX_ATTEMPTS
+    };
+    for _ in 0..max_attempts {
+        if !is_pid_alive(state.pid) {
+            break;
+        }
+        sleep(Duration::from_millis(250)).await;
+    }
+    if is_pid_alive(state.pid) {
+        return Err(eyre!(
+            "anvil (pid {}) on port {} did not exit",
+            state.pid,
+            state.port
+        )
+        .with_suggestion(|| {
+            format!(
+                "Wait for it to exit, or kill it with `kill -9 {}` (losing any state it has not saved).",
+                state.pid
+            )
+        }));
+    }
+    remove_chain_state(state.port)?;
+    Ok(())
+}
+
+/// kit chain stop: stop the fakechain kit started on `port`
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_stop(port: u16) -> Result<()> {
+    match get_chain_status(port).await? {
+        ChainStatus::Running(state) | ChainStatus::Outdated(state) => {
+            stop_chain(&state).await?;
+            info!("Stopped the fakechain on port {port}.");
+        }
+        ChainStatus::Unmanaged => {
+            return Err(
+                eyre!("The chain on port {port} was not started by kit").with_suggestion(|| {
+                    "Stop it from where it was started, or use another port with `--port`."
+                }),
+            );
+        }
+        ChainStatus::Stopped => info!("No fakechain is running on port {port}."),
+    }
+    Ok(())
+}
+
+/// kit chain status: print whether a fakechain is running on `port`, & how it was started
 #[instrument(level = "trace", skip_all)]
-pub async fn execute(port: u16, verbose: bool, tracing: bool) -> Result<()> {
+pub async fn execute_status(port: u16) -> Result<()> {
+    match get_chain_status(port).await? {
+        ChainStatus::Running(state) => info!(
+            "Fakechain {}running on port {port}: pid {}, up {}s, started with `anvil {}`.",
+            state
+                .name
+                .as_deref()
+                .map(|n| format!("{n} "))
+                .unwrap_or_default(),
+            state.pid,
+            now().saturating_sub(state.started_at),
+            state.args.join(" "),
+        ),
+        ChainStatus::Outdated(state) => warn!(
+            "Fakechain running on port {port} (pid {}), but initialized by another version of kit: \
+             `kit chain restart` to update it.",
+            state.pid,
+        ),
+        ChainStatus::Unmanaged => info!("A chain not started by kit is running on port {port}."),
+        ChainStatus::Stopped => info!("No fakechain is running on port {port}."),
+    }
+    Ok(())
+}
+
+/// kit chain restart: stop the fakechain kit started on `port`, if any, & start another
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_restart(
+    port: u16,
+    name: Option<&str>,
+    fork_url: Option<&str>,
+    fork_block_number: Option<u64>,
+    state_dir: Option<&Path>,
+    verbose: bool,
+    tracing: bool,
+) -> Result<()> {
+    execute_stop(port).await?;
+    execute(
+        port,
+        name,
+        fork_url,
+        fork_block_number,
+        state_dir,
+        verbose,
+        tracing,
+    )
+    .await
+}
+
+/// kit chain start, alias to anvil; `fork_url` forks a live chain, e.g. Base, & `state_dir`
+///  keeps the chain's state across restarts (by default, in its own dir for a `name`d chain)
+#[instrument(level = "trace", skip_all)]
+pub async fn execute(
+    port: u16,
+    name: Option<&str>,
+    fork_url: Option<&str>,
+    fork_block_number: Option<u64>,
+    state_dir: Option<&Path>,
+    verbose: bool,
+    tracing: bool,
+) -> Result<()> {
     let (send_to_cleanup, mut recv_in_cleanup) = tokio::sync::mpsc::unbounded_channel();
     let (send_to_kill, _recv_kill) = tokio::sync::broadcast::channel(1);
     let recv_kill_in_cos = send_to_kill.subscribe();
@@ -411,12 +985,23 @@ pub async fn execute(port: u16, verbose: bool, tracing: bool) -> Result<()> {
     let handle_signals = tokio::spawn(cleanup_on_signal(send_to_cleanup.clone(), recv_kill_in_cos));
 
     let recv_kill_in_start_chain = send_to_kill.subscribe();
-    let child = start_chain(port, recv_kill_in_start_chain, verbose, tracing).await?;
+    let child = start_chain(
+        port,
+        name,
+        fork_url,
+        fork_block_number,
+        state_dir,
+        recv_kill_in_start_chain,
+        verbose,
+        tracing,
+    )
+    .await?;
     let Some(mut child) = child else {
-        return Err(eyre!(
-            "Port {} is already in use by another anvil process",
-            port
-        ));
+        return Err(
+            eyre!("Port {} is already in use by another anvil process", port).with_suggestion(
+                || "Check it with `kit chain status`, or replace it with `kit chain restart`.",
+            ),
+        );
     };
     let child_id = child.id() as i32;
 
@@ -426,6 +1011,10 @@ pub async fn execute(port: u16, verbose: bool, tracing: bool) -> Result<()> {
     });
 
     let _ = child.wait();
+    if let Some(state_dir) = resolve_state_dir(name, state_dir) {
+        save_deployments(port, &state_dir)?;
+    }
+    remove_chain_state(port)?;
 
     let _ = handle_signals.await;
     let _ = cleanup_anvil.await;
@@ -0,0 +1,160 @@
+use std::path::Path;
+
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use fs_err as fs;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::time::Duration;
+use tracing::{info, instrument, warn};
+
+use super::state::{read_deployments, write_deployments};
+use super::{
+    check_chain_running, fakechain_url, get_nonce, rpc_request, send_impersonated, wait_for_receipt,
+};
+
+/// A forge script broadcast, as recorded in `broadcast/<script>/<chain-id>/run-latest.json`
+#[derive(Debug, Deserialize)]
+struct Broadcast {
+    transactions: Vec<BroadcastTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastTransaction {
+    transaction_type: String,
+    contract_name: Option<String>,
+    contract_address: Option<String>,
+    transaction: TransactionFields,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionFields {
+    from: String,
+    to: Option<String>,
+    value: Option<String>,
+    gas: Option<String>,
+    // `data` in broadcasts of older forges
+    #[serde(alias = "data")]
+    input: Option<String>,
+    nonce: Option<String>,
+}
+
+fn parse_quantity(quantity: &str) -> Result<u64> {
+    Ok(u64::from_str_radix(quantity.trim_start_matches("0x"), 16)?)
+}
+
+// Replay `tx` from its (impersonated) sender, returning its receipt
+#[instrument(level = "trace", skip_all)]
+async fn replay_transaction(
+    url: &str,
+    client: &Client,
+    tx: &BroadcastTransaction,
+    receipt_timeout: Duration,
+) -> Result<serde_json::Value> {
+    let fields = &tx.transaction;
+
+    // contracts created by the sender land at the recorded addresses only from its recorded nonce
+    if let Some(ref recorded) = fields.nonce {
+        let recorded = parse_quantity(recorded)?;
+        let nonce = get_nonce(url, client, &fields.from).await?;
+        if nonce < recorded {
+            rpc_request(
+                url,
+                client,
+                "anvil_setNonce",
+                serde_json::json!([fields.from, format!("0x{recorded:x}")]),
+            )
+            .await?;
+        } else if nonce > recorded {
+            warn!(
+                "{} has sent more transactions than when the broadcast was recorded: contracts it \
+                 creates will not be at their recorded addresses",
+                fields.from,
+            );
+        }
+    }
+
+    let mut request = serde_json::json!({
+        "data": fields.input.as_deref().unwrap_or("0x"),
+    });
+    for (key, value) in [
+        ("to", &fields.to),
+        ("value", &fields.value),
+        ("gas", &fields.gas),
+    ] {
+        if let Some(value) = value {
+            request[key] = serde_json::json!(value);
+        }
+    }
+    let tx_hash = send_impersonated(url, client, &fields.from, request).await?;
+
+    let receipt = wait_for_receipt(url, client, &tx_hash, receipt_timeout).await?;
+    if receipt["status"].as_str() != Some("0x1") {
+        return Err(eyre!("Transaction {tx_hash} reverted"));
+    }
+    Ok(receipt)
+}
+
+/// kit chain replay: send the transactions of a forge script broadcast to the fakechain on
+///  `port`, each from its recorded sender, recording the addresses of contracts it creates;
+///  fail if one is not mined within `receipt_timeout`
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_replay(
+    port: u16,
+    broadcast_path: &Path,
+    receipt_timeout: Duration,
+) -> Result<()> {
+    check_chain_running(port).await?;
+
+    let broadcast: Broadcast = serde_json::from_slice(&fs::read(broadcast_path)?)
+        .map_err(|e| eyre!("Failed to parse broadcast {broadcast_path:?}: {e}"))
+        .with_suggestion(|| {
+            "Pass a forge broadcast, e.g. `broadcast/Deploy.s.sol/8453/run-latest.json`."
+        })?;
+
+    let url = fakechain_url(port);
+    let client = Client::new();
+    let mut deployments = read_deployments(port)?;
+    let count = broadcast.transactions.len();
+    for (i, tx) in broadcast.transactions.iter().enumerate() {
+        let description = match tx.contract_name {
+            Some(ref name) => format!("{} {name}", tx.transaction_type),
+            None => tx.transaction_type.clone(),
+        };
+        let receipt = replay_transaction(&url, &client, tx, receipt_timeout)
+            .await
+            .map_err(|e| {
+                eyre!(
+                    "Failed to replay transaction {}/{count} ({description}): {e}",
+                    i + 1
+                )
+            })?;
+
+        if tx.transaction_type.starts_with("CREATE") {
+            let address = receipt["contractAddress"]
+                .as_str()
+                .map(|a| a.to_string())
+                .or_else(|| tx.contract_address.clone());
+            if let (Some(name), Some(address)) = (&tx.contract_name, address) {
+                if tx
+                    .contract_address
+                    .as_ref()
+                    .is_some_and(|recorded| !recorded.eq_ignore_ascii_case(&address))
+                {
+                    warn!("{name} deployed at {address}, not its recorded address");
+                }
+                info!("Deployed {name} at {address}");
+                deployments.insert(name.clone(), address);
+                // recorded as it is deployed, so a later failure does not lose it
+                write_deployments(port, &deployments)?;
+            }
+        } else {
+            info!("Replayed transaction {}/{count}: {description}", i + 1);
+        }
+    }
+    info!("Replayed {count} transaction(s) from {broadcast_path:?}.");
+    Ok(())
+}
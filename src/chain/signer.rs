@@ -0,0 +1,121 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use alloy::network::{eip2718::Encodable2718, EthereumWallet, TransactionBuilder};
+use alloy::primitives::Address;
+use alloy::rpc::types::eth::{TransactionInput, TransactionRequest};
+use alloy::signers::local::{LocalSigner, PrivateKeySigner};
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use reqwest::Client;
+use tracing::{debug, instrument};
+
+use super::{get_nonce, rpc_request};
+
+/// A key signing the transactions of chain commands, which otherwise have anvil impersonate
+///  their senders: needed for chains that are not anvil's, e.g. testnets
+pub struct Signer {
+    pub address: Address,
+    wallet: EthereumWallet,
+}
+
+/// The signer given by `--private-key` or `--keystore`, if either
+#[instrument(level = "trace", skip_all)]
+pub fn read_signer(
+    private_key: Option<&str>,
+    keystore_path: Option<&Path>,
+) -> Result<Option<Signer>> {
+    let signer = match (private_key, keystore_path) {
+        (None, None) => return Ok(None),
+        (Some(private_key), None) => PrivateKeySigner::from_str(private_key)
+            .map_err(|e| eyre!("Invalid private key: {e}"))?,
+        (None, Some(keystore_path)) => {
+            let password = rpassword::prompt_password("Enter password: ")?;
+            LocalSigner::decrypt_keystore(keystore_path, password)?
+        }
+        (Some(_), Some(_)) => {
+            return Err(eyre!(
+                "Must supply at most one of `--private-key` & `--keystore`"
+            ))
+        }
+    };
+    Ok(Some(Signer {
+        address: signer.address(),
+        wallet: EthereumWallet::from(signer),
+    }))
+}
+
+fn parse_quantity(quantity: &serde_json::Value) -> Result<u128> {
+    let hex = quantity
+        .as_str()
+        .ok_or_else(|| eyre!("unexpected quantity: {quantity}"))?;
+    Ok(u128::from_str_radix(hex.trim_start_matches("0x"), 16)?)
+}
+
+impl Signer {
+    /// Fail if `user` asks to be sent `from` an address other than the signer's
+    pub fn check_sender(&self, user: &str, from: Option<&str>) -> Result<()> {
+        match from {
+            Some(from) if !from.eq_ignore_ascii_case(&self.address.to_string()) => Err(eyre!(
+                "{user} is sent from {from}, but transactions are signed by {}",
+                self.address
+            )
+            .with_suggestion(|| "Sign with the key of that address, or drop its `from`.")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sign a transaction sending `data` to `to` (or deploying it, if `None`) & send it to the
+    ///  chain at `url`, returning its hash
+    #[instrument(level = "trace", skip_all)]
+    pub async fn send(
+        &self,
+        url: &str,
+        client: &Client,
+        to: Option<Address>,
+        data: Vec<u8>,
+    ) -> Result<String> {
+        let from = self.address.to_string();
+        let mut request =
+            serde_json::json!({"from": from, "data": format!("0x{}", hex::encode(&data))});
+        if let Some(to) = to {
+            request["to"] = serde_json::json!(to.to_string());
+        }
+        let chain_id =
+            parse_quantity(&rpc_request(url, client, "eth_chainId", serde_json::json!([])).await?)?;
+        let gas_limit = parse_quantity(
+            &rpc_request(url, client, "eth_estimateGas", serde_json::json!([request])).await?,
+        )?;
+        let gas_price = parse_quantity(
+            &rpc_request(url, client, "eth_gasPrice", serde_json::json!([])).await?,
+        )?;
+        let nonce = get_nonce(url, client, &from).await?;
+
+        let tx = TransactionRequest::default()
+            .from(self.address)
+            .input(TransactionInput::new(data.into()))
+            .nonce(nonce)
+            .with_chain_id(chain_id as u64)
+            .with_gas_limit(gas_limit as u64)
+            .with_gas_price(gas_price);
+        let tx = match to {
+            Some(to) => tx.to(to),
+            None => tx.into_create(),
+        };
+        let tx_envelope = tx.build(&self.wallet).await?;
+        debug!("sending transaction signed by {from}, nonce {nonce}");
+        let tx_hash = rpc_request(
+            url,
+            client,
+            "eth_sendRawTransaction",
+            serde_json::json!([format!("0x{}", hex::encode(tx_envelope.encoded_2718()))]),
+        )
+        .await?;
+        tx_hash
+            .as_str()
+            .map(|h| h.to_string())
+            .ok_or_else(|| eyre!("unexpected transaction hash: {tx_hash}"))
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use fs_err as fs;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use crate::KIT_CACHE;
+
+use super::state::{now, read_chain_state, read_deployments, write_deployments};
+use super::{check_chain_running, fakechain_url, rpc_request};
+
+const SNAPSHOT_DIR_NAME: &str = "chains/snapshots";
+
+/// Chain state saved by `kit chain snapshot`, at `KIT_CACHE/chains/snapshots/<name>.json`
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    /// As returned by `anvil_dumpState`
+    state: String,
+    /// Addresses of contracts `kit chain deploy` deployed, by name
+    deployments: BTreeMap<String, String>,
+    /// Seconds since the epoch
+    created_at: u64,
+    /// `evm_snapshot` taken alongside `state`, valid only while the same anvil runs
+    #[serde(default)]
+    session: Option<SnapshotSession>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotSession {
+    /// PID of the anvil the snapshot was taken on
+    pid: u32,
+    /// When that anvil was started, in seconds since the epoch
+    started_at: u64,
+    /// As returned by `evm_snapshot`
+    id: String,
+}
+
+// An `evm_snapshot` of the chain at `url`, tied to the fakechain on `port` if kit started it
+async fn take_session(port: u16, url: &str, client: &Client) -> Result<Option<SnapshotSession>> {
+    let Some(chain_state) = read_chain_state(port)? else {
+        return Ok(None);
+    };
+    let id = rpc_request(url, client, "evm_snapshot", serde_json::json!([])).await?;
+    let id = id
+        .as_str()
+        .ok_or_else(|| eyre!("unexpected evm_snapshot result: {id}"))?
+        .to_string();
+    Ok(Some(SnapshotSession {
+        pid: chain_state.pid,
+        started_at: chain_state.started_at,
+        id,
+    }))
+}
+
+fn get_snapshot_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(eyre!("Invalid snapshot name {name:?}")
+            .with_suggestion(|| "Use a name without path separators."));
+    }
+    Ok(PathBuf::from(KIT_CACHE)
+        .join(SNAPSHOT_DIR_NAME)
+        .join(format!("{name}.json")))
+}
+
+/// kit chain snapshot: save the state of the chain on `port` as `name`
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_snapshot(port: u16, name: &str) -> Result<()> {
+    check_chain_running(port).await?;
+    let path = get_snapshot_path(name)?;
+
+    let url = fakechain_url(port);
+    let client = Client::new();
+    let state = rpc_request(&url, &client, "anvil_dumpState", serde_json::json!([])).await?;
+    let state = state
+        .as_str()
+        .ok_or_else(|| eyre!("unexpected anvil_dumpState result: {state}"))?
+        .to_string();
+    let snapshot = Snapshot {
+        state,
+        deployments: read_deployments(port)?,
+        created_at: now(),
+        session: take_session(port, &url, &client).await?,
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string(&snapshot)?)?;
+    info!("Saved the state of the chain on port {port} as snapshot {name}.");
+    Ok(())
+}
+
+/// kit chain restore: return the chain on `port` to the state saved as `name`, discarding
+///  everything since
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_restore(port: u16, name: &str) -> Result<()> {
+    check_chain_running(port).await?;
+    let path = get_snapshot_path(name)?;
+    if !path.exists() {
+        return Err(eyre!("No snapshot {name}")
+            .with_suggestion(|| format!("Save one with `kit chain snapshot {name}`.")));
+    }
+    let mut snapshot: Snapshot = serde_json::from_slice(&fs::read(&path)?)?;
+
+    let url = fakechain_url(port);
+    let client = Client::new();
+    let chain_state = read_chain_state(port)?;
+    let is_same_session = match (&snapshot.session, &chain_state) {
+        (Some(session), Some(chain_state)) => {
+            session.pid == chain_state.pid && session.started_at == chain_state.started_at
+        }
+        _ => false,
+    };
+    // anvil drops a snapshot once reverted to (& any taken after it), so this may fail
+    let is_reverted = is_same_session
+        && rpc_request(
+            &url,
+            &client,
+            "evm_revert",
+            serde_json::json!([snapshot.session.as_ref().map(|s| &s.id)]),
+        )
+        .await?
+        .as_bool()
+        .unwrap_or(false);
+    if !is_reverted {
+        // `anvil_loadState` merges into the current state: start from a fresh chain (or the
+        //  forked block) so accounts & storage written since the snapshot do not survive
+        rpc_request(&url, &client, "anvil_reset", serde_json::json!([])).await?;
+        rpc_request(
+            &url,
+            &client,
+            "anvil_loadState",
+            serde_json::json!([snapshot.state]),
+        )
+        .await?;
+    }
+    write_deployments(port, &snapshot.deployments)?;
+    // so the snapshot can be restored again in this session
+    snapshot.session = take_session(port, &url, &client).await?;
+    fs::write(&path, serde_json::to_string(&snapshot)?)?;
+    info!("Restored snapshot {name} to the chain on port {port}.");
+    Ok(())
+}
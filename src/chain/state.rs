@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::Result;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use crate::KIT_CACHE;
+
+const CHAIN_STATE_DIR_NAME: &str = "chains";
+
+/// Name of the file `kit chain start --state <dir>` keeps anvil's state in
+pub const ANVIL_STATE_FILE_NAME: &str = "anvil-state.json";
+const DEPLOYMENTS_FILE_NAME: &str = "deployments.json";
+
+/// A fakechain started by kit, recorded at `KIT_CACHE/chains/<port>.json` while it runs, so
+///  that other kit commands (and `kit chain status`) can tell whether it is running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainState {
+    /// PID of its anvil
+    pub pid: u32,
+    pub port: u16,
+    /// Name given with `kit chain start --name`
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Arguments its anvil was started with
+    pub args: Vec<String>,
+    /// Hash of the contracts & transactions it was initialized with
+    pub fingerprint: String,
+    /// Seconds since the epoch
+    pub started_at: u64,
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn get_chain_state_path(port: u16) -> PathBuf {
+    PathBuf::from(KIT_CACHE)
+        .join(CHAIN_STATE_DIR_NAME)
+        .join(format!("{port}.json"))
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn write_chain_state(state: &ChainState) -> Result<()> {
+    let path = get_chain_state_path(state.port);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// The recorded state of the fakechain on `port`, if kit started one there
+#[instrument(level = "trace", skip_all)]
+pub fn read_chain_state(port: u16) -> Result<Option<ChainState>> {
+    let path = get_chain_state_path(port);
+    if !path.exists() {
+        return Ok(None);
+    }
+    match serde_json::from_slice(&fs::read(&path)?) {
+        Ok(state) => Ok(Some(state)),
+        Err(e) => {
+            // Written by another version of kit: treat as unrecorded
+            debug!("ignoring unreadable chain state {path:?}: {e}");
+            Ok(None)
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn remove_chain_state(port: u16) -> Result<()> {
+    // deployments do not outlive the chain they were deployed to
+    for path in [get_chain_state_path(port), get_deployments_path(port, None)] {
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The recorded state of the running fakechain named `name`, if any
+#[instrument(level = "trace", skip_all)]
+pub fn find_chain_state_by_name(name: &str) -> Result<Option<ChainState>> {
+    let dir = PathBuf::from(KIT_CACHE).join(CHAIN_STATE_DIR_NAME);
+    if !dir.exists() {
+        return Ok(None);
+    }
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        // `<port>.json`, but not `<port>-deployments.json`
+        let Some(port) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse::<u16>().ok())
+        else {
+            continue;
+        };
+        if let Some(state) = read_chain_state(port)? {
+            if state.name.as_deref() == Some(name) && is_pid_alive(state.pid) {
+                return Ok(Some(state));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Directory a fakechain named `name` keeps its state in, unless started with `--state`
+pub fn get_named_state_dir(name: &str) -> PathBuf {
+    PathBuf::from(KIT_CACHE)
+        .join(CHAIN_STATE_DIR_NAME)
+        .join("named")
+        .join(name)
+}
+
+/// Whether a process with `pid` exists
+pub fn is_pid_alive(pid: u32) -> bool {
+    nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid as i32), None).is_ok()
+}
+
+// Deployments to a chain reached with `--rpc` are kept apart from any fakechain's, by RPC URL
+fn get_deployments_path(port: u16, rpc_url: Option<&str>) -> PathBuf {
+    let dir = PathBuf::from(KIT_CACHE).join(CHAIN_STATE_DIR_NAME);
+    match rpc_url {
+        None => dir.join(format!("{port}-deployments.json")),
+        Some(rpc_url) => {
+            let key: String = rpc_url
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            dir.join("rpc").join(format!("{key}-deployments.json"))
+        }
+    }
+}
+
+/// Addresses of the contracts `kit chain deploy` deployed to the fakechain on `port`, by name
+#[instrument(level = "trace", skip_all)]
+pub fn read_deployments(port: u16) -> Result<BTreeMap<String, String>> {
+    read_deployments_on(port, None)
+}
+
+/// Addresses of the contracts `kit chain deploy` deployed to the chain at `rpc_url`, if given,
+///  else to the fakechain on `port`, by name
+#[instrument(level = "trace", skip_all)]
+pub fn read_deployments_on(port: u16, rpc_url: Option<&str>) -> Result<BTreeMap<String, String>> {
+    let path = get_deployments_path(port, rpc_url);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_json::from_slice(&fs::read(&path)?)?)
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn write_deployments(port: u16, deployments: &BTreeMap<String, String>) -> Result<()> {
+    write_deployments_on(port, None, deployments)
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn write_deployments_on(
+    port: u16,
+    rpc_url: Option<&str>,
+    deployments: &BTreeMap<String, String>,
+) -> Result<()> {
+    let path = get_deployments_path(port, rpc_url);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(deployments)?)?;
+    Ok(())
+}
+
+/// Copy the deployments recorded in `state_dir` (by `save_deployments`) to the fakechain on
+///  `port`, which was started from the anvil state in `state_dir`
+#[instrument(level = "trace", skip_all)]
+pub fn load_deployments(port: u16, state_dir: &Path) -> Result<()> {
+    let path = state_dir.join(DEPLOYMENTS_FILE_NAME);
+    if path.exists() {
+        let deployments = serde_json::from_slice(&fs::read(&path)?)?;
+        write_deployments(port, &deployments)?;
+    }
+    Ok(())
+}
+
+/// Copy the deployments recorded for the fakechain on `port` to `state_dir`, alongside the
+///  anvil state it dumps there on exit
+#[instrument(level = "trace", skip_all)]
+pub fn save_deployments(port: u16, state_dir: &Path) -> Result<()> {
+    let deployments = read_deployments(port)?;
+    fs::write(
+        state_dir.join(DEPLOYMENTS_FILE_NAME),
+        serde_json::to_string_pretty(&deployments)?,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use alloy::dyn_abi::{DynSolType, DynSolValue, FunctionExt, Specifier};
+use alloy::json_abi::Function;
+use alloy::primitives::{Address, U256};
+use color_eyre::{
+    eyre::{eyre, Result},
+    Section,
+};
+use fs_err as fs;
+use reqwest::Client;
+use tracing::{info, instrument};
+
+use super::call::{
+    decode_revert, encode_call, encode_manifest_call, eth_call, resolve_function, resolve_target,
+};
+use super::deploy::{Assertion, DeployManifest, DEPLOY_MANIFEST_NAME};
+use super::logs::{format_value, read_deployment_abi};
+use super::{check_chain_running, fakechain_url, rpc_request, OWNER_ADDRESS};
+
+// Type of what `function` returns: a tuple, if it returns several values
+fn output_type(function: &Function) -> Result<DynSolType> {
+    let mut types = function
+        .outputs
+        .iter()
+        .map(|param| param.resolve())
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(if types.len() == 1 {
+        types.pop().unwrap()
+    } else {
+        DynSolType::Tuple(types)
+    })
+}
+
+// Check that calling `signature` on `address` returns `expect`
+#[instrument(level = "trace", skip_all)]
+async fn check_call(
+    url: &str,
+    client: &Client,
+    project_dir: &Path,
+    address: Address,
+    deployment: Option<String>,
+    signature: &str,
+    assertion: &Assertion,
+) -> Result<()> {
+    let abi = deployment.and_then(|name| read_deployment_abi(project_dir, &name).ok());
+    let function = resolve_function(signature, abi.as_ref())?;
+    let data = encode_call(&function, &assertion.args)?;
+    let output = eth_call(url, client, OWNER_ADDRESS, address, &data)
+        .await?
+        .map_err(|revert| eyre!("reverted: {}", decode_revert(&revert, abi.as_ref())))?;
+
+    let ty = output_type(&function)?;
+    let mut values = function
+        .abi_decode_output(&output, true)
+        .map_err(|e| eyre!("failed to decode output 0x{}: {e}", hex::encode(&output)))?;
+    let actual = if values.len() == 1 {
+        values.pop().unwrap()
+    } else {
+        DynSolValue::Tuple(values)
+    };
+    let expected = ty
+        .coerce_str(&assertion.expect)
+        .map_err(|e| eyre!("`expect` {:?} is not a {ty}: {e}", assertion.expect))?;
+    if actual != expected {
+        return Err(eyre!(
+            "returned {}, expected {}",
+            format_value(&actual),
+            format_value(&expected),
+        ));
+    }
+    Ok(())
+}
+
+// Check that storage `slot` of `address` holds `expect`
+#[instrument(level = "trace", skip_all)]
+async fn check_slot(
+    url: &str,
+    client: &Client,
+    address: Address,
+    slot: &str,
+    expect: &str,
+) -> Result<()> {
+    let slot = U256::from_str(slot).map_err(|e| eyre!("invalid `slot` {slot:?}: {e}"))?;
+    let expected = U256::from_str(expect).map_err(|e| eyre!("invalid `expect` {expect:?}: {e}"))?;
+    let contents = rpc_request(
+        url,
+        client,
+        "eth_getStorageAt",
+        serde_json::json!([address.to_string(), format!("0x{slot:x}"), "latest"]),
+    )
+    .await?;
+    let actual = U256::from_str(contents.as_str().unwrap_or_default())
+        .map_err(|e| eyre!("unexpected eth_getStorageAt result {contents}: {e}"))?;
+    if actual != expected {
+        return Err(eyre!("holds 0x{actual:x}, expected 0x{expected:x}"));
+    }
+    Ok(())
+}
+
+// Check `assertion` against the chain at `url` (the fakechain on `port`), failing with what does
+//  not hold
+async fn check_assertion(
+    port: u16,
+    url: &str,
+    client: &Client,
+    project_dir: &Path,
+    assertion: &Assertion,
+) -> Result<()> {
+    let (address, deployment) = resolve_target(port, None, url, client, &assertion.target).await?;
+    match (&assertion.call, &assertion.slot) {
+        (Some(signature), None) => {
+            check_call(
+                url,
+                client,
+                project_dir,
+                address,
+                deployment,
+                signature,
+                assertion,
+            )
+            .await
+        }
+        (None, Some(slot)) => check_slot(url, client, address, slot, &assertion.expect).await,
+        _ => Err(eyre!("must set exactly one of `call` & `slot`")),
+    }
+}
+
+/// Check `assertion` of the `kit-deploy.toml` of the foundry project at `project_dir` without a
+///  chain: that its call encodes & its `expect` is a value of what it returns, or that its slot &
+///  `expect` are words; `listed` holds the contracts the manifest deploys
+pub fn check_assertion_config(
+    project_dir: &Path,
+    listed: &BTreeMap<String, String>,
+    assertion: &Assertion,
+) -> Result<()> {
+    match (&assertion.call, &assertion.slot) {
+        (Some(signature), None) => {
+            let (function, _) = encode_manifest_call(
+                project_dir,
+                listed,
+                &assertion.target,
+                signature,
+                &assertion.args,
+            )?;
+            let ty = output_type(&function)?;
+            ty.coerce_str(&assertion.expect)
+                .map_err(|e| eyre!("`expect` {:?} is not a {ty}: {e}", assertion.expect))?;
+        }
+        (None, Some(slot)) => {
+            U256::from_str(slot).map_err(|e| eyre!("invalid `slot` {slot:?}: {e}"))?;
+            U256::from_str(&assertion.expect)
+                .map_err(|e| eyre!("invalid `expect` {:?}: {e}", assertion.expect))?;
+        }
+        _ => return Err(eyre!("must set exactly one of `call` & `slot`")),
+    }
+    Ok(())
+}
+
+/// kit chain verify: check the `assertions` of the `kit-deploy.toml` of the foundry project at
+///  `project_dir` against the chain on `port`, reporting every one that does not hold
+#[instrument(level = "trace", skip_all)]
+pub async fn execute_verify(port: u16, project_dir: &Path) -> Result<()> {
+    check_chain_running(port).await?;
+
+    let manifest_path = project_dir.join(DEPLOY_MANIFEST_NAME);
+    if !manifest_path.exists() {
+        return Err(eyre!("No {manifest_path:?}"));
+    }
+    let manifest: DeployManifest = toml::from_str(&fs::read_to_string(&manifest_path)?)
+        .map_err(|e| eyre!("{manifest_path:?} is not a valid {DEPLOY_MANIFEST_NAME}: {e}"))?;
+    if manifest.assertions.is_empty() {
+        return Err(eyre!("No assertions in {manifest_path:?}")
+            .with_suggestion(|| "List them as `[[assertions]]`, each with a `call` or `slot`."));
+    }
+
+    let url = fakechain_url(port);
+    let client = Client::new();
+    let mut problems = vec![];
+    for (i, assertion) in manifest.assertions.iter().enumerate() {
+        if let Err(e) = check_assertion(port, &url, &client, project_dir, assertion).await {
+            problems.push(format!("{} ({}): {e}", i + 1, assertion.describe()));
+        }
+    }
+    if !problems.is_empty() {
+        return Err(eyre!(
+            "{}/{} assertion(s) in {manifest_path:?} failed:\n{}",
+            problems.len(),
+            manifest.assertions.len(),
+            problems
+                .iter()
+                .map(|p| format!(" - {p}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ));
+    }
+    info!(
+        "All {} assertion(s) in {manifest_path:?} hold.",
+        manifest.assertions.len()
+    );
+    Ok(())
+}
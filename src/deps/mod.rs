@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use color_eyre::{eyre::eyre, Result, Section};
+use fs_err as fs;
+use serde::Serialize;
+use tracing::{info, instrument, warn};
+
+use crate::build::{discover_workspace_packages, WorkspacePackage};
+
+#[derive(Debug, Serialize)]
+struct DepsGraph {
+    packages: Vec<PackageNode>,
+    /// Imported interfaces that no world in the workspace exports and no WIT file defines,
+    ///  in packages with no external dependencies that could provide them
+    missing: Vec<MissingExport>,
+}
+
+#[derive(Debug, Serialize)]
+struct PackageNode {
+    /// `package:publisher`
+    id: String,
+    dir: PathBuf,
+    /// Dependencies from `metadata.json` found in the workspace
+    dependencies: Vec<String>,
+    /// Dependencies from `metadata.json` not found in the workspace: fetched from a node at build
+    external_dependencies: Vec<String>,
+    worlds: Vec<WorldNode>,
+}
+
+#[derive(Debug, Serialize)]
+struct WorldNode {
+    name: String,
+    imports: Vec<Import>,
+    exports: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Import {
+    interface: String,
+    /// `package:publisher/world` that exports the interface, or `package:publisher` of the
+    ///  package whose API defines it; `None` if not found in the workspace
+    provider: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MissingExport {
+    package: String,
+    world: String,
+    interface: String,
+}
+
+/// Interfaces defined, and worlds with their (imports, exports), in a package's `api/`
+#[derive(Debug, Default)]
+struct PackageApi {
+    interfaces: BTreeSet<String>,
+    worlds: BTreeMap<String, (Vec<String>, Vec<String>)>,
+}
+
+fn is_standard_interface(interface: &str) -> bool {
+    interface.contains("wasi") || interface.contains(':')
+}
+
+fn read_package_api(package_dir: &Path) -> Result<PackageApi> {
+    let interface_re = regex::Regex::new(r"(?m)^\s*interface\s+([\w-]+)\s*\{").unwrap();
+    let world_re = regex::Regex::new(r"(?s)world\s+([\w-]+)\s*\{([^}]*)\}").unwrap();
+    let import_re = regex::Regex::new(r"import\s+([^\s;]+)\s*;").unwrap();
+    let export_re = regex::Regex::new(r"export\s+([^\s;]+)\s*;").unwrap();
+
+    let mut api = PackageApi::default();
+    let api_dir = package_dir.join("api");
+    if !api_dir.is_dir() {
+        return Ok(api);
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(&api_dir)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| Some("wit") == path.extension().and_then(|e| e.to_str()))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let wit = fs::read_to_string(&path)?;
+        for cap in interface_re.captures_iter(&wit) {
+            api.interfaces.insert(cap[1].to_string());
+        }
+        for cap in world_re.captures_iter(&wit) {
+            let body = &cap[2];
+            let imports = import_re
+                .captures_iter(body)
+                .map(|c| c[1].to_string())
+                .filter(|i| !is_standard_interface(i))
+                .collect();
+            let exports = export_re
+                .captures_iter(body)
+                .map(|c| c[1].to_string())
+                .filter(|e| !is_standard_interface(e) && e != "init")
+                .collect();
+            api.worlds.insert(cap[1].to_string(), (imports, exports));
+        }
+    }
+    Ok(api)
+}
+
+#[instrument(level = "trace", skip_all)]
+fn make_deps_graph(packages: Vec<WorkspacePackage>) -> Result<DepsGraph> {
+    let ids: BTreeSet<String> = packages.iter().map(|p| p.package_id.clone()).collect();
+    let mut apis = BTreeMap::new();
+    for package in &packages {
+        apis.insert(package.package_id.clone(), read_package_api(&package.dir)?);
+    }
+
+    // interface -> provider: exporting worlds take precedence over defining packages
+    let mut providers: BTreeMap<String, String> = BTreeMap::new();
+    for (id, api) in &apis {
+        for interface in &api.interfaces {
+            providers
+                .entry(interface.clone())
+                .or_insert_with(|| id.clone());
+        }
+    }
+    for (id, api) in &apis {
+        for (world, (_, exports)) in &api.worlds {
+            for export in exports {
+                providers.insert(export.clone(), format!("{id}/{world}"));
+            }
+        }
+    }
+
+    let mut missing = vec![];
+    let mut package_nodes = vec![];
+    for package in packages {
+        let (dependencies, external_dependencies): (Vec<String>, Vec<String>) = package
+            .dependencies
+            .iter()
+            .cloned()
+            .partition(|dep| ids.contains(dep));
+        let api = apis.remove(&package.package_id).unwrap_or_default();
+        let mut worlds = vec![];
+        for (world, (imports, exports)) in api.worlds {
+            let imports = imports
+                .into_iter()
+                .map(|interface| {
+                    let provider = providers.get(&interface).cloned();
+                    // unresolved imports may be provided by an external dependency
+                    if provider.is_none() && external_dependencies.is_empty() {
+                        missing.push(MissingExport {
+                            package: package.package_id.clone(),
+                            world: world.clone(),
+                            interface: interface.clone(),
+                        });
+                    }
+                    Import {
+                        interface,
+                        provider,
+                    }
+                })
+                .collect();
+            worlds.push(WorldNode {
+                name: world,
+                imports,
+                exports,
+            });
+        }
+        package_nodes.push(PackageNode {
+            id: package.package_id,
+            dir: package.dir,
+            dependencies,
+            external_dependencies,
+            worlds,
+        });
+    }
+    Ok(DepsGraph {
+        packages: package_nodes,
+        missing,
+    })
+}
+
+fn render_text(graph: &DepsGraph) -> String {
+    let mut out = String::new();
+    for package in &graph.packages {
+        out.push_str(&format!("{} ({})\n", package.id, package.dir.display()));
+        for dep in &package.dependencies {
+            out.push_str(&format!("  depends on {dep}\n"));
+        }
+        for dep in &package.external_dependencies {
+            out.push_str(&format!("  depends on {dep} (external)\n"));
+        }
+        for world in &package.worlds {
+            out.push_str(&format!("  world {}\n", world.name));
+            for import in &world.imports {
+                match import.provider {
+                    Some(ref provider) => out.push_str(&format!(
+                        "    imports {} from {provider}\n",
+                        import.interface,
+                    )),
+                    None if package.external_dependencies.is_empty() => {
+                        out.push_str(&format!("    imports {} (MISSING)\n", import.interface))
+                    }
+                    None => out.push_str(&format!(
+                        "    imports {} (not in workspace)\n",
+                        import.interface,
+                    )),
+                }
+            }
+            for export in &world.exports {
+                out.push_str(&format!("    exports {export}\n"));
+            }
+        }
+    }
+    out
+}
+
+fn render_dot(graph: &DepsGraph) -> String {
+    let mut out = String::from("digraph deps {\n    rankdir=LR;\n");
+    for package in &graph.packages {
+        out.push_str(&format!("    \"{}\" [shape=box];\n", package.id));
+        for dep in &package.dependencies {
+            out.push_str(&format!("    \"{}\" -> \"{dep}\";\n", package.id));
+        }
+        for dep in &package.external_dependencies {
+            out.push_str(&format!("    \"{dep}\" [shape=box, style=dashed];\n"));
+            out.push_str(&format!("    \"{}\" -> \"{dep}\";\n", package.id));
+        }
+        for world in &package.worlds {
+            let world_id = format!("{}/{}", package.id, world.name);
+            out.push_str(&format!("    \"{world_id}\" [shape=ellipse];\n"));
+            out.push_str(&format!(
+                "    \"{}\" -> \"{world_id}\" [style=dotted, arrowhead=none];\n",
+                package.id,
+            ));
+            for import in &world.imports {
+                match import.provider {
+                    Some(ref provider) => out.push_str(&format!(
+                        "    \"{world_id}\" -> \"{provider}\" [label=\"{}\"];\n",
+                        import.interface,
+                    )),
+                    None => out.push_str(&format!(
+                        "    \"{world_id}\" -> \"{} (missing)\" [color=red];\n",
+                        import.interface,
+                    )),
+                }
+            }
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn execute(root_dir: &Path, format: &str) -> Result<()> {
+    let graph = make_deps_graph(discover_workspace_packages(root_dir)?)?;
+
+    let rendered = match format {
+        "text" => render_text(&graph),
+        "dot" => render_dot(&graph),
+        "json" => serde_json::to_string_pretty(&graph)?,
+        _ => return Err(eyre!("Unknown format {format:?}")),
+    };
+    info!("{}", rendered.trim_end());
+
+    if !graph.missing.is_empty() {
+        for m in &graph.missing {
+            warn!(
+                "{} world {} imports {}, which nothing in the workspace exports",
+                m.package, m.world, m.interface,
+            );
+        }
+        return Err(
+            eyre!("Found {} imports with no exporter", graph.missing.len()).with_suggestion(|| {
+                "Add the exporting package to the workspace, or fetch its API with `kit view-api`."
+            }),
+        );
+    }
+    Ok(())
+}
@@ -1,9 +1,11 @@
+pub mod api;
 pub mod boot_fake_node;
 pub mod boot_real_node;
 pub mod build;
 pub mod build_start_package;
 pub mod chain;
 pub mod connect;
+pub mod deps;
 pub mod dev_ui;
 pub mod inject_message;
 pub mod new;
@@ -14,7 +16,9 @@ pub mod run_tests;
 pub mod setup;
 pub mod start_package;
 pub mod update;
+pub mod verify;
 pub mod view_api;
+pub mod wit;
 
 pub const KIT_CACHE: &str = "/tmp/hyperware-kit-cache";
 pub const KIT_LOG_PATH_DEFAULT: &str = "/tmp/hyperware-kit-cache/logs/log.log";
@@ -3,7 +3,9 @@ use std::env;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use clap::{builder::PossibleValuesParser, command, value_parser, Arg, ArgAction, Command};
+use clap::{
+    builder::PossibleValuesParser, command, value_parser, Arg, ArgAction, ArgGroup, Command,
+};
 use color_eyre::{
     eyre::{eyre, Result},
     Section,
@@ -23,9 +25,9 @@ use tracing_subscriber::{
 };
 
 use kit::{
-    boot_fake_node, boot_real_node, build, build_start_package, chain, connect, dev_ui,
+    api, boot_fake_node, boot_real_node, build, build_start_package, chain, connect, deps, dev_ui,
     inject_message, new, publish, remove_package, reset_cache, run_tests, setup, start_package,
-    update, view_api, KIT_LOG_PATH_DEFAULT,
+    update, verify, view_api, wit, KIT_LOG_PATH_DEFAULT,
 };
 
 const MAX_REMOTE_VALUES: usize = 3;
@@ -100,7 +102,28 @@ async fn get_latest_commit_sha_from_branch(
     Ok(Some(serde_json::from_slice(&bytes)?))
 }
 
-fn init_tracing(log_path: PathBuf) -> tracing_appender::non_blocking::WorkerGuard {
+/// Tracing is initialized before args are parsed (building the app may log), so read
+///  `--log-format` from the raw args; clap validates it when parsing the subcommand
+fn get_log_format() -> String {
+    let mut args = env::args();
+    while let Some(arg) = args.next() {
+        if arg == "--log-format" {
+            return args.next().unwrap_or_default();
+        }
+        if let Some(log_format) = arg.strip_prefix("--log-format=") {
+            return log_format.to_string();
+        }
+        if arg == "--" {
+            break;
+        }
+    }
+    build::DEFAULT_LOG_FORMAT.to_string()
+}
+
+fn init_tracing(
+    log_path: PathBuf,
+    log_format: &str,
+) -> tracing_appender::non_blocking::WorkerGuard {
     // Define a fixed log file name with rolling based on size or execution instance.
     let log_parent_path = log_path.parent().unwrap();
     let log_file_name = log_path.file_name().and_then(|f| f.to_str()).unwrap();
@@ -118,46 +141,90 @@ fn init_tracing(log_path: PathBuf) -> tracing_appender::non_blocking::WorkerGuar
         .into_iter()
         .filter(|&l| l <= level)
         .collect();
+    let is_json = log_format == "json";
+    // structured build events are only output with `--log-format json`
     let stdout_filter = filter::filter_fn(move |metadata: &tracing::Metadata<'_>| {
-        allowed_levels.contains(metadata.level())
+        if metadata.target() == build::BUILD_LOG_TARGET {
+            is_json
+        } else {
+            allowed_levels.contains(metadata.level())
+        }
     });
 
     let stderr_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(STDERR_LOG_LEVEL_DEFAULT));
+        .unwrap_or_else(|_| EnvFilter::new(STDERR_LOG_LEVEL_DEFAULT))
+        .add_directive(format!("{}=off", build::BUILD_LOG_TARGET).parse().unwrap());
     let file_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new(FILE_LOG_LEVEL_DEFAULT))
         .add_directive("hyper=off".parse().unwrap())
         .add_directive("reqwest=off".parse().unwrap());
 
-    tracing_subscriber::registry()
-        .with(
-            tracing_fmt::layer()
-                .event_format(AnsiPreservingFormatter::new(
-                    tracing_fmt::format()
-                        .without_time()
-                        .with_level(false)
-                        .with_target(false),
-                ))
-                .fmt_fields(PrettyFields::new().display_messages())
-                .with_writer(std::io::stdout)
-                .with_ansi(true)
-                .with_filter(stdout_filter),
+    let (stdout_layer, stdout_json_layer) = if is_json {
+        (
+            None,
+            Some(
+                tracing_fmt::layer()
+                    .json()
+                    .with_writer(std::io::stdout)
+                    .with_ansi(false)
+                    .with_filter(stdout_filter),
+            ),
         )
-        .with(
-            tracing_fmt::layer()
-                .event_format(AnsiPreservingFormatter::new(
-                    tracing_fmt::format()
-                        .without_time()
-                        .with_level(true)
-                        .with_target(false)
-                        .with_file(true)
-                        .with_line_number(true),
-                ))
-                .fmt_fields(PrettyFields::new().display_messages())
-                .with_writer(std::io::stderr)
-                .with_ansi(true)
-                .with_filter(stderr_filter),
+    } else {
+        (
+            Some(
+                tracing_fmt::layer()
+                    .event_format(AnsiPreservingFormatter::new(
+                        tracing_fmt::format()
+                            .without_time()
+                            .with_level(false)
+                            .with_target(false),
+                    ))
+                    .fmt_fields(PrettyFields::new().display_messages())
+                    .with_writer(std::io::stdout)
+                    .with_ansi(true)
+                    .with_filter(stdout_filter),
+            ),
+            None,
+        )
+    };
+    let (stderr_layer, stderr_json_layer) = if is_json {
+        (
+            None,
+            Some(
+                tracing_fmt::layer()
+                    .json()
+                    .with_writer(std::io::stderr)
+                    .with_ansi(false)
+                    .with_filter(stderr_filter),
+            ),
         )
+    } else {
+        (
+            Some(
+                tracing_fmt::layer()
+                    .event_format(AnsiPreservingFormatter::new(
+                        tracing_fmt::format()
+                            .without_time()
+                            .with_level(true)
+                            .with_target(false)
+                            .with_file(true)
+                            .with_line_number(true),
+                    ))
+                    .fmt_fields(PrettyFields::new().display_messages())
+                    .with_writer(std::io::stderr)
+                    .with_ansi(true)
+                    .with_filter(stderr_filter),
+            ),
+            None,
+        )
+    };
+
+    tracing_subscriber::registry()
+        .with(stdout_layer)
+        .with(stdout_json_layer)
+        .with(stderr_layer)
+        .with(stderr_json_layer)
         .with(
             tracing_fmt::layer()
                 .with_writer(non_blocking)
@@ -177,6 +244,32 @@ async fn execute(
     matches: Option<(&str, &clap::ArgMatches)>,
 ) -> Result<()> {
     match matches {
+        Some(("api", matches)) => {
+            let Some((subcommand, matches)) = matches.subcommand() else {
+                warn!("Invalid subcommand. Usage:\n{}", usage);
+                return Ok(());
+            };
+            let package_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
+
+            match subcommand {
+                "export" => api::execute_export(
+                    &package_dir,
+                    matches.get_one::<PathBuf>("OUTPUT").map(|o| o.as_path()),
+                ),
+                "publish-client" => api::execute_publish_client(
+                    &package_dir,
+                    matches.get_one::<String>("VERSION").map(|v| v.as_str()),
+                    matches.get_one::<String>("REGISTRY").map(|r| r.as_str()),
+                    matches.get_one::<PathBuf>("OUT").map(|o| o.as_path()),
+                    *matches.get_one::<bool>("DRY_RUN").unwrap(),
+                    *matches.get_one::<bool>("VERBOSE").unwrap(),
+                ),
+                _ => {
+                    warn!("Invalid subcommand. Usage:\n{}", usage);
+                    Ok(())
+                }
+            }
+        }
         Some(("boot-fake-node", matches)) => {
             let runtime_path = matches
                 .get_one::<String>("PATH")
@@ -185,6 +278,9 @@ async fn execute(
             let node_home = PathBuf::from(matches.get_one::<String>("HOME").unwrap());
             let node_port = matches.get_one::<u16>("NODE_PORT").unwrap();
             let fakechain_port = matches.get_one::<u16>("FAKECHAIN_PORT").unwrap();
+            let fakechain_name = matches
+                .get_one::<String>("FAKECHAIN_NAME")
+                .map(|s| s.as_str());
             let rpc = matches
                 .get_one::<String>("RPC_ENDPOINT")
                 .and_then(|s| Some(s.as_str()));
@@ -205,6 +301,7 @@ async fn execute(
                 node_home,
                 *node_port,
                 *fakechain_port,
+                fakechain_name,
                 rpc,
                 fake_node_name.clone(),
                 password,
@@ -250,16 +347,6 @@ async fn execute(
             let package_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
             let no_ui = matches.get_one::<bool>("NO_UI").unwrap();
             let ui_only = matches.get_one::<bool>("UI_ONLY").unwrap();
-            let include: HashSet<PathBuf> = matches
-                .get_many::<String>("INCLUDE")
-                .unwrap_or_default()
-                .map(|s| package_dir.join(s))
-                .collect();
-            let exclude: HashSet<PathBuf> = matches
-                .get_many::<String>("EXCLUDE")
-                .unwrap_or_default()
-                .map(|s| package_dir.join(s))
-                .collect();
             let skip_deps_check = matches.get_one::<bool>("SKIP_DEPS_CHECK").unwrap();
             let features = match matches.get_one::<String>("FEATURES") {
                 Some(f) => f.clone(),
@@ -286,31 +373,106 @@ async fn execute(
             let hyperapp = matches.get_one::<bool>("HYPERAPP").unwrap();
             let reproducible = matches.get_one::<bool>("REPRODUCIBLE").unwrap();
             let force = matches.get_one::<bool>("FORCE").unwrap();
+            let frozen = matches.get_one::<bool>("FROZEN").unwrap();
+            let locked = matches.get_one::<bool>("LOCKED").unwrap() || *frozen;
+            let offline = matches.get_one::<bool>("OFFLINE").unwrap() || *frozen;
+            let cache_backend = matches.get_one::<String>("CACHE_BACKEND").unwrap();
             let verbose = matches.get_one::<bool>("VERBOSE").unwrap();
+            let cargo_args: Vec<String> = matches
+                .get_many::<String>("CARGO_ARGS")
+                .unwrap_or_default()
+                .cloned()
+                .collect();
+            let no_fetch = matches.get_one::<bool>("NO_FETCH").unwrap();
+            let api_cache_ttl = matches.get_one::<u64>("API_CACHE_TTL").unwrap();
+            let dep_source = matches.get_one::<build::DepSource>("DEP_SOURCE").unwrap();
+            let sign = matches.get_one::<PathBuf>("SIGN");
+            let profile = matches.get_one::<String>("PROFILE").unwrap();
+            let debug_info = matches.get_one::<String>("DEBUG_INFO").unwrap();
+            let ui_package_manager = matches.get_one::<String>("UI_PACKAGE_MANAGER").unwrap();
+            let force_ui = matches.get_one::<bool>("FORCE_UI").unwrap();
+            let deny_warnings = matches.get_one::<bool>("DENY_WARNINGS").unwrap();
+            let log_format = matches.get_one::<String>("LOG_FORMAT").unwrap();
+            let backend = matches.get_one::<String>("BACKEND").unwrap();
+            let wit_rename = matches.get_one::<bool>("WIT_RENAME").unwrap();
+            let dry_run = matches.get_one::<bool>("DRY_RUN").unwrap();
             let toolchain = matches.get_one::<String>("TOOLCHAIN").unwrap();
+            let ts_sdk = matches.get_one::<PathBuf>("TS_SDK");
+            let py_client = matches.get_one::<PathBuf>("PY_CLIENT");
+            let rust_client = matches.get_one::<PathBuf>("RUST_CLIENT");
+            let swift_client = matches.get_one::<PathBuf>("SWIFT_CLIENT");
+            let kotlin_client = matches.get_one::<PathBuf>("KOTLIN_CLIENT");
+            let workspace = matches.get_one::<bool>("WORKSPACE").unwrap();
 
-            build::execute(
-                &package_dir,
-                *no_ui,
-                *ui_only,
-                &include,
-                &exclude,
-                *skip_deps_check,
-                &features,
-                url,
-                download_from,
-                default_world.map(|w| w.as_str()),
-                local_dependencies,
-                add_paths_to_api,
-                *rewrite,
-                *hyperapp,
-                *reproducible,
-                *force,
-                *verbose,
-                false,
-                toolchain,
-            )
-            .await
+            // with --workspace, build each package under DIR after the packages it depends on
+            let package_dirs = if *workspace {
+                build::get_workspace_build_order(&package_dir)?
+            } else {
+                vec![(package_dir, vec![])]
+            };
+            for (package_dir, workspace_dependencies) in package_dirs {
+                let include: HashSet<PathBuf> = matches
+                    .get_many::<String>("INCLUDE")
+                    .unwrap_or_default()
+                    .map(|s| package_dir.join(s))
+                    .collect();
+                let exclude: HashSet<PathBuf> = matches
+                    .get_many::<String>("EXCLUDE")
+                    .unwrap_or_default()
+                    .map(|s| package_dir.join(s))
+                    .collect();
+                let mut local_dependencies = local_dependencies.clone();
+                local_dependencies.extend(workspace_dependencies);
+
+                build::execute(
+                    &package_dir,
+                    build::BuildOptions {
+                        no_ui: *no_ui,
+                        ui_only: *ui_only,
+                        include,
+                        exclude,
+                        skip_deps_check: *skip_deps_check,
+                        features: &features,
+                        url: url.clone(),
+                        download_from,
+                        default_world: default_world.map(|w| w.as_str()),
+                        local_dependencies,
+                        add_paths_to_api: add_paths_to_api.clone(),
+                        rewrite: *rewrite,
+                        hyperapp: *hyperapp,
+                        reproducible: *reproducible,
+                        force: *force,
+                        verbose: *verbose,
+                        ignore_deps: false,
+                        offline,
+                        cache_backend,
+                        locked,
+                        cargo_args: &cargo_args,
+                        no_fetch: *no_fetch,
+                        api_cache_ttl: *api_cache_ttl,
+                        dep_source: dep_source.clone(),
+                        sign: sign.map(|s| s.as_path()),
+                        profile,
+                        debug_info,
+                        ui_package_manager,
+                        force_ui: *force_ui,
+                        deny_warnings: *deny_warnings,
+                        log_format,
+                        dry_run: *dry_run,
+                        backend,
+                        wit_rename: *wit_rename,
+                        toolchain,
+                        env: Default::default(),
+                        ts_sdk: ts_sdk.map(|s| s.as_path()),
+                        py_client: py_client.map(|p| p.as_path()),
+                        rust_client: rust_client.map(|r| r.as_path()),
+                        swift_client: swift_client.map(|s| s.as_path()),
+                        kotlin_client: kotlin_client.map(|k| k.as_path()),
+                    },
+                )
+                .await?;
+            }
+            Ok(())
         }
         Some(("build-start-package", matches)) => {
             let package_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
@@ -353,36 +515,254 @@ async fn execute(
             let hyperapp = matches.get_one::<bool>("HYPERAPP").unwrap();
             let reproducible = matches.get_one::<bool>("REPRODUCIBLE").unwrap();
             let force = matches.get_one::<bool>("FORCE").unwrap();
+            let frozen = matches.get_one::<bool>("FROZEN").unwrap();
+            let locked = matches.get_one::<bool>("LOCKED").unwrap() || *frozen;
+            let offline = matches.get_one::<bool>("OFFLINE").unwrap() || *frozen;
+            let cache_backend = matches.get_one::<String>("CACHE_BACKEND").unwrap();
             let verbose = matches.get_one::<bool>("VERBOSE").unwrap();
+            let cargo_args: Vec<String> = matches
+                .get_many::<String>("CARGO_ARGS")
+                .unwrap_or_default()
+                .cloned()
+                .collect();
+            let no_fetch = matches.get_one::<bool>("NO_FETCH").unwrap();
+            let api_cache_ttl = matches.get_one::<u64>("API_CACHE_TTL").unwrap();
+            let dep_source = matches.get_one::<build::DepSource>("DEP_SOURCE").unwrap();
+            let sign = matches.get_one::<PathBuf>("SIGN");
+            let profile = matches.get_one::<String>("PROFILE").unwrap();
+            let debug_info = matches.get_one::<String>("DEBUG_INFO").unwrap();
+            let ui_package_manager = matches.get_one::<String>("UI_PACKAGE_MANAGER").unwrap();
+            let force_ui = matches.get_one::<bool>("FORCE_UI").unwrap();
+            let deny_warnings = matches.get_one::<bool>("DENY_WARNINGS").unwrap();
+            let log_format = matches.get_one::<String>("LOG_FORMAT").unwrap();
+            let backend = matches.get_one::<String>("BACKEND").unwrap();
+            let wit_rename = matches.get_one::<bool>("WIT_RENAME").unwrap();
             let toolchain = matches.get_one::<String>("TOOLCHAIN").unwrap();
 
             build_start_package::execute(
                 &package_dir,
-                *no_ui,
-                *ui_only,
-                &include,
-                &exclude,
                 &url,
-                *skip_deps_check,
-                &features,
-                download_from,
-                default_world.map(|w| w.as_str()),
-                local_dependencies,
-                add_paths_to_api,
-                *rewrite,
-                *hyperapp,
-                *reproducible,
-                *force,
-                *verbose,
-                toolchain,
+                build::BuildOptions {
+                    no_ui: *no_ui,
+                    ui_only: *ui_only,
+                    include,
+                    exclude,
+                    skip_deps_check: *skip_deps_check,
+                    features: &features,
+                    download_from,
+                    default_world: default_world.map(|w| w.as_str()),
+                    local_dependencies,
+                    add_paths_to_api,
+                    rewrite: *rewrite,
+                    hyperapp: *hyperapp,
+                    reproducible: *reproducible,
+                    force: *force,
+                    verbose: *verbose,
+                    offline,
+                    cache_backend,
+                    locked,
+                    cargo_args: &cargo_args,
+                    no_fetch: *no_fetch,
+                    api_cache_ttl: *api_cache_ttl,
+                    dep_source: dep_source.clone(),
+                    sign: sign.map(|s| s.as_path()),
+                    profile,
+                    debug_info,
+                    ui_package_manager,
+                    force_ui: *force_ui,
+                    deny_warnings: *deny_warnings,
+                    log_format,
+                    backend,
+                    wit_rename: *wit_rename,
+                    toolchain,
+                    ..Default::default()
+                },
             )
             .await
         }
         Some(("chain", matches)) => {
-            let port = matches.get_one::<u16>("PORT").unwrap();
-            let verbose = matches.get_one::<bool>("VERBOSE").unwrap();
-            let tracing = matches.get_one::<bool>("TRACING").unwrap();
-            chain::execute(*port, *verbose, *tracing).await
+            // `kit chain` without a subcommand starts the chain
+            let (subcommand, matches) = matches.subcommand().unwrap_or(("start", matches));
+            // check-config does not need a running chain
+            if subcommand == "check-config" {
+                let path = PathBuf::from(matches.get_one::<String>("PATH").unwrap());
+                chain::execute_check_config(&path)
+            } else {
+                let port = matches.get_one::<u16>("PORT").unwrap();
+
+                match subcommand {
+                    "start" | "restart" => {
+                        let verbose = matches.get_one::<bool>("VERBOSE").unwrap();
+                        let tracing = matches.get_one::<bool>("TRACING").unwrap();
+                        let fork_url = matches.get_one::<String>("FORK_URL").map(|s| s.as_str());
+                        let fork_block_number =
+                            matches.get_one::<u64>("FORK_BLOCK_NUMBER").cloned();
+                        let name = matches.get_one::<String>("CHAIN_NAME").map(|s| s.as_str());
+                        let state_dir = matches.get_one::<String>("STATE").map(PathBuf::from);
+                        if subcommand == "start" {
+                            chain::execute(
+                                *port,
+                                name,
+                                fork_url,
+                                fork_block_number,
+                                state_dir.as_deref(),
+                                *verbose,
+                                *tracing,
+                            )
+                            .await
+                        } else {
+                            chain::execute_restart(
+                                *port,
+                                name,
+                                fork_url,
+                                fork_block_number,
+                                state_dir.as_deref(),
+                                *verbose,
+                                *tracing,
+                            )
+                            .await
+                        }
+                    }
+                    "stop" | "status" => {
+                        let port = match matches.get_one::<String>("CHAIN_NAME") {
+                            Some(name) => chain::resolve_chain_port(name)?,
+                            None => *port,
+                        };
+                        if subcommand == "stop" {
+                            chain::execute_stop(port).await
+                        } else {
+                            chain::execute_status(port).await
+                        }
+                    }
+                    "deploy" => {
+                        let project_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
+                        let contracts: Vec<String> = matches
+                            .get_many::<String>("CONTRACT")
+                            .unwrap_or_default()
+                            .map(|s| s.to_string())
+                            .collect();
+                        let receipt_timeout = matches.get_one::<u64>("RECEIPT_TIMEOUT").unwrap();
+                        let signer = chain::read_signer(
+                            matches.get_one::<String>("PRIVATE_KEY").map(|s| s.as_str()),
+                            matches
+                                .get_one::<String>("KEYSTORE")
+                                .map(std::path::Path::new),
+                        )?;
+                        let rpc_url = matches.get_one::<String>("RPC_URL").map(|s| s.as_str());
+                        let verbose = matches.get_one::<bool>("VERBOSE").unwrap();
+                        chain::execute_deploy(
+                            *port,
+                            rpc_url,
+                            &project_dir,
+                            &contracts,
+                            std::time::Duration::from_secs(*receipt_timeout),
+                            signer.as_ref(),
+                            *verbose,
+                        )
+                        .await
+                    }
+                    "replay" => {
+                        let broadcast =
+                            PathBuf::from(matches.get_one::<String>("BROADCAST").unwrap());
+                        let receipt_timeout = matches.get_one::<u64>("RECEIPT_TIMEOUT").unwrap();
+                        chain::execute_replay(
+                            *port,
+                            &broadcast,
+                            std::time::Duration::from_secs(*receipt_timeout),
+                        )
+                        .await
+                    }
+                    "snapshot" => {
+                        let name = matches.get_one::<String>("NAME").unwrap();
+                        chain::execute_snapshot(*port, name).await
+                    }
+                    "restore" => {
+                        let name = matches.get_one::<String>("NAME").unwrap();
+                        chain::execute_restore(*port, name).await
+                    }
+                    "accounts" => chain::execute_accounts(*port).await,
+                    "fund" => {
+                        let address = matches.get_one::<String>("ADDRESS").unwrap();
+                        let amount = matches.get_one::<String>("AMOUNT").unwrap();
+                        chain::execute_fund(*port, address, amount).await
+                    }
+                    "verify" => {
+                        let project_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
+                        chain::execute_verify(*port, &project_dir).await
+                    }
+                    "logs" => {
+                        let from_block = matches.get_one::<u64>("FROM_BLOCK").cloned();
+                        let project_dir = matches.get_one::<String>("PROJECT").map(PathBuf::from);
+                        chain::execute_logs(*port, from_block, project_dir.as_deref()).await
+                    }
+                    "call" | "send" => {
+                        let target = matches.get_one::<String>("TARGET").unwrap();
+                        let signature = matches.get_one::<String>("SIGNATURE").unwrap();
+                        let args: Vec<String> = matches
+                            .get_many::<String>("ARGS")
+                            .unwrap_or_default()
+                            .map(|s| s.to_string())
+                            .collect();
+                        let from = matches.get_one::<String>("FROM").map(|s| s.as_str());
+                        let project_dir = matches.get_one::<String>("PROJECT").map(PathBuf::from);
+                        if subcommand == "call" {
+                            chain::execute_call(
+                                *port,
+                                target,
+                                signature,
+                                &args,
+                                from,
+                                project_dir.as_deref(),
+                            )
+                            .await
+                        } else {
+                            let signer = chain::read_signer(
+                                matches.get_one::<String>("PRIVATE_KEY").map(|s| s.as_str()),
+                                matches
+                                    .get_one::<String>("KEYSTORE")
+                                    .map(std::path::Path::new),
+                            )?;
+                            let rpc_url = matches.get_one::<String>("RPC_URL").map(|s| s.as_str());
+                            chain::execute_send(
+                                *port,
+                                rpc_url,
+                                target,
+                                signature,
+                                &args,
+                                from,
+                                project_dir.as_deref(),
+                                signer.as_ref(),
+                            )
+                            .await
+                        }
+                    }
+                    "mint" => {
+                        let name = matches.get_one::<String>("NAME").unwrap();
+                        let owner = matches.get_one::<String>("OWNER").map(|s| s.as_str());
+                        let implementation = matches.get_one::<String>("IMPL").unwrap();
+                        let signer = chain::read_signer(
+                            matches.get_one::<String>("PRIVATE_KEY").map(|s| s.as_str()),
+                            matches
+                                .get_one::<String>("KEYSTORE")
+                                .map(std::path::Path::new),
+                        )?;
+                        let rpc_url = matches.get_one::<String>("RPC_URL").map(|s| s.as_str());
+                        chain::execute_mint(
+                            *port,
+                            rpc_url,
+                            name,
+                            owner,
+                            implementation,
+                            signer.as_ref(),
+                        )
+                        .await
+                    }
+                    _ => {
+                        warn!("Invalid subcommand. Usage:\n{}", usage);
+                        Ok(())
+                    }
+                }
+            }
         }
         Some(("connect", matches)) => {
             let local_port = matches.get_one::<u16>("LOCAL_PORT").unwrap();
@@ -391,6 +771,11 @@ async fn execute(
             let host_port = matches.get_one::<u16>("HOST_PORT").map(|hp| hp.clone());
             connect::execute(*local_port, *disconnect, host, host_port)
         }
+        Some(("deps", matches)) => {
+            let package_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
+            let format = matches.get_one::<String>("FORMAT").unwrap();
+            deps::execute(&package_dir, format)
+        }
         Some(("dev-ui", matches)) => {
             let package_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
             let url = format!(
@@ -427,7 +812,16 @@ async fn execute(
                 .map(|pn| pn.to_string());
             let publisher = matches.get_one::<String>("PUBLISHER").unwrap();
             let language: new::Language = matches.get_one::<String>("LANGUAGE").unwrap().into();
-            let template: new::Template = matches.get_one::<String>("TEMPLATE").unwrap().into();
+            // only `echo` is written in Go
+            let default_template = match language {
+                new::Language::Go => "echo",
+                _ => "chat",
+            }
+            .to_string();
+            let template: new::Template = matches
+                .get_one::<String>("TEMPLATE")
+                .unwrap_or(&default_template)
+                .into();
             let ui = matches.get_one::<bool>("UI").unwrap_or(&false);
 
             new::execute(
@@ -508,7 +902,10 @@ async fn execute(
                 return Err(eyre!(error));
             }
 
-            run_tests::execute(config_path).await
+            let frozen = matches.get_one::<bool>("FROZEN").unwrap();
+            let locked = matches.get_one::<bool>("LOCKED").unwrap() || *frozen;
+
+            run_tests::execute(config_path, locked, *frozen).await
         }
         Some(("setup", matches)) => {
             let verbose = matches.get_one::<bool>("VERBOSE").unwrap();
@@ -550,6 +947,19 @@ async fn execute(
 
             update::execute(args, branch)
         }
+        Some(("verify", matches)) => {
+            let package_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
+            let zip_path = matches.get_one::<PathBuf>("ZIP");
+            let signature_path = matches.get_one::<PathBuf>("SIGNATURE");
+            let public_key = matches.get_one::<String>("PUBLIC_KEY");
+
+            verify::execute(
+                &package_dir,
+                zip_path.map(|p| p.as_path()),
+                signature_path.map(|p| p.as_path()),
+                public_key.map(|k| k.as_str()),
+            )
+        }
         Some(("view-api", matches)) => {
             let package_id = matches
                 .get_one::<String>("PACKAGE_ID")
@@ -565,6 +975,30 @@ async fn execute(
             view_api::execute(None, package_id, &url, download_from, true).await?;
             Ok(())
         }
+        Some(("wit", matches)) => {
+            let Some((subcommand, matches)) = matches.subcommand() else {
+                warn!("Invalid subcommand. Usage:\n{}", usage);
+                return Ok(());
+            };
+            let package_dir = PathBuf::from(matches.get_one::<String>("DIR").unwrap());
+            let processes: Vec<String> = matches
+                .get_many::<String>("PROCESS")
+                .unwrap_or_default()
+                .map(|s| s.to_string())
+                .collect();
+            let wit_rename = matches.get_one::<bool>("WIT_RENAME").unwrap();
+
+            match subcommand {
+                "generate" => wit::execute_generate(&package_dir, &processes, *wit_rename),
+                "check" => wit::execute_check(&package_dir, &processes, *wit_rename),
+                "diff" => wit::execute_diff(&package_dir, &processes, *wit_rename),
+                "lint" => wit::execute_lint(&package_dir, &processes, *wit_rename),
+                _ => {
+                    warn!("Invalid subcommand. Usage:\n{}", usage);
+                    Ok(())
+                }
+            }
+        }
         _ => {
             warn!("Invalid subcommand. Usage:\n{}", usage);
             Ok(())
@@ -587,6 +1021,69 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
             .action(ArgAction::Version)
             .help("Print version")
         )
+        .subcommand(Command::new("api")
+            .about("Distribute the generated API clients of a hyperapp package")
+            .subcommand_required(true)
+            .subcommand(Command::new("export")
+                .about("Bundle the WIT, OpenAPI spec, JSON Schema and TypeScript SDK of the package's API at its current version into a zip, e.g. for integration partners")
+                .arg(Arg::new("DIR")
+                    .action(ArgAction::Set)
+                    .help("The package directory")
+                    .default_value(current_dir)
+                )
+                .arg(Arg::new("OUTPUT")
+                    .action(ArgAction::Set)
+                    .short('o')
+                    .long("output")
+                    .help("Path to write the zip to [default: target/<package>-api-<version>.zip]")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                )
+            )
+            .subcommand(Command::new("publish-client")
+                .about("Version the caller-utils crate generated by `kit build --hyperapp` and publish it to a registry, or vendor it into clients/")
+                .arg(Arg::new("DIR")
+                    .action(ArgAction::Set)
+                    .help("The package directory")
+                    .default_value(current_dir)
+                )
+                .arg(Arg::new("VERSION")
+                    .action(ArgAction::Set)
+                    .long("version")
+                    .help("Version of the published crate [default: the package's current_version in metadata.json]")
+                    .required(false)
+                )
+                .arg(Arg::new("REGISTRY")
+                    .action(ArgAction::Set)
+                    .short('r')
+                    .long("registry")
+                    .help("Publish to this registry (as named in .cargo/config.toml) [default: `[caller-utils] registry` in kit.toml, else vendor]")
+                    .required(false)
+                )
+                .arg(Arg::new("OUT")
+                    .action(ArgAction::Set)
+                    .short('o')
+                    .long("out")
+                    .help("Dir, relative to the package dir, to vendor the crate into, if not publishing it")
+                    .default_value(api::DEFAULT_CLIENTS_DIR)
+                    .value_parser(value_parser!(PathBuf))
+                    .conflicts_with("REGISTRY")
+                )
+                .arg(Arg::new("DRY_RUN")
+                    .action(ArgAction::SetTrue)
+                    .long("dry-run")
+                    .help("Check the crate can be published, without publishing it")
+                    .required(false)
+                )
+                .arg(Arg::new("VERBOSE")
+                    .action(ArgAction::SetTrue)
+                    .short('v')
+                    .long("verbose")
+                    .help("If set, output stdout and stderr")
+                    .required(false)
+                )
+            )
+        )
         .subcommand(Command::new("boot-fake-node")
             .about("Boot a fake node for development")
             .visible_alias("f")
@@ -647,6 +1144,12 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .default_value("8545")
                 .value_parser(value_parser!(u16))
             )
+            .arg(Arg::new("FAKECHAIN_NAME")
+                .action(ArgAction::Set)
+                .long("fakechain-name")
+                .help("Name of the fakechain (`kit chain start --name`) to connect to, wherever it runs; started on --fakechain-port if it is not running")
+                .required(false)
+            )
             .arg(Arg::new("RPC_ENDPOINT")
                 .action(ArgAction::Set)
                 .long("rpc")
@@ -772,6 +1275,12 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .help("The package directory to build")
                 .default_value(current_dir)
             )
+            .arg(Arg::new("WORKSPACE")
+                .action(ArgAction::SetTrue)
+                .long("workspace")
+                .help("Build all packages under DIR, ordered by their dependencies on each other")
+                .required(false)
+            )
             .arg(Arg::new("NO_UI")
                 .action(ArgAction::SetTrue)
                 .long("no-ui")
@@ -854,6 +1363,52 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .help("Build using the Hyperapp framework [default: don't use Hyperapp framework]")
                 .required(false)
             )
+            .arg(Arg::new("WIT_RENAME")
+                .action(ArgAction::SetTrue)
+                .long("wit-rename")
+                .help("With --hyperapp, rewrite digits in names as words in the generated WIT (e.g. `v2` -> `v-two`) rather than failing; caller-utils map them back to the original names")
+                .required(false)
+            )
+            .arg(Arg::new("TS_SDK")
+                .action(ArgAction::Set)
+                .long("ts-sdk")
+                .help("Generate a publishable npm package in this dir with a typed TypeScript client for the package's HTTP & WebSocket handlers (from the WIT in api/)")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("WORKSPACE")
+                .required(false)
+            )
+            .arg(Arg::new("PY_CLIENT")
+                .action(ArgAction::Set)
+                .long("py-client")
+                .help("Generate a typed Python module (dataclasses & a `requests` client) for the package's HTTP handlers at this path (from the WIT in api/)")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("WORKSPACE")
+                .required(false)
+            )
+            .arg(Arg::new("RUST_CLIENT")
+                .action(ArgAction::Set)
+                .long("rust-client")
+                .help("Generate a Rust crate with a typed `reqwest` client for the package's HTTP handlers in this dir, for tests & scripts run outside the node (from the WIT in api/)")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("WORKSPACE")
+                .required(false)
+            )
+            .arg(Arg::new("SWIFT_CLIENT")
+                .action(ArgAction::Set)
+                .long("swift-client")
+                .help("Generate a Swift file with `Codable` types & a `URLSession` client for the package's HTTP handlers at this path, for iOS & macOS apps (from the WIT in api/)")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("WORKSPACE")
+                .required(false)
+            )
+            .arg(Arg::new("KOTLIN_CLIENT")
+                .action(ArgAction::Set)
+                .long("kotlin-client")
+                .help("Generate a Kotlin file with `kotlinx.serialization` types & an OkHttp client for the package's HTTP handlers at this path, for Android apps (from the WIT in api/)")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("WORKSPACE")
+                .required(false)
+            )
             .arg(Arg::new("REPRODUCIBLE")
                 .action(ArgAction::SetTrue)
                 .short('r')
@@ -868,6 +1423,117 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .help("Force a rebuild")
                 .required(false)
             )
+            .arg(Arg::new("OFFLINE")
+                .action(ArgAction::SetTrue)
+                .long("offline")
+                .help("Fail fast if the build would need to fetch anything not already cached")
+                .required(false)
+            )
+            .arg(Arg::new("CACHE_BACKEND")
+                .action(ArgAction::Set)
+                .long("cache-backend")
+                .help("Compiler cache for Rust builds; `auto` uses sccache if installed")
+                .default_value(build::DEFAULT_CACHE_BACKEND)
+                .value_parser(["none", "sccache", "auto"])
+            )
+            .arg(Arg::new("NO_FETCH")
+                .action(ArgAction::SetTrue)
+                .long("no-fetch")
+                .help("Use cached dependency APIs regardless of age rather than fetching from a node")
+                .required(false)
+            )
+            .arg(Arg::new("API_CACHE_TTL")
+                .action(ArgAction::Set)
+                .long("api-cache-ttl")
+                .help("Seconds to use a cached dependency API before refetching it from a node")
+                .default_value(build::DEFAULT_API_CACHE_TTL.to_string())
+                .value_parser(value_parser!(u64))
+            )
+            .arg(Arg::new("DEP_SOURCE")
+                .action(ArgAction::Set)
+                .long("dep-source")
+                .help("Where to get dependency APIs: `node`, a dir of api zips, a git URL, or an HTTP registry URL")
+                .default_value("node")
+                .value_parser(clap::builder::ValueParser::new(build::DepSource::from_str))
+            )
+            .arg(Arg::new("SIGN")
+                .action(ArgAction::Set)
+                .long("sign")
+                .help("Sign the package zip with this ed25519 key (PKCS#8 PEM or hex seed), writing pkg/signature.json")
+                .value_parser(value_parser!(PathBuf))
+                .required(false)
+            )
+            .arg(Arg::new("PROFILE")
+                .action(ArgAction::Set)
+                .long("profile")
+                .help("Cargo profile to build Rust processes with (e.g. a custom `release-small`)")
+                .default_value(build::DEFAULT_CARGO_PROFILE)
+            )
+            .arg(Arg::new("DEBUG_INFO")
+                .action(ArgAction::Set)
+                .long("debug-info")
+                .help("Keep or strip debug info in built components; `split` strips pkg/*.wasm and writes unstripped copies (with DWARF) to target/debug-info/")
+                .default_value(build::DEFAULT_DEBUG_INFO)
+                .value_parser(["profile", "keep", "strip", "split"])
+            )
+            .arg(Arg::new("UI_PACKAGE_MANAGER")
+                .action(ArgAction::Set)
+                .long("ui-package-manager")
+                .help("JS package manager to build the UI with; `auto` detects it from the UI's lockfile")
+                .default_value(build::DEFAULT_UI_PACKAGE_MANAGER)
+                .value_parser(["auto", "npm", "pnpm", "yarn", "bun"])
+            )
+            .arg(Arg::new("FORCE_UI")
+                .action(ArgAction::SetTrue)
+                .long("force-ui")
+                .help("Rebuild the UI even if its sources, lockfile and build env are unchanged")
+                .required(false)
+            )
+            .arg(Arg::new("DENY_WARNINGS")
+                .action(ArgAction::SetTrue)
+                .long("deny-warnings")
+                .help("Fail the build on Rust warnings and on UI lint or type-check errors")
+                .required(false)
+            )
+            .arg(Arg::new("DRY_RUN")
+                .action(ArgAction::SetTrue)
+                .long("dry-run")
+                .help("Print what would be built, fetched and composed, and why, without building")
+                .required(false)
+            )
+            .arg(Arg::new("LOG_FORMAT")
+                .action(ArgAction::Set)
+                .long("log-format")
+                .help("Output format; `json` emits build steps, timings and cargo diagnostics as JSON lines")
+                .default_value(build::DEFAULT_LOG_FORMAT)
+                .value_parser(["text", "json"])
+            )
+            .arg(Arg::new("BACKEND")
+                .action(ArgAction::Set)
+                .long("backend")
+                .help("How to build Rust processes into components; `cargo-component` delegates to cargo-component, which bundles its own WASI adapter")
+                .default_value(build::DEFAULT_BUILD_BACKEND)
+                .value_parser(["cargo", "cargo-component"])
+            )
+            .arg(Arg::new("LOCKED")
+                .action(ArgAction::SetTrue)
+                .long("locked")
+                .help("Pass `--locked` to cargo: fail if Cargo.lock needs to be updated")
+                .required(false)
+            )
+            .arg(Arg::new("FROZEN")
+                .action(ArgAction::SetTrue)
+                .long("frozen")
+                .help("Equivalent to `--locked --offline`")
+                .required(false)
+            )
+            .arg(Arg::new("CARGO_ARGS")
+                .action(ArgAction::Append)
+                .num_args(0..)
+                .last(true)
+                .allow_hyphen_values(true)
+                .help("Additional arguments to `cargo build` of Rust processes, given after `--` (also read from `$KIT_CARGO_ARGS`)")
+            )
             .arg(Arg::new("VERBOSE")
                 .action(ArgAction::SetTrue)
                 .short('v')
@@ -975,6 +1641,12 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .help("Build using the Hyperapp framework [default: don't use Hyperapp framework]")
                 .required(false)
             )
+            .arg(Arg::new("WIT_RENAME")
+                .action(ArgAction::SetTrue)
+                .long("wit-rename")
+                .help("With --hyperapp, rewrite digits in names as words in the generated WIT (e.g. `v2` -> `v-two`) rather than failing; caller-utils map them back to the original names")
+                .required(false)
+            )
             .arg(Arg::new("REPRODUCIBLE")
                 .action(ArgAction::SetTrue)
                 .short('r')
@@ -989,6 +1661,111 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .help("Force a rebuild")
                 .required(false)
             )
+            .arg(Arg::new("OFFLINE")
+                .action(ArgAction::SetTrue)
+                .long("offline")
+                .help("Fail fast if the build would need to fetch anything not already cached")
+                .required(false)
+            )
+            .arg(Arg::new("CACHE_BACKEND")
+                .action(ArgAction::Set)
+                .long("cache-backend")
+                .help("Compiler cache for Rust builds; `auto` uses sccache if installed")
+                .default_value(build::DEFAULT_CACHE_BACKEND)
+                .value_parser(["none", "sccache", "auto"])
+            )
+            .arg(Arg::new("NO_FETCH")
+                .action(ArgAction::SetTrue)
+                .long("no-fetch")
+                .help("Use cached dependency APIs regardless of age rather than fetching from a node")
+                .required(false)
+            )
+            .arg(Arg::new("API_CACHE_TTL")
+                .action(ArgAction::Set)
+                .long("api-cache-ttl")
+                .help("Seconds to use a cached dependency API before refetching it from a node")
+                .default_value(build::DEFAULT_API_CACHE_TTL.to_string())
+                .value_parser(value_parser!(u64))
+            )
+            .arg(Arg::new("DEP_SOURCE")
+                .action(ArgAction::Set)
+                .long("dep-source")
+                .help("Where to get dependency APIs: `node`, a dir of api zips, a git URL, or an HTTP registry URL")
+                .default_value("node")
+                .value_parser(clap::builder::ValueParser::new(build::DepSource::from_str))
+            )
+            .arg(Arg::new("SIGN")
+                .action(ArgAction::Set)
+                .long("sign")
+                .help("Sign the package zip with this ed25519 key (PKCS#8 PEM or hex seed), writing pkg/signature.json")
+                .value_parser(value_parser!(PathBuf))
+                .required(false)
+            )
+            .arg(Arg::new("PROFILE")
+                .action(ArgAction::Set)
+                .long("profile")
+                .help("Cargo profile to build Rust processes with (e.g. a custom `release-small`)")
+                .default_value(build::DEFAULT_CARGO_PROFILE)
+            )
+            .arg(Arg::new("DEBUG_INFO")
+                .action(ArgAction::Set)
+                .long("debug-info")
+                .help("Keep or strip debug info in built components; `split` strips pkg/*.wasm and writes unstripped copies (with DWARF) to target/debug-info/")
+                .default_value(build::DEFAULT_DEBUG_INFO)
+                .value_parser(["profile", "keep", "strip", "split"])
+            )
+            .arg(Arg::new("UI_PACKAGE_MANAGER")
+                .action(ArgAction::Set)
+                .long("ui-package-manager")
+                .help("JS package manager to build the UI with; `auto` detects it from the UI's lockfile")
+                .default_value(build::DEFAULT_UI_PACKAGE_MANAGER)
+                .value_parser(["auto", "npm", "pnpm", "yarn", "bun"])
+            )
+            .arg(Arg::new("FORCE_UI")
+                .action(ArgAction::SetTrue)
+                .long("force-ui")
+                .help("Rebuild the UI even if its sources, lockfile and build env are unchanged")
+                .required(false)
+            )
+            .arg(Arg::new("DENY_WARNINGS")
+                .action(ArgAction::SetTrue)
+                .long("deny-warnings")
+                .help("Fail the build on Rust warnings and on UI lint or type-check errors")
+                .required(false)
+            )
+            .arg(Arg::new("LOG_FORMAT")
+                .action(ArgAction::Set)
+                .long("log-format")
+                .help("Output format; `json` emits build steps, timings and cargo diagnostics as JSON lines")
+                .default_value(build::DEFAULT_LOG_FORMAT)
+                .value_parser(["text", "json"])
+            )
+            .arg(Arg::new("BACKEND")
+                .action(ArgAction::Set)
+                .long("backend")
+                .help("How to build Rust processes into components; `cargo-component` delegates to cargo-component, which bundles its own WASI adapter")
+                .default_value(build::DEFAULT_BUILD_BACKEND)
+                .value_parser(["cargo", "cargo-component"])
+            )
+            .arg(Arg::new("LOCKED")
+                .action(ArgAction::SetTrue)
+                .long("locked")
+                .help("Pass `--locked` to cargo: fail if Cargo.lock needs to be updated")
+                .required(false)
+            )
+            .arg(Arg::new("FROZEN")
+                .action(ArgAction::SetTrue)
+                .long("frozen")
+                .help("Equivalent to `--locked --offline`")
+                .required(false)
+            )
+            .arg(Arg::new("CARGO_ARGS")
+                .action(ArgAction::Append)
+                .num_args(0..)
+                .last(true)
+                .allow_hyphen_values(true)
+                .help("Additional arguments to `cargo build` of Rust processes, given after `--` (also read from `$KIT_CARGO_ARGS`)")
+            )
             .arg(Arg::new("VERBOSE")
                 .action(ArgAction::SetTrue)
                 .short('v')
@@ -1006,8 +1783,9 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
             )
         )
         .subcommand(Command::new("chain")
-            .about("Start a local chain for development")
+            .about("Start, stop and query a local chain for development (`kit chain` starts it)")
             .visible_alias("c")
+            .args_conflicts_with_subcommands(true)
             .arg(Arg::new("PORT")
                 .action(ArgAction::Set)
                 .short('p')
@@ -1030,6 +1808,513 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .help("If set, enable tracing/steps-tracing")
                 .required(false)
             )
+            .arg(Arg::new("CHAIN_NAME")
+                .action(ArgAction::Set)
+                .long("name")
+                .help("Name of the chain, to select it by in other commands; it keeps its state in its own dir")
+                .required(false)
+            )
+            .arg(Arg::new("FORK_URL")
+                .action(ArgAction::Set)
+                .long("fork-url")
+                .help("RPC of a live chain (e.g. Base) to fork; Hypermap is not deployed if it exists there")
+                .required(false)
+            )
+            .arg(Arg::new("FORK_BLOCK_NUMBER")
+                .action(ArgAction::Set)
+                .long("fork-block-number")
+                .help("Block number to fork at [default: latest]")
+                .requires("FORK_URL")
+                .value_parser(value_parser!(u64))
+            )
+            .arg(Arg::new("STATE")
+                .action(ArgAction::Set)
+                .long("state")
+                .help("Directory to load the chain's state from and save it to on exit, to keep it across restarts")
+                .required(false)
+            )
+            .subcommand(Command::new("start")
+                .about("Start a local chain, until interrupted")
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port to run the chain on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+                .arg(Arg::new("VERBOSE")
+                    .action(ArgAction::SetTrue)
+                    .short('v')
+                    .long("verbose")
+                    .help("If set, output stdout and stderr")
+                    .required(false)
+                )
+                .arg(Arg::new("TRACING")
+                    .action(ArgAction::SetTrue)
+                    .short('t')
+                    .long("tracing")
+                    .help("If set, enable tracing/steps-tracing")
+                    .required(false)
+                )
+                .arg(Arg::new("CHAIN_NAME")
+                    .action(ArgAction::Set)
+                    .long("name")
+                    .help("Name of the chain, to select it by in other commands; it keeps its state in its own dir")
+                    .required(false)
+                )
+                .arg(Arg::new("FORK_URL")
+                    .action(ArgAction::Set)
+                    .long("fork-url")
+                    .help("RPC of a live chain (e.g. Base) to fork; Hypermap is not deployed if it exists there")
+                    .required(false)
+                )
+                .arg(Arg::new("FORK_BLOCK_NUMBER")
+                    .action(ArgAction::Set)
+                    .long("fork-block-number")
+                    .help("Block number to fork at [default: latest]")
+                    .requires("FORK_URL")
+                    .value_parser(value_parser!(u64))
+                )
+                .arg(Arg::new("STATE")
+                    .action(ArgAction::Set)
+                    .long("state")
+                    .help("Directory to load the chain's state from and save it to on exit, to keep it across restarts")
+                    .required(false)
+                )
+            )
+            .subcommand(Command::new("stop")
+                .about("Stop the local chain started by kit on a port")
+                .arg(Arg::new("CHAIN_NAME")
+                    .action(ArgAction::Set)
+                    .long("name")
+                    .help("Name of the chain (instead of its port)")
+                    .required(false)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("status")
+                .about("Show whether a local chain is running on a port, and whether kit started it")
+                .arg(Arg::new("CHAIN_NAME")
+                    .action(ArgAction::Set)
+                    .long("name")
+                    .help("Name of the chain (instead of its port)")
+                    .required(false)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("restart")
+                .about("Stop the local chain started by kit on a port, if any, and start another")
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port to run the chain on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+                .arg(Arg::new("VERBOSE")
+                    .action(ArgAction::SetTrue)
+                    .short('v')
+                    .long("verbose")
+                    .help("If set, output stdout and stderr")
+                    .required(false)
+                )
+                .arg(Arg::new("TRACING")
+                    .action(ArgAction::SetTrue)
+                    .short('t')
+                    .long("tracing")
+                    .help("If set, enable tracing/steps-tracing")
+                    .required(false)
+                )
+                .arg(Arg::new("CHAIN_NAME")
+                    .action(ArgAction::Set)
+                    .long("name")
+                    .help("Name of the chain, to select it by in other commands; it keeps its state in its own dir")
+                    .required(false)
+                )
+                .arg(Arg::new("FORK_URL")
+                    .action(ArgAction::Set)
+                    .long("fork-url")
+                    .help("RPC of a live chain (e.g. Base) to fork; Hypermap is not deployed if it exists there")
+                    .required(false)
+                )
+                .arg(Arg::new("FORK_BLOCK_NUMBER")
+                    .action(ArgAction::Set)
+                    .long("fork-block-number")
+                    .help("Block number to fork at [default: latest]")
+                    .requires("FORK_URL")
+                    .value_parser(value_parser!(u64))
+                )
+                .arg(Arg::new("STATE")
+                    .action(ArgAction::Set)
+                    .long("state")
+                    .help("Directory to load the chain's state from and save it to on exit, to keep it across restarts")
+                    .required(false)
+                )
+            )
+            .subcommand(Command::new("mint")
+                .about("Mint a Hypermap name (e.g. `sub.label.os`) on the local chain, from its parent's TBA")
+                .arg(Arg::new("NAME")
+                    .action(ArgAction::Set)
+                    .help("Name to mint; its parent must already be minted")
+                    .required(true)
+                )
+                .arg(Arg::new("OWNER")
+                    .action(ArgAction::Set)
+                    .long("owner")
+                    .help("Address to mint to [default: the signer, or else first anvil account]")
+                    .required(false)
+                )
+                .arg(Arg::new("IMPL")
+                    .action(ArgAction::Set)
+                    .long("impl")
+                    .help("Account implementation of the minted TBA: `hyper-account`, `hyper-account-minter`, or an address")
+                    .default_value("hyper-account-minter")
+                )
+                .arg(Arg::new("PRIVATE_KEY")
+                    .action(ArgAction::Set)
+                    .long("private-key")
+                    .help("Private key to sign transactions with, instead of impersonating senders")
+                    .required(false)
+                )
+                .arg(Arg::new("KEYSTORE")
+                    .action(ArgAction::Set)
+                    .long("keystore")
+                    .help("Path to private key keystore to sign transactions with, instead of impersonating senders")
+                    .conflicts_with("PRIVATE_KEY")
+                    .required(false)
+                )
+                .group(ArgGroup::new("SIGNER")
+                    .args(["PRIVATE_KEY", "KEYSTORE"])
+                )
+                .arg(Arg::new("RPC_URL")
+                    .action(ArgAction::Set)
+                    .long("rpc")
+                    .help("HTTP RPC endpoint of the chain to send signed transactions to, instead of the local chain on --port (requires --private-key or --keystore)")
+                    .requires("SIGNER")
+                    .required(false)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("deploy")
+                .about("Build a foundry project with forge and deploy its contracts to the local chain")
+                .arg(Arg::new("DIR")
+                    .action(ArgAction::Set)
+                    .help("The foundry project directory")
+                    .default_value(current_dir)
+                )
+                .arg(Arg::new("CONTRACT")
+                    .action(ArgAction::Append)
+                    .short('c')
+                    .long("contract")
+                    .help("Contract to deploy, taking no constructor args (overrides the contracts and transactions of kit-deploy.toml; can specify multiple)")
+                    .required(false)
+                )
+                .arg(Arg::new("PRIVATE_KEY")
+                    .action(ArgAction::Set)
+                    .long("private-key")
+                    .help("Private key to sign transactions with, instead of impersonating senders")
+                    .required(false)
+                )
+                .arg(Arg::new("KEYSTORE")
+                    .action(ArgAction::Set)
+                    .long("keystore")
+                    .help("Path to private key keystore to sign transactions with, instead of impersonating senders")
+                    .conflicts_with("PRIVATE_KEY")
+                    .required(false)
+                )
+                .group(ArgGroup::new("SIGNER")
+                    .args(["PRIVATE_KEY", "KEYSTORE"])
+                )
+                .arg(Arg::new("RPC_URL")
+                    .action(ArgAction::Set)
+                    .long("rpc")
+                    .help("HTTP RPC endpoint of the chain to send signed transactions to, instead of the local chain on --port (requires --private-key or --keystore)")
+                    .requires("SIGNER")
+                    .required(false)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+                .arg(Arg::new("RECEIPT_TIMEOUT")
+                    .action(ArgAction::Set)
+                    .long("receipt-timeout")
+                    .help("Seconds to wait for each transaction to be mined before failing")
+                    .default_value("60")
+                    .value_parser(value_parser!(u64))
+                )
+                .arg(Arg::new("VERBOSE")
+                    .action(ArgAction::SetTrue)
+                    .short('v')
+                    .long("verbose")
+                    .help("If set, output stdout and stderr")
+                    .required(false)
+                )
+            )
+            .subcommand(Command::new("replay")
+                .about("Replay the transactions of a forge script broadcast on the local chain")
+                .arg(Arg::new("BROADCAST")
+                    .action(ArgAction::Set)
+                    .long("broadcast")
+                    .help("Path to the broadcast, e.g. broadcast/Deploy.s.sol/8453/run-latest.json")
+                    .required(true)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+                .arg(Arg::new("RECEIPT_TIMEOUT")
+                    .action(ArgAction::Set)
+                    .long("receipt-timeout")
+                    .help("Seconds to wait for each transaction to be mined before failing")
+                    .default_value("60")
+                    .value_parser(value_parser!(u64))
+                )
+            )
+            .subcommand(Command::new("snapshot")
+                .about("Save the state of the local chain under a name")
+                .arg(Arg::new("NAME")
+                    .action(ArgAction::Set)
+                    .help("Name of the snapshot")
+                    .required(true)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("restore")
+                .about("Return the local chain to a state saved with `kit chain snapshot`")
+                .arg(Arg::new("NAME")
+                    .action(ArgAction::Set)
+                    .help("Name of the snapshot")
+                    .required(true)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("accounts")
+                .about("List the accounts of the local chain and their balances")
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("fund")
+                .about("Set the ETH balance of an address on the local chain")
+                .arg(Arg::new("ADDRESS")
+                    .action(ArgAction::Set)
+                    .help("Address to fund, e.g. a TBA")
+                    .required(true)
+                )
+                .arg(Arg::new("AMOUNT")
+                    .action(ArgAction::Set)
+                    .help("Balance to set, in ETH (e.g. `10` or `0.5`)")
+                    .required(true)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("check-config")
+                .about("Check a kit-deploy.toml against its foundry project's artifacts without deploying")
+                .arg(Arg::new("PATH")
+                    .action(ArgAction::Set)
+                    .help("Path to the kit-deploy.toml, or the foundry project directory holding it")
+                    .default_value(current_dir)
+                )
+            )
+            .subcommand(Command::new("verify")
+                .about("Check the assertions of a foundry project's kit-deploy.toml against the local chain")
+                .arg(Arg::new("DIR")
+                    .action(ArgAction::Set)
+                    .help("The foundry project directory")
+                    .default_value(current_dir)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("logs")
+                .about("Print events emitted on the local chain as blocks are mined, decoding Hypermap's and those of deployed contracts")
+                .arg(Arg::new("FROM_BLOCK")
+                    .action(ArgAction::Set)
+                    .long("from-block")
+                    .help("Block to print events from [default: the next block]")
+                    .value_parser(value_parser!(u64))
+                    .required(false)
+                )
+                .arg(Arg::new("PROJECT")
+                    .action(ArgAction::Set)
+                    .long("project")
+                    .help("Foundry project `kit chain deploy` deployed from, to decode events of its contracts")
+                    .required(false)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("call")
+                .about("Call a contract function on the local chain without a transaction, decoding its return values")
+                .arg(Arg::new("TARGET")
+                    .action(ArgAction::Set)
+                    .help("Address, name of a contract `kit chain deploy` deployed, or Hypermap name (for its TBA)")
+                    .required(true)
+                )
+                .arg(Arg::new("SIGNATURE")
+                    .action(ArgAction::Set)
+                    .help("Function signature, e.g. `balanceOf(address) returns (uint256)`, or name (with --project)")
+                    .required(true)
+                )
+                .arg(Arg::new("ARGS")
+                    .action(ArgAction::Append)
+                    .help("Function arguments")
+                    .num_args(0..)
+                    .allow_hyphen_values(true)
+                )
+                .arg(Arg::new("FROM")
+                    .action(ArgAction::Set)
+                    .long("from")
+                    .help("Address to call from [default: first anvil account]")
+                    .required(false)
+                )
+                .arg(Arg::new("PROJECT")
+                    .action(ArgAction::Set)
+                    .long("project")
+                    .help("Foundry project `kit chain deploy` deployed TARGET from, for its ABI")
+                    .required(false)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
+            .subcommand(Command::new("send")
+                .about("Send a transaction calling a contract function on the local chain")
+                .arg(Arg::new("TARGET")
+                    .action(ArgAction::Set)
+                    .help("Address, name of a contract `kit chain deploy` deployed, or Hypermap name (for its TBA)")
+                    .required(true)
+                )
+                .arg(Arg::new("SIGNATURE")
+                    .action(ArgAction::Set)
+                    .help("Function signature, e.g. `balanceOf(address) returns (uint256)`, or name (with --project)")
+                    .required(true)
+                )
+                .arg(Arg::new("ARGS")
+                    .action(ArgAction::Append)
+                    .help("Function arguments")
+                    .num_args(0..)
+                    .allow_hyphen_values(true)
+                )
+                .arg(Arg::new("FROM")
+                    .action(ArgAction::Set)
+                    .long("from")
+                    .help("Address to send from, impersonated [default: first anvil account]")
+                    .required(false)
+                )
+                .arg(Arg::new("PROJECT")
+                    .action(ArgAction::Set)
+                    .long("project")
+                    .help("Foundry project `kit chain deploy` deployed TARGET from, for its ABI")
+                    .required(false)
+                )
+                .arg(Arg::new("PRIVATE_KEY")
+                    .action(ArgAction::Set)
+                    .long("private-key")
+                    .help("Private key to sign transactions with, instead of impersonating senders")
+                    .required(false)
+                )
+                .arg(Arg::new("KEYSTORE")
+                    .action(ArgAction::Set)
+                    .long("keystore")
+                    .help("Path to private key keystore to sign transactions with, instead of impersonating senders")
+                    .conflicts_with("PRIVATE_KEY")
+                    .required(false)
+                )
+                .group(ArgGroup::new("SIGNER")
+                    .args(["PRIVATE_KEY", "KEYSTORE"])
+                )
+                .arg(Arg::new("RPC_URL")
+                    .action(ArgAction::Set)
+                    .long("rpc")
+                    .help("HTTP RPC endpoint of the chain to send signed transactions to, instead of the local chain on --port (requires --private-key or --keystore)")
+                    .requires("SIGNER")
+                    .required(false)
+                )
+                .arg(Arg::new("PORT")
+                    .action(ArgAction::Set)
+                    .short('p')
+                    .long("port")
+                    .help("Port the chain runs on")
+                    .default_value("8545")
+                    .value_parser(value_parser!(u16))
+                )
+            )
         )
         .subcommand(Command::new("connect")
             .about("Connect (or disconnect) a ssh tunnel to a remote server")
@@ -1062,6 +2347,22 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .required(false)
             )
         )
+        .subcommand(Command::new("deps")
+            .about("Print the dependency graph of the package(s) under a dir, flagging imports with no exporter")
+            .arg(Arg::new("DIR")
+                .action(ArgAction::Set)
+                .help("The package or workspace directory")
+                .default_value(current_dir)
+            )
+            .arg(Arg::new("FORMAT")
+                .action(ArgAction::Set)
+                .short('f')
+                .long("format")
+                .help("Output format")
+                .default_value("text")
+                .value_parser(["text", "dot", "json"])
+            )
+        )
         .subcommand(Command::new("dev-ui")
             .about("Start the web UI development server with hot reloading (same as `cd ui && npm i && npm run dev`)")
             .visible_alias("d")
@@ -1159,17 +2460,16 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .short('l')
                 .long("language")
                 .help("Programming language of the template")
-                .value_parser(["rust"])
-                //.value_parser(["rust", "python", "javascript"]) // TODO: resupport
+                .value_parser(["rust", "go"])
+                //.value_parser(["rust", "python", "javascript", "go"]) // TODO: resupport
                 .default_value("rust")
             )
             .arg(Arg::new("TEMPLATE")
                 .action(ArgAction::Set)
                 .short('t')
                 .long("template")
-                .help("Template to create")
+                .help("Template to create [default: chat; echo for go]")
                 .value_parser(["blank", "chat", "echo", "fibonacci", "file-transfer", "hyperapp-skeleton"])
-                .default_value("chat")
             )
             .arg(Arg::new("UI")
                 .action(ArgAction::SetTrue)
@@ -1315,6 +2615,18 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .help("Path to tests configuration file (or test dir)")
                 .default_value(current_dir)
             )
+            .arg(Arg::new("LOCKED")
+                .action(ArgAction::SetTrue)
+                .long("locked")
+                .help("Pass `--locked` to cargo: fail if Cargo.lock needs to be updated")
+                .required(false)
+            )
+            .arg(Arg::new("FROZEN")
+                .action(ArgAction::SetTrue)
+                .long("frozen")
+                .help("Equivalent to `--locked` plus building offline")
+                .required(false)
+            )
         )
         .subcommand(Command::new("setup")
             .about("Fetch & setup kit dependencies")
@@ -1399,6 +2711,34 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .default_value("master")
             )
         )
+        .subcommand(Command::new("verify")
+            .about("Verify the signature of a package zip made by `kit build --sign`")
+            .arg(Arg::new("DIR")
+                .action(ArgAction::Set)
+                .help("The package directory")
+                .default_value(current_dir)
+            )
+            .arg(Arg::new("ZIP")
+                .action(ArgAction::Set)
+                .long("zip")
+                .help("Path to the package zip [default: DIR/target/<package>:<publisher>.zip]")
+                .value_parser(value_parser!(PathBuf))
+                .required(false)
+            )
+            .arg(Arg::new("SIGNATURE")
+                .action(ArgAction::Set)
+                .long("signature")
+                .help("Path to the signature [default: DIR/pkg/signature.json]")
+                .value_parser(value_parser!(PathBuf))
+                .required(false)
+            )
+            .arg(Arg::new("PUBLIC_KEY")
+                .action(ArgAction::Set)
+                .long("public-key")
+                .help("Hex ed25519 public key the package must be signed by [default: any]")
+                .required(false)
+            )
+        )
         .subcommand(Command::new("view-api")
             .about("Fetch the list of APIs or a specific API")
             .visible_alias("v")
@@ -1423,6 +2763,90 @@ async fn make_app(current_dir: &std::ffi::OsString) -> Result<Command> {
                 .required(false)
             )
         )
+        .subcommand(Command::new("wit")
+            .about("Generate and check the WIT API of a hyperapp package")
+            .subcommand_required(true)
+            .subcommand(Command::new("generate")
+                .about("Generate WIT into api/ from the package's hyperapp processes, without building")
+                .arg(Arg::new("DIR")
+                    .action(ArgAction::Set)
+                    .help("The package directory")
+                    .default_value(current_dir)
+                )
+                .arg(Arg::new("PROCESS")
+                    .action(ArgAction::Append)
+                    .short('p')
+                    .long("process")
+                    .help("Only (re)generate WIT for these processes (can specify multiple times) [default: all]")
+                )
+                .arg(Arg::new("WIT_RENAME")
+                    .action(ArgAction::SetTrue)
+                    .long("wit-rename")
+                    .help("Generate as `kit build --wit-rename` does")
+                    .required(false)
+                )
+            )
+            .subcommand(Command::new("check")
+                .about("Check WIT can be generated from the package's hyperapp processes, without writing api/")
+                .arg(Arg::new("DIR")
+                    .action(ArgAction::Set)
+                    .help("The package directory")
+                    .default_value(current_dir)
+                )
+                .arg(Arg::new("PROCESS")
+                    .action(ArgAction::Append)
+                    .short('p')
+                    .long("process")
+                    .help("Only (re)generate WIT for these processes (can specify multiple times) [default: all]")
+                )
+                .arg(Arg::new("WIT_RENAME")
+                    .action(ArgAction::SetTrue)
+                    .long("wit-rename")
+                    .help("Generate as `kit build --wit-rename` does")
+                    .required(false)
+                )
+            )
+            .subcommand(Command::new("diff")
+                .about("Regenerate WIT from the package's hyperapp processes and diff it against the committed api/, failing if they differ")
+                .arg(Arg::new("DIR")
+                    .action(ArgAction::Set)
+                    .help("The package directory")
+                    .default_value(current_dir)
+                )
+                .arg(Arg::new("PROCESS")
+                    .action(ArgAction::Append)
+                    .short('p')
+                    .long("process")
+                    .help("Only (re)generate WIT for these processes (can specify multiple times) [default: all]")
+                )
+                .arg(Arg::new("WIT_RENAME")
+                    .action(ArgAction::SetTrue)
+                    .long("wit-rename")
+                    .help("Generate as `kit build --wit-rename` does")
+                    .required(false)
+                )
+            )
+            .subcommand(Command::new("lint")
+                .about("Report all names in the package's hyperapp processes that WIT generation would reject, with their locations")
+                .arg(Arg::new("DIR")
+                    .action(ArgAction::Set)
+                    .help("The package directory")
+                    .default_value(current_dir)
+                )
+                .arg(Arg::new("PROCESS")
+                    .action(ArgAction::Append)
+                    .short('p')
+                    .long("process")
+                    .help("Only lint these processes (can specify multiple times) [default: all]")
+                )
+                .arg(Arg::new("WIT_RENAME")
+                    .action(ArgAction::SetTrue)
+                    .long("wit-rename")
+                    .help("Lint as for `kit build --wit-rename`, allowing digits in names")
+                    .required(false)
+                )
+            )
+        )
     )
 }
 
@@ -1432,7 +2856,7 @@ async fn main() -> Result<()> {
     let log_path =
         std::env::var("KIT_LOG_PATH").unwrap_or_else(|_| KIT_LOG_PATH_DEFAULT.to_string());
     let log_path = PathBuf::from(log_path);
-    let _guard = init_tracing(log_path);
+    let _guard = init_tracing(log_path, &get_log_format());
     color_eyre::config::HookBuilder::default()
         .display_env_section(false)
         .install()?;
@@ -14,6 +14,7 @@ pub enum Language {
     Rust,
     Python,
     Javascript,
+    Go,
 }
 
 #[derive(Clone)]
@@ -32,6 +33,7 @@ impl Language {
             Language::Rust => "rust",
             Language::Python => "python",
             Language::Javascript => "javascript",
+            Language::Go => "go",
         }
         .to_string()
     }
@@ -57,7 +59,8 @@ impl From<&String> for Language {
             "rust" => Language::Rust,
             "python" => Language::Python,
             "javascript" => Language::Javascript,
-            _ => panic!("kit: language must be 'rust' or 'python'; not '{s}'"),
+            "go" => Language::Go,
+            _ => panic!("kit: language must be 'rust', 'python', 'javascript' or 'go'; not '{s}'"),
         }
     }
 }
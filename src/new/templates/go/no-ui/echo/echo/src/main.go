@@ -0,0 +1,59 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+
+	"go.bytecodealliance.org/cm"
+
+	process "echo/bindings/hyperware/process/process-v1"
+	"echo/bindings/hyperware/process/standard"
+)
+
+func handleMessage() error {
+	result := standard.Receive()
+	if err := result.Err(); err != nil {
+		return fmt.Errorf("got SendError")
+	}
+	message := result.OK().F1
+
+	request := message.Request()
+	if request == nil {
+		return fmt.Errorf("unexpected Response")
+	}
+	var body any
+	if err := json.Unmarshal(request.Body.Slice(), &body); err != nil {
+		return err
+	}
+	standard.PrintToTerminal(0, fmt.Sprintf("echo: got message %v", body))
+
+	ack, err := json.Marshal("Ack")
+	if err != nil {
+		return err
+	}
+	standard.SendResponse(
+		standard.Response{
+			Inherit:      false,
+			Body:         cm.ToList(ack),
+			Metadata:     cm.None[string](),
+			Capabilities: cm.ToList([]standard.Capability{}),
+		},
+		cm.None[standard.LazyLoadBlob](),
+	)
+	return nil
+}
+
+func init() {
+	process.Exports.Init = func(our string) {
+		standard.PrintToTerminal(0, "echo: begin (go)")
+
+		for {
+			if err := handleMessage(); err != nil {
+				standard.PrintToTerminal(0, fmt.Sprintf("echo: got error %v", err))
+			}
+		}
+	}
+}
+
+// processes are entered through the `init` export: `main` is never called
+func main() {}
@@ -32,6 +32,7 @@ export default defineConfig({
   resolve: {
     alias: {
       '#caller-utils': resolve(__dirname, '../target/ui/caller-utils.ts'),
+      '#caller-utils-hooks': resolve(__dirname, '../target/ui/caller-utils-hooks.ts'),
     }
   },
   server: {
@@ -46,10 +47,11 @@ export default defineConfig({
         changeOrigin: true,
         rewrite: (path) => path.replace(BASE_URL, ''),
       },
-      // This route will match all other HTTP requests to the backend
+      // This route will match all other HTTP (and WebSocket) requests to the backend
       [`^${BASE_URL}/(?!(@vite/client|src/.*|node_modules/.*|@react-refresh|$))`]: {
         target: PROXY_URL,
         changeOrigin: true,
+        ws: true,
       },
       // '/example': {
       //   target: PROXY_URL,
@@ -139,7 +139,8 @@ async fn read_trezor(chain_id: u64) -> Result<(Address, EthereumWallet)> {
     Ok((address, wallet))
 }
 
-fn namehash(name: &str) -> [u8; 32] {
+/// Hypermap namehash of a `.`-separated name (the root for `""`)
+pub fn namehash(name: &str) -> [u8; 32] {
     let mut node = B256::default();
 
     if name.is_empty() {
@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
@@ -13,7 +12,7 @@ use tracing::{debug, info, instrument};
 use hyperware_process_lib::kernel_types::PackageManifestEntry;
 
 use crate::boot_fake_node;
-use crate::build::{self, DEFAULT_RUST_TOOLCHAIN};
+use crate::build;
 use crate::chain;
 use crate::inject_message;
 use crate::start_package;
@@ -280,6 +279,8 @@ async fn build_packages(
     detached: &bool,
     persist_home: &bool,
     runtime_path: &Path,
+    locked: bool,
+    offline: bool,
 ) -> Result<(Vec<SetupPackage>, Vec<PathBuf>)> {
     let dependency_package_paths: Vec<PathBuf> = test
         .dependency_package_paths
@@ -342,8 +343,14 @@ async fn build_packages(
 
     // boot fakechain
     let recv_kill_in_start_chain = send_to_kill.subscribe();
+    let fakechain_port =
+        chain::get_chain_port(test.fakechain_router, test.fakechain_name.as_deref())?;
     let anvil_process = chain::start_chain(
-        test.fakechain_router,
+        fakechain_port,
+        test.fakechain_name.as_deref(),
+        None,
+        None,
+        None,
         recv_kill_in_start_chain,
         false,
         false,
@@ -352,7 +359,7 @@ async fn build_packages(
 
     boot_nodes(
         &nodes,
-        &test.fakechain_router,
+        &fakechain_port,
         &runtime_path,
         &detached,
         &mut master_node_port,
@@ -377,24 +384,16 @@ async fn build_packages(
         debug!("Build {path:?}");
         build::execute(
             &path,
-            false,
-            false,
-            &HashSet::new(),
-            &HashSet::new(),
-            false,
-            feature_string.as_str(),
-            Some(url.clone()),
-            None,
-            None,
-            dependency_package_paths.clone(),
-            vec![], // TODO
-            false,
-            test.hyperapp.unwrap_or_default(),
-            false,
-            false,
-            false,
-            false,
-            DEFAULT_RUST_TOOLCHAIN,
+            build::BuildOptions {
+                features: feature_string.as_str(),
+                url: Some(url.clone()),
+                local_dependencies: dependency_package_paths.clone(),
+                add_paths_to_api: vec![], // TODO
+                hyperapp: test.hyperapp.unwrap_or_default(),
+                offline,
+                locked,
+                ..Default::default()
+            },
         )
         .await?;
         debug!("Start {path:?}");
@@ -404,48 +403,32 @@ async fn build_packages(
     for setup_package in &setup_packages {
         build::execute(
             &setup_package.path,
-            false,
-            false,
-            &HashSet::new(),
-            &HashSet::new(),
-            false,
-            feature_string.as_str(),
-            Some(url.clone()),
-            None,
-            None,
-            dependency_package_paths.clone(),
-            vec![], // TODO
-            false,
-            test.hyperapp.unwrap_or_default(),
-            false,
-            false,
-            false,
-            false,
-            DEFAULT_RUST_TOOLCHAIN,
+            build::BuildOptions {
+                features: feature_string.as_str(),
+                url: Some(url.clone()),
+                local_dependencies: dependency_package_paths.clone(),
+                add_paths_to_api: vec![], // TODO
+                hyperapp: test.hyperapp.unwrap_or_default(),
+                offline,
+                locked,
+                ..Default::default()
+            },
         )
         .await?;
     }
     for test_package_path in &test_package_paths {
         build::execute(
             &test_package_path,
-            false,
-            false,
-            &HashSet::new(),
-            &HashSet::new(),
-            false,
-            feature_string.as_str(),
-            Some(url.clone()),
-            None,
-            None,
-            dependency_package_paths.clone(),
-            vec![], // TODO
-            false,
-            test.hyperapp.unwrap_or_default(),
-            false,
-            false,
-            false,
-            false,
-            DEFAULT_RUST_TOOLCHAIN,
+            build::BuildOptions {
+                features: feature_string.as_str(),
+                url: Some(url.clone()),
+                local_dependencies: dependency_package_paths.clone(),
+                add_paths_to_api: vec![], // TODO
+                hyperapp: test.hyperapp.unwrap_or_default(),
+                offline,
+                locked,
+                ..Default::default()
+            },
         )
         .await?;
     }
@@ -710,9 +693,19 @@ async fn handle_test(
     test_dir_path: &Path,
     persist_home: bool,
     always_print_node_output: bool,
+    locked: bool,
+    offline: bool,
 ) -> Result<()> {
-    let (setup_packages, test_package_paths) =
-        build_packages(&test, test_dir_path, &detached, &persist_home, runtime_path).await?;
+    let (setup_packages, test_package_paths) = build_packages(
+        &test,
+        test_dir_path,
+        &detached,
+        &persist_home,
+        runtime_path,
+        locked,
+        offline,
+    )
+    .await?;
 
     let SetupCleanupReturn {
         send_to_cleanup,
@@ -750,8 +743,14 @@ async fn handle_test(
 
     // boot fakechain
     let recv_kill_in_start_chain = send_to_kill.subscribe();
+    let fakechain_port =
+        chain::get_chain_port(test.fakechain_router, test.fakechain_name.as_deref())?;
     let anvil_process = chain::start_chain(
-        test.fakechain_router,
+        fakechain_port,
+        test.fakechain_name.as_deref(),
+        None,
+        None,
+        None,
         recv_kill_in_start_chain,
         false,
         false,
@@ -761,7 +760,7 @@ async fn handle_test(
     // Process each node
     boot_nodes(
         &test.nodes,
-        &test.fakechain_router,
+        &fakechain_port,
         &runtime_path,
         &detached,
         &mut master_node_port,
@@ -819,7 +818,7 @@ async fn handle_test(
 }
 
 #[instrument(level = "trace", skip_all)]
-pub async fn execute(config_path: PathBuf) -> Result<()> {
+pub async fn execute(config_path: PathBuf, locked: bool, offline: bool) -> Result<()> {
     let detached = true; // TODO: to arg?
 
     let (config_path, config) = load_config(&config_path)?;
@@ -849,6 +848,8 @@ pub async fn execute(config_path: PathBuf) -> Result<()> {
             &test_dir_path,
             config.persist_home,
             config.always_print_node_output,
+            locked,
+            offline,
         )
         .await?;
     }
@@ -31,6 +31,9 @@ pub struct Test {
     pub test_scripts: Vec<String>,
     pub timeout_secs: u64,
     pub fakechain_router: u16,
+    /// Name of a fakechain (`kit chain start --name`) to use, on whichever port it runs; if it is
+    ///  not running, it is started on `fakechain_router`
+    pub fakechain_name: Option<String>,
     pub nodes: Vec<Node>,
     pub hyperapp: Option<bool>,
     pub features: Option<Vec<String>>,
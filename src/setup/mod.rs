@@ -21,6 +21,7 @@ pub const REQUIRED_PY_MAJOR: u32 = 3;
 pub const MINIMUM_PY_MINOR: u32 = 10;
 pub const REQUIRED_PY_PACKAGE: &str = "componentize-py==0.11.0";
 const WASM_TOOLS_VERSION: &str = "1.225.0";
+const WIT_BINDGEN_VERSION: &str = "0.41.0";
 
 #[derive(Clone)]
 pub enum Dependency {
@@ -30,7 +31,9 @@ pub enum Dependency {
     Node,
     Rust,
     RustWasm32Wasi,
+    RustWasm32Wasip2,
     WasmTools,
+    WitBindgen,
     Docker,
 }
 
@@ -43,7 +46,9 @@ impl std::fmt::Display for Dependency {
             Dependency::Node => write!(f, "node {}.{}", REQUIRED_NODE_MAJOR, MINIMUM_NODE_MINOR),
             Dependency::Rust => write!(f, "rust"),
             Dependency::RustWasm32Wasi => write!(f, "rust wasm32-wasip1 target"),
+            Dependency::RustWasm32Wasip2 => write!(f, "rust wasm32-wasip2 target"),
             Dependency::WasmTools => write!(f, "wasm-tools"),
+            Dependency::WitBindgen => write!(f, "wit-bindgen {}", WIT_BINDGEN_VERSION),
             Dependency::Docker => write!(f, "docker"),
         }
     }
@@ -274,6 +279,26 @@ fn check_rust_toolchains_targets(toolchain: &str) -> Result<Vec<Dependency>> {
     Ok(missing_deps)
 }
 
+/// Check for the wasm32-wasip2 target, used by packages that build components natively
+#[instrument(level = "trace", skip_all)]
+pub fn check_rust_wasip2_deps(toolchain: &str) -> Result<Vec<Dependency>> {
+    let output = Command::new("rustup")
+        .arg(toolchain)
+        .arg("show")
+        .output()?
+        .stdout;
+    let output = String::from_utf8_lossy(&output);
+
+    if output
+        .split('\n')
+        .any(|item| item.contains("wasm32-wasip2"))
+    {
+        Ok(vec![])
+    } else {
+        Ok(vec![Dependency::RustWasm32Wasip2])
+    }
+}
+
 /// Find the newest Python version (>= 3.10 or given major, minor)
 #[instrument(level = "trace", skip_all)]
 pub fn get_python_version(
@@ -386,6 +411,59 @@ pub fn check_rust_deps(toolchain: &str) -> Result<Vec<Dependency>> {
     Ok(missing_deps)
 }
 
+/// Check for Go deps, erroring if Go, TinyGo or wit-bindgen-go is not found: they cannot be
+///  automatically fetched
+#[instrument(level = "trace", skip_all)]
+pub fn check_go_deps() -> Result<Vec<Dependency>> {
+    for (command, link, name) in [
+        ("go", "https://go.dev/doc/install", "install Go"),
+        (
+            "tinygo",
+            "https://tinygo.org/getting-started/install",
+            "install TinyGo",
+        ),
+        (
+            "wit-bindgen-go",
+            "https://github.com/bytecodealliance/go-modules#wit-bindgen-go",
+            "install wit-bindgen-go",
+        ),
+    ] {
+        if !is_command_installed(command)? {
+            return Err(eyre!(
+                "{command} not found: please {} and try again",
+                make_remote_link(link, name),
+            ));
+        }
+    }
+    let mut missing_deps = Vec::new();
+    if !is_command_installed("wasm-tools")? {
+        missing_deps.push(Dependency::WasmTools);
+    }
+    Ok(missing_deps)
+}
+
+/// Check for C/C++ deps, returning a Vec of not found: can be automatically fetched
+///
+/// The wasi-sdk toolchain itself is managed by kit during the build
+#[instrument(level = "trace", skip_all)]
+pub fn check_c_deps() -> Result<Vec<Dependency>> {
+    if !is_command_installed("cargo")? {
+        return Ok(vec![
+            Dependency::Rust,
+            Dependency::WitBindgen,
+            Dependency::WasmTools,
+        ]);
+    }
+    let mut missing_deps = Vec::new();
+    if !is_command_installed("wit-bindgen")? {
+        missing_deps.push(Dependency::WitBindgen);
+    }
+    if !is_command_installed("wasm-tools")? {
+        missing_deps.push(Dependency::WasmTools);
+    }
+    Ok(missing_deps)
+}
+
 // Check for Foundry deps, returning a Vec of not found: can be automatically fetched?
 #[instrument(level = "trace", skip_all)]
 pub fn check_docker_deps() -> Result<Vec<Dependency>> {
@@ -477,11 +555,19 @@ fn install_deps(deps: Vec<Dependency>, verbose: bool, toolchain: &str) -> Result
             Dependency::RustWasm32Wasi => {
                 call_rustup("target add wasm32-wasip1", verbose, toolchain)?
             }
+            Dependency::RustWasm32Wasip2 => {
+                call_rustup("target add wasm32-wasip2", verbose, toolchain)?
+            }
             Dependency::WasmTools => call_cargo(
                 &format!("install wasm-tools --locked --version {WASM_TOOLS_VERSION}"),
                 verbose,
                 toolchain,
             )?,
+            Dependency::WitBindgen => call_cargo(
+                &format!("install wit-bindgen-cli --locked --version {WIT_BINDGEN_VERSION}"),
+                verbose,
+                toolchain,
+            )?,
             Dependency::Foundry => install_foundry(verbose)?,
             Dependency::Docker => {}
         }
@@ -7,7 +7,10 @@ use tracing::{debug, info, instrument};
 
 use hyperware_process_lib::kernel_types::{Erc721Metadata, PackageManifestEntry};
 
-use crate::build::{hash_zip_pkg, make_pkg_publisher, make_zip_filename, read_and_update_metadata};
+use crate::build::{
+    hash_zip_pkg, is_path_dependency, make_pkg_publisher, make_zip_filename,
+    read_and_update_metadata, read_metadata, resolve_path_dependency,
+};
 use crate::new::is_hypermap_safe;
 use crate::publish::{make_local_file_link_path, make_remote_link};
 use crate::{inject_message, KIT_LOG_PATH_DEFAULT};
@@ -204,7 +207,16 @@ pub async fn execute(package_dir: &Path, url: &str) -> Result<()> {
         ));
     }
     let pkg_dir = package_dir.join("pkg").canonicalize()?;
-    let metadata = read_and_update_metadata(package_dir)?;
+    let mut metadata = read_and_update_metadata(package_dir)?;
+    // the node only understands package ID dependencies
+    if let Some(ref mut dependencies) = metadata.properties.dependencies {
+        for dependency in dependencies.iter_mut() {
+            if is_path_dependency(dependency) {
+                let path = resolve_path_dependency(package_dir, dependency)?;
+                *dependency = make_pkg_publisher(&read_metadata(&path)?);
+            }
+        }
+    }
     let package_name = metadata.properties.package_name.as_str();
     let publisher = metadata.properties.publisher.as_str();
     let pkg_publisher = make_pkg_publisher(&metadata);
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use color_eyre::Result;
+use tracing::instrument;
+
+use crate::build::{
+    make_pkg_publisher, make_zip_filename, read_metadata, verify_pkg, SIGNATURE_NAME,
+};
+
+#[instrument(level = "trace", skip_all)]
+pub fn execute(
+    package_dir: &Path,
+    zip_path: Option<&Path>,
+    signature_path: Option<&Path>,
+    public_key: Option<&str>,
+) -> Result<()> {
+    let zip_path = match zip_path {
+        Some(zip_path) => zip_path.to_path_buf(),
+        None => make_zip_filename(
+            package_dir,
+            &make_pkg_publisher(&read_metadata(package_dir)?),
+        ),
+    };
+    let signature_path = match signature_path {
+        Some(signature_path) => signature_path.to_path_buf(),
+        None => package_dir.join("pkg").join(SIGNATURE_NAME),
+    };
+    verify_pkg(&zip_path, &signature_path, public_key)
+}
@@ -305,6 +305,17 @@ async fn get_api(
     Ok(zip_dir)
 }
 
+/// Get the version hash of `package_id` installed on the node at `url`
+#[instrument(level = "trace", skip_all)]
+pub async fn get_package_version_hash(
+    node: Option<&str>,
+    url: &str,
+    package_id: &str,
+) -> Result<String> {
+    let (package_name, publisher_node) = split_package_id(package_id)?;
+    get_version_hash(node, url, &package_name, &publisher_node).await
+}
+
 #[instrument(level = "trace", skip_all)]
 pub async fn execute(
     node: Option<&str>,
@@ -0,0 +1,310 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use color_eyre::{eyre::eyre, Result, Section};
+use fs_err as fs;
+use tracing::{info, instrument};
+
+use crate::build::{
+    generate_wit_files, lint_wit, read_kit_toml, read_metadata, write_versioned_api,
+};
+
+/// Dir, within the package's `target/`, that WIT is regenerated into for `kit wit check` & `diff`
+const REGENERATE_DIR: &str = "wit-regenerate";
+
+const TYPE_KEYWORDS: [&str; 6] = ["record", "variant", "enum", "flags", "resource", "type"];
+
+/// The definitions in a WIT file that `kit wit diff` summarizes changes to
+#[derive(Debug, Default)]
+struct WitDefinitions {
+    /// `<function> (<attr>)` -> signature record, with its `//` annotations (args, HTTP path, ...)
+    functions: BTreeMap<String, String>,
+    /// type name -> definition
+    types: BTreeMap<String, String>,
+    /// `<world> imports <interface>`
+    imports: BTreeSet<String>,
+}
+
+/// Parse the functions, types and world imports of a WIT file; doc comments are ignored
+fn parse_definitions(content: &str) -> WitDefinitions {
+    let mut definitions = WitDefinitions::default();
+    let mut annotations: Vec<&str> = Vec::new();
+    let mut world: Option<&str> = None;
+    let mut lines = content.lines().map(str::trim);
+
+    while let Some(line) = lines.next() {
+        if line.starts_with("///") {
+            continue;
+        }
+        if line.starts_with("//") {
+            annotations.push(line);
+            continue;
+        }
+        let pending_annotations = std::mem::take(&mut annotations);
+
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let name = tokens
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches(['{', ';'])
+            .trim_start_matches('%');
+        if keyword == "world" {
+            world = Some(name);
+            continue;
+        }
+        if keyword == "import" {
+            if let Some(world) = world {
+                definitions
+                    .imports
+                    .insert(format!("{world} imports {name}"));
+            }
+            continue;
+        }
+        if !TYPE_KEYWORDS.contains(&keyword) {
+            continue;
+        }
+
+        let mut definition = vec![line];
+        if line.ends_with('{') {
+            for line in lines.by_ref() {
+                // `// serde: <wire name>` comments are kept: they change the JSON form
+                if line.starts_with("///") {
+                    continue;
+                }
+                definition.push(line);
+                if line.starts_with('}') {
+                    break;
+                }
+            }
+        }
+        let definition = definition.join("\n");
+
+        match name.split_once("-signature-") {
+            Some((function, attr)) if keyword == "record" => {
+                let mut signature = pending_annotations;
+                signature.push(&definition);
+                definitions
+                    .functions
+                    .insert(format!("{function} ({attr})"), signature.join("\n"));
+            }
+            _ => {
+                definitions.types.insert(name.to_string(), definition);
+            }
+        }
+    }
+    definitions
+}
+
+fn diff_definitions(
+    kind: &str,
+    interface: &str,
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+    changes: &mut Vec<String>,
+) {
+    for (name, definition) in after {
+        match before.get(name) {
+            None => changes.push(format!("  added {kind}: {interface}/{name}")),
+            Some(old) if old != definition => {
+                changes.push(format!("  changed {kind}: {interface}/{name}"))
+            }
+            _ => {}
+        }
+    }
+    for name in before.keys() {
+        if !after.contains_key(name) {
+            changes.push(format!("  removed {kind}: {interface}/{name}"));
+        }
+    }
+}
+
+/// Summarize the changes from the `committed` WIT files to the `generated` ones
+fn diff_wit_files(
+    committed: &BTreeMap<String, String>,
+    generated: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    for (file, content) in generated {
+        let Some(committed_content) = committed.get(file) else {
+            changes.push(format!("  added file: {file}"));
+            continue;
+        };
+        if committed_content == content {
+            continue;
+        }
+        let interface = file.trim_end_matches(".wit");
+        let before = parse_definitions(committed_content);
+        let after = parse_definitions(content);
+
+        let mut file_changes = Vec::new();
+        diff_definitions(
+            "function",
+            interface,
+            &before.functions,
+            &after.functions,
+            &mut file_changes,
+        );
+        diff_definitions(
+            "type",
+            interface,
+            &before.types,
+            &after.types,
+            &mut file_changes,
+        );
+        for import in after.imports.difference(&before.imports) {
+            file_changes.push(format!("  added import: {import}"));
+        }
+        for import in before.imports.difference(&after.imports) {
+            file_changes.push(format!("  removed import: {import}"));
+        }
+        if file_changes.is_empty() {
+            file_changes.push(format!(
+                "  changed file (docs, comments or formatting): {file}"
+            ));
+        }
+        changes.extend(file_changes);
+    }
+    changes
+}
+
+/// Read the `.wit` files of a dir: file name -> contents
+fn read_wit_files(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    if !dir.exists() {
+        return Ok(files);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("wit") {
+            continue;
+        }
+        let Some(file_name) = path.file_name().and_then(|f| f.to_str()) else {
+            continue;
+        };
+        files.insert(file_name.to_string(), fs::read_to_string(&path)?);
+    }
+    Ok(files)
+}
+
+/// Regenerate the WIT of the package's hyperapp processes into `target/`, starting from a copy
+///  of `api/` so that world files are updated just as `kit build --hyperapp` would
+fn regenerate_wit(
+    package_dir: &Path,
+    processes: &[String],
+    wit_rename: bool,
+) -> Result<(PathBuf, Vec<String>)> {
+    let api_dir = package_dir.join("api");
+    let generated_api_dir = package_dir.join("target").join(REGENERATE_DIR).join("api");
+    if generated_api_dir.exists() {
+        fs::remove_dir_all(&generated_api_dir)?;
+    }
+    fs::create_dir_all(&generated_api_dir)?;
+    for file in read_wit_files(&api_dir)?.keys() {
+        fs::copy(api_dir.join(file), generated_api_dir.join(file))?;
+    }
+
+    let interfaces = generate(package_dir, &generated_api_dir, processes, wit_rename)?;
+    Ok((generated_api_dir, interfaces))
+}
+
+fn generate(
+    package_dir: &Path,
+    api_dir: &Path,
+    processes: &[String],
+    wit_rename: bool,
+) -> Result<Vec<String>> {
+    let kit_toml = read_kit_toml(package_dir)?;
+    let metadata = read_metadata(package_dir)?;
+    let shared_types_package = kit_toml
+        .api
+        .shared_types
+        .then_some(metadata.properties.package_name.as_str());
+    let (processed_projects, interfaces) = generate_wit_files(
+        package_dir,
+        api_dir,
+        wit_rename,
+        processes,
+        &kit_toml.processes,
+        shared_types_package,
+    )?;
+    if processed_projects.is_empty() {
+        return Err(eyre!("No hyperapp processes found in {package_dir:?}")
+            .with_suggestion(|| "WIT is only generated for processes with a #[hyperapp] impl."));
+    }
+    if kit_toml.api.versioned && !interfaces.is_empty() {
+        write_versioned_api(
+            api_dir,
+            &metadata.properties.package_name,
+            &metadata.properties.current_version,
+            &interfaces,
+        )?;
+    }
+    Ok(interfaces)
+}
+
+/// Generate the WIT of the package's hyperapp processes (or only `processes`, if given)
+///  into `api/`, as `kit build --hyperapp` does, but without building anything
+#[instrument(level = "trace", skip_all)]
+pub fn execute_generate(package_dir: &Path, processes: &[String], wit_rename: bool) -> Result<()> {
+    let api_dir = package_dir.join("api");
+    let interfaces = generate(package_dir, &api_dir, processes, wit_rename)?;
+    info!("Generated WIT interfaces {interfaces:?} in {api_dir:?}.");
+    Ok(())
+}
+
+/// Check that WIT can be generated from the package's hyperapp processes (or only `processes`,
+///  if given), without writing `api/`
+#[instrument(level = "trace", skip_all)]
+pub fn execute_check(package_dir: &Path, processes: &[String], wit_rename: bool) -> Result<()> {
+    let (generated_api_dir, interfaces) = regenerate_wit(package_dir, processes, wit_rename)?;
+    info!("WIT interfaces {interfaces:?} generated successfully (into {generated_api_dir:?}).");
+    Ok(())
+}
+
+/// Regenerate the package's WIT (or only that of `processes`, if given) and compare it against
+///  the committed `api/`, failing with a summary of the changes if they differ
+#[instrument(level = "trace", skip_all)]
+pub fn execute_diff(package_dir: &Path, processes: &[String], wit_rename: bool) -> Result<()> {
+    let api_dir = package_dir.join("api");
+    let (generated_api_dir, _) = regenerate_wit(package_dir, processes, wit_rename)?;
+
+    let changes = diff_wit_files(
+        &read_wit_files(&api_dir)?,
+        &read_wit_files(&generated_api_dir)?,
+    );
+    if changes.is_empty() {
+        info!("WIT in {api_dir:?} is up-to-date.");
+        return Ok(());
+    }
+
+    info!(
+        "WIT in {api_dir:?} differs from that generated from the package's hyperapp processes (in {generated_api_dir:?}):\n{}",
+        changes.join("\n"),
+    );
+    Err(eyre!("{} WIT change(s) not in {api_dir:?}", changes.len())
+        .with_suggestion(|| "Run `kit wit generate` to regenerate api/, and commit the result."))
+}
+
+/// Lint the package's hyperapp processes (or only `processes`, if given) for the names that WIT
+///  generation fails on, reporting all of them, with their locations, in one run
+#[instrument(level = "trace", skip_all)]
+pub fn execute_lint(package_dir: &Path, processes: &[String], wit_rename: bool) -> Result<()> {
+    let issues = lint_wit(package_dir, processes, wit_rename)?;
+    if issues.is_empty() {
+        info!("No WIT issues found in {package_dir:?}.");
+        return Ok(());
+    }
+
+    info!(
+        "WIT issues in the hyperapp processes of {package_dir:?}:\n{}",
+        issues.join("\n"),
+    );
+    Err(
+        eyre!("{} WIT issue(s) found", issues.len()).with_suggestion(|| {
+            "Rename the identifiers listed above, or use `--wit-rename` to rewrite digits as words."
+        }),
+    )
+}